@@ -1,7 +1,9 @@
-use acvm::pwg::OpcodeResolutionError;
+use acvm::{acir::native_types::Witness, pwg::OpcodeResolutionError, FieldElement};
 use noirc_printable_type::ForeignCallError;
 use thiserror::Error;
 
+use crate::ops::{OverflowingOperation, ValuePredicate, WitnessBackingError};
+
 #[derive(Debug, Error)]
 pub enum NargoError {
     /// Error while compiling Noir into ACIR.
@@ -14,4 +16,202 @@ pub enum NargoError {
 
     #[error(transparent)]
     ForeignCallError(#[from] ForeignCallError),
+
+    /// The inputs of a foreign call did not match the schema given for it
+    #[error("Foreign call `{function}` does not match its schema: {reason}")]
+    ForeignCallSchemaViolation { function: String, reason: String },
+
+    /// A foreign call was made to a function disabled by the execution's policy
+    #[error("Foreign call `{0}` is disabled")]
+    ForeignCallDisabled(String),
+
+    /// A batch of foreign calls was resolved with the wrong number of results
+    #[error("Expected {expected} results for a batch of foreign calls but got {actual}")]
+    ForeignCallBatchMismatch { expected: usize, actual: usize },
+
+    /// Unconstrained code took more steps than permitted by its fuel budget
+    #[error(
+        "Unconstrained execution exhausted its budget of {fuel} steps in opcode {opcode_index}"
+    )]
+    UnconstrainedFuelExhausted { fuel: usize, opcode_index: usize },
+
+    /// A witness did not have its expected value when execution reached a checkpoint
+    #[error(
+        "Expected witness {witness_index} to be {expected} at opcode {opcode_index} but it was {}",
+        .actual.map_or("unassigned".to_string(), |actual| actual.to_string())
+    )]
+    CheckpointAssertionFailed {
+        opcode_index: usize,
+        witness_index: u32,
+        expected: FieldElement,
+        actual: Option<FieldElement>,
+    },
+
+    /// A witness was assigned a value its predicate doesn't allow. `opcode_index` is `None` when
+    /// the value was given in the initial witness.
+    #[error(
+        "Witness {witness_index} was assigned {value} {} but must be {predicate}",
+        assigned_by(*.opcode_index)
+    )]
+    WitnessConstraintViolated {
+        opcode_index: Option<usize>,
+        witness_index: u32,
+        value: FieldElement,
+        predicate: ValuePredicate,
+    },
+
+    /// Witnesses of the circuit were left without a value once it was solved, so the circuit's
+    /// constraints don't determine them. Witnesses are listed in ascending order.
+    #[error(
+        "Circuit is under-constrained: {} never assigned a value",
+        free_witness_list(.free_witnesses)
+    )]
+    UnderConstrained { free_witnesses: Vec<Witness> },
+
+    /// A foreign call returned different results when made twice with the same inputs
+    #[error(
+        "Foreign call `{name}` is not deterministic: repeating it returned a different result"
+    )]
+    NonDeterministicForeignCall { name: String },
+
+    /// The condition of a `host_assert` call was false. This is only checked by the host
+    /// executing the program and is never enforced by the circuit.
+    #[error("Host assertion failed: {0}")]
+    HostAssertionFailed(String),
+
+    /// The result of an integer operation failed the range constraint on its type
+    #[error(
+        "Integer overflow in opcode {opcode_index}: {operation} does not fit in {bit_size} bits"
+    )]
+    ArithmeticOverflow { opcode_index: usize, bit_size: u32, operation: OverflowingOperation },
+
+    /// A `std::merkle_open` call named a root which isn't the root of any tree given to execution
+    #[error("No Merkle tree was provided with root {0}")]
+    UnknownMerkleRoot(FieldElement),
+
+    /// A `std::merkle_open` call asked for a leaf beyond the end of its tree
+    #[error("Cannot open leaf {index} of a Merkle tree with {leaf_count} leaves")]
+    MerkleIndexOutOfRange { index: FieldElement, leaf_count: usize },
+
+    /// Error while spilling witnesses to, or restoring them from, their backing storage
+    #[error(transparent)]
+    WitnessBackingError(#[from] WitnessBackingError),
+}
+
+impl NargoError {
+    /// Formats this error like its [`Display`](std::fmt::Display) impl, but with any field
+    /// elements within it formatted according to `mode`.
+    pub fn display_with(&self, mode: FieldDisplayMode) -> String {
+        match self {
+            NargoError::CheckpointAssertionFailed {
+                opcode_index,
+                witness_index,
+                expected,
+                actual,
+            } => {
+                let expected = mode.format(*expected);
+                let actual = actual.map_or("unassigned".to_string(), |actual| mode.format(actual));
+                format!(
+                    "Expected witness {witness_index} to be {expected} at opcode {opcode_index} but it was {actual}"
+                )
+            }
+            NargoError::WitnessConstraintViolated {
+                opcode_index,
+                witness_index,
+                value,
+                predicate,
+            } => {
+                let value = mode.format(*value);
+                let predicate = predicate.display_with(mode);
+                let assigned_by = assigned_by(*opcode_index);
+                format!(
+                    "Witness {witness_index} was assigned {value} {assigned_by} but must be {predicate}"
+                )
+            }
+            NargoError::ArithmeticOverflow { opcode_index, bit_size, operation } => {
+                let operation = operation.display_with(mode);
+                format!(
+                    "Integer overflow in opcode {opcode_index}: {operation} does not fit in {bit_size} bits"
+                )
+            }
+            NargoError::UnknownMerkleRoot(root) => {
+                format!("No Merkle tree was provided with root {}", mode.format(*root))
+            }
+            NargoError::MerkleIndexOutOfRange { index, leaf_count } => {
+                let index = mode.format(*index);
+                format!("Cannot open leaf {index} of a Merkle tree with {leaf_count} leaves")
+            }
+            _ => self.to_string(),
+        }
+    }
+}
+
+fn assigned_by(opcode_index: Option<usize>) -> String {
+    match opcode_index {
+        Some(opcode_index) => format!("by opcode {opcode_index}"),
+        None => "in the initial witness".to_string(),
+    }
+}
+
+fn free_witness_list(free_witnesses: &[Witness]) -> String {
+    let indices: Vec<String> =
+        free_witnesses.iter().map(|witness| witness.witness_index().to_string()).collect();
+    match indices.as_slice() {
+        [index] => format!("witness {index} was"),
+        indices => format!("witnesses {} were", indices.join(", ")),
+    }
+}
+
+/// How field elements are formatted when displaying an error.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FieldDisplayMode {
+    /// As by the [`Display`](std::fmt::Display) impl of [`FieldElement`], which gives values in
+    /// decimal
+    #[default]
+    Decimal,
+    /// In hexadecimal with a `0x` prefix and without leading zeros, as in `0x1f`
+    Hex,
+}
+
+impl FieldDisplayMode {
+    pub fn format(self, field: FieldElement) -> String {
+        match self {
+            FieldDisplayMode::Decimal => field.to_string(),
+            FieldDisplayMode::Hex => {
+                let hex = field.to_hex();
+                let digits = hex.trim_start_matches('0');
+                format!("0x{}", if digits.is_empty() { "0" } else { digits })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+
+    use super::{FieldDisplayMode, NargoError};
+    use crate::ops::OverflowingOperation;
+
+    #[test]
+    fn fields_are_displayed_in_the_chosen_mode() {
+        let hex = FieldDisplayMode::Hex;
+        assert_eq!(hex.format(FieldElement::from(0xdead_beef_u128)), "0xdeadbeef");
+        assert_eq!(hex.format(FieldElement::zero()), "0x0");
+
+        let error = NargoError::UnknownMerkleRoot(FieldElement::from(255_u128));
+        assert_eq!(error.display_with(hex), "No Merkle tree was provided with root 0xff");
+        assert_eq!(error.display_with(FieldDisplayMode::Decimal), error.to_string());
+
+        let operation = OverflowingOperation::Add {
+            lhs: FieldElement::from(0xff_u128),
+            rhs: FieldElement::from(1_u128),
+        };
+        let error = NargoError::ArithmeticOverflow { opcode_index: 3, bit_size: 8, operation };
+        assert_eq!(
+            error.display_with(hex),
+            "Integer overflow in opcode 3: 0xff + 0x1 does not fit in 8 bits"
+        );
+        assert_eq!(error.display_with(FieldDisplayMode::Decimal), error.to_string());
+    }
 }
@@ -24,7 +24,7 @@ use noirc_frontend::{
 };
 use package::{Dependency, Package};
 
-pub use self::errors::NargoError;
+pub use self::errors::{FieldDisplayMode, NargoError};
 
 pub fn prepare_dependencies(
     context: &mut Context,
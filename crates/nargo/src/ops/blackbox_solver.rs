@@ -0,0 +1,61 @@
+use acvm::{BlackBoxFunctionSolver, BlackBoxResolutionError, FieldElement};
+
+/// A [`BlackBoxFunctionSolver`] which first offers each black box function call to a user
+/// provided solver, falling back to an inner solver for any call it reports as unsupported.
+///
+/// This allows adding support for new black box functions (or overriding existing ones)
+/// without having to reimplement the solvers for the standard black box functions.
+#[derive(Debug, Clone, Default)]
+pub struct CompositeBlackBoxSolver<U, D> {
+    user_solver: U,
+    fallback_solver: D,
+}
+
+impl<U: BlackBoxFunctionSolver, D: BlackBoxFunctionSolver> CompositeBlackBoxSolver<U, D> {
+    pub fn new(user_solver: U, fallback_solver: D) -> Self {
+        CompositeBlackBoxSolver { user_solver, fallback_solver }
+    }
+
+    /// Calls `solve` on the user solver, retrying with the fallback solver if the user solver
+    /// does not support the black box function. Any other error is returned as is.
+    fn solve_with<T>(
+        &self,
+        solve: impl Fn(&dyn BlackBoxFunctionSolver) -> Result<T, BlackBoxResolutionError>,
+    ) -> Result<T, BlackBoxResolutionError> {
+        match solve(&self.user_solver) {
+            Err(BlackBoxResolutionError::Unsupported(_)) => solve(&self.fallback_solver),
+            result => result,
+        }
+    }
+}
+
+impl<U: BlackBoxFunctionSolver, D: BlackBoxFunctionSolver> BlackBoxFunctionSolver
+    for CompositeBlackBoxSolver<U, D>
+{
+    fn schnorr_verify(
+        &self,
+        public_key_x: &FieldElement,
+        public_key_y: &FieldElement,
+        signature: &[u8],
+        message: &[u8],
+    ) -> Result<bool, BlackBoxResolutionError> {
+        self.solve_with(|solver| {
+            solver.schnorr_verify(public_key_x, public_key_y, signature, message)
+        })
+    }
+
+    fn pedersen(
+        &self,
+        inputs: &[FieldElement],
+        domain_separator: u32,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        self.solve_with(|solver| solver.pedersen(inputs, domain_separator))
+    }
+
+    fn fixed_base_scalar_mul(
+        &self,
+        input: &FieldElement,
+    ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+        self.solve_with(|solver| solver.fixed_base_scalar_mul(input))
+    }
+}
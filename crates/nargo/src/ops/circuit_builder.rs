@@ -0,0 +1,188 @@
+//! Construction of circuits directly in Rust, for exercising the executor without compiling a
+//! Noir program.
+
+use std::collections::BTreeSet;
+
+use acvm::acir::circuit::brillig::BrilligOutputs;
+use acvm::acir::circuit::opcodes::BlackBoxFuncCall;
+use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+use acvm::acir::native_types::{Expression, Witness};
+use thiserror::Error;
+
+use super::transcript::opcode_input_witnesses;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CircuitBuilderError {
+    #[error(
+        "Opcode {opcode_index} uses witness {witness_index} which was not created by the builder"
+    )]
+    UnknownWitness { opcode_index: usize, witness_index: u32 },
+
+    #[error("Witness {0} is returned but was not created by the builder")]
+    UnknownReturnValue(u32),
+}
+
+/// Builds a [`Circuit`] one opcode at a time, ready to be passed to
+/// [`execute_circuit`][super::execute_circuit].
+///
+/// Witnesses are created by the builder, either as inputs to the circuit or as intermediate
+/// values, and are numbered from 1 in the order they are created. [`CircuitBuilder::build`]
+/// checks that every witness used by the circuit was created this way.
+#[derive(Debug, Default)]
+pub struct CircuitBuilder {
+    current_witness_index: u32,
+    opcodes: Vec<Opcode>,
+    private_parameters: BTreeSet<Witness>,
+    public_parameters: BTreeSet<Witness>,
+    return_values: BTreeSet<Witness>,
+}
+
+impl CircuitBuilder {
+    pub fn new() -> CircuitBuilder {
+        CircuitBuilder::default()
+    }
+
+    /// Creates a witness which isn't an input to the circuit, to be solved by its opcodes.
+    pub fn new_witness(&mut self) -> Witness {
+        self.current_witness_index += 1;
+        Witness(self.current_witness_index)
+    }
+
+    /// Creates a witness which is a private input to the circuit.
+    pub fn private_input(&mut self) -> Witness {
+        let witness = self.new_witness();
+        self.private_parameters.insert(witness);
+        witness
+    }
+
+    /// Creates a witness which is a public input to the circuit.
+    pub fn public_input(&mut self) -> Witness {
+        let witness = self.new_witness();
+        self.public_parameters.insert(witness);
+        witness
+    }
+
+    /// Declares `witness` as one of the values returned by the circuit.
+    pub fn return_value(&mut self, witness: Witness) {
+        self.return_values.insert(witness);
+    }
+
+    /// Adds an arithmetic opcode constraining `expr` to equal zero.
+    pub fn assert_zero(&mut self, expr: Expression) {
+        self.opcodes.push(Opcode::Arithmetic(expr));
+    }
+
+    /// Adds a call to a black box function.
+    pub fn black_box_call(&mut self, call: BlackBoxFuncCall) {
+        self.opcodes.push(Opcode::BlackBoxFuncCall(call));
+    }
+
+    /// Adds any other opcode, such as a Brillig opcode.
+    pub fn opcode(&mut self, opcode: Opcode) {
+        self.opcodes.push(opcode);
+    }
+
+    /// Returns the circuit built, failing if it uses any witness which wasn't created by the
+    /// builder.
+    pub fn build(self) -> Result<Circuit, CircuitBuilderError> {
+        let is_known =
+            |witness: &Witness| (1..=self.current_witness_index).contains(&witness.witness_index());
+
+        for (opcode_index, opcode) in self.opcodes.iter().enumerate() {
+            if let Some(witness) =
+                opcode_witnesses(opcode).iter().find(|witness| !is_known(witness))
+            {
+                let witness_index = witness.witness_index();
+                return Err(CircuitBuilderError::UnknownWitness { opcode_index, witness_index });
+            }
+        }
+        if let Some(witness) = self.return_values.iter().find(|witness| !is_known(witness)) {
+            return Err(CircuitBuilderError::UnknownReturnValue(witness.witness_index()));
+        }
+
+        Ok(Circuit {
+            current_witness_index: self.current_witness_index,
+            opcodes: self.opcodes,
+            private_parameters: self.private_parameters,
+            public_parameters: PublicInputs(self.public_parameters),
+            return_values: PublicInputs(self.return_values),
+        })
+    }
+}
+
+/// Returns the witnesses read or assigned by `opcode`. As with the witnesses read, those
+/// assigned are only found for arithmetic, Brillig and black box function opcodes.
+fn opcode_witnesses(opcode: &Opcode) -> BTreeSet<Witness> {
+    let mut witnesses = opcode_input_witnesses(opcode);
+    match opcode {
+        Opcode::Brillig(brillig) => {
+            for output in &brillig.outputs {
+                match output {
+                    BrilligOutputs::Simple(witness) => {
+                        witnesses.insert(*witness);
+                    }
+                    BrilligOutputs::Array(outputs) => witnesses.extend(outputs),
+                }
+            }
+        }
+        Opcode::BlackBoxFuncCall(call) => witnesses.extend(call.get_outputs_vec()),
+        _ => (),
+    }
+    witnesses
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+    use acvm::acir::native_types::{Expression, Witness};
+    use acvm::FieldElement;
+
+    use super::{CircuitBuilder, CircuitBuilderError};
+
+    /// x * y - z == 0
+    fn product(x: Witness, y: Witness, z: Witness) -> Expression {
+        Expression {
+            mul_terms: vec![(FieldElement::one(), x, y)],
+            linear_combinations: vec![(-FieldElement::one(), z)],
+            q_c: FieldElement::zero(),
+        }
+    }
+
+    #[test]
+    fn builds_circuits() {
+        let mut builder = CircuitBuilder::new();
+        let x = builder.private_input();
+        let y = builder.public_input();
+        let z = builder.new_witness();
+        builder.assert_zero(product(x, y, z));
+        builder.return_value(z);
+
+        let expected = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![Opcode::Arithmetic(product(Witness(1), Witness(2), Witness(3)))],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::from([Witness(2)])),
+            return_values: PublicInputs(BTreeSet::from([Witness(3)])),
+        };
+        assert_eq!(builder.build(), Ok(expected));
+    }
+
+    #[test]
+    fn rejects_unknown_witnesses() {
+        let mut builder = CircuitBuilder::new();
+        let x = builder.private_input();
+        builder.assert_zero(Expression::from(x));
+        builder.assert_zero(product(x, x, Witness(5)));
+        assert_eq!(
+            builder.build(),
+            Err(CircuitBuilderError::UnknownWitness { opcode_index: 1, witness_index: 5 })
+        );
+
+        let mut builder = CircuitBuilder::new();
+        builder.private_input();
+        builder.return_value(Witness(2));
+        assert_eq!(builder.build(), Err(CircuitBuilderError::UnknownReturnValue(2)));
+    }
+}
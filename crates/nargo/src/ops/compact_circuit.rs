@@ -0,0 +1,145 @@
+//! A versioned binary format for precompiled circuits.
+//!
+//! Circuits in this format can be loaded and passed straight to
+//! [`execute_circuit`][super::execute_circuit] without recompiling the program they came from.
+
+use std::path::Path;
+
+use acvm::acir::circuit::Circuit;
+use thiserror::Error;
+
+/// The bytes every compact circuit begins with.
+const MAGIC: &[u8; 4] = b"NCIR";
+
+/// The version of the format written by [`CompactCircuit::serialize_compact`]. This must be
+/// bumped whenever a change to ACIR alters the serialized circuit.
+pub const COMPACT_CIRCUIT_VERSION: u16 = 1;
+
+const HEADER_LENGTH: usize = MAGIC.len() + std::mem::size_of::<u16>();
+
+#[derive(Debug, Error)]
+pub enum CompactCircuitError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Not a compact circuit, the header is missing")]
+    MissingHeader,
+
+    #[error("Compact circuit has format version {found} but version {expected} is required. Recompile the circuit with this version of nargo")]
+    VersionMismatch { found: u16, expected: u16 },
+}
+
+/// Conversion of a [`Circuit`] to and from the compact binary format.
+///
+/// The format is the magic bytes `NCIR`, followed by the format version as a little endian
+/// `u16`, followed by the circuit serialized with [`Circuit::write`].
+pub trait CompactCircuit: Sized {
+    fn serialize_compact(&self) -> Vec<u8>;
+
+    fn deserialize_compact(bytes: &[u8]) -> Result<Self, CompactCircuitError>;
+}
+
+impl CompactCircuit for Circuit {
+    fn serialize_compact(&self) -> Vec<u8> {
+        let mut bytes = Vec::from(*MAGIC);
+        bytes.extend(COMPACT_CIRCUIT_VERSION.to_le_bytes());
+        self.write(&mut bytes).expect("writing to a vector cannot fail");
+        bytes
+    }
+
+    fn deserialize_compact(bytes: &[u8]) -> Result<Self, CompactCircuitError> {
+        if bytes.len() < HEADER_LENGTH || !bytes.starts_with(MAGIC) {
+            return Err(CompactCircuitError::MissingHeader);
+        }
+
+        let found = u16::from_le_bytes([bytes[MAGIC.len()], bytes[MAGIC.len() + 1]]);
+        if found != COMPACT_CIRCUIT_VERSION {
+            return Err(CompactCircuitError::VersionMismatch {
+                found,
+                expected: COMPACT_CIRCUIT_VERSION,
+            });
+        }
+
+        Ok(Circuit::read(&bytes[HEADER_LENGTH..])?)
+    }
+}
+
+/// Loads a circuit written in the compact binary format from the file at `path`.
+pub fn load_compact_circuit(path: &Path) -> Result<Circuit, CompactCircuitError> {
+    let bytes = std::fs::read(path)?;
+    Circuit::deserialize_compact(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use acvm::acir::brillig::{Opcode as BrilligOpcode, RegisterIndex, RegisterOrMemory};
+    use acvm::acir::circuit::brillig::{Brillig, BrilligInputs, BrilligOutputs};
+    use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+    use acvm::acir::native_types::{Expression, Witness};
+    use acvm::FieldElement;
+
+    use super::{CompactCircuit, CompactCircuitError, COMPACT_CIRCUIT_VERSION, MAGIC};
+
+    /// A circuit which asks an oracle for the inverse of `w1` and constrains the result.
+    fn inverse_circuit() -> Circuit {
+        let (x, inverse) = (Witness(1), Witness(2));
+        let register = RegisterIndex::from(0);
+
+        let brillig = Opcode::Brillig(Brillig {
+            inputs: vec![BrilligInputs::Single(Expression::from(x))],
+            outputs: vec![BrilligOutputs::Simple(inverse)],
+            foreign_call_results: vec![],
+            bytecode: vec![
+                BrilligOpcode::ForeignCall {
+                    function: "invert".to_string(),
+                    destinations: vec![RegisterOrMemory::RegisterIndex(register)],
+                    inputs: vec![RegisterOrMemory::RegisterIndex(register)],
+                },
+                BrilligOpcode::Stop,
+            ],
+            predicate: None,
+        });
+
+        // x * inverse - 1 == 0
+        let constraint = Expression {
+            mul_terms: vec![(FieldElement::one(), x, inverse)],
+            linear_combinations: vec![],
+            q_c: -FieldElement::one(),
+        };
+
+        Circuit {
+            current_witness_index: 2,
+            opcodes: vec![brillig, Opcode::Arithmetic(constraint)],
+            private_parameters: BTreeSet::from([x]),
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs(BTreeSet::from([inverse])),
+        }
+    }
+
+    #[test]
+    fn compact_round_trip() {
+        let circuit = inverse_circuit();
+        let bytes = circuit.serialize_compact();
+        assert!(bytes.starts_with(MAGIC));
+        assert_eq!(Circuit::deserialize_compact(&bytes).unwrap(), circuit);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut bytes = inverse_circuit().serialize_compact();
+        bytes[MAGIC.len()..MAGIC.len() + 2]
+            .copy_from_slice(&(COMPACT_CIRCUIT_VERSION + 1).to_le_bytes());
+
+        let error = Circuit::deserialize_compact(&bytes).unwrap_err();
+        assert!(matches!(
+            error,
+            CompactCircuitError::VersionMismatch { found, expected: COMPACT_CIRCUIT_VERSION }
+                if found == COMPACT_CIRCUIT_VERSION + 1
+        ));
+
+        let error = Circuit::deserialize_compact(b"NC").unwrap_err();
+        assert!(matches!(error, CompactCircuitError::MissingHeader));
+    }
+}
@@ -1,35 +1,2563 @@
-use acvm::pwg::{ACVMStatus, ACVM};
-use acvm::BlackBoxFunctionSolver;
-use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
+//! Execution of ACIR circuits by the ACVM.
+//!
+//! The field a circuit is defined over isn't a type parameter of [`Circuit`], [`WitnessMap`] or
+//! [`BlackBoxFunctionSolver`]. These all use [`FieldElement`], which `acvm` fixes when it is built
+//! by its choice of the `bn254` (the default) or `bls12_381` feature, so a circuit targeting
+//! another curve is executed by building nargo against `acvm` with that feature. Executing with
+//! several fields within the same build would require `acvm` itself to be generic over the field.
+//!
+//! Nothing in this module depends on which field is used, and neither does any foreign call
+//! natively supported by nargo other than that of `std::ec_add`, which adds points on the Baby
+//! Jubjub curve and so fails unless the field is the BN254 scalar field. Hosts which represent
+//! field elements differently can convert them with a [`FieldCodec`].
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::iter::Peekable;
+use std::sync::mpsc::SyncSender;
+
+use acvm::acir::brillig::{ForeignCallResult, Opcode as BrilligOpcode, Value};
+use acvm::acir::circuit::brillig::{Brillig, BrilligInputs, BrilligOutputs};
+use acvm::acir::circuit::directives::Directive;
+use acvm::acir::circuit::opcodes::BlackBoxFuncCall;
+use acvm::acir::circuit::{Opcode, OpcodeLocation};
+use acvm::acir::native_types::Expression;
+use acvm::brillig_vm::{Registers, VMStatus, VM};
+use acvm::pwg::{ACVMStatus, ErrorLocation, ForeignCallWaitInfo, OpcodeResolutionError, ACVM};
+use acvm::{
+    acir::circuit::{Circuit, PublicInputs},
+    acir::native_types::{Witness, WitnessMap},
+};
+use acvm::{BlackBoxFunctionSolver, FieldElement};
+
+use iter_extended::vecmap;
+use noirc_printable_type::{ForeignCallError, PrintEvent};
 
 use crate::NargoError;
 
-use super::foreign_calls::ForeignCall;
+use super::field_codec::{FieldCodec, HostForeignCallOutput};
+use super::foreign_call_policy::ForeignCallPolicy;
+use super::foreign_call_schema::ForeignCallSchema;
+use super::foreign_calls::{ForeignCall, DEFAULT_TIMESTAMP};
+use super::merkle_tree::MerkleTree;
+use super::overflow::overflowing_operation;
+use super::transcript::{
+    add_expression_witnesses, opcode_input_witnesses, ExecutionTranscript, OpcodeTranscript,
+};
+use super::value_predicate::ValuePredicate;
+use super::witness_backing::WitnessBacking;
+use super::witness_log::WitnessLog;
+
+/// Options controlling how [`execute_circuit_with_options`] executes a circuit. The default
+/// options execute it exactly as [`execute_circuit`] does, without showing its output.
+#[derive(Debug, Clone)]
+pub struct ExecutionOptions {
+    /// Whether to print the output of `println` calls
+    pub show_output: bool,
+    /// The value returned by each call to `std::now`. The timestamp is supplied by
+    /// the host and is never constrained, so it only affects execution and can't be relied upon
+    /// by a proof to reflect the time at which it was created.
+    pub timestamp: u64,
+    /// Whether to report the failure of a range constraint on the result of an integer
+    /// operation as [`NargoError::ArithmeticOverflow`], giving the operation and the values of
+    /// its operands where they can be recovered.
+    ///
+    /// The operation is found by looking for the arithmetic opcode which assigned the witness
+    /// range constrained, see [`OverflowingOperation`][super::OverflowingOperation]. Range
+    /// constraints which fail on other witnesses, such as integer inputs to the circuit, are
+    /// reported with the witness's value.
+    pub overflow_diagnostics: bool,
+    /// The index of an opcode at which to stop, returning the witness as solved by the opcodes
+    /// before it. This is intended for stepping through a circuit while debugging. If the
+    /// circuit is fully solved before reaching this opcode then the final witness is returned.
+    pub stop_at: Option<usize>,
+    /// Whether to return each witness in the order in which it was first assigned a value, see
+    /// [`ExecutionOutput::assignment_order`]. Finding these requires checking the witness map
+    /// after solving each opcode, so this slows down execution.
+    pub record_assignment_order: bool,
+    /// The number of Brillig instructions the Brillig opcodes of the circuit may execute in
+    /// total before execution fails with [`NargoError::UnconstrainedFuelExhausted`], if
+    /// limited. Constrained opcodes don't consume any fuel.
+    ///
+    /// The ACVM runs each Brillig opcode to completion, so each one is first run in a separate
+    /// Brillig VM which counts its steps. Any foreign calls it makes are resolved once and the
+    /// results replayed when the ACVM then solves the opcode, relying on the calls being issued
+    /// in the same order. This roughly doubles the time spent executing unconstrained code.
+    pub fuel: Option<usize>,
+    /// Which foreign calls may be made, failing with [`NargoError::ForeignCallDisabled`] as soon
+    /// as a call is made to a function which the policy disables.
+    pub policy: ForeignCallPolicy,
+    /// The schema against which the inputs of each foreign call are checked before it is
+    /// executed, failing with [`NargoError::ForeignCallSchemaViolation`] if they don't match.
+    pub schema: ForeignCallSchema,
+    /// Checkpoints to check while executing the circuit.
+    ///
+    /// A checkpoint `(opcode_index, witness, expected_value)` asserts that `witness` has been
+    /// assigned `expected_value` once the opcodes before `opcode_index` have been solved, in the
+    /// same way as [`ExecutionOptions::stop_at`] stops at that opcode. Checkpoints at or past
+    /// the end of the circuit are checked against the final witness, and those past the opcode
+    /// at which execution stops aren't checked. Execution fails with
+    /// [`NargoError::CheckpointAssertionFailed`] at the first checkpoint which doesn't hold.
+    pub checkpoints: Vec<(usize, Witness, FieldElement)>,
+    /// Whether to first propagate the values of the initial witness through the arithmetic
+    /// opcodes at the start of the circuit.
+    ///
+    /// Each of these opcodes which has at most one unknown witness, appearing linearly, is solved
+    /// in the same way as the ACVM would solve it and removed, so the ACVM only has to solve the
+    /// remaining opcodes. Propagation stops at the first opcode which isn't arithmetic or can't
+    /// be solved this way, including any constraint which doesn't hold, leaving the ACVM to
+    /// solve it and report any failure. Opcodes after it are never considered, as an earlier
+    /// opcode may assign the witnesses they use. Propagation also stops at the first checkpoint
+    /// and at [`ExecutionOptions::stop_at`]. The solved witness is therefore identical to that
+    /// solved without the prepass, and errors still give the index of the opcode in `circuit`.
+    pub prepass: bool,
+    /// Whether to resolve each foreign call which nargo doesn't support natively twice, failing
+    /// with [`NargoError::NonDeterministicForeignCall`] if the two results differ. Foreign calls
+    /// natively supported by nargo are deterministic so are only made once.
+    ///
+    /// This is meant for testing oracles, such as one which accidentally returns time-dependent
+    /// data, rather than for executing circuits in production, as it doubles the cost of every
+    /// call.
+    pub check_determinism: bool,
+    /// Whether to return an [`ExecutionTranscript`] of the witnesses read and assigned by each
+    /// opcode, see [`ExecutionOutput::transcript`]. This requires checking the witness map
+    /// before and after solving each opcode, so slows down execution.
+    pub record_transcript: bool,
+    /// Whether to return a [`WitnessLog`] of the witnesses assigned by each opcode, see
+    /// [`ExecutionOutput::witness_log`]. Unlike an [`ExecutionTranscript`], the log records only
+    /// assignments, along with the initial witness, so that the full assignment can be replayed
+    /// from it. This requires checking the witness map after solving each opcode, so slows down
+    /// execution.
+    pub record_witness_log: bool,
+    /// Predicates on the values of witnesses, each of which must hold as soon as its witness is
+    /// assigned. A witness may be given several predicates, all of which must hold.
+    ///
+    /// Witnesses are only assigned once, so each predicate is checked once: against the initial
+    /// witness, or after solving the opcode which assigns its witness. Execution fails with
+    /// [`NargoError::WitnessConstraintViolated`] at the first predicate which doesn't hold, even
+    /// if the circuit's constraints would be satisfied. Predicates on witnesses which are never
+    /// assigned are not checked.
+    pub witness_predicates: Vec<(Witness, ValuePredicate)>,
+    /// Whether to fail with [`NargoError::UnderConstrained`] if any witness of the circuit is
+    /// left without a value once it has been solved.
+    ///
+    /// Such a witness is one which no opcode assigns, such as an input missing from the initial
+    /// witness which no opcode reads, or a witness index which is allocated but never used.
+    /// Provers typically assign these a default value, so the circuit's constraints don't depend
+    /// on them. This is meant for auditing circuits for under-constrained regions, as listing the
+    /// circuit's witnesses adds to the cost of execution. Circuits which stop early at
+    /// [`ExecutionOptions::stop_at`] aren't checked.
+    pub check_free_witnesses: bool,
+    /// The Merkle trees opened by `std::merkle_open(root, index)` calls, each of which returns the
+    /// sibling path of leaf `index` in whichever tree has the given root. Execution fails if no
+    /// tree has that root or if the tree has no leaf `index`.
+    pub merkle_trees: Vec<MerkleTree>,
+}
+
+impl Default for ExecutionOptions {
+    fn default() -> Self {
+        ExecutionOptions {
+            show_output: false,
+            timestamp: DEFAULT_TIMESTAMP,
+            overflow_diagnostics: false,
+            stop_at: None,
+            record_assignment_order: false,
+            fuel: None,
+            policy: ForeignCallPolicy::default(),
+            schema: ForeignCallSchema::default(),
+            checkpoints: Vec::new(),
+            prepass: false,
+            check_determinism: false,
+            record_transcript: false,
+            record_witness_log: false,
+            witness_predicates: Vec::new(),
+            check_free_witnesses: false,
+            merkle_trees: Vec::new(),
+        }
+    }
+}
+
+/// The result of executing a circuit with [`execute_circuit_with_options`].
+#[derive(Debug, Clone)]
+pub struct ExecutionOutput {
+    /// The solved witness
+    pub witness: WitnessMap,
+    /// Each witness in the order in which it was first assigned a value, if
+    /// [`ExecutionOptions::record_assignment_order`] is set.
+    ///
+    /// Witnesses given in the initial witness come first, followed by the witnesses assigned by
+    /// each opcode in turn, with witnesses assigned by the same opcode ordered by index.
+    pub assignment_order: Option<Vec<Witness>>,
+    /// The witnesses read and assigned by each opcode, in the order the opcodes were solved, if
+    /// [`ExecutionOptions::record_transcript`] is set. This includes any opcodes solved by the
+    /// prepass.
+    pub transcript: Option<ExecutionTranscript>,
+    /// The witnesses assigned by each opcode, in the order the opcodes were solved, if
+    /// [`ExecutionOptions::record_witness_log`] is set. This includes any opcodes solved by the
+    /// prepass.
+    pub witness_log: Option<WitnessLog>,
+    /// Counters describing the work done by the ACVM
+    pub stats: ExecutionStats,
+}
+
+/// Counters describing the work done by the ACVM while executing a circuit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionStats {
+    /// The number of times the ACVM attempted to solve an opcode. Opcodes which make foreign
+    /// calls are attempted once more after each call is resolved.
+    pub solve_iterations: usize,
+    /// The number of foreign calls resolved
+    pub foreign_calls: usize,
+    /// The number of opcodes solved
+    pub opcodes_solved: usize,
+}
 
+/// Executes `circuit`, resolving any foreign calls made by its Brillig opcodes along the way.
+///
+/// Foreign calls are issued deterministically: opcodes are solved in order, each Brillig opcode
+/// issues its calls in the order in which it executes them, and solving cannot continue until
+/// the pending call has been resolved. Executing the same circuit with the same initial witness
+/// therefore always issues the same sequence of foreign calls.
 pub fn execute_circuit<B: BlackBoxFunctionSolver>(
     blackbox_solver: &B,
     circuit: Circuit,
     initial_witness: WitnessMap,
     show_output: bool,
 ) -> Result<WitnessMap, NargoError> {
-    let mut acvm = ACVM::new(blackbox_solver, circuit.opcodes, initial_witness);
+    let options = ExecutionOptions { show_output, ..ExecutionOptions::default() };
+    let output = execute_circuit_with_options(blackbox_solver, circuit, initial_witness, &options)?;
+    Ok(output.witness)
+}
+
+/// Executes `circuit` like [`execute_circuit`], as configured by `options`.
+pub fn execute_circuit_with_options<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    circuit: Circuit,
+    initial_witness: WitnessMap,
+    options: &ExecutionOptions,
+) -> Result<ExecutionOutput, NargoError> {
+    let resolve = |foreign_call: &ForeignCallWaitInfo, _: usize, _: &WitnessMap| {
+        ForeignCall::execute_with_options(foreign_call, options)
+    };
+    solve(blackbox_solver, circuit, initial_witness, options, resolve)
+}
+
+/// Executes `circuit` like [`execute_circuit_with_options`], but moves witnesses which are no
+/// longer needed out of memory and into `backing` as it goes, restoring them once the circuit
+/// has been solved. This trades speed for a lower peak memory use when solving large circuits.
+///
+/// Each time another `spill_interval` opcodes have been solved, every witness which isn't read by
+/// the remaining opcodes, or checked by a remaining checkpoint, is spilled. The ACVM is then
+/// restarted on the remaining opcodes with the witnesses still needed. Directives other than
+/// `ToLeRadix` and ACIR memory opcodes read witnesses, or memory held by the ACVM, which aren't
+/// tracked, so no witnesses are spilled while any of these remain to be solved. The witnesses
+/// spilled are briefly held in memory alongside the rest of the witness map while being moved.
+///
+/// The solved witness, and the rest of the output, are identical to those of
+/// [`execute_circuit_with_options`].
+pub fn execute_circuit_spilling<B, W>(
+    blackbox_solver: &B,
+    circuit: Circuit,
+    initial_witness: WitnessMap,
+    options: &ExecutionOptions,
+    backing: &mut W,
+    spill_interval: usize,
+) -> Result<ExecutionOutput, NargoError>
+where
+    B: BlackBoxFunctionSolver,
+    W: WitnessBacking,
+{
+    let resolve = |foreign_call: &ForeignCallWaitInfo, _: usize, _: &WitnessMap| {
+        ForeignCall::execute_with_options(foreign_call, options)
+    };
+    let spill = Spill { backing, interval: spill_interval.max(1), solved: 0 };
+    solve_spilling(blackbox_solver, circuit, initial_witness, options, Some(spill), resolve)
+}
+
+/// Solves `circuit` as configured by `options`, passing each foreign call made to `resolve`
+/// along with the index of the opcode making it and the witness as solved by the opcodes before
+/// that one. This is the solve loop used by each way of executing a circuit.
+fn solve<B, R>(
+    blackbox_solver: &B,
+    circuit: Circuit,
+    initial_witness: WitnessMap,
+    options: &ExecutionOptions,
+    resolve: R,
+) -> Result<ExecutionOutput, NargoError>
+where
+    B: BlackBoxFunctionSolver,
+    R: FnMut(&ForeignCallWaitInfo, usize, &WitnessMap) -> Result<ForeignCallResult, NargoError>,
+{
+    solve_spilling(blackbox_solver, circuit, initial_witness, options, None, resolve)
+}
+
+/// Solves `circuit` like [`solve`], spilling witnesses to `spill` if given, see
+/// [`execute_circuit_spilling`].
+fn solve_spilling<B, R>(
+    blackbox_solver: &B,
+    mut circuit: Circuit,
+    mut initial_witness: WitnessMap,
+    options: &ExecutionOptions,
+    mut spill: Option<Spill<'_>>,
+    mut resolve: R,
+) -> Result<ExecutionOutput, NargoError>
+where
+    B: BlackBoxFunctionSolver,
+    R: FnMut(&ForeignCallWaitInfo, usize, &WitnessMap) -> Result<ForeignCallResult, NargoError>,
+{
+    let mut resolve =
+        |foreign_call: &ForeignCallWaitInfo, opcode_index: usize, witness_map: &WitnessMap| {
+            check_foreign_call(foreign_call, options)?;
+            let result = resolve(foreign_call, opcode_index, witness_map)?;
+
+            let is_native = ForeignCall::lookup(&foreign_call.function).is_some();
+            if options.check_determinism && !is_native {
+                let repeated_result = resolve(foreign_call, opcode_index, witness_map)?;
+                if repeated_result != result {
+                    let name = foreign_call.function.clone();
+                    return Err(NargoError::NonDeterministicForeignCall { name });
+                }
+            }
+            Ok(result)
+        };
+
+    let mut recorder =
+        AssignmentRecorder::new(options, circuit.current_witness_index, &initial_witness);
+    let mut predicates = WitnessPredicates(options.witness_predicates.clone());
+    predicates.check(None, &initial_witness)?;
+    let witnesses = options.check_free_witnesses.then(|| circuit_witnesses(&circuit));
+
+    let num_opcodes = circuit.opcodes.len();
+    let stop_at = options.stop_at.map_or(num_opcodes, |stop_at| stop_at.min(num_opcodes));
+    // The ACVM takes ownership of the opcodes, so keep a copy for the options which inspect them
+    let inspects_opcodes = options.overflow_diagnostics
+        || options.fuel.is_some()
+        || options.record_transcript
+        || spill.is_some();
+    let opcodes = inspects_opcodes.then(|| circuit.opcodes.clone());
+    // Witnesses are only spilled once every remaining opcode has reads which are tracked
+    let spill_from = circuit
+        .opcodes
+        .iter()
+        .rposition(|opcode| witnesses_read(opcode).is_none())
+        .map_or(0, |opcode_index| opcode_index + 1);
+    let mut fuel_meter = options.fuel.map(FuelMeter::new);
+    let mut checkpoints = Checkpoints::new(&options.checkpoints);
+
+    // The ACVM doesn't see the opcodes solved by the prepass, or before the last spill, so its
+    // instruction pointer is offset from the index of the opcode it is solving
+    let mut offset = if options.prepass {
+        // Stop before the first checkpoint, which must see the witness as solved by the ACVM
+        let limit = options.checkpoints.iter().map(|(index, ..)| *index).fold(stop_at, usize::min);
+        let replays_assignments = recorder.is_some() || !predicates.0.is_empty();
+        let unpropagated_witness = replays_assignments.then(|| initial_witness.clone());
+        let assigned = propagate_constants(&circuit.opcodes, &mut initial_witness, limit);
+        if let Some(mut witness_map) = unpropagated_witness {
+            // Replay the assignments one opcode at a time, as the ACVM would have made them
+            for (opcode_index, witness) in assigned.iter().enumerate() {
+                let inputs = recorder.as_ref().map_or_else(BTreeMap::new, |recorder| {
+                    recorder.inputs(&circuit.opcodes[opcode_index], &witness_map)
+                });
+                if let Some(witness) = witness {
+                    witness_map.insert(*witness, initial_witness[witness]);
+                }
+                if let Some(recorder) = &mut recorder {
+                    recorder.record_opcode(opcode_index, inputs, &witness_map);
+                }
+                predicates.check(Some(opcode_index), &witness_map)?;
+            }
+        }
+        assigned.len()
+    } else {
+        0
+    };
+    let remaining_opcodes = circuit.opcodes.split_off(offset);
+    let mut acvm = ACVM::new(blackbox_solver, remaining_opcodes, initial_witness);
+
+    // The instruction pointer only moves past the last opcode once the circuit is solved
+    let mut stats = ExecutionStats::default();
+    while offset + acvm.instruction_pointer() < stop_at {
+        let opcode_index = offset + acvm.instruction_pointer();
+        checkpoints.check_until(opcode_index, acvm.witness_map())?;
+        let inputs = match (&recorder, &opcodes) {
+            (Some(recorder), Some(opcodes)) => {
+                recorder.inputs(&opcodes[opcode_index], acvm.witness_map())
+            }
+            _ => BTreeMap::new(),
+        };
+
+        if let (Some(fuel_meter), Some(opcodes)) = (&mut fuel_meter, &opcodes) {
+            fuel_meter.meter(
+                blackbox_solver,
+                opcodes,
+                opcode_index,
+                acvm.witness_map(),
+                &mut resolve,
+            )?;
+        }
+
+        stats.solve_iterations += 1;
+        match acvm.solve_opcode() {
+            ACVMStatus::Solved | ACVMStatus::InProgress => (),
+            ACVMStatus::Failure(error) => {
+                let error = offset_error_location(error, offset);
+                let overflow = match (&opcodes, &mut spill) {
+                    // Operands of the overflowing operation may have been spilled
+                    (Some(opcodes), Some(spill)) => {
+                        let witness_map = spill.restore(acvm.witness_map().clone())?;
+                        overflow_error(opcodes, &witness_map, &error)
+                    }
+                    (Some(opcodes), None) => overflow_error(opcodes, acvm.witness_map(), &error),
+                    (None, _) => None,
+                };
+                return Err(overflow.unwrap_or_else(|| error.into()));
+            }
+            ACVMStatus::RequiresForeignCall(foreign_call) => {
+                let foreign_call_result = match fuel_meter.as_mut().and_then(FuelMeter::replay) {
+                    Some(result) => result,
+                    None => resolve(&foreign_call, opcode_index, acvm.witness_map())?,
+                };
+                acvm.resolve_pending_foreign_call(foreign_call_result);
+                stats.foreign_calls += 1;
+            }
+        }
+        // Opcodes making foreign calls are solved once all of their calls are resolved
+        if offset + acvm.instruction_pointer() > opcode_index {
+            stats.opcodes_solved += 1;
+            if let Some(recorder) = &mut recorder {
+                recorder.record_opcode(opcode_index, inputs, acvm.witness_map());
+            }
+            predicates.check(Some(opcode_index), acvm.witness_map())?;
+
+            if let (Some(spill), Some(opcodes)) = (&mut spill, &opcodes) {
+                spill.solved += 1;
+                let next_index = offset + acvm.instruction_pointer();
+                if spill.solved >= spill.interval
+                    && spill_from <= next_index
+                    && next_index < stop_at
+                {
+                    let remaining_opcodes = &opcodes[next_index..];
+                    let live_witness =
+                        spill.spill(remaining_opcodes, &checkpoints, acvm.witness_map())?;
+                    acvm = ACVM::new(blackbox_solver, remaining_opcodes.to_vec(), live_witness);
+                    offset = next_index;
+                }
+            }
+        }
+    }
+
+    let witness = if offset + acvm.instruction_pointer() == num_opcodes {
+        let mut witness = acvm.finalize();
+        if let Some(spill) = &mut spill {
+            witness = spill.restore(witness)?;
+        }
+        checkpoints.check_until(usize::MAX, &witness)?;
+        let free_witnesses: Vec<Witness> = witnesses
+            .into_iter()
+            .flatten()
+            .filter(|unassigned| !witness.contains_key(unassigned))
+            .collect();
+        if !free_witnesses.is_empty() {
+            return Err(NargoError::UnderConstrained { free_witnesses });
+        }
+        witness
+    } else {
+        let mut witness = acvm.witness_map().clone();
+        if let Some(spill) = &mut spill {
+            witness = spill.restore(witness)?;
+        }
+        checkpoints.check_until(stop_at, &witness)?;
+        witness
+    };
+    let (assignment_order, transcript, witness_log) = match recorder {
+        Some(recorder) => (recorder.assignment_order, recorder.transcript, recorder.log),
+        None => (None, None, None),
+    };
+    Ok(ExecutionOutput { witness, assignment_order, transcript, witness_log, stats })
+}
+
+/// Adds `offset` to the index of the opcode at which `error` occurred, for errors from an ACVM
+/// which was only given the opcodes from `offset` onwards.
+fn offset_error_location(mut error: OpcodeResolutionError, offset: usize) -> OpcodeResolutionError {
+    match &mut error {
+        OpcodeResolutionError::IndexOutOfBounds { opcode_location, .. }
+        | OpcodeResolutionError::UnsatisfiedConstrain { opcode_location } => {
+            if let ErrorLocation::Resolved(OpcodeLocation::Acir(opcode_index)) = opcode_location {
+                *opcode_index += offset;
+            }
+        }
+        _ => (),
+    }
+    error
+}
+
+/// Checks that `foreign_call` may be executed as configured by `options`.
+fn check_foreign_call(
+    foreign_call: &ForeignCallWaitInfo,
+    options: &ExecutionOptions,
+) -> Result<(), NargoError> {
+    if !options.policy.is_enabled(&foreign_call.function) {
+        return Err(NargoError::ForeignCallDisabled(foreign_call.function.clone()));
+    }
+    options.schema.check(foreign_call).map_err(|reason| NargoError::ForeignCallSchemaViolation {
+        function: foreign_call.function.clone(),
+        reason,
+    })
+}
+
+/// The checkpoints remaining to be checked, see [`ExecutionOptions::checkpoints`].
+struct Checkpoints(Peekable<std::vec::IntoIter<(usize, Witness, FieldElement)>>);
+
+impl Checkpoints {
+    fn new(checkpoints: &[(usize, Witness, FieldElement)]) -> Self {
+        let mut checkpoints = checkpoints.to_vec();
+        checkpoints.sort_by_key(|(opcode_index, ..)| *opcode_index);
+        Checkpoints(checkpoints.into_iter().peekable())
+    }
+
+    /// Checks each checkpoint at or before `opcode_index` against `witness_map`.
+    fn check_until(
+        &mut self,
+        opcode_index: usize,
+        witness_map: &WitnessMap,
+    ) -> Result<(), NargoError> {
+        while let Some((checkpoint_index, witness, expected)) =
+            self.0.next_if(|(checkpoint_index, ..)| *checkpoint_index <= opcode_index)
+        {
+            let actual = witness_map.get(&witness).copied();
+            if actual != Some(expected) {
+                return Err(NargoError::CheckpointAssertionFailed {
+                    opcode_index: checkpoint_index,
+                    witness_index: witness.witness_index(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the witnesses of the checkpoints remaining to be checked.
+    fn witnesses(&self) -> impl Iterator<Item = Witness> {
+        self.0.clone().map(|(_, witness, _)| witness)
+    }
+}
+
+/// The witness predicates remaining to be checked, see [`ExecutionOptions::witness_predicates`].
+struct WitnessPredicates(Vec<(Witness, ValuePredicate)>);
+
+impl WitnessPredicates {
+    /// Checks the predicates on each witness assigned in `witness_map`, which has just been left
+    /// by solving the opcode at `opcode_index`, or is the initial witness if that is `None`.
+    fn check(
+        &mut self,
+        opcode_index: Option<usize>,
+        witness_map: &WitnessMap,
+    ) -> Result<(), NargoError> {
+        let mut violation = None;
+        self.0.retain(|(witness, predicate)| {
+            let Some(value) = witness_map.get(witness).copied() else { return true };
+            if violation.is_none() && !predicate.holds(value) {
+                violation = Some(NargoError::WitnessConstraintViolated {
+                    opcode_index,
+                    witness_index: witness.witness_index(),
+                    value,
+                    predicate: predicate.clone(),
+                });
+            }
+            false
+        });
+        violation.map_or(Ok(()), Err)
+    }
+}
+
+/// Records the witnesses assigned by each opcode, for the options which return them.
+struct AssignmentRecorder {
+    unassigned: BTreeSet<Witness>,
+    assignment_order: Option<Vec<Witness>>,
+    transcript: Option<ExecutionTranscript>,
+    log: Option<WitnessLog>,
+}
+
+impl AssignmentRecorder {
+    /// Returns `None` if `options` don't record any assignments.
+    fn new(
+        options: &ExecutionOptions,
+        current_witness_index: u32,
+        initial_witness: &WitnessMap,
+    ) -> Option<Self> {
+        if !options.record_assignment_order
+            && !options.record_transcript
+            && !options.record_witness_log
+        {
+            return None;
+        }
+        let mut recorder = AssignmentRecorder {
+            unassigned: (0..=current_witness_index).map(Witness).collect(),
+            assignment_order: options.record_assignment_order.then(Vec::new),
+            transcript: options.record_transcript.then(ExecutionTranscript::new),
+            log: options.record_witness_log.then(|| WitnessLog::new(initial_witness)),
+        };
+        recorder.take_assigned(initial_witness);
+        Some(recorder)
+    }
+
+    /// Returns the values of the witnesses read by `opcode` which are assigned in `witness_map`,
+    /// if they are being recorded.
+    fn inputs(&self, opcode: &Opcode, witness_map: &WitnessMap) -> BTreeMap<u32, FieldElement> {
+        if self.transcript.is_none() {
+            return BTreeMap::new();
+        }
+        opcode_input_witnesses(opcode)
+            .into_iter()
+            .filter_map(|witness| Some((witness.witness_index(), *witness_map.get(&witness)?)))
+            .collect()
+    }
+
+    /// Records that the opcode at `opcode_index` has been solved, reading `inputs` and leaving
+    /// `witness_map`.
+    fn record_opcode(
+        &mut self,
+        opcode_index: usize,
+        inputs: BTreeMap<u32, FieldElement>,
+        witness_map: &WitnessMap,
+    ) {
+        let outputs = self.take_assigned(witness_map);
+        if let Some(log) = &mut self.log {
+            log.push(opcode_index, outputs.clone());
+        }
+        if let Some(transcript) = &mut self.transcript {
+            transcript.opcodes.push(OpcodeTranscript { opcode_index, inputs, outputs });
+        }
+    }
+
+    /// Returns the values of the witnesses which have been assigned in `witness_map` since the
+    /// last call, keyed on their indices.
+    fn take_assigned(&mut self, witness_map: &WitnessMap) -> BTreeMap<u32, FieldElement> {
+        let mut assigned = BTreeMap::new();
+        self.unassigned.retain(|witness| match witness_map.get(witness) {
+            Some(value) => {
+                assigned.insert(witness.witness_index(), *value);
+                false
+            }
+            None => true,
+        });
+        if let Some(assignment_order) = &mut self.assignment_order {
+            assignment_order.extend(assigned.keys().map(|index| Witness(*index)));
+        }
+        assigned
+    }
+}
+
+/// The backing to which witnesses are spilled, see [`execute_circuit_spilling`].
+struct Spill<'a> {
+    backing: &'a mut dyn WitnessBacking,
+    /// The number of opcodes to solve between spills
+    interval: usize,
+    /// The number of opcodes solved since the last spill
+    solved: usize,
+}
+
+impl Spill<'_> {
+    /// Spills each witness in `witness_map` which isn't read by `remaining_opcodes` or checked
+    /// by `checkpoints`, returning the witnesses left.
+    fn spill(
+        &mut self,
+        remaining_opcodes: &[Opcode],
+        checkpoints: &Checkpoints,
+        witness_map: &WitnessMap,
+    ) -> Result<WitnessMap, NargoError> {
+        let mut live_witnesses: BTreeSet<Witness> = checkpoints.witnesses().collect();
+        for opcode in remaining_opcodes {
+            live_witnesses.extend(witnesses_read(opcode).expect("reads of opcode are tracked"));
+        }
+        let (live, spilled): (BTreeMap<_, _>, BTreeMap<_, _>) = witness_map
+            .clone()
+            .into_iter()
+            .partition(|(witness, _)| live_witnesses.contains(witness));
+        self.backing.spill(WitnessMap::from(spilled))?;
+        self.solved = 0;
+        Ok(WitnessMap::from(live))
+    }
+
+    /// Restores every witness spilled so far into `witness_map`.
+    fn restore(&mut self, mut witness_map: WitnessMap) -> Result<WitnessMap, NargoError> {
+        for (witness, value) in self.backing.restore()? {
+            witness_map.insert(witness, value);
+        }
+        Ok(witness_map)
+    }
+}
+
+/// Returns the witnesses read by `opcode`, or `None` if these aren't tracked for its kind of
+/// opcode.
+fn witnesses_read(opcode: &Opcode) -> Option<BTreeSet<Witness>> {
+    match opcode {
+        Opcode::Arithmetic(_) | Opcode::Brillig(_) | Opcode::BlackBoxFuncCall(_) => {
+            Some(opcode_input_witnesses(opcode))
+        }
+        Opcode::Directive(Directive::ToLeRadix { a, .. }) => {
+            let mut witnesses = BTreeSet::new();
+            add_expression_witnesses(a, &mut witnesses);
+            Some(witnesses)
+        }
+        _ => None,
+    }
+}
+
+/// Returns every witness of `circuit` in ascending order: each witness index it allocates, along
+/// with its inputs, its return values and the witnesses its opcodes read.
+fn circuit_witnesses(circuit: &Circuit) -> Vec<Witness> {
+    // Witness indices are allocated from 1
+    let mut witnesses: BTreeSet<Witness> =
+        (1..=circuit.current_witness_index).map(Witness).collect();
+    witnesses.extend(&circuit.private_parameters);
+    witnesses.extend(&circuit.public_parameters.0);
+    witnesses.extend(&circuit.return_values.0);
+    witnesses.extend(circuit.opcodes.iter().flat_map(opcode_input_witnesses));
+    witnesses.into_iter().collect()
+}
+
+/// Executes `circuit` like [`execute_circuit_with_options`], but passes any foreign call which
+/// nargo doesn't support natively to `host_call` along with the name of the function called.
+///
+/// The foreign call's inputs and outputs are converted between the ACVM's field elements and
+/// the host's representation by `codec`. Hosts which work with field elements directly should
+/// use [`IdentityCodec`][super::IdentityCodec].
+///
+/// Foreign calls are issued in the same deterministic order as in [`execute_circuit`], so
+/// `host_call` may be used to record the calls made by a circuit for later replay.
+pub fn execute_circuit_with_host_calls<B, C, H>(
+    blackbox_solver: &B,
+    circuit: Circuit,
+    initial_witness: WitnessMap,
+    options: &ExecutionOptions,
+    codec: &C,
+    mut host_call: H,
+) -> Result<ExecutionOutput, NargoError>
+where
+    B: BlackBoxFunctionSolver,
+    C: FieldCodec,
+    H: FnMut(
+        &str,
+        Vec<Vec<C::HostField>>,
+    ) -> Result<Vec<HostForeignCallOutput<C::HostField>>, ForeignCallError>,
+{
+    solve(blackbox_solver, circuit, initial_witness, options, |foreign_call, _, _| {
+        ForeignCall::execute_with_host(foreign_call, options, codec, &mut host_call)
+    })
+}
+
+/// Executes `circuit` like [`execute_circuit_with_options`], but passes the message of each
+/// `println` call to `on_print` as a [`PrintEvent`] rather than printing it. This keeps the values
+/// printed separate from the template of any format string so they may be rendered differently.
+pub fn execute_circuit_with_print_events<B, P>(
+    blackbox_solver: &B,
+    circuit: Circuit,
+    initial_witness: WitnessMap,
+    options: &ExecutionOptions,
+    mut on_print: P,
+) -> Result<ExecutionOutput, NargoError>
+where
+    B: BlackBoxFunctionSolver,
+    P: FnMut(PrintEvent),
+{
+    solve(blackbox_solver, circuit, initial_witness, options, |foreign_call, _, _| {
+        match ForeignCall::lookup(&foreign_call.function) {
+            Some(ForeignCall::Println) => {
+                on_print(PrintEvent::try_from(foreign_call.inputs.as_slice())?);
+                Ok(ForeignCallResult { values: vec![] })
+            }
+            _ => ForeignCall::execute_with_options(foreign_call, options),
+        }
+    })
+}
+
+/// An event emitted by [`execute_circuit_streaming`] as execution progresses.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionEvent {
+    /// A `println` call printed this message
+    Print(String),
+    /// Any other foreign call was made with `inputs` and resolved with `result`
+    ForeignCall { function: String, inputs: Vec<Vec<Value>>, result: ForeignCallResult },
+    /// The circuit has been solved
+    Done,
+    /// Execution failed with this error
+    Failed(String),
+}
+
+/// Executes `circuit` like [`execute_circuit_with_options`], but passes an [`ExecutionEvent`] to
+/// `on_event` for each foreign call as soon as it has been resolved, rather than printing any
+/// output. This lets a long-running circuit report its progress while it is still being solved.
+/// The last event is always either [`ExecutionEvent::Done`] or [`ExecutionEvent::Failed`].
+///
+/// Events may be sent to a channel using [`stream_to_channel`].
+pub fn execute_circuit_streaming<B, E>(
+    blackbox_solver: &B,
+    circuit: Circuit,
+    initial_witness: WitnessMap,
+    options: &ExecutionOptions,
+    mut on_event: E,
+) -> Result<ExecutionOutput, NargoError>
+where
+    B: BlackBoxFunctionSolver,
+    E: FnMut(ExecutionEvent),
+{
+    let result = solve(blackbox_solver, circuit, initial_witness, options, |foreign_call, _, _| {
+        match ForeignCall::lookup(&foreign_call.function) {
+            Some(ForeignCall::Println) => {
+                let print_event = PrintEvent::try_from(foreign_call.inputs.as_slice())?;
+                on_event(ExecutionEvent::Print(print_event.to_string()));
+                Ok(ForeignCallResult { values: vec![] })
+            }
+            _ => {
+                let result = ForeignCall::execute_with_options(foreign_call, options)?;
+                on_event(ExecutionEvent::ForeignCall {
+                    function: foreign_call.function.clone(),
+                    inputs: foreign_call.inputs.clone(),
+                    result: result.clone(),
+                });
+                Ok(result)
+            }
+        }
+    });
+    on_event(match &result {
+        Ok(_) => ExecutionEvent::Done,
+        Err(error) => ExecutionEvent::Failed(error.to_string()),
+    });
+    result
+}
+
+/// Returns an `on_event` callback for [`execute_circuit_streaming`] which sends each event to
+/// `sender` without ever waiting on its receiver.
+///
+/// Events are sent with [`SyncSender::try_send`], so any event which doesn't fit because the
+/// channel is full is dropped, as is every event once the receiver has hung up. A slow receiver
+/// therefore loses events rather than holding up execution, and a receiver on the executing
+/// thread can't deadlock it. The channel should be large enough to hold the events expected
+/// between reads. As the final `Done` or `Failed` event may also be dropped, the result returned
+/// by [`execute_circuit_streaming`] is the authoritative outcome of execution.
+pub fn stream_to_channel(sender: SyncSender<ExecutionEvent>) -> impl FnMut(ExecutionEvent) {
+    move |event| {
+        // Dropping the event is the documented policy for both full and disconnected channels
+        let _ = sender.try_send(event);
+    }
+}
+
+/// Returns a [`NargoError::ArithmeticOverflow`] if `error` is the failure of a range constraint.
+fn overflow_error(
+    opcodes: &[Opcode],
+    witness_map: &WitnessMap,
+    error: &OpcodeResolutionError,
+) -> Option<NargoError> {
+    let OpcodeResolutionError::UnsatisfiedConstrain {
+        opcode_location: ErrorLocation::Resolved(OpcodeLocation::Acir(opcode_index)),
+    } = error
+    else {
+        return None;
+    };
+    let Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input }) = &opcodes[*opcode_index]
+    else {
+        return None;
+    };
+
+    let operation = overflowing_operation(opcodes, *opcode_index, input.witness, witness_map)?;
+    Some(NargoError::ArithmeticOverflow {
+        opcode_index: *opcode_index,
+        bit_size: input.num_bits,
+        operation,
+    })
+}
+
+/// Counts the steps taken by Brillig opcodes against the fuel remaining, see
+/// [`ExecutionOptions::fuel`].
+struct FuelMeter {
+    remaining_fuel: usize,
+    fuel: usize,
+    metered_opcode: Option<usize>,
+    replayed_results: VecDeque<ForeignCallResult>,
+}
+
+impl FuelMeter {
+    fn new(fuel: usize) -> Self {
+        FuelMeter {
+            remaining_fuel: fuel,
+            fuel,
+            metered_opcode: None,
+            replayed_results: VecDeque::new(),
+        }
+    }
+
+    /// Meters the opcode at `opcode_index` if it is a Brillig opcode, before the ACVM solves it.
+    fn meter<B, R>(
+        &mut self,
+        blackbox_solver: &B,
+        opcodes: &[Opcode],
+        opcode_index: usize,
+        witness_map: &WitnessMap,
+        resolve: &mut R,
+    ) -> Result<(), NargoError>
+    where
+        B: BlackBoxFunctionSolver,
+        R: FnMut(&ForeignCallWaitInfo, usize, &WitnessMap) -> Result<ForeignCallResult, NargoError>,
+    {
+        let Opcode::Brillig(brillig) = &opcodes[opcode_index] else {
+            return Ok(());
+        };
+        // The ACVM reruns a Brillig opcode after each of its foreign calls is resolved
+        if self.metered_opcode == Some(opcode_index) {
+            return Ok(());
+        }
+        self.metered_opcode = Some(opcode_index);
+        let results = self.run(blackbox_solver, brillig, opcode_index, witness_map, resolve)?;
+        self.replayed_results = results.into();
+        Ok(())
+    }
+
+    /// Returns the result of the next foreign call made while metering the current opcode.
+    fn replay(&mut self) -> Option<ForeignCallResult> {
+        self.replayed_results.pop_front()
+    }
+
+    /// Runs `brillig` to completion, returning the results of the foreign calls it made.
+    ///
+    /// If the opcode can't be run, as its inputs aren't known, or it fails, no fuel is consumed
+    /// and the ACVM is left to report the error when it solves the opcode.
+    fn run<B, R>(
+        &mut self,
+        blackbox_solver: &B,
+        brillig: &Brillig,
+        opcode_index: usize,
+        witness_map: &WitnessMap,
+        resolve: &mut R,
+    ) -> Result<Vec<ForeignCallResult>, NargoError>
+    where
+        B: BlackBoxFunctionSolver,
+        R: FnMut(&ForeignCallWaitInfo, usize, &WitnessMap) -> Result<ForeignCallResult, NargoError>,
+    {
+        let predicate =
+            brillig.predicate.as_ref().map(|predicate| evaluate(predicate, witness_map));
+        let Some((registers, memory)) = brillig_inputs(&brillig.inputs, witness_map) else {
+            return Ok(Vec::new());
+        };
+        match predicate {
+            Some(None) => return Ok(Vec::new()),
+            Some(Some(predicate)) if predicate.is_zero() => return Ok(Vec::new()),
+            _ => (),
+        }
+
+        let mut foreign_call_results = brillig.foreign_call_results.clone();
+        let mut new_results = Vec::new();
+        loop {
+            // Each run restarts from the first instruction, replaying the foreign calls so far
+            let mut vm = VM::new(
+                Registers::load(registers.clone()),
+                memory.clone(),
+                brillig.bytecode.clone(),
+                foreign_call_results.clone(),
+                blackbox_solver,
+            );
+
+            let mut steps = 0;
+            let status = loop {
+                if steps == self.remaining_fuel {
+                    return Err(NargoError::UnconstrainedFuelExhausted {
+                        fuel: self.fuel,
+                        opcode_index,
+                    });
+                }
+                steps += 1;
+
+                match vm.process_opcode() {
+                    VMStatus::InProgress => (),
+                    status => break status,
+                }
+            };
+
+            match status {
+                VMStatus::ForeignCallWait { function, inputs } => {
+                    let foreign_call = ForeignCallWaitInfo { function, inputs };
+                    let result = resolve(&foreign_call, opcode_index, witness_map)?;
+                    foreign_call_results.push(result.clone());
+                    new_results.push(result);
+                }
+                VMStatus::Finished => {
+                    self.remaining_fuel -= steps;
+                    return Ok(new_results);
+                }
+                _ => return Ok(new_results),
+            }
+        }
+    }
+}
+
+/// Executes `circuit` like [`execute_circuit_with_options`], but passes any foreign calls which
+/// nargo doesn't support natively to `resolve_batch` in batches, returning one result for each
+/// call in the batch given.
+///
+/// When execution requires such a call, the Brillig opcodes after the current one are checked
+/// for first foreign calls which can be made immediately. A call is added to the batch if:
+/// - every input and the predicate of its opcode are expressions over witnesses already solved,
+/// - its predicate is non-zero,
+/// - it is the first foreign call made by its opcode,
+/// - it is not natively supported by nargo, so `println` output is never reordered, and
+/// - `options` allow it to be made, so a disallowed call is never passed to `resolve_batch`.
+///
+/// Witnesses are never reassigned, so these calls are exactly those the ACVM will make once it
+/// reaches their opcodes and their results are held until then. Calls which depend on the result
+/// of an earlier call, including any later calls made by the same opcode, can't be found this
+/// way and are batched once execution reaches them, so fully sequential circuits make one call
+/// per batch. Finding a batch runs each candidate opcode up to its first call in a separate
+/// Brillig VM, which repeats that part of its execution.
+pub fn execute_circuit_with_batched_calls<B, R>(
+    blackbox_solver: &B,
+    circuit: Circuit,
+    initial_witness: WitnessMap,
+    options: &ExecutionOptions,
+    mut resolve_batch: R,
+) -> Result<ExecutionOutput, NargoError>
+where
+    B: BlackBoxFunctionSolver,
+    R: FnMut(&[ForeignCallWaitInfo]) -> Result<Vec<ForeignCallResult>, ForeignCallError>,
+{
+    let opcodes = circuit.opcodes.clone();
+
+    // Results of calls batched ahead of execution, keyed on the index of the opcode making them
+    let mut prefetched: HashMap<usize, ForeignCallResult> = HashMap::new();
+
+    let resolve =
+        |foreign_call: &ForeignCallWaitInfo, opcode_index: usize, witness_map: &WitnessMap| {
+            if ForeignCall::lookup(&foreign_call.function).is_some() {
+                return ForeignCall::execute_with_options(foreign_call, options);
+            }
+            if let Some(result) = prefetched.remove(&opcode_index) {
+                return Ok(result);
+            }
+
+            let mut batch_indices = vec![opcode_index];
+            let mut batch = vec![foreign_call.clone()];
+            for (index, opcode) in opcodes.iter().enumerate().skip(opcode_index + 1) {
+                let Opcode::Brillig(brillig) = opcode else { continue };
+                if prefetched.contains_key(&index) {
+                    continue;
+                }
+                if let Some(call) = first_foreign_call(blackbox_solver, brillig, witness_map) {
+                    // Calls which would fail to be made are left for the ACVM to report
+                    let is_batchable = ForeignCall::lookup(&call.function).is_none()
+                        && check_foreign_call(&call, options).is_ok();
+                    if is_batchable {
+                        batch_indices.push(index);
+                        batch.push(call);
+                    }
+                }
+            }
+
+            let results = resolve_batch(&batch)?;
+            if results.len() != batch.len() {
+                return Err(NargoError::ForeignCallBatchMismatch {
+                    expected: batch.len(),
+                    actual: results.len(),
+                });
+            }
+            let mut results = batch_indices.into_iter().zip(results);
+            let (_, result) = results.next().expect("batch contains the pending call");
+            prefetched.extend(results);
+            Ok(result)
+        };
+    solve(blackbox_solver, circuit, initial_witness, options, resolve)
+}
+
+/// Runs `brillig` in a separate Brillig VM up to the first foreign call it makes, as the ACVM
+/// would given `witness_map`. Returns `None` if the opcode can't be run yet, would be skipped by
+/// its predicate, or finishes without making a foreign call.
+fn first_foreign_call<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    brillig: &Brillig,
+    witness_map: &WitnessMap,
+) -> Option<ForeignCallWaitInfo> {
+    if let Some(predicate) = &brillig.predicate {
+        if evaluate(predicate, witness_map)?.is_zero() {
+            return None;
+        }
+    }
+    let (registers, memory) = brillig_inputs(&brillig.inputs, witness_map)?;
 
+    let mut vm = VM::new(
+        Registers::load(registers),
+        memory,
+        brillig.bytecode.clone(),
+        brillig.foreign_call_results.clone(),
+        blackbox_solver,
+    );
     loop {
-        let solver_status = acvm.solve();
+        match vm.process_opcode() {
+            VMStatus::InProgress => (),
+            VMStatus::ForeignCallWait { function, inputs } => {
+                return Some(ForeignCallWaitInfo { function, inputs })
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Evaluates the inputs to a Brillig opcode into its initial registers and memory, in the same
+/// way as the ACVM. Returns `None` if any input depends on an unknown witness.
+fn brillig_inputs(
+    inputs: &[BrilligInputs],
+    witness_map: &WitnessMap,
+) -> Option<(Vec<Value>, Vec<Value>)> {
+    let mut registers = Vec::with_capacity(inputs.len());
+    let mut memory = Vec::new();
+    for input in inputs {
+        match input {
+            BrilligInputs::Single(expr) => registers.push(evaluate(expr, witness_map)?.into()),
+            BrilligInputs::Array(exprs) => {
+                // Arrays are passed as a pointer to the start of their elements in memory
+                let memory_pointer = memory.len();
+                for expr in exprs {
+                    memory.push(evaluate(expr, witness_map)?.into());
+                }
+                registers.push(Value::from(memory_pointer));
+            }
+        }
+    }
+    Some((registers, memory))
+}
+
+fn evaluate(expr: &Expression, witness_map: &WitnessMap) -> Option<FieldElement> {
+    let mut value = expr.q_c;
+    for (coefficient, lhs, rhs) in &expr.mul_terms {
+        value += *coefficient * *witness_map.get(lhs)? * *witness_map.get(rhs)?;
+    }
+    for (coefficient, witness) in &expr.linear_combinations {
+        value += *coefficient * *witness_map.get(witness)?;
+    }
+    Some(value)
+}
+
+/// Solves the leading arithmetic opcodes of `opcodes` which can be solved from `witness_map`
+/// alone, up to the opcode at index `limit`, adding the witnesses they assign to `witness_map`.
+/// Returns the witness assigned by each opcode solved, if any.
+fn propagate_constants(
+    opcodes: &[Opcode],
+    witness_map: &mut WitnessMap,
+    limit: usize,
+) -> Vec<Option<Witness>> {
+    let mut assigned = Vec::new();
+    for opcode in opcodes.iter().take(limit) {
+        let Opcode::Arithmetic(expr) = opcode else { break };
+        match solve_linear(expr, witness_map) {
+            Some(Some((witness, value))) => {
+                witness_map.insert(witness, value);
+                assigned.push(Some(witness));
+            }
+            Some(None) => assigned.push(None),
+            None => break,
+        }
+    }
+    assigned
+}
+
+/// Solves `expr == 0` for its single unknown witness given the known witnesses of `witness_map`.
+///
+/// Returns `Some(None)` if every witness is known and the constraint holds, and `None` if the
+/// constraint doesn't hold or can't be solved without also solving for another witness.
+fn solve_linear(
+    expr: &Expression,
+    witness_map: &WitnessMap,
+) -> Option<Option<(Witness, FieldElement)>> {
+    let mut constant = expr.q_c;
+    let mut unknown: Option<(Witness, FieldElement)> = None;
+    let mut add_unknown = |witness: Witness, coefficient: FieldElement| match &mut unknown {
+        Some((existing, sum)) if *existing == witness => {
+            *sum += coefficient;
+            Some(())
+        }
+        Some(_) => None,
+        None => {
+            unknown = Some((witness, coefficient));
+            Some(())
+        }
+    };
+
+    for (coefficient, lhs, rhs) in &expr.mul_terms {
+        match (witness_map.get(lhs), witness_map.get(rhs)) {
+            (Some(lhs), Some(rhs)) => constant += *coefficient * *lhs * *rhs,
+            (Some(known), None) => add_unknown(*rhs, *coefficient * *known)?,
+            (None, Some(known)) => add_unknown(*lhs, *coefficient * *known)?,
+            (None, None) => return None,
+        }
+    }
+    for (coefficient, witness) in &expr.linear_combinations {
+        match witness_map.get(witness) {
+            Some(value) => constant += *coefficient * *value,
+            None => add_unknown(*witness, *coefficient)?,
+        }
+    }
+
+    match unknown {
+        None if constant.is_zero() => Some(None),
+        None => None,
+        Some((_, coefficient)) if coefficient.is_zero() => None,
+        Some((witness, coefficient)) => Some(Some((witness, -constant * coefficient.inverse()))),
+    }
+}
+
+/// Searches for a witness other than `solved_witness` which satisfies `circuit` with the same
+/// inputs, returning it if one is found. Finding one shows that `circuit` is under-constrained.
+///
+/// This is a heuristic for auditing circuits rather than a proof that their witness is unique,
+/// so finding no alternate solution doesn't mean there is none. Only the values computed by
+/// unconstrained Brillig opcodes are free to change, so each output of each Brillig opcode is
+/// perturbed in turn: to one more than its value in `solved_witness` and to its negation, with
+/// the opcode's other outputs kept. The circuit is then re-solved without that opcode, and any
+/// perturbation for which every constraint still holds is an alternate solution. Attempts which
+/// make a foreign call that nargo doesn't support natively are abandoned.
+pub fn find_alternate_solution<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    circuit: &Circuit,
+    solved_witness: &WitnessMap,
+) -> Option<WitnessMap> {
+    let inputs: Vec<Witness> =
+        circuit.private_parameters.iter().chain(&circuit.public_parameters.0).copied().collect();
+
+    for (opcode_index, opcode) in circuit.opcodes.iter().enumerate() {
+        let Opcode::Brillig(brillig) = opcode else { continue };
+        let outputs: Vec<Witness> = brillig
+            .outputs
+            .iter()
+            .flat_map(|output| match output {
+                BrilligOutputs::Simple(witness) => vec![*witness],
+                BrilligOutputs::Array(witnesses) => witnesses.clone(),
+            })
+            .collect();
+        let mut opcodes = circuit.opcodes.clone();
+        opcodes.remove(opcode_index);
+
+        for witness in &outputs {
+            let Some(value) = solved_witness.get(witness).copied() else { continue };
+            for perturbed in [value + FieldElement::one(), -value] {
+                if perturbed == value {
+                    continue;
+                }
+
+                let mut initial_witness = WitnessMap::new();
+                for known in inputs.iter().chain(&outputs) {
+                    if let Some(known_value) = solved_witness.get(known) {
+                        initial_witness.insert(*known, *known_value);
+                    }
+                }
+                initial_witness.insert(*witness, perturbed);
+
+                let alternate = solve_natively(blackbox_solver, opcodes.clone(), initial_witness);
+                if alternate.is_some() {
+                    return alternate;
+                }
+            }
+        }
+    }
+    None
+}
 
-        match solver_status {
-            ACVMStatus::Solved => break,
+/// Solves `opcodes` from `initial_witness`, returning `None` if solving fails or requires a
+/// foreign call which nargo doesn't support natively.
+fn solve_natively<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    opcodes: Vec<Opcode>,
+    initial_witness: WitnessMap,
+) -> Option<WitnessMap> {
+    let mut acvm = ACVM::new(blackbox_solver, opcodes, initial_witness);
+
+    loop {
+        match acvm.solve() {
+            ACVMStatus::Solved => return Some(acvm.finalize()),
             ACVMStatus::InProgress => {
                 unreachable!("Execution should not stop while in `InProgress` state.")
             }
-            ACVMStatus::Failure(error) => return Err(error.into()),
+            ACVMStatus::Failure(_) => return None,
             ACVMStatus::RequiresForeignCall(foreign_call) => {
-                let foreign_call_result = ForeignCall::execute(&foreign_call, show_output)?;
+                ForeignCall::lookup(&foreign_call.function)?;
+                let foreign_call_result = ForeignCall::execute(&foreign_call, false).ok()?;
                 acvm.resolve_pending_foreign_call(foreign_call_result);
             }
         }
     }
+}
+
+/// Executes the unconstrained function compiled to `bytecode` on its own, as if it were called
+/// with `inputs`, returning its first `num_outputs` return values.
+///
+/// The function is run by the ACVM as the only Brillig opcode of a circuit without any
+/// constraints, so inputs are loaded into its registers and outputs read from them exactly as
+/// when it is called from a circuit. Each input and output is a single field element, so
+/// functions taking or returning arrays can't be executed this way. Foreign calls made by the
+/// function are resolved like those of [`execute_circuit`].
+pub fn execute_unconstrained<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    bytecode: Vec<BrilligOpcode>,
+    inputs: &[FieldElement],
+    num_outputs: usize,
+    show_output: bool,
+) -> Result<Vec<FieldElement>, NargoError> {
+    let outputs: Vec<Witness> = (1..=num_outputs as u32).map(Witness).collect();
+    let brillig = Brillig {
+        inputs: vecmap(inputs, |input| {
+            BrilligInputs::Single(Expression {
+                mul_terms: Vec::new(),
+                linear_combinations: Vec::new(),
+                q_c: *input,
+            })
+        }),
+        outputs: vecmap(&outputs, |output| BrilligOutputs::Simple(*output)),
+        foreign_call_results: Vec::new(),
+        bytecode,
+        predicate: None,
+    };
+    let circuit = Circuit {
+        current_witness_index: num_outputs as u32,
+        opcodes: vec![Opcode::Brillig(brillig)],
+        private_parameters: BTreeSet::new(),
+        public_parameters: PublicInputs(BTreeSet::new()),
+        return_values: PublicInputs(outputs.iter().copied().collect()),
+    };
+
+    let solved_witness = execute_circuit(blackbox_solver, circuit, WitnessMap::new(), show_output)?;
+    Ok(vecmap(&outputs, |output| {
+        *solved_witness.get(output).expect("outputs of solved Brillig opcodes are assigned")
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use acvm::acir::brillig::{
+        BinaryFieldOp, ForeignCallOutput, ForeignCallResult, HeapArray, Opcode as BrilligOpcode,
+        RegisterIndex, RegisterOrMemory, Value,
+    };
+    use acvm::acir::circuit::brillig::{Brillig, BrilligInputs, BrilligOutputs};
+    use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+    use acvm::acir::native_types::{Expression, Witness, WitnessMap};
+    use acvm::{BlackBoxFunctionSolver, BlackBoxResolutionError, FieldElement};
+
+    use crate::ops::{HostForeignCallOutput, IdentityCodec};
+
+    use super::{
+        execute_circuit, execute_circuit_spilling, execute_circuit_streaming,
+        execute_circuit_with_batched_calls, execute_circuit_with_host_calls,
+        execute_circuit_with_options, execute_unconstrained, find_alternate_solution,
+        propagate_constants, stream_to_channel, ExecutionEvent, ExecutionOptions, ExecutionStats,
+    };
+    use crate::ops::{
+        ArgumentSchema, ForeignCallPolicy, ForeignCallSchema, InMemoryWitnessBacking, MerkleTree,
+        OpcodeTranscript, ValuePredicate, WitnessBacking, WitnessBackingError, DEFAULT_TIMESTAMP,
+    };
+    use crate::NargoError;
+
+    struct DummyBlackBoxSolver;
+
+    impl BlackBoxFunctionSolver for DummyBlackBoxSolver {
+        fn schnorr_verify(
+            &self,
+            _public_key_x: &FieldElement,
+            _public_key_y: &FieldElement,
+            _signature: &[u8],
+            _message: &[u8],
+        ) -> Result<bool, BlackBoxResolutionError> {
+            unreachable!("no black box functions are called")
+        }
+        fn pedersen(
+            &self,
+            _inputs: &[FieldElement],
+            _domain_separator: u32,
+        ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+            unreachable!("no black box functions are called")
+        }
+        fn fixed_base_scalar_mul(
+            &self,
+            _input: &FieldElement,
+        ) -> Result<(FieldElement, FieldElement), BlackBoxResolutionError> {
+            unreachable!("no black box functions are called")
+        }
+    }
+
+    /// A Brillig opcode which makes a single call to the oracle `function` with the value of
+    /// `witness` and expects no outputs.
+    fn oracle_call(function: &str, witness: Witness) -> Opcode {
+        let input = RegisterOrMemory::RegisterIndex(RegisterIndex::from(0));
+        Opcode::Brillig(Brillig {
+            inputs: vec![BrilligInputs::Single(Expression::from(witness))],
+            outputs: vec![],
+            foreign_call_results: vec![],
+            bytecode: vec![
+                BrilligOpcode::ForeignCall {
+                    function: function.to_string(),
+                    destinations: vec![],
+                    inputs: vec![input],
+                },
+                BrilligOpcode::Stop,
+            ],
+            predicate: None,
+        })
+    }
+
+    #[test]
+    fn foreign_calls_are_issued_in_opcode_order() {
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![
+                oracle_call("oracle_a", Witness(1)),
+                oracle_call("oracle_b", Witness(2)),
+                oracle_call("oracle_a", Witness(2)),
+                oracle_call("oracle_b", Witness(1)),
+            ],
+            private_parameters: BTreeSet::from([Witness(1), Witness(2)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let initial_witness = WitnessMap::from(BTreeMap::from([
+            (Witness(1), FieldElement::from(1_u128)),
+            (Witness(2), FieldElement::from(2_u128)),
+        ]));
+
+        let record_calls = || {
+            let mut calls = Vec::new();
+            execute_circuit_with_host_calls(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness.clone(),
+                &ExecutionOptions::default(),
+                &IdentityCodec,
+                |function, inputs| {
+                    calls.push((function.to_string(), inputs));
+                    Ok(vec![])
+                },
+            )
+            .expect("circuit should execute");
+            calls
+        };
+
+        let first_run = record_calls();
+        let second_run = record_calls();
+
+        let one = FieldElement::from(1_u128);
+        let two = FieldElement::from(2_u128);
+        let expected = vec![
+            ("oracle_a".to_string(), vec![vec![one]]),
+            ("oracle_b".to_string(), vec![vec![two]]),
+            ("oracle_a".to_string(), vec![vec![two]]),
+            ("oracle_b".to_string(), vec![vec![one]]),
+        ];
+        assert_eq!(first_run, expected);
+        assert_eq!(second_run, first_run);
+    }
+
+    #[test]
+    fn unconstrained_loops_exhaust_fuel() {
+        let brillig = |bytecode| {
+            Opcode::Brillig(Brillig {
+                inputs: vec![],
+                outputs: vec![],
+                foreign_call_results: vec![],
+                bytecode,
+                predicate: None,
+            })
+        };
+        let circuit = |opcodes| Circuit {
+            current_witness_index: 0,
+            opcodes,
+            private_parameters: BTreeSet::new(),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let execute = |opcodes, fuel| {
+            let options = ExecutionOptions { fuel: Some(fuel), ..ExecutionOptions::default() };
+            execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit(opcodes),
+                WitnessMap::new(),
+                &options,
+            )
+        };
+
+        let infinite_loop = brillig(vec![BrilligOpcode::Jump { location: 0 }]);
+        let result = execute(vec![infinite_loop], 1000);
+        assert!(matches!(
+            result,
+            Err(NargoError::UnconstrainedFuelExhausted { fuel: 1000, opcode_index: 0 })
+        ));
+
+        // Fuel is shared between all Brillig opcodes
+        let stop = || brillig(vec![BrilligOpcode::Stop]);
+        assert!(execute(vec![stop(), stop()], 2).is_ok());
+        assert!(matches!(
+            execute(vec![stop(), stop()], 1),
+            Err(NargoError::UnconstrainedFuelExhausted { opcode_index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn disabled_foreign_calls_fail_execution() {
+        let circuit = Circuit {
+            current_witness_index: 1,
+            opcodes: vec![oracle_call("__nargo_host_assert", Witness(1))],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let initial_witness = WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::one())]));
+        let execute = |policy: &ForeignCallPolicy| {
+            let options =
+                ExecutionOptions { policy: policy.clone(), ..ExecutionOptions::default() };
+            execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness.clone(),
+                &options,
+            )
+        };
+
+        let mut policy = ForeignCallPolicy::default();
+        assert!(execute(&policy).is_ok());
+
+        policy.disable("__nargo_host_assert");
+        assert!(matches!(
+            execute(&policy),
+            Err(NargoError::ForeignCallDisabled(name)) if name == "__nargo_host_assert"
+        ));
+
+        policy.enable("__nargo_host_assert");
+        assert!(execute(&policy).is_ok());
+    }
+
+    #[test]
+    fn foreign_calls_are_checked_against_schema() {
+        let circuit = Circuit {
+            current_witness_index: 1,
+            opcodes: vec![oracle_call("oracle_a", Witness(1))],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let initial_witness =
+            WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::from(300_u128))]));
+
+        let violation = |arguments| {
+            let mut schema = ForeignCallSchema::default();
+            schema.expect("oracle_a", arguments);
+            let options = ExecutionOptions { schema, ..ExecutionOptions::default() };
+            let result = execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness.clone(),
+                &options,
+            );
+            match result {
+                Err(NargoError::ForeignCallSchemaViolation { function, reason }) => {
+                    assert_eq!(function, "oracle_a");
+                    reason
+                }
+                _ => panic!("expected the foreign call to violate its schema"),
+            }
+        };
+
+        let input = |length, max_bits| ArgumentSchema { length, max_bits };
+        assert_eq!(violation(vec![]), "expected 0 inputs but got 1");
+        assert_eq!(
+            violation(vec![input(Some(2), None)]),
+            "expected input 0 to have 2 values but got 1"
+        );
+        let reason = violation(vec![input(None, Some(8))]);
+        assert!(reason.starts_with("expected the values of input 0 to fit in 8 bits"), "{reason}");
+    }
+
+    #[test]
+    fn checkpoints_are_checked_as_execution_passes_them() {
+        let one = FieldElement::one();
+        // w2 = w1 + 1, then w3 = 2 * w2
+        let circuit = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![(one, Witness(1)), (-one, Witness(2))],
+                    q_c: one,
+                }),
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![(one + one, Witness(2)), (-one, Witness(3))],
+                    q_c: FieldElement::zero(),
+                }),
+            ],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let execute = |checkpoints| {
+            let initial_witness = WitnessMap::from(BTreeMap::from([(Witness(1), 5_u128.into())]));
+            let options = ExecutionOptions { checkpoints, ..ExecutionOptions::default() };
+            execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness,
+                &options,
+            )
+        };
+
+        let passing = vec![(2, Witness(3), 12_u128.into()), (1, Witness(2), 6_u128.into())];
+        assert!(execute(passing).is_ok());
+
+        // w3 is only assigned by the second opcode
+        let unassigned = execute(vec![(1, Witness(3), 12_u128.into())]);
+        assert!(matches!(
+            unassigned,
+            Err(NargoError::CheckpointAssertionFailed {
+                opcode_index: 1,
+                witness_index: 3,
+                actual: None,
+                ..
+            })
+        ));
+
+        let mismatched = execute(vec![(5, Witness(3), 13_u128.into())]);
+        assert!(matches!(
+            mismatched,
+            Err(NargoError::CheckpointAssertionFailed {
+                opcode_index: 5,
+                witness_index: 3,
+                actual: Some(actual),
+                ..
+            })
+                if actual == FieldElement::from(12_u128)
+        ));
+    }
+
+    #[test]
+    fn witness_predicates_are_checked_as_witnesses_are_assigned() {
+        let one = FieldElement::one();
+        // The selector w2 = w1 - 1
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![Opcode::Arithmetic(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(one, Witness(1)), (-one, Witness(2))],
+                q_c: -one,
+            })],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let execute_with_prepass = |input: u128, predicates, prepass| {
+            let initial_witness = WitnessMap::from(BTreeMap::from([(Witness(1), input.into())]));
+            let options = ExecutionOptions {
+                witness_predicates: predicates,
+                prepass,
+                ..ExecutionOptions::default()
+            };
+            execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness,
+                &options,
+            )
+            .map(|output| output.witness)
+        };
+        let execute = |input, predicates| execute_with_prepass(input, predicates, false);
+        let selector = || vec![(Witness(2), ValuePredicate::boolean())];
+
+        assert!(execute(1, selector()).is_ok());
+        assert!(execute(2, selector()).is_ok());
+
+        let violated = execute(3, selector());
+        assert!(matches!(
+            violated,
+            Err(NargoError::WitnessConstraintViolated {
+                opcode_index: Some(0),
+                witness_index: 2,
+                value,
+                ..
+            })
+                if value == FieldElement::from(2_u128)
+        ));
+        // Opcodes solved by the prepass are checked in the same way
+        assert!(matches!(
+            execute_with_prepass(3, selector(), true),
+            Err(NargoError::WitnessConstraintViolated {
+                opcode_index: Some(0),
+                witness_index: 2,
+                ..
+            })
+        ));
+
+        let initial = execute(3, vec![(Witness(1), ValuePredicate::MaxBits(1))]);
+        assert!(matches!(
+            initial,
+            Err(NargoError::WitnessConstraintViolated { opcode_index: None, witness_index: 1, .. })
+        ));
+        assert_eq!(
+            initial.unwrap_err().to_string(),
+            "Witness 1 was assigned 3 in the initial witness but must be less than 2^1"
+        );
+    }
+
+    #[test]
+    fn free_witnesses_are_reported() {
+        let one = FieldElement::one();
+        // w2 = w1 + 1, while w3 is allocated but never constrained
+        let circuit = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![Opcode::Arithmetic(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(one, Witness(1)), (-one, Witness(2))],
+                q_c: one,
+            })],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::from([Witness(2)])),
+        };
+        let initial_witness = || WitnessMap::from(BTreeMap::from([(Witness(1), one)]));
+
+        // Free witnesses are only reported when asked for
+        let solved =
+            execute_circuit(&DummyBlackBoxSolver, circuit.clone(), initial_witness(), false);
+        assert!(solved.unwrap().get(&Witness(3)).is_none());
+
+        let options =
+            ExecutionOptions { check_free_witnesses: true, ..ExecutionOptions::default() };
+        let execute = |circuit| {
+            execute_circuit_with_options(&DummyBlackBoxSolver, circuit, initial_witness(), &options)
+                .map(|output| output.witness)
+        };
+        let result = execute(circuit.clone());
+        assert!(matches!(
+            &result,
+            Err(NargoError::UnderConstrained { free_witnesses }) if free_witnesses == &[Witness(3)]
+        ));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Circuit is under-constrained: witness 3 was never assigned a value"
+        );
+
+        let constrained = Circuit { current_witness_index: 2, ..circuit };
+        let solved = execute(constrained);
+        assert_eq!(solved.unwrap().get(&Witness(2)), Some(&FieldElement::from(2_u128)));
+    }
+
+    #[test]
+    fn stats_count_work_done() {
+        let circuit = Circuit {
+            current_witness_index: 1,
+            opcodes: vec![
+                oracle_call("println", Witness(1)),
+                // w1 - 1 == 0
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![(FieldElement::one(), Witness(1))],
+                    q_c: -FieldElement::one(),
+                }),
+                oracle_call("println", Witness(1)),
+            ],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let initial_witness = WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::one())]));
+
+        let stats = execute_circuit_with_options(
+            &DummyBlackBoxSolver,
+            circuit,
+            initial_witness,
+            &ExecutionOptions::default(),
+        )
+        .expect("circuit should execute")
+        .stats;
+
+        // Each Brillig opcode is attempted again once its foreign call has been resolved
+        let expected = ExecutionStats { solve_iterations: 5, foreign_calls: 2, opcodes_solved: 3 };
+        assert_eq!(stats, expected);
+    }
+
+    #[test]
+    fn execution_stops_at_the_given_opcode() {
+        let one = FieldElement::one();
+        // w(i + 1) = w(i) + 1 for each i up to 3
+        let opcodes = (1..4)
+            .map(|index| {
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![(one, Witness(index)), (-one, Witness(index + 1))],
+                    q_c: one,
+                })
+            })
+            .collect();
+        let circuit = Circuit {
+            current_witness_index: 4,
+            opcodes,
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::from([Witness(4)])),
+        };
+        let execute = |stop_at| {
+            let options =
+                ExecutionOptions { stop_at: Some(stop_at), ..ExecutionOptions::default() };
+            let initial_witness = WitnessMap::from(BTreeMap::from([(Witness(1), one)]));
+            execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness,
+                &options,
+            )
+            .expect("circuit should execute")
+        };
+
+        // Only the opcodes before the one stopped at are solved
+        let stopped = execute(2);
+        let expected = WitnessMap::from(BTreeMap::from([
+            (Witness(1), one),
+            (Witness(2), FieldElement::from(2_u128)),
+            (Witness(3), FieldElement::from(3_u128)),
+        ]));
+        assert_eq!(stopped.witness, expected);
+        assert_eq!(stopped.stats.opcodes_solved, 2);
+
+        assert_eq!(execute(0).witness, WitnessMap::from(BTreeMap::from([(Witness(1), one)])));
+        let solved = execute(10);
+        assert_eq!(solved.witness.get(&Witness(4)), Some(&FieldElement::from(4_u128)));
+        assert_eq!(solved.stats.opcodes_solved, 3);
+    }
+
+    #[test]
+    fn assignment_order_follows_the_order_witnesses_are_solved() {
+        let one = FieldElement::one();
+        let circuit = Circuit {
+            current_witness_index: 5,
+            opcodes: vec![
+                // w4 = w1 + w5
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![
+                        (one, Witness(1)),
+                        (one, Witness(5)),
+                        (-one, Witness(4)),
+                    ],
+                    q_c: FieldElement::zero(),
+                }),
+                // w2 = w4 * w4
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![(one, Witness(4), Witness(4))],
+                    linear_combinations: vec![(-one, Witness(2))],
+                    q_c: FieldElement::zero(),
+                }),
+                // w3 = w2 + 1
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![(one, Witness(2)), (-one, Witness(3))],
+                    q_c: one,
+                }),
+            ],
+            private_parameters: BTreeSet::from([Witness(1), Witness(5)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::from([Witness(3)])),
+        };
+        let initial_witness = WitnessMap::from(BTreeMap::from([
+            (Witness(1), FieldElement::from(2_u128)),
+            (Witness(5), one),
+        ]));
+
+        let options =
+            ExecutionOptions { record_assignment_order: true, ..ExecutionOptions::default() };
+        let output =
+            execute_circuit_with_options(&DummyBlackBoxSolver, circuit, initial_witness, &options)
+                .expect("circuit should execute");
+
+        // The initial witnesses come first, then those of each opcode as it is solved
+        let order = vec![Witness(1), Witness(5), Witness(4), Witness(2), Witness(3)];
+        assert_eq!(output.assignment_order, Some(order));
+        assert_eq!(output.witness.get(&Witness(3)), Some(&FieldElement::from(10_u128)));
+    }
+
+    #[test]
+    fn witness_log_records_each_assignment() {
+        let circuit = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![
+                oracle_call("println", Witness(1)),
+                // w1 * w1 - w2 == 0
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![(FieldElement::one(), Witness(1), Witness(1))],
+                    linear_combinations: vec![(-FieldElement::one(), Witness(2))],
+                    q_c: FieldElement::zero(),
+                }),
+                // w2 + w1 - w3 == 0
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![
+                        (FieldElement::one(), Witness(2)),
+                        (FieldElement::one(), Witness(1)),
+                        (-FieldElement::one(), Witness(3)),
+                    ],
+                    q_c: FieldElement::zero(),
+                }),
+            ],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let initial_witness =
+            WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::from(3_u128))]));
+
+        let options = ExecutionOptions { record_witness_log: true, ..ExecutionOptions::default() };
+        let output =
+            execute_circuit_with_options(&DummyBlackBoxSolver, circuit, initial_witness, &options)
+                .expect("circuit should execute");
+        let log = output.witness_log.expect("witness log should be recorded");
+
+        assert_eq!(log.initial_witnesses, vec![1]);
+        let assignments: Vec<_> = log
+            .assignments
+            .iter()
+            .map(|entry| (entry.opcode_index, entry.witnesses.clone()))
+            .collect();
+        assert_eq!(assignments, vec![(0, vec![]), (1, vec![2]), (2, vec![3])]);
+        assert_eq!(log.assignments[2].values, vec![FieldElement::from(12_u128)]);
+        assert_eq!(log.witness_map(), output.witness);
+    }
+
+    #[test]
+    fn transcript_records_each_opcode() {
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![
+                // w1 * w1 - w2 == 0
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![(FieldElement::one(), Witness(1), Witness(1))],
+                    linear_combinations: vec![(-FieldElement::one(), Witness(2))],
+                    q_c: FieldElement::zero(),
+                }),
+                oracle_call("println", Witness(2)),
+            ],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let initial_witness =
+            WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::from(3_u128))]));
+
+        let options = ExecutionOptions { record_transcript: true, ..ExecutionOptions::default() };
+        let execute = |options: &ExecutionOptions| {
+            execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness.clone(),
+                options,
+            )
+            .expect("circuit should execute")
+            .transcript
+            .expect("transcript should be recorded")
+        };
+        let transcript = execute(&options);
+
+        let nine = FieldElement::from(9_u128);
+        let expected = vec![
+            OpcodeTranscript {
+                opcode_index: 0,
+                inputs: BTreeMap::from([(1, FieldElement::from(3_u128))]),
+                outputs: BTreeMap::from([(2, nine)]),
+            },
+            OpcodeTranscript {
+                opcode_index: 1,
+                inputs: BTreeMap::from([(2, nine)]),
+                outputs: BTreeMap::new(),
+            },
+        ];
+        assert_eq!(transcript.opcodes, expected);
+
+        // The prepass solves the arithmetic opcode, which is still recorded
+        let prepass = ExecutionOptions { prepass: true, ..options };
+        assert_eq!(execute(&prepass).opcodes, expected);
+    }
+
+    #[test]
+    fn overflows_report_their_operation() {
+        use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+
+        use crate::ops::OverflowingOperation;
+
+        // w1 + w2 - w3 == 0, with w3 range constrained to 8 bits
+        let circuit = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![
+                        (FieldElement::one(), Witness(1)),
+                        (FieldElement::one(), Witness(2)),
+                        (-FieldElement::one(), Witness(3)),
+                    ],
+                    q_c: FieldElement::zero(),
+                }),
+                Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                    input: FunctionInput { witness: Witness(3), num_bits: 8 },
+                }),
+            ],
+            private_parameters: BTreeSet::from([Witness(1), Witness(2)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let initial_witness = |lhs: u128, rhs: u128| {
+            WitnessMap::from(BTreeMap::from([
+                (Witness(1), FieldElement::from(lhs)),
+                (Witness(2), FieldElement::from(rhs)),
+            ]))
+        };
+
+        let options =
+            ExecutionOptions { overflow_diagnostics: true, ..ExecutionOptions::default() };
+        let result = execute_circuit_with_options(
+            &DummyBlackBoxSolver,
+            circuit.clone(),
+            initial_witness(200, 100),
+            &options,
+        );
+        let expected = OverflowingOperation::Add {
+            lhs: FieldElement::from(200_u128),
+            rhs: FieldElement::from(100_u128),
+        };
+        assert!(matches!(
+            result,
+            Err(NargoError::ArithmeticOverflow { opcode_index: 1, bit_size: 8, operation })
+                if operation == expected
+        ));
+
+        let solved = execute_circuit_with_options(
+            &DummyBlackBoxSolver,
+            circuit.clone(),
+            initial_witness(200, 55),
+            &options,
+        );
+        assert_eq!(solved.unwrap().witness.get(&Witness(3)), Some(&FieldElement::from(255_u128)));
+
+        let result =
+            execute_circuit(&DummyBlackBoxSolver, circuit, initial_witness(200, 100), false);
+        assert!(matches!(result, Err(NargoError::SolvingError(_))));
+    }
+
+    #[test]
+    fn now_returns_the_configured_timestamp() {
+        let timestamp = Witness(1);
+        let register = RegisterIndex::from(0);
+        let circuit = Circuit {
+            current_witness_index: 1,
+            opcodes: vec![Opcode::Brillig(Brillig {
+                inputs: vec![],
+                outputs: vec![BrilligOutputs::Simple(timestamp)],
+                foreign_call_results: vec![],
+                bytecode: vec![
+                    BrilligOpcode::ForeignCall {
+                        function: "__nargo_now".to_string(),
+                        destinations: vec![RegisterOrMemory::RegisterIndex(register)],
+                        inputs: vec![],
+                    },
+                    BrilligOpcode::Stop,
+                ],
+                predicate: None,
+            })],
+            private_parameters: BTreeSet::new(),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::from([timestamp])),
+        };
+        let execute = |timestamp| {
+            let options = ExecutionOptions { timestamp, ..ExecutionOptions::default() };
+            execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                WitnessMap::new(),
+                &options,
+            )
+            .expect("circuit should execute")
+            .witness
+        };
+
+        let expected = FieldElement::from(1_700_000_000_u128);
+        assert_eq!(execute(1_700_000_000).get(&timestamp), Some(&expected));
+        assert_eq!(execute(DEFAULT_TIMESTAMP).get(&timestamp), Some(&FieldElement::zero()));
+    }
+
+    #[test]
+    fn independent_foreign_calls_are_batched() {
+        // A Brillig opcode which assigns `output` the result of calling `fetch` with `input`
+        let fetch = |input: Witness, output: Witness| {
+            let register = RegisterOrMemory::RegisterIndex(RegisterIndex::from(0));
+            Opcode::Brillig(Brillig {
+                inputs: vec![BrilligInputs::Single(Expression::from(input))],
+                outputs: vec![BrilligOutputs::Simple(output)],
+                foreign_call_results: vec![],
+                bytecode: vec![
+                    BrilligOpcode::ForeignCall {
+                        function: "fetch".to_string(),
+                        destinations: vec![register],
+                        inputs: vec![register],
+                    },
+                    BrilligOpcode::Stop,
+                ],
+                predicate: None,
+            })
+        };
+        let circuit = Circuit {
+            current_witness_index: 5,
+            opcodes: vec![
+                fetch(Witness(1), Witness(3)),
+                // Depends on the result of the first call
+                fetch(Witness(3), Witness(4)),
+                fetch(Witness(2), Witness(5)),
+            ],
+            private_parameters: BTreeSet::from([Witness(1), Witness(2)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::from([Witness(4), Witness(5)])),
+        };
+        let initial_witness = WitnessMap::from(BTreeMap::from([
+            (Witness(1), FieldElement::from(10_u128)),
+            (Witness(2), FieldElement::from(20_u128)),
+        ]));
+
+        let mut batches = Vec::new();
+        let solved_witness = execute_circuit_with_batched_calls(
+            &DummyBlackBoxSolver,
+            circuit,
+            initial_witness,
+            &ExecutionOptions::default(),
+            |calls| {
+                let inputs: Vec<_> =
+                    calls.iter().map(|call| call.inputs[0][0].to_field()).collect();
+                batches.push(inputs.clone());
+                Ok(inputs
+                    .into_iter()
+                    .map(|input| {
+                        let output = input + FieldElement::one();
+                        ForeignCallResult { values: vec![ForeignCallOutput::Single(output.into())] }
+                    })
+                    .collect())
+            },
+        )
+        .expect("circuit should execute")
+        .witness;
+
+        let field = |value: u128| FieldElement::from(value);
+        assert_eq!(batches, vec![vec![field(10), field(20)], vec![field(11)]]);
+        assert_eq!(solved_witness.get(&Witness(4)), Some(&field(12)));
+        assert_eq!(solved_witness.get(&Witness(5)), Some(&field(21)));
+    }
+
+    #[test]
+    fn prepass_solves_the_same_witness() {
+        let one = FieldElement::one();
+        let circuit = Circuit {
+            current_witness_index: 5,
+            opcodes: vec![
+                // w3 = w1 * w2
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![(one, Witness(1), Witness(2))],
+                    linear_combinations: vec![(-one, Witness(3))],
+                    q_c: FieldElement::zero(),
+                }),
+                // 2 * w4 = w3 + 4
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![(one, Witness(3)), (-(one + one), Witness(4))],
+                    q_c: FieldElement::from(4_u128),
+                }),
+                oracle_call("println", Witness(4)),
+                // w5 = w4 + 1 is left to the ACVM as it follows a Brillig opcode
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![(one, Witness(4)), (-one, Witness(5))],
+                    q_c: one,
+                }),
+            ],
+            private_parameters: BTreeSet::from([Witness(1), Witness(2)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::from([Witness(5)])),
+        };
+        let initial_witness = WitnessMap::from(BTreeMap::from([
+            (Witness(1), FieldElement::from(3_u128)),
+            (Witness(2), FieldElement::from(2_u128)),
+        ]));
+
+        let mut propagated = initial_witness.clone();
+        let assigned = propagate_constants(&circuit.opcodes, &mut propagated, usize::MAX);
+        assert_eq!(assigned, vec![Some(Witness(3)), Some(Witness(4))]);
+        assert_eq!(propagated.get(&Witness(4)), Some(&FieldElement::from(5_u128)));
+
+        let execute = |options: &ExecutionOptions| {
+            execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness.clone(),
+                options,
+            )
+            .expect("circuit should execute")
+        };
+        let expected = execute(&ExecutionOptions::default());
+        let prepass = ExecutionOptions { prepass: true, ..ExecutionOptions::default() };
+        let solved = execute(&prepass);
+        assert_eq!(solved.witness, expected.witness);
+        assert_eq!(solved.witness.get(&Witness(5)), Some(&FieldElement::from(6_u128)));
+        // Only the opcodes after the prepass are solved by the ACVM
+        assert_eq!(solved.stats.opcodes_solved, 2);
+
+        let stop_early = ExecutionOptions { stop_at: Some(1), ..prepass };
+        assert_eq!(execute(&stop_early).witness.get(&Witness(4)), None);
+    }
+
+    #[test]
+    fn spilling_solves_the_same_witness() {
+        /// Keeps spilled witnesses in memory, recording the witnesses in each spill
+        #[derive(Default)]
+        struct RecordingBacking {
+            spills: Vec<Vec<Witness>>,
+            witnesses: InMemoryWitnessBacking,
+        }
+
+        impl WitnessBacking for RecordingBacking {
+            fn spill(&mut self, witnesses: WitnessMap) -> Result<(), WitnessBackingError> {
+                self.spills
+                    .push(witnesses.clone().into_iter().map(|(witness, _)| witness).collect());
+                self.witnesses.spill(witnesses)
+            }
+
+            fn restore(&mut self) -> Result<WitnessMap, WitnessBackingError> {
+                self.witnesses.restore()
+            }
+        }
+
+        let one = FieldElement::one();
+        // w(i + 1) = w(i) + 1 for each i up to 4
+        let mut opcodes: Vec<Opcode> = (1..5)
+            .map(|index| {
+                Opcode::Arithmetic(Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![(one, Witness(index)), (-one, Witness(index + 1))],
+                    q_c: one,
+                })
+            })
+            .collect();
+        // w6 = w1 * w5
+        opcodes.push(Opcode::Arithmetic(Expression {
+            mul_terms: vec![(one, Witness(1), Witness(5))],
+            linear_combinations: vec![(-one, Witness(6))],
+            q_c: FieldElement::zero(),
+        }));
+        let circuit = Circuit {
+            current_witness_index: 6,
+            opcodes,
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::from([Witness(6)])),
+        };
+        let initial_witness =
+            WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::from(2_u128))]));
+        let options =
+            ExecutionOptions { record_assignment_order: true, ..ExecutionOptions::default() };
+
+        let expected = execute_circuit_with_options(
+            &DummyBlackBoxSolver,
+            circuit.clone(),
+            initial_witness.clone(),
+            &options,
+        )
+        .expect("circuit should execute");
+        let mut backing = RecordingBacking::default();
+        let spilled = execute_circuit_spilling(
+            &DummyBlackBoxSolver,
+            circuit,
+            initial_witness,
+            &options,
+            &mut backing,
+            2,
+        )
+        .expect("circuit should execute");
+
+        assert_eq!(spilled.witness, expected.witness);
+        assert_eq!(spilled.assignment_order, expected.assignment_order);
+        assert_eq!(spilled.witness.get(&Witness(6)), Some(&FieldElement::from(12_u128)));
+        // w1 is read by the last opcode so is never spilled
+        assert_eq!(backing.spills, vec![vec![Witness(2)], vec![Witness(3), Witness(4)]]);
+    }
+
+    #[test]
+    fn execution_events_are_streamed_as_they_happen() {
+        let register = RegisterOrMemory::RegisterIndex(RegisterIndex::from(0));
+        let circuit = Circuit {
+            current_witness_index: 1,
+            opcodes: vec![Opcode::Brillig(Brillig {
+                inputs: vec![],
+                outputs: vec![BrilligOutputs::Simple(Witness(1))],
+                foreign_call_results: vec![],
+                bytecode: vec![
+                    BrilligOpcode::ForeignCall {
+                        function: "__nargo_now".to_string(),
+                        destinations: vec![register],
+                        inputs: vec![],
+                    },
+                    BrilligOpcode::Stop,
+                ],
+                predicate: None,
+            })],
+            private_parameters: BTreeSet::new(),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::from([Witness(1)])),
+        };
+        let now = ExecutionEvent::ForeignCall {
+            function: "__nargo_now".to_string(),
+            inputs: vec![],
+            result: ForeignCallResult {
+                values: vec![ForeignCallOutput::Single(u128::from(DEFAULT_TIMESTAMP).into())],
+            },
+        };
+
+        let mut events = Vec::new();
+        execute_circuit_streaming(
+            &DummyBlackBoxSolver,
+            circuit.clone(),
+            WitnessMap::new(),
+            &ExecutionOptions::default(),
+            |event| {
+                events.push(event);
+            },
+        )
+        .expect("circuit should execute");
+        assert_eq!(events, vec![now.clone(), ExecutionEvent::Done]);
 
-    let solved_witness = acvm.finalize();
-    Ok(solved_witness)
+        // Events which don't fit in the channel are dropped rather than blocking execution
+        let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+        execute_circuit_streaming(
+            &DummyBlackBoxSolver,
+            circuit,
+            WitnessMap::new(),
+            &ExecutionOptions::default(),
+            stream_to_channel(sender),
+        )
+        .expect("circuit should execute");
+        assert_eq!(receiver.iter().collect::<Vec<_>>(), vec![now]);
+    }
+
+    #[test]
+    fn finds_alternate_solutions_of_under_constrained_circuits() {
+        let one = FieldElement::one();
+        // Assigns `w2` a copy of `w1` in unconstrained code
+        let hint = Opcode::Brillig(Brillig {
+            inputs: vec![BrilligInputs::Single(Expression::from(Witness(1)))],
+            outputs: vec![BrilligOutputs::Simple(Witness(2))],
+            foreign_call_results: vec![],
+            bytecode: vec![BrilligOpcode::Stop],
+            predicate: None,
+        });
+        // w3 = w1 + w2
+        let sum = Opcode::Arithmetic(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![(one, Witness(1)), (one, Witness(2)), (-one, Witness(3))],
+            q_c: FieldElement::zero(),
+        });
+        // w2 = w1
+        let copy_constraint = Opcode::Arithmetic(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![(one, Witness(1)), (-one, Witness(2))],
+            q_c: FieldElement::zero(),
+        });
+
+        let circuit = |opcodes| Circuit {
+            current_witness_index: 3,
+            opcodes,
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::from([Witness(3)])),
+        };
+        let initial_witness =
+            WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::from(5_u128))]));
+        let solve_and_search = |circuit: Circuit| {
+            let solved_witness = execute_circuit(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness.clone(),
+                false,
+            )
+            .expect("circuit should execute");
+            find_alternate_solution(&DummyBlackBoxSolver, &circuit, &solved_witness)
+        };
+
+        // Nothing constrains the hint, so any value of `w2` gives a solution
+        let alternate = solve_and_search(circuit(vec![hint.clone(), sum.clone()]))
+            .expect("circuit is under-constrained");
+        assert_eq!(alternate.get(&Witness(2)), Some(&FieldElement::from(6_u128)));
+        assert_eq!(alternate.get(&Witness(3)), Some(&FieldElement::from(11_u128)));
+
+        assert!(solve_and_search(circuit(vec![hint, copy_constraint, sum])).is_none());
+    }
+
+    #[test]
+    fn non_deterministic_foreign_calls_are_caught() {
+        let circuit = Circuit {
+            current_witness_index: 1,
+            opcodes: vec![oracle_call("oracle_a", Witness(1)), oracle_call("clock", Witness(1))],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let initial_witness = WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::one())]));
+
+        let mut ticks = 0_u128;
+        let options = ExecutionOptions { check_determinism: true, ..ExecutionOptions::default() };
+        let result = execute_circuit_with_host_calls(
+            &DummyBlackBoxSolver,
+            circuit,
+            initial_witness,
+            &options,
+            &IdentityCodec,
+            |function, _| {
+                let output = if function == "clock" {
+                    ticks += 1;
+                    vec![HostForeignCallOutput::Single(FieldElement::from(ticks))]
+                } else {
+                    vec![]
+                };
+                Ok(output)
+            },
+        );
+
+        assert!(matches!(
+            result,
+            Err(NargoError::NonDeterministicForeignCall { name }) if name == "clock"
+        ));
+        assert_eq!(ticks, 2);
+    }
+
+    #[test]
+    fn user_oracles_sharing_a_host_oracle_name_are_forwarded() {
+        // The host answers `__nargo_merkle_open`, so a user oracle named `merkle_open` is the user's
+        let circuit = Circuit {
+            current_witness_index: 1,
+            opcodes: vec![oracle_call("merkle_open", Witness(1))],
+            private_parameters: BTreeSet::from([Witness(1)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let initial_witness = WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::one())]));
+
+        let mut forwarded = Vec::new();
+        execute_circuit_with_host_calls(
+            &DummyBlackBoxSolver,
+            circuit,
+            initial_witness,
+            &ExecutionOptions::default(),
+            &IdentityCodec,
+            |function, inputs| {
+                forwarded.push((function.to_string(), inputs));
+                Ok(vec![])
+            },
+        )
+        .expect("the user's oracle should be answered by its handler");
+
+        assert_eq!(forwarded, vec![("merkle_open".to_string(), vec![vec![FieldElement::one()]])]);
+    }
+
+    #[test]
+    fn unconstrained_functions_are_executed_on_their_own() {
+        let sum = RegisterIndex::from(0);
+        let timestamp = RegisterIndex::from(1);
+        let bytecode = vec![
+            BrilligOpcode::BinaryFieldOp {
+                destination: sum,
+                op: BinaryFieldOp::Add,
+                lhs: RegisterIndex::from(0),
+                rhs: RegisterIndex::from(1),
+            },
+            BrilligOpcode::ForeignCall {
+                function: "__nargo_now".to_string(),
+                destinations: vec![RegisterOrMemory::RegisterIndex(timestamp)],
+                inputs: vec![],
+            },
+            BrilligOpcode::Stop,
+        ];
+        let inputs = [FieldElement::from(2_u128), FieldElement::from(3_u128)];
+
+        let outputs =
+            execute_unconstrained(&DummyBlackBoxSolver, bytecode, &inputs, 2, false).unwrap();
+        assert_eq!(
+            outputs,
+            vec![FieldElement::from(5_u128), FieldElement::from(u128::from(DEFAULT_TIMESTAMP))]
+        );
+    }
+    #[test]
+    fn merkle_openings_are_read_from_the_host_tree() {
+        let fields = |values: &[u128]| -> Vec<FieldElement> {
+            values.iter().map(|value| FieldElement::from(*value)).collect()
+        };
+        let tree = MerkleTree::from_layers(vec![
+            fields(&[1, 2, 3, 4]),
+            fields(&[12, 34]),
+            fields(&[1234]),
+        ])
+        .unwrap();
+
+        // Calls `std::merkle_open(w1, w2)`, writing the sibling path to memory and then to `w3, w4`
+        let pointer = RegisterIndex::from(2);
+        let opening = Opcode::Brillig(Brillig {
+            inputs: vec![
+                BrilligInputs::Single(Expression::from(Witness(1))),
+                BrilligInputs::Single(Expression::from(Witness(2))),
+            ],
+            outputs: vec![BrilligOutputs::Array(vec![Witness(3), Witness(4)])],
+            foreign_call_results: vec![],
+            bytecode: vec![
+                BrilligOpcode::Const { destination: pointer, value: Value::from(0_u128) },
+                BrilligOpcode::ForeignCall {
+                    function: "__nargo_merkle_open".to_string(),
+                    destinations: vec![RegisterOrMemory::HeapArray(HeapArray { pointer, size: 2 })],
+                    inputs: vec![
+                        RegisterOrMemory::RegisterIndex(RegisterIndex::from(0)),
+                        RegisterOrMemory::RegisterIndex(RegisterIndex::from(1)),
+                    ],
+                },
+                BrilligOpcode::Mov { destination: RegisterIndex::from(0), source: pointer },
+                BrilligOpcode::Stop,
+            ],
+            predicate: None,
+        });
+        let circuit = Circuit {
+            current_witness_index: 4,
+            opcodes: vec![opening],
+            private_parameters: BTreeSet::from([Witness(1), Witness(2)]),
+            public_parameters: PublicInputs(BTreeSet::new()),
+            return_values: PublicInputs(BTreeSet::new()),
+        };
+        let open = |root: u128, index: u128, trees: &[MerkleTree]| {
+            let initial_witness = WitnessMap::from(BTreeMap::from([
+                (Witness(1), FieldElement::from(root)),
+                (Witness(2), FieldElement::from(index)),
+            ]));
+            let options =
+                ExecutionOptions { merkle_trees: trees.to_vec(), ..ExecutionOptions::default() };
+            execute_circuit_with_options(
+                &DummyBlackBoxSolver,
+                circuit.clone(),
+                initial_witness,
+                &options,
+            )
+            .map(|output| output.witness)
+        };
+
+        let solved = open(1234, 2, &[tree.clone()]).unwrap();
+        assert_eq!(solved.get(&Witness(3)), Some(&FieldElement::from(4_u128)));
+        assert_eq!(solved.get(&Witness(4)), Some(&FieldElement::from(12_u128)));
+
+        assert!(matches!(
+            open(1234, 4, &[tree.clone()]),
+            Err(NargoError::MerkleIndexOutOfRange { index, leaf_count: 4 })
+                if index == FieldElement::from(4_u128)
+        ));
+        assert!(matches!(
+            open(4321, 0, &[tree]),
+            Err(NargoError::UnknownMerkleRoot(root)) if root == FieldElement::from(4321_u128)
+        ));
+        assert!(matches!(open(1234, 0, &[]), Err(NargoError::UnknownMerkleRoot(_))));
+    }
 }
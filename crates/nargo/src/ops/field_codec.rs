@@ -0,0 +1,63 @@
+use acvm::{
+    acir::brillig::{ForeignCallOutput, ForeignCallResult, Value},
+    FieldElement,
+};
+use iter_extended::{try_vecmap, vecmap};
+use noirc_printable_type::ForeignCallError;
+
+/// Converts between the ACVM's field elements and the representation used by the host when
+/// handling foreign calls, such as a big integer type from another library.
+pub trait FieldCodec {
+    type HostField;
+
+    fn decode(&self, field: FieldElement) -> Self::HostField;
+
+    /// Fails if `value` cannot be represented as a field element, e.g. as it exceeds the modulus.
+    fn encode(&self, value: &Self::HostField) -> Result<FieldElement, ForeignCallError>;
+
+    /// Decodes the inputs of a foreign call, each of which is a list of values.
+    fn decode_inputs(&self, inputs: &[Vec<Value>]) -> Vec<Vec<Self::HostField>> {
+        vecmap(inputs, |input| vecmap(input, |value| self.decode(value.to_field())))
+    }
+
+    /// Encodes the outputs of a foreign call once it has been handled by the host.
+    fn encode_outputs(
+        &self,
+        outputs: &[HostForeignCallOutput<Self::HostField>],
+    ) -> Result<ForeignCallResult, ForeignCallError> {
+        let values = try_vecmap(outputs, |output| match output {
+            HostForeignCallOutput::Single(value) => {
+                Ok(ForeignCallOutput::Single(Value::from(self.encode(value)?)))
+            }
+            HostForeignCallOutput::Array(values) => {
+                let values = try_vecmap(values, |value| self.encode(value).map(Value::from))?;
+                Ok(ForeignCallOutput::Array(values))
+            }
+        })?;
+        Ok(ForeignCallResult { values })
+    }
+}
+
+/// A single output of a foreign call in the host's representation,
+/// mirroring [`ForeignCallOutput`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostForeignCallOutput<F> {
+    Single(F),
+    Array(Vec<F>),
+}
+
+/// The default codec, for hosts which work with the ACVM's field elements directly.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdentityCodec;
+
+impl FieldCodec for IdentityCodec {
+    type HostField = FieldElement;
+
+    fn decode(&self, field: FieldElement) -> FieldElement {
+        field
+    }
+
+    fn encode(&self, value: &FieldElement) -> Result<FieldElement, ForeignCallError> {
+        Ok(*value)
+    }
+}
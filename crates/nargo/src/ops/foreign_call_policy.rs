@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+/// Which foreign calls may be made during execution, keyed on the name of the function called.
+///
+/// This lets the same circuit be executed with different sets of calls available, such as with
+/// debugging oracles enabled during development but disabled in production. Calls to functions
+/// which haven't been enabled or disabled are always allowed, so the default policy is
+/// permissive.
+#[derive(Debug, Clone, Default)]
+pub struct ForeignCallPolicy {
+    calls: HashMap<String, bool>,
+}
+
+impl ForeignCallPolicy {
+    /// Allows calls to `function`.
+    pub fn enable(&mut self, function: impl Into<String>) {
+        self.calls.insert(function.into(), true);
+    }
+
+    /// Rejects calls to `function`, failing execution if one is made.
+    pub fn disable(&mut self, function: impl Into<String>) {
+        self.calls.insert(function.into(), false);
+    }
+
+    pub fn is_enabled(&self, function: &str) -> bool {
+        self.calls.get(function).copied().unwrap_or(true)
+    }
+}
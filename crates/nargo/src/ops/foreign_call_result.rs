@@ -0,0 +1,151 @@
+use acvm::{
+    acir::brillig::{ForeignCallOutput, ForeignCallResult, Value},
+    FieldElement,
+};
+use iter_extended::vecmap;
+use thiserror::Error;
+
+/// The ways in which the outputs of a [`ForeignCallResult`] can differ from the shape expected
+/// when decoding them.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ForeignCallResultError {
+    #[error("Expected {expected} outputs but got {found}")]
+    WrongOutputCount { expected: usize, found: usize },
+
+    #[error("Expected output {index} to be a single value but it is an array")]
+    ExpectedSingle { index: usize },
+
+    #[error("Expected output {index} to be an array but it is a single value")]
+    ExpectedArray { index: usize },
+
+    #[error("Expected output {index} to be an array of {expected} values but it has {found}")]
+    WrongArrayLength { index: usize, expected: usize, found: usize },
+}
+
+/// Decoding of the outputs of a [`ForeignCallResult`] into field elements, checking that they
+/// have the shape expected.
+pub trait DecodeForeignCallResult {
+    /// Decodes a result consisting of one single value.
+    fn as_single_field(&self) -> Result<FieldElement, ForeignCallResultError>;
+
+    /// Decodes a result consisting of one array of `len` values.
+    fn as_array(&self, len: usize) -> Result<Vec<FieldElement>, ForeignCallResultError>;
+
+    /// Decodes a result consisting of any number of arrays, such as returned for a tuple of
+    /// arrays, into the values of each array in turn.
+    fn as_nested(&self) -> Result<Vec<Vec<FieldElement>>, ForeignCallResultError>;
+}
+
+impl DecodeForeignCallResult for ForeignCallResult {
+    fn as_single_field(&self) -> Result<FieldElement, ForeignCallResultError> {
+        match single_output(self)? {
+            ForeignCallOutput::Single(value) => Ok(value.to_field()),
+            ForeignCallOutput::Array(_) => Err(ForeignCallResultError::ExpectedSingle { index: 0 }),
+        }
+    }
+
+    fn as_array(&self, len: usize) -> Result<Vec<FieldElement>, ForeignCallResultError> {
+        let values = array_values(0, single_output(self)?)?;
+        if values.len() != len {
+            return Err(ForeignCallResultError::WrongArrayLength {
+                index: 0,
+                expected: len,
+                found: values.len(),
+            });
+        }
+        Ok(to_fields(values))
+    }
+
+    fn as_nested(&self) -> Result<Vec<Vec<FieldElement>>, ForeignCallResultError> {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(index, output)| array_values(index, output).map(to_fields))
+            .collect()
+    }
+}
+
+fn single_output(result: &ForeignCallResult) -> Result<&ForeignCallOutput, ForeignCallResultError> {
+    match result.values.as_slice() {
+        [output] => Ok(output),
+        outputs => {
+            Err(ForeignCallResultError::WrongOutputCount { expected: 1, found: outputs.len() })
+        }
+    }
+}
+
+fn array_values(
+    index: usize,
+    output: &ForeignCallOutput,
+) -> Result<&[Value], ForeignCallResultError> {
+    match output {
+        ForeignCallOutput::Array(values) => Ok(values),
+        ForeignCallOutput::Single(_) => Err(ForeignCallResultError::ExpectedArray { index }),
+    }
+}
+
+fn to_fields(values: &[Value]) -> Vec<FieldElement> {
+    vecmap(values, Value::to_field)
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::brillig::{ForeignCallOutput, ForeignCallResult, Value},
+        FieldElement,
+    };
+
+    use super::{DecodeForeignCallResult, ForeignCallResultError};
+
+    fn single(value: u128) -> ForeignCallOutput {
+        ForeignCallOutput::Single(Value::from(value))
+    }
+
+    fn array(values: &[u128]) -> ForeignCallOutput {
+        ForeignCallOutput::Array(values.iter().copied().map(Value::from).collect())
+    }
+
+    fn result(values: Vec<ForeignCallOutput>) -> ForeignCallResult {
+        ForeignCallResult { values }
+    }
+
+    fn fields(values: &[u128]) -> Vec<FieldElement> {
+        values.iter().copied().map(FieldElement::from).collect()
+    }
+
+    #[test]
+    fn decodes_results_of_the_expected_shape() {
+        assert_eq!(result(vec![single(7)]).as_single_field(), Ok(FieldElement::from(7_u128)));
+        assert_eq!(result(vec![array(&[1, 2, 3])]).as_array(3), Ok(fields(&[1, 2, 3])));
+        assert_eq!(
+            result(vec![array(&[1]), array(&[]), array(&[2, 3])]).as_nested(),
+            Ok(vec![fields(&[1]), fields(&[]), fields(&[2, 3])])
+        );
+    }
+
+    #[test]
+    fn rejects_results_of_other_shapes() {
+        use ForeignCallResultError::*;
+
+        assert_eq!(
+            result(vec![]).as_single_field(),
+            Err(WrongOutputCount { expected: 1, found: 0 })
+        );
+        assert_eq!(
+            result(vec![single(1), single(2)]).as_single_field(),
+            Err(WrongOutputCount { expected: 1, found: 2 })
+        );
+        assert_eq!(result(vec![array(&[1])]).as_single_field(), Err(ExpectedSingle { index: 0 }));
+
+        assert_eq!(result(vec![single(1)]).as_array(1), Err(ExpectedArray { index: 0 }));
+        assert_eq!(
+            result(vec![array(&[1, 2])]).as_array(3),
+            Err(WrongArrayLength { index: 0, expected: 3, found: 2 })
+        );
+
+        assert_eq!(
+            result(vec![array(&[1]), single(2)]).as_nested(),
+            Err(ExpectedArray { index: 1 })
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use acvm::pwg::ForeignCallWaitInfo;
+
+/// The expected shape of a single input to a foreign call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArgumentSchema {
+    /// The number of values the input must contain, or `None` to allow any number
+    pub length: Option<usize>,
+    /// The maximum number of bits each value of the input may take up, or `None` for no limit
+    pub max_bits: Option<u32>,
+}
+
+/// The expected inputs to foreign calls, keyed on the name of the function called.
+///
+/// Calls to functions without a schema are always accepted, so the default schema is
+/// permissive.
+#[derive(Debug, Clone, Default)]
+pub struct ForeignCallSchema {
+    calls: HashMap<String, Vec<ArgumentSchema>>,
+}
+
+impl ForeignCallSchema {
+    /// Requires calls to `function` to have one input matching each of `arguments`.
+    pub fn expect(&mut self, function: impl Into<String>, arguments: Vec<ArgumentSchema>) {
+        self.calls.insert(function.into(), arguments);
+    }
+
+    /// Checks the inputs of `foreign_call` against the schema for its function, returning a
+    /// description of the first mismatch found.
+    pub fn check(&self, foreign_call: &ForeignCallWaitInfo) -> Result<(), String> {
+        let Some(arguments) = self.calls.get(&foreign_call.function) else {
+            return Ok(());
+        };
+
+        let inputs = &foreign_call.inputs;
+        if inputs.len() != arguments.len() {
+            return Err(format!("expected {} inputs but got {}", arguments.len(), inputs.len()));
+        }
+
+        for (index, (input, argument)) in inputs.iter().zip(arguments).enumerate() {
+            if let Some(length) = argument.length {
+                if input.len() != length {
+                    return Err(format!(
+                        "expected input {index} to have {length} values but got {}",
+                        input.len()
+                    ));
+                }
+            }
+
+            if let Some(max_bits) = argument.max_bits {
+                let too_large = input.iter().find(|value| value.to_field().num_bits() > max_bits);
+                if let Some(value) = too_large {
+                    return Err(format!(
+                        "expected the values of input {index} to fit in {max_bits} bits but got {}",
+                        value.to_field()
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,20 +1,35 @@
+use std::sync::Once;
+
 use acvm::{
     acir::brillig::{ForeignCallOutput, ForeignCallResult, Value},
     pwg::ForeignCallWaitInfo,
+    FieldElement,
 };
 use iter_extended::vecmap;
-use noirc_printable_type::PrintableValueDisplay;
+use noirc_printable_type::{ForeignCallError, PrintEvent, PrintableValueDisplay};
 
 use crate::NargoError;
 
+use super::execute::ExecutionOptions;
+use super::field_codec::{FieldCodec, HostForeignCallOutput};
+use super::merkle_tree::MerkleTree;
+
 /// This enumeration represents the Brillig foreign calls that are natively supported by nargo.
 /// After resolution of a foreign call, nargo will restart execution of the ACVM
 pub(crate) enum ForeignCall {
     Println,
     Sequence,
     ReverseSequence,
+    EcAdd,
+    Log,
+    Now,
+    HostAssert,
+    MerkleOpen,
 }
 
+/// The timestamp returned by `std::now` unless execution is given another.
+pub const DEFAULT_TIMESTAMP: u64 = 0;
+
 impl std::fmt::Display for ForeignCall {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name())
@@ -27,6 +42,11 @@ impl ForeignCall {
             ForeignCall::Println => "println",
             ForeignCall::Sequence => "get_number_sequence",
             ForeignCall::ReverseSequence => "get_reverse_number_sequence",
+            ForeignCall::EcAdd => "__nargo_ec_add",
+            ForeignCall::Log => "__nargo_log",
+            ForeignCall::Now => "__nargo_now",
+            ForeignCall::HostAssert => "__nargo_host_assert",
+            ForeignCall::MerkleOpen => "__nargo_merkle_open",
         }
     }
 
@@ -35,6 +55,11 @@ impl ForeignCall {
             "println" => Some(ForeignCall::Println),
             "get_number_sequence" => Some(ForeignCall::Sequence),
             "get_reverse_number_sequence" => Some(ForeignCall::ReverseSequence),
+            "__nargo_ec_add" => Some(ForeignCall::EcAdd),
+            "__nargo_log" => Some(ForeignCall::Log),
+            "__nargo_now" => Some(ForeignCall::Now),
+            "__nargo_host_assert" => Some(ForeignCall::HostAssert),
+            "__nargo_merkle_open" => Some(ForeignCall::MerkleOpen),
             _ => None,
         }
     }
@@ -42,11 +67,20 @@ impl ForeignCall {
     pub(crate) fn execute(
         foreign_call: &ForeignCallWaitInfo,
         show_output: bool,
+    ) -> Result<ForeignCallResult, NargoError> {
+        let options = ExecutionOptions { show_output, ..ExecutionOptions::default() };
+        Self::execute_with_options(foreign_call, &options)
+    }
+
+    /// Executes `foreign_call` as configured by `options`.
+    pub(crate) fn execute_with_options(
+        foreign_call: &ForeignCallWaitInfo,
+        options: &ExecutionOptions,
     ) -> Result<ForeignCallResult, NargoError> {
         let foreign_call_name = foreign_call.function.as_str();
         match Self::lookup(foreign_call_name) {
             Some(ForeignCall::Println) => {
-                if show_output {
+                if options.show_output {
                     Self::execute_println(&foreign_call.inputs)?;
                 }
                 Ok(ForeignCallResult { values: vec![] })
@@ -73,13 +107,190 @@ impl ForeignCall {
                     ],
                 })
             }
+            Some(ForeignCall::EcAdd) => {
+                let sum = Self::execute_ec_add(&foreign_call.inputs)?;
+                Ok(ForeignCallResult { values: vec![ForeignCallOutput::Array(sum)] })
+            }
+            Some(ForeignCall::Log) => {
+                Self::execute_log(&foreign_call.inputs)?;
+                Ok(ForeignCallResult { values: vec![] })
+            }
+            Some(ForeignCall::Now) => Ok(ForeignCallResult {
+                values: vec![ForeignCallOutput::Single(u128::from(options.timestamp).into())],
+            }),
+            Some(ForeignCall::HostAssert) => {
+                Self::execute_host_assert(&foreign_call.inputs)?;
+                Ok(ForeignCallResult { values: vec![] })
+            }
+            Some(ForeignCall::MerkleOpen) => {
+                let siblings =
+                    Self::execute_merkle_open(&foreign_call.inputs, &options.merkle_trees)?;
+                Ok(ForeignCallResult { values: vec![ForeignCallOutput::Array(siblings)] })
+            }
             None => panic!("unexpected foreign call {:?}", foreign_call_name),
         }
     }
 
+    /// Adds two points on the Baby Jubjub curve (ERC-2494), each given as an `[x, y]` array of
+    /// affine coordinates, returning the sum in the same form.
+    ///
+    /// This is computed on the host without any constraints, so is only intended for use while
+    /// prototyping circuits which will later perform the addition in-circuit. Baby Jubjub is
+    /// defined over the BN254 scalar field, so this fails for any other field.
+    fn execute_ec_add(foreign_call_inputs: &[Vec<Value>]) -> Result<Vec<Value>, NargoError> {
+        let malformed_input = |message: String| ForeignCallError::InvalidInputs {
+            function: ForeignCall::EcAdd.name().to_string(),
+            message,
+        };
+
+        if !is_bn254_scalar_field() {
+            let message = "Baby Jubjub points require the BN254 scalar field".to_string();
+            return Err(malformed_input(message).into());
+        }
+
+        let [p1, p2] = foreign_call_inputs else {
+            let message = format!("expected 2 points but got {}", foreign_call_inputs.len());
+            return Err(malformed_input(message).into());
+        };
+        let coordinates = |point: &[Value], index: usize| match point {
+            [x, y] => Ok((x.to_field(), y.to_field())),
+            _ => Err(malformed_input(format!(
+                "expected point {index} to have 2 coordinates but got {}",
+                point.len()
+            ))),
+        };
+        let (x1, y1) = coordinates(p1, 1)?;
+        let (x2, y2) = coordinates(p2, 2)?;
+
+        // Twisted Edwards curve `a*x^2 + y^2 = 1 + d*x^2*y^2` with Baby Jubjub's parameters
+        let a = FieldElement::from(168700_u128);
+        let d = FieldElement::from(168696_u128);
+
+        let t = d * x1 * x2 * y1 * y2;
+        let x_denominator = FieldElement::one() + t;
+        let y_denominator = FieldElement::one() - t;
+        if x_denominator.is_zero() || y_denominator.is_zero() {
+            let message = "points are not on the Baby Jubjub curve".to_string();
+            return Err(malformed_input(message).into());
+        }
+
+        let x3 = (x1 * y2 + y1 * x2) * x_denominator.inverse();
+        let y3 = (y1 * y2 - a * x1 * x2) * y_denominator.inverse();
+        Ok(vec![Value::from(x3), Value::from(y3)])
+    }
+
+    /// Executes `foreign_call` as configured by `options` if it is natively supported by nargo.
+    /// Otherwise it is passed to `host_call`, with its inputs and outputs converted to and from
+    /// the host's representation by `codec`.
+    pub(crate) fn execute_with_host<C, H>(
+        foreign_call: &ForeignCallWaitInfo,
+        options: &ExecutionOptions,
+        codec: &C,
+        host_call: &mut H,
+    ) -> Result<ForeignCallResult, NargoError>
+    where
+        C: FieldCodec,
+        H: FnMut(
+            &str,
+            Vec<Vec<C::HostField>>,
+        ) -> Result<Vec<HostForeignCallOutput<C::HostField>>, ForeignCallError>,
+    {
+        if Self::lookup(&foreign_call.function).is_some() {
+            return Self::execute_with_options(foreign_call, options);
+        }
+
+        let inputs = codec.decode_inputs(&foreign_call.inputs);
+        let outputs = host_call(&foreign_call.function, inputs)?;
+        Ok(codec.encode_outputs(&outputs)?)
+    }
+
+    /// Returns the sibling path of a leaf of the tree in `trees` with the given root, for a
+    /// `std::merkle_open(root, index)` call. The path starts with the sibling of the leaf itself.
+    ///
+    /// The path is read from the host's tree without any constraints, so the circuit must check
+    /// it hashes up to the root.
+    fn execute_merkle_open(
+        foreign_call_inputs: &[Vec<Value>],
+        trees: &[MerkleTree],
+    ) -> Result<Vec<Value>, NargoError> {
+        let [root, index] = foreign_call_inputs else {
+            return Err(ForeignCallError::InvalidInputs {
+                function: ForeignCall::MerkleOpen.name().to_string(),
+                message: format!("expected 2 inputs but got {}", foreign_call_inputs.len()),
+            }
+            .into());
+        };
+        let root = root.first().ok_or(ForeignCallError::MissingForeignCallInputs)?.to_field();
+        let index = index.first().ok_or(ForeignCallError::MissingForeignCallInputs)?.to_field();
+
+        let tree = trees
+            .iter()
+            .find(|tree| tree.root() == root)
+            .ok_or(NargoError::UnknownMerkleRoot(root))?;
+        let siblings = index
+            .try_into_u128()
+            .and_then(|index| usize::try_from(index).ok())
+            .and_then(|index| tree.opening(index))
+            .ok_or(NargoError::MerkleIndexOutOfRange { index, leaf_count: tree.leaf_count() })?;
+        Ok(vecmap(siblings, Value::from))
+    }
+
+    /// Emits the message of a `log` call as a `tracing` event. Levels are numbered as in the
+    /// `log` crate, from 1 for `error` up to 5 for `trace`. Any other level is logged at `info`.
+    fn execute_log(foreign_call_inputs: &[Vec<Value>]) -> Result<(), NargoError> {
+        static INVALID_LEVEL_WARNING: Once = Once::new();
+
+        let (level, message) =
+            foreign_call_inputs.split_first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+        let level = level.first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+        let level = level.to_field().to_u128();
+        let message: PrintableValueDisplay = message.try_into()?;
+
+        match level {
+            1 => tracing::error!("{message}"),
+            2 => tracing::warn!("{message}"),
+            3 => tracing::info!("{message}"),
+            4 => tracing::debug!("{message}"),
+            5 => tracing::trace!("{message}"),
+            _ => {
+                INVALID_LEVEL_WARNING.call_once(|| {
+                    tracing::warn!("log level {level} is out of range 1..=5, logging at info");
+                });
+                tracing::info!("{message}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the condition of a `host_assert` call, failing execution with its message if the
+    /// condition is zero. Any nonzero condition holds.
+    ///
+    /// The check happens only on the host executing the program: it adds no constraints, so a
+    /// proof says nothing about whether the condition held. It gives no cryptographic guarantee
+    /// whatsoever and must never stand in for an `assert`.
+    fn execute_host_assert(foreign_call_inputs: &[Vec<Value>]) -> Result<(), NargoError> {
+        let (condition, message) =
+            foreign_call_inputs.split_first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+        let condition = condition.first().ok_or(ForeignCallError::MissingForeignCallInputs)?;
+        if !condition.to_field().is_zero() {
+            return Ok(());
+        }
+
+        let message: PrintableValueDisplay = message.try_into()?;
+        Err(NargoError::HostAssertionFailed(message.to_string()))
+    }
+
     fn execute_println(foreign_call_inputs: &[Vec<Value>]) -> Result<(), NargoError> {
-        let display_values: PrintableValueDisplay = foreign_call_inputs.try_into()?;
-        println!("{display_values}");
+        let print_event: PrintEvent = foreign_call_inputs.try_into()?;
+        println!("{print_event}");
         Ok(())
     }
 }
+
+/// Returns true if [`FieldElement`] is the scalar field of BN254, which `acvm` uses unless built
+/// for another curve.
+fn is_bn254_scalar_field() -> bool {
+    const BN254_MODULUS_MINUS_ONE: &str =
+        "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000";
+    (-FieldElement::one()).to_hex().trim_start_matches('0') == BN254_MODULUS_MINUS_ONE
+}
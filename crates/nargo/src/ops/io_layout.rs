@@ -0,0 +1,80 @@
+use std::collections::BTreeSet;
+
+use acvm::acir::{
+    circuit::Circuit,
+    native_types::{Witness, WitnessMap},
+};
+
+/// The witnesses through which a circuit receives its inputs and produces its outputs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IoLayout {
+    pub public_inputs: BTreeSet<Witness>,
+    pub private_inputs: BTreeSet<Witness>,
+    /// Return values are always public, but are not included in `public_inputs`
+    pub return_values: BTreeSet<Witness>,
+}
+
+/// Returns which witnesses of `circuit` are its public inputs, private inputs and return values.
+///
+/// This is read from the circuit's own metadata so does not require executing it.
+pub fn circuit_io_layout(circuit: &Circuit) -> IoLayout {
+    let return_values = circuit.return_values.0.clone();
+
+    // A parameter which is returned directly is only counted as a return value
+    let public_inputs = circuit.public_parameters.0.difference(&return_values).copied().collect();
+    let private_inputs = circuit.private_parameters.difference(&return_values).copied().collect();
+
+    IoLayout { public_inputs, private_inputs, return_values }
+}
+
+/// Checks that `initial_witness` assigns a value to every input of `circuit`, returning the
+/// inputs which are missing in ascending order otherwise.
+///
+/// Executing a circuit with a missing input fails part way through with an unsatisfied opcode,
+/// so this allows the missing inputs to be reported before execution starts.
+pub fn check_initial_witness(
+    circuit: &Circuit,
+    initial_witness: &WitnessMap,
+) -> Result<(), Vec<Witness>> {
+    let inputs = circuit.private_parameters.union(&circuit.public_parameters.0);
+    let missing: BTreeSet<Witness> =
+        inputs.filter(|input| initial_witness.get(input).is_none()).copied().collect();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use acvm::acir::circuit::{Circuit, PublicInputs};
+    use acvm::acir::native_types::{Witness, WitnessMap};
+    use acvm::FieldElement;
+
+    use super::check_initial_witness;
+
+    #[test]
+    fn reports_missing_inputs() {
+        let circuit = Circuit {
+            current_witness_index: 4,
+            opcodes: vec![],
+            private_parameters: BTreeSet::from([Witness(1), Witness(3)]),
+            public_parameters: PublicInputs(BTreeSet::from([Witness(2)])),
+            return_values: PublicInputs(BTreeSet::from([Witness(4)])),
+        };
+        let assigned = |witnesses: &[u32]| {
+            let values = witnesses.iter().map(|index| (Witness(*index), FieldElement::one()));
+            WitnessMap::from(BTreeMap::from_iter(values))
+        };
+
+        assert_eq!(check_initial_witness(&circuit, &assigned(&[1, 2, 3])), Ok(()));
+        assert_eq!(
+            check_initial_witness(&circuit, &assigned(&[1, 4])),
+            Err(vec![Witness(2), Witness(3)])
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Merkle trees supplied by the host, for opening commitments with `std::merkle_open`.
+//!
+//! Nothing is hashed while executing: the host provides every layer of each tree, so the
+//! circuit is free to use whichever hash function it commits with. The sibling paths returned
+//! are unconstrained and must be checked against the root in-circuit.
+
+use acvm::FieldElement;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MerkleTreeError {
+    #[error("Merkle tree has no layers")]
+    Empty,
+
+    #[error("Merkle tree has {0} leaves but must have a power of two")]
+    LeafCountNotPowerOfTwo(usize),
+
+    #[error("Layer {layer} of Merkle tree has {found} nodes but {expected} were expected")]
+    LayerSizeMismatch { layer: usize, expected: usize, found: usize },
+}
+
+/// A binary Merkle tree of fixed depth, stored as each of its layers from the leaves up to the
+/// root. Node `i` of each layer is the parent of nodes `2 * i` and `2 * i + 1` in the layer
+/// below.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    layers: Vec<Vec<FieldElement>>,
+}
+
+impl MerkleTree {
+    /// Creates a tree from its layers, starting with the leaves and ending with a layer holding
+    /// only the root. There must be a power of two leaves, with each layer half the size of the
+    /// one below it.
+    pub fn from_layers(layers: Vec<Vec<FieldElement>>) -> Result<MerkleTree, MerkleTreeError> {
+        let leaf_count = layers.first().ok_or(MerkleTreeError::Empty)?.len();
+        if !leaf_count.is_power_of_two() {
+            return Err(MerkleTreeError::LeafCountNotPowerOfTwo(leaf_count));
+        }
+
+        let depth = leaf_count.trailing_zeros() as usize;
+        if layers.len() != depth + 1 {
+            // Report the first layer which is missing or shouldn't be there
+            let layer = layers.len().min(depth + 1);
+            let expected = if layer <= depth { leaf_count >> layer } else { 0 };
+            let found = layers.get(layer).map_or(0, Vec::len);
+            return Err(MerkleTreeError::LayerSizeMismatch { layer, expected, found });
+        }
+        for (layer, nodes) in layers.iter().enumerate() {
+            let expected = leaf_count >> layer;
+            if nodes.len() != expected {
+                return Err(MerkleTreeError::LayerSizeMismatch {
+                    layer,
+                    expected,
+                    found: nodes.len(),
+                });
+            }
+        }
+        Ok(MerkleTree { layers })
+    }
+
+    pub fn root(&self) -> FieldElement {
+        self.layers[self.depth()][0]
+    }
+
+    /// The number of layers above the leaves, which is also the length of every sibling path.
+    pub fn depth(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    /// Returns the siblings of the leaf at `index` and of each of its ancestors below the root,
+    /// starting with the leaf's own sibling, or `None` if there is no such leaf.
+    pub fn opening(&self, index: usize) -> Option<Vec<FieldElement>> {
+        if index >= self.leaf_count() {
+            return None;
+        }
+        let siblings = self.layers[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(level, nodes)| nodes[(index >> level) ^ 1])
+            .collect();
+        Some(siblings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+
+    use super::{MerkleTree, MerkleTreeError};
+
+    fn fields(values: &[u128]) -> Vec<FieldElement> {
+        values.iter().map(|value| FieldElement::from(*value)).collect()
+    }
+
+    #[test]
+    fn opens_each_leaf() {
+        let tree = MerkleTree::from_layers(vec![
+            fields(&[1, 2, 3, 4]),
+            fields(&[12, 34]),
+            fields(&[1234]),
+        ])
+        .unwrap();
+        assert_eq!(tree.root(), FieldElement::from(1234_u128));
+        assert_eq!(tree.depth(), 2);
+
+        assert_eq!(tree.opening(0), Some(fields(&[2, 34])));
+        assert_eq!(tree.opening(1), Some(fields(&[1, 34])));
+        assert_eq!(tree.opening(2), Some(fields(&[4, 12])));
+        assert_eq!(tree.opening(3), Some(fields(&[3, 12])));
+        assert_eq!(tree.opening(4), None);
+    }
+
+    #[test]
+    fn rejects_malformed_layers() {
+        assert_eq!(MerkleTree::from_layers(vec![]), Err(MerkleTreeError::Empty));
+        assert_eq!(
+            MerkleTree::from_layers(vec![fields(&[1, 2, 3]), fields(&[4, 5]), fields(&[6])]),
+            Err(MerkleTreeError::LeafCountNotPowerOfTwo(3))
+        );
+        assert_eq!(
+            MerkleTree::from_layers(vec![
+                fields(&[1, 2, 3, 4]),
+                fields(&[12, 34, 56]),
+                fields(&[7])
+            ]),
+            Err(MerkleTreeError::LayerSizeMismatch { layer: 1, expected: 2, found: 3 })
+        );
+        assert_eq!(
+            MerkleTree::from_layers(vec![fields(&[1, 2]), fields(&[12])]).map(|tree| tree.depth()),
+            Ok(1)
+        );
+        assert_eq!(
+            MerkleTree::from_layers(vec![fields(&[1, 2, 3, 4]), fields(&[12, 34])]),
+            Err(MerkleTreeError::LayerSizeMismatch { layer: 2, expected: 1, found: 0 })
+        );
+    }
+}
@@ -1,12 +1,58 @@
+pub use self::blackbox_solver::CompositeBlackBoxSolver;
+pub use self::circuit_builder::{CircuitBuilder, CircuitBuilderError};
 pub use self::codegen_verifier::codegen_verifier;
-pub use self::execute::execute_circuit;
+pub use self::compact_circuit::{
+    load_compact_circuit, CompactCircuit, CompactCircuitError, COMPACT_CIRCUIT_VERSION,
+};
+pub use self::execute::{
+    execute_circuit, execute_circuit_spilling, execute_circuit_streaming,
+    execute_circuit_with_batched_calls, execute_circuit_with_host_calls,
+    execute_circuit_with_options, execute_circuit_with_print_events, execute_unconstrained,
+    find_alternate_solution, stream_to_channel, ExecutionEvent, ExecutionOptions, ExecutionOutput,
+    ExecutionStats,
+};
+pub use self::field_codec::{FieldCodec, HostForeignCallOutput, IdentityCodec};
+pub use self::foreign_call_policy::ForeignCallPolicy;
+pub use self::foreign_call_result::{DecodeForeignCallResult, ForeignCallResultError};
+pub use self::foreign_call_schema::{ArgumentSchema, ForeignCallSchema};
+pub use self::foreign_calls::DEFAULT_TIMESTAMP;
+pub use self::io_layout::{check_initial_witness, circuit_io_layout, IoLayout};
+pub use self::merkle_tree::{MerkleTree, MerkleTreeError};
+pub use self::overflow::OverflowingOperation;
 pub use self::prove::prove_execution;
 pub use self::test::{run_test, TestStatus};
+pub use self::transcript::{
+    ExecutionTranscript, ExecutionTranscriptError, OpcodeTranscript, EXECUTION_TRANSCRIPT_VERSION,
+};
+pub use self::value_predicate::ValuePredicate;
 pub use self::verify::verify_proof;
+pub use self::witness_backing::{
+    FileWitnessBacking, InMemoryWitnessBacking, WitnessBacking, WitnessBackingError,
+};
+pub use self::witness_log::{WitnessAssignment, WitnessLog, WitnessLogError, WITNESS_LOG_VERSION};
+pub use self::witness_serializer::{
+    read_witness, write_witness, BinaryWitnessSerializer, JsonWitnessSerializer,
+    WitnessSerializationError, WitnessSerializer,
+};
 
+mod blackbox_solver;
+mod circuit_builder;
 mod codegen_verifier;
+mod compact_circuit;
 mod execute;
+mod field_codec;
+mod foreign_call_policy;
+mod foreign_call_result;
+mod foreign_call_schema;
 mod foreign_calls;
+mod io_layout;
+mod merkle_tree;
+mod overflow;
 mod prove;
 mod test;
+mod transcript;
+mod value_predicate;
 mod verify;
+mod witness_backing;
+mod witness_log;
+mod witness_serializer;
@@ -0,0 +1,126 @@
+//! Recognition of the integer operations whose results fail their range constraints.
+//!
+//! The result of an integer operation is assigned to a witness by an arithmetic opcode and then
+//! range constrained to the bit size of its type. When that range constraint fails, the
+//! arithmetic opcode assigning the witness is found among the earlier opcodes so that the
+//! operation and its operands can be reported rather than the constraint alone.
+
+use std::fmt::Display;
+
+use acvm::acir::circuit::Opcode;
+use acvm::acir::native_types::{Expression, Witness, WitnessMap};
+use acvm::FieldElement;
+
+use crate::FieldDisplayMode;
+
+/// An integer operation whose result didn't fit in the bit size of its type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowingOperation {
+    Add {
+        lhs: FieldElement,
+        rhs: FieldElement,
+    },
+    Subtract {
+        lhs: FieldElement,
+        rhs: FieldElement,
+    },
+    Multiply {
+        lhs: FieldElement,
+        rhs: FieldElement,
+    },
+    /// A value which couldn't be attributed to any of the operations above, such as an input
+    /// to the circuit or the result of an opcode of another shape
+    Value(FieldElement),
+}
+
+impl OverflowingOperation {
+    /// Formats the operation with its operands formatted according to `mode`.
+    pub fn display_with(&self, mode: FieldDisplayMode) -> String {
+        let format = |field: &FieldElement| mode.format(*field);
+        match self {
+            OverflowingOperation::Add { lhs, rhs } => format!("{} + {}", format(lhs), format(rhs)),
+            OverflowingOperation::Subtract { lhs, rhs } => {
+                format!("{} - {}", format(lhs), format(rhs))
+            }
+            OverflowingOperation::Multiply { lhs, rhs } => {
+                format!("{} * {}", format(lhs), format(rhs))
+            }
+            OverflowingOperation::Value(value) => format(value),
+        }
+    }
+}
+
+impl Display for OverflowingOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_with(FieldDisplayMode::Decimal))
+    }
+}
+
+/// Returns the operation which assigned `witness`, a witness range constrained by the opcode at
+/// `range_index`, with the values of its operands in `witness_map`. Only the last arithmetic
+/// opcode before the range constraint which uses `witness` is considered, and the operation is
+/// only recognized if that opcode assigns `witness` the sum, difference or product of two
+/// witnesses which have been solved. Anything else is reported as `witness`'s value.
+pub(super) fn overflowing_operation(
+    opcodes: &[Opcode],
+    range_index: usize,
+    witness: Witness,
+    witness_map: &WitnessMap,
+) -> Option<OverflowingOperation> {
+    let value = *witness_map.get(&witness)?;
+    let assignment = opcodes[..range_index].iter().rev().find_map(|opcode| match opcode {
+        Opcode::Arithmetic(expr) if uses_witness(expr, witness) => Some(expr),
+        _ => None,
+    });
+    let operation = assignment.and_then(|expr| recognize_operation(expr, witness, witness_map));
+    Some(operation.unwrap_or(OverflowingOperation::Value(value)))
+}
+
+fn uses_witness(expr: &Expression, witness: Witness) -> bool {
+    expr.linear_combinations.iter().any(|(_, term)| *term == witness)
+        || expr.mul_terms.iter().any(|(_, lhs, rhs)| *lhs == witness || *rhs == witness)
+}
+
+/// Recognizes `expr` as `witness = lhs + rhs`, `witness = lhs - rhs` or `witness = lhs * rhs`,
+/// in any arrangement of its terms.
+fn recognize_operation(
+    expr: &Expression,
+    witness: Witness,
+    witness_map: &WitnessMap,
+) -> Option<OverflowingOperation> {
+    if !expr.q_c.is_zero() {
+        return None;
+    }
+    let (result_coefficient, _) =
+        expr.linear_combinations.iter().find(|(_, term)| *term == witness)?;
+    // Each remaining term's coefficient when the expression is solved for `witness`
+    let normalize = |coefficient: &FieldElement| -*coefficient / *result_coefficient;
+    let operands: Vec<_> = expr
+        .linear_combinations
+        .iter()
+        .filter(|(_, term)| *term != witness)
+        .map(|(coefficient, term)| (normalize(coefficient), *term))
+        .collect();
+    let value = |operand: Witness| witness_map.get(&operand).copied();
+    let one = FieldElement::one();
+
+    match (expr.mul_terms.as_slice(), operands.as_slice()) {
+        ([(coefficient, lhs, rhs)], []) if normalize(coefficient) == one => {
+            Some(OverflowingOperation::Multiply { lhs: value(*lhs)?, rhs: value(*rhs)? })
+        }
+        ([], [(lhs_coefficient, lhs), (rhs_coefficient, rhs)]) => {
+            let (lhs, rhs) = (value(*lhs)?, value(*rhs)?);
+            match (*lhs_coefficient == one, *rhs_coefficient == one) {
+                (true, true) => Some(OverflowingOperation::Add { lhs, rhs }),
+                (true, false) if *rhs_coefficient == -one => {
+                    Some(OverflowingOperation::Subtract { lhs, rhs })
+                }
+                (false, true) if *lhs_coefficient == -one => {
+                    Some(OverflowingOperation::Subtract { lhs: rhs, rhs: lhs })
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,199 @@
+//! A versioned record of the values read and assigned by each opcode while executing a circuit.
+//!
+//! Transcripts are recorded when executing a circuit with
+//! [`ExecutionOptions::record_transcript`][super::ExecutionOptions::record_transcript] set, and
+//! are intended for comparing nargo's execution of a circuit against that of another executor or
+//! prover, in order to find the first opcode at which they diverge.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::acir::circuit::brillig::BrilligInputs;
+use acvm::acir::circuit::Opcode;
+use acvm::acir::native_types::{Expression, Witness};
+use acvm::FieldElement;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The version of the format written by [`ExecutionTranscript::to_json`]. This must be bumped
+/// whenever the transcript changes in a way which would alter its JSON.
+pub const EXECUTION_TRANSCRIPT_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum ExecutionTranscriptError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("Execution transcript has format version {found} but version {expected} is required")]
+    VersionMismatch { found: u32, expected: u32 },
+}
+
+/// The opcodes solved while executing a circuit, in the order they were solved.
+///
+/// As JSON this is an object holding the format `version` and an entry for each opcode, with
+/// witness values given as hex strings in the same way as the JSON witness format, e.g.
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "opcodes": [{ "opcode_index": 0, "inputs": { "1": "0x03" }, "outputs": { "2": "0x09" } }]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionTranscript {
+    pub version: u32,
+    pub opcodes: Vec<OpcodeTranscript>,
+}
+
+/// The values read and assigned by a single opcode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpcodeTranscript {
+    pub opcode_index: usize,
+    /// The witnesses read by the opcode which were assigned before it was solved, keyed on their
+    /// indices. These are only recorded for arithmetic, Brillig and black box function opcodes.
+    #[serde(with = "hex_values")]
+    pub inputs: BTreeMap<u32, FieldElement>,
+    /// The witnesses assigned by solving the opcode, keyed on their indices
+    #[serde(with = "hex_values")]
+    pub outputs: BTreeMap<u32, FieldElement>,
+}
+
+impl ExecutionTranscript {
+    pub(crate) fn new() -> ExecutionTranscript {
+        ExecutionTranscript { version: EXECUTION_TRANSCRIPT_VERSION, opcodes: Vec::new() }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("transcripts are always serializable")
+    }
+
+    /// Reads a transcript written by [`ExecutionTranscript::to_json`], failing if it was written
+    /// in another version of the format.
+    pub fn from_json(json: &str) -> Result<ExecutionTranscript, ExecutionTranscriptError> {
+        // The version is read on its own first, as the rest may not parse in other versions
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u32,
+        }
+
+        let Versioned { version } = serde_json::from_str(json)?;
+        if version != EXECUTION_TRANSCRIPT_VERSION {
+            return Err(ExecutionTranscriptError::VersionMismatch {
+                found: version,
+                expected: EXECUTION_TRANSCRIPT_VERSION,
+            });
+        }
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Returns the witnesses read by `opcode`. Only arithmetic, Brillig and black box function
+/// opcodes are inspected, with no witnesses returned for any other opcode.
+pub(super) fn opcode_input_witnesses(opcode: &Opcode) -> BTreeSet<Witness> {
+    let mut witnesses = BTreeSet::new();
+    match opcode {
+        Opcode::Arithmetic(expr) => add_expression_witnesses(expr, &mut witnesses),
+        Opcode::Brillig(brillig) => {
+            for input in &brillig.inputs {
+                match input {
+                    BrilligInputs::Single(expr) => add_expression_witnesses(expr, &mut witnesses),
+                    BrilligInputs::Array(exprs) => {
+                        for expr in exprs {
+                            add_expression_witnesses(expr, &mut witnesses);
+                        }
+                    }
+                }
+            }
+            if let Some(predicate) = &brillig.predicate {
+                add_expression_witnesses(predicate, &mut witnesses);
+            }
+        }
+        Opcode::BlackBoxFuncCall(call) => {
+            witnesses.extend(call.get_inputs_vec().iter().map(|input| input.witness));
+        }
+        _ => (),
+    }
+    witnesses
+}
+
+pub(super) fn add_expression_witnesses(expr: &Expression, witnesses: &mut BTreeSet<Witness>) {
+    for (_, lhs, rhs) in &expr.mul_terms {
+        witnesses.insert(*lhs);
+        witnesses.insert(*rhs);
+    }
+    witnesses.extend(expr.linear_combinations.iter().map(|(_, witness)| *witness));
+}
+
+/// Serializes witness values as hex strings, as in the JSON witness format.
+mod hex_values {
+    use std::collections::BTreeMap;
+
+    use acvm::FieldElement;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        values: &BTreeMap<u32, FieldElement>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let hex_values: BTreeMap<u32, String> =
+            values.iter().map(|(index, value)| (*index, format!("0x{}", value.to_hex()))).collect();
+        hex_values.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<u32, FieldElement>, D::Error> {
+        let hex_values = BTreeMap::<u32, String>::deserialize(deserializer)?;
+        hex_values
+            .into_iter()
+            .map(|(index, value)| match FieldElement::from_hex(&value) {
+                Some(field) => Ok((index, field)),
+                None => {
+                    let message = format!("invalid value `{value}` for witness {index}");
+                    Err(D::Error::custom(message))
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::FieldElement;
+
+    use super::{
+        ExecutionTranscript, ExecutionTranscriptError, OpcodeTranscript,
+        EXECUTION_TRANSCRIPT_VERSION,
+    };
+
+    fn transcript() -> ExecutionTranscript {
+        let mut transcript = ExecutionTranscript::new();
+        transcript.opcodes.push(OpcodeTranscript {
+            opcode_index: 0,
+            inputs: BTreeMap::from([(1, FieldElement::from(3_u128))]),
+            outputs: BTreeMap::from([(2, -FieldElement::one())]),
+        });
+        transcript
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let json = transcript().to_json();
+        assert!(json.contains("\"1\": \"0x"));
+        assert_eq!(ExecutionTranscript::from_json(&json).unwrap(), transcript());
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut other_version = transcript();
+        other_version.version = EXECUTION_TRANSCRIPT_VERSION + 1;
+
+        let error = ExecutionTranscript::from_json(&other_version.to_json()).unwrap_err();
+        assert!(matches!(
+            error,
+            ExecutionTranscriptError::VersionMismatch { found, expected: EXECUTION_TRANSCRIPT_VERSION }
+                if found == EXECUTION_TRANSCRIPT_VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,56 @@
+//! Conditions on the values witnesses may take, checked by the host as execution assigns them.
+//!
+//! These are runtime invariants independent of the circuit's own constraints: a witness
+//! violating its predicate fails execution even if the circuit would be satisfied, which helps
+//! to catch miscompilations such as a selector which is no longer constrained to be boolean.
+
+use std::fmt::Display;
+
+use acvm::FieldElement;
+
+use crate::FieldDisplayMode;
+
+/// The values a witness may be assigned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValuePredicate {
+    /// Only the given values are allowed
+    OneOf(Vec<FieldElement>),
+    /// Any value other than the given ones is allowed
+    NoneOf(Vec<FieldElement>),
+    /// The value must fit in the given number of bits
+    MaxBits(u32),
+}
+
+impl ValuePredicate {
+    /// Allows only 0 and 1, as for a selector.
+    pub fn boolean() -> ValuePredicate {
+        ValuePredicate::OneOf(vec![FieldElement::zero(), FieldElement::one()])
+    }
+
+    pub fn holds(&self, value: FieldElement) -> bool {
+        match self {
+            ValuePredicate::OneOf(allowed) => allowed.contains(&value),
+            ValuePredicate::NoneOf(forbidden) => !forbidden.contains(&value),
+            ValuePredicate::MaxBits(bits) => value.num_bits() <= *bits,
+        }
+    }
+
+    /// Formats the predicate with any values in it formatted according to `mode`.
+    pub fn display_with(&self, mode: FieldDisplayMode) -> String {
+        let format = |values: &[FieldElement]| {
+            let values: Vec<String> = values.iter().map(|value| mode.format(*value)).collect();
+            format!("{{{}}}", values.join(", "))
+        };
+        match self {
+            ValuePredicate::OneOf(allowed) => format!("one of {}", format(allowed)),
+            ValuePredicate::NoneOf(forbidden) => format!("none of {}", format(forbidden)),
+            ValuePredicate::MaxBits(bits) => format!("less than 2^{bits}"),
+        }
+    }
+}
+
+impl Display for ValuePredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_with(FieldDisplayMode::Decimal))
+    }
+}
@@ -0,0 +1,169 @@
+//! Storage for the witnesses spilled out of memory by
+//! [`execute_circuit_spilling`][super::execute_circuit_spilling].
+//!
+//! A [`FileWitnessBacking`] appends each spilled chunk of witnesses to its file as a record made
+//! of the length of the chunk in bytes, as a little-endian `u64`, followed by the chunk itself in
+//! the same format as the `.tr` witness files written by `nargo execute`: the witness map
+//! serialized with bincode and then gzip compressed. Chunks hold disjoint sets of witnesses, so
+//! the spilled witness map is the union of every record in the file.
+
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+use acvm::acir::native_types::{WitnessMap, WitnessMapError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WitnessBackingError {
+    #[error("Failed to access witness file {}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    #[error("Witness file {} ends within a record", path.display())]
+    Truncated { path: PathBuf },
+
+    #[error(transparent)]
+    Serialization(#[from] WitnessMapError),
+}
+
+/// A location to which witnesses can be moved out of memory and later restored from.
+pub trait WitnessBacking {
+    /// Moves `witnesses` into the backing, alongside any witnesses spilled previously.
+    fn spill(&mut self, witnesses: WitnessMap) -> Result<(), WitnessBackingError>;
+
+    /// Takes every witness spilled so far out of the backing, leaving it empty.
+    fn restore(&mut self) -> Result<WitnessMap, WitnessBackingError>;
+}
+
+/// Keeps spilled witnesses in memory. Executing a circuit with this backing uses as much memory
+/// as executing it without spilling, so this is mainly useful for testing.
+#[derive(Debug, Default)]
+pub struct InMemoryWitnessBacking {
+    witnesses: WitnessMap,
+}
+
+impl WitnessBacking for InMemoryWitnessBacking {
+    fn spill(&mut self, witnesses: WitnessMap) -> Result<(), WitnessBackingError> {
+        for (witness, value) in witnesses {
+            self.witnesses.insert(witness, value);
+        }
+        Ok(())
+    }
+
+    fn restore(&mut self) -> Result<WitnessMap, WitnessBackingError> {
+        Ok(std::mem::take(&mut self.witnesses))
+    }
+}
+
+/// Appends spilled witnesses to a file, in the format described in the [module docs][self].
+/// The file is created on the first spill and removed once the witnesses have been restored.
+#[derive(Debug)]
+pub struct FileWitnessBacking {
+    path: PathBuf,
+}
+
+impl FileWitnessBacking {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileWitnessBacking { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn io_error(&self, source: std::io::Error) -> WitnessBackingError {
+        WitnessBackingError::Io { path: self.path.clone(), source }
+    }
+
+    /// Reads the next record from `file`, returning `None` at the end of the file.
+    fn read_record(&self, file: &mut File) -> Result<Option<Vec<u8>>, WitnessBackingError> {
+        let mut length = [0; 8];
+        match file.read_exact(&mut length) {
+            Ok(()) => (),
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(self.io_error(error)),
+        }
+        let mut record = vec![0; u64::from_le_bytes(length) as usize];
+        file.read_exact(&mut record).map_err(|error| match error.kind() {
+            ErrorKind::UnexpectedEof => WitnessBackingError::Truncated { path: self.path.clone() },
+            _ => self.io_error(error),
+        })?;
+        Ok(Some(record))
+    }
+}
+
+impl WitnessBacking for FileWitnessBacking {
+    fn spill(&mut self, witnesses: WitnessMap) -> Result<(), WitnessBackingError> {
+        let record: Vec<u8> = witnesses.try_into()?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|error| self.io_error(error))?;
+        file.write_all(&(record.len() as u64).to_le_bytes())
+            .and_then(|()| file.write_all(&record))
+            .map_err(|error| self.io_error(error))
+    }
+
+    fn restore(&mut self) -> Result<WitnessMap, WitnessBackingError> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            // Nothing has been spilled
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(WitnessMap::new()),
+            Err(error) => return Err(self.io_error(error)),
+        };
+        let mut witnesses = WitnessMap::new();
+        while let Some(record) = self.read_record(&mut file)? {
+            for (witness, value) in WitnessMap::try_from(record.as_slice())? {
+                witnesses.insert(witness, value);
+            }
+        }
+        std::fs::remove_file(&self.path).map_err(|error| self.io_error(error))?;
+        Ok(witnesses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::native_types::{Witness, WitnessMap};
+    use acvm::FieldElement;
+
+    use super::{FileWitnessBacking, WitnessBacking, WitnessBackingError};
+
+    fn chunk(witnesses: &[u32]) -> WitnessMap {
+        WitnessMap::from(
+            witnesses
+                .iter()
+                .map(|index| (Witness(*index), FieldElement::from(*index as u128)))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn file_backing_restores_every_spilled_chunk() {
+        let path = std::env::temp_dir()
+            .join(format!("nargo-witness-backing-{}-restore", std::process::id()));
+        let mut backing = FileWitnessBacking::new(&path);
+
+        assert_eq!(backing.restore().unwrap(), WitnessMap::new());
+        backing.spill(chunk(&[1, 2])).unwrap();
+        backing.spill(chunk(&[4])).unwrap();
+        assert_eq!(backing.restore().unwrap(), chunk(&[1, 2, 4]));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn file_backing_rejects_truncated_records() {
+        let path = std::env::temp_dir()
+            .join(format!("nargo-witness-backing-{}-truncated", std::process::id()));
+        let mut backing = FileWitnessBacking::new(&path);
+
+        backing.spill(chunk(&[1, 2])).unwrap();
+        let contents = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &contents[..contents.len() - 1]).unwrap();
+        assert!(matches!(backing.restore(), Err(WitnessBackingError::Truncated { .. })));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,210 @@
+//! A versioned, ordered record of the witnesses assigned while executing a circuit.
+//!
+//! Witness logs are recorded when executing a circuit with
+//! [`ExecutionOptions::record_witness_log`][super::ExecutionOptions::record_witness_log] set, and
+//! are intended for exporting an execution to external tools, such as SMT-based checkers, which
+//! need to know which opcode assigned each witness as well as its value. Replaying the log in
+//! order reconstructs the full witness assignment.
+
+use std::collections::BTreeMap;
+
+use acvm::acir::native_types::{Witness, WitnessMap};
+use acvm::FieldElement;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The version of the format written by [`WitnessLog::to_json`]. This must be bumped whenever
+/// the log changes in a way which would alter its JSON.
+pub const WITNESS_LOG_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum WitnessLogError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("Witness log has format version {found} but version {expected} is required")]
+    VersionMismatch { found: u32, expected: u32 },
+}
+
+/// The witnesses given to a circuit's execution, followed by those assigned by each opcode in
+/// the order the opcodes were solved.
+///
+/// As JSON this is an object holding the format `version`, the initial witnesses and an entry
+/// for each opcode, with witness values given as hex strings in the same way as the JSON
+/// witness format, e.g.
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "initial_witnesses": [1],
+///   "initial_values": ["0x03"],
+///   "assignments": [{ "opcode_index": 0, "witnesses": [2], "values": ["0x09"] }]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WitnessLog {
+    pub version: u32,
+    /// The indices of the witnesses given to execution, in ascending order
+    pub initial_witnesses: Vec<u32>,
+    /// The value of each of `initial_witnesses`
+    #[serde(with = "hex_list")]
+    pub initial_values: Vec<FieldElement>,
+    pub assignments: Vec<WitnessAssignment>,
+}
+
+/// The witnesses assigned by solving a single opcode. Every opcode solved has an entry, even if
+/// it assigned no witnesses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WitnessAssignment {
+    pub opcode_index: usize,
+    /// The indices of the witnesses assigned, in ascending order
+    pub witnesses: Vec<u32>,
+    /// The value assigned to each of `witnesses`
+    #[serde(with = "hex_list")]
+    pub values: Vec<FieldElement>,
+}
+
+impl WitnessLog {
+    pub(crate) fn new(initial_witness: &WitnessMap) -> WitnessLog {
+        let (initial_witnesses, initial_values) = split_assignments(
+            initial_witness
+                .clone()
+                .into_iter()
+                .map(|(witness, value)| (witness.witness_index(), value)),
+        );
+        WitnessLog {
+            version: WITNESS_LOG_VERSION,
+            initial_witnesses,
+            initial_values,
+            assignments: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, opcode_index: usize, assigned: BTreeMap<u32, FieldElement>) {
+        let (witnesses, values) = split_assignments(assigned);
+        self.assignments.push(WitnessAssignment { opcode_index, witnesses, values });
+    }
+
+    /// Returns the witness map obtained by replaying the log, which is the solved witness of the
+    /// execution it was recorded from.
+    pub fn witness_map(&self) -> WitnessMap {
+        let initial = self.initial_witnesses.iter().zip(&self.initial_values);
+        let assigned = self
+            .assignments
+            .iter()
+            .flat_map(|assignment| assignment.witnesses.iter().zip(&assignment.values));
+        let assignments: BTreeMap<_, _> =
+            initial.chain(assigned).map(|(index, value)| (Witness(*index), *value)).collect();
+        WitnessMap::from(assignments)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("witness logs are always serializable")
+    }
+
+    /// Reads a log written by [`WitnessLog::to_json`], failing if it was written in another
+    /// version of the format.
+    pub fn from_json(json: &str) -> Result<WitnessLog, WitnessLogError> {
+        // The version is read on its own first, as the rest may not parse in other versions
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u32,
+        }
+
+        let Versioned { version } = serde_json::from_str(json)?;
+        if version != WITNESS_LOG_VERSION {
+            return Err(WitnessLogError::VersionMismatch {
+                found: version,
+                expected: WITNESS_LOG_VERSION,
+            });
+        }
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+fn split_assignments(
+    assignments: impl IntoIterator<Item = (u32, FieldElement)>,
+) -> (Vec<u32>, Vec<FieldElement>) {
+    let sorted: BTreeMap<_, _> = assignments.into_iter().collect();
+    sorted.into_iter().unzip()
+}
+
+/// Serializes lists of witness values as hex strings, as in the JSON witness format.
+mod hex_list {
+    use acvm::FieldElement;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        values: &[FieldElement],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let hex_values: Vec<String> =
+            values.iter().map(|value| format!("0x{}", value.to_hex())).collect();
+        hex_values.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<FieldElement>, D::Error> {
+        let hex_values = Vec::<String>::deserialize(deserializer)?;
+        hex_values
+            .into_iter()
+            .map(|value| {
+                FieldElement::from_hex(&value)
+                    .ok_or_else(|| D::Error::custom(format!("invalid witness value `{value}`")))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::native_types::{Witness, WitnessMap};
+    use acvm::FieldElement;
+
+    use super::{WitnessLog, WitnessLogError, WITNESS_LOG_VERSION};
+
+    fn log() -> WitnessLog {
+        let initial_witness =
+            WitnessMap::from(BTreeMap::from([(Witness(1), FieldElement::from(3_u128))]));
+        let mut log = WitnessLog::new(&initial_witness);
+        log.push(0, BTreeMap::from([(3, FieldElement::one()), (2, -FieldElement::one())]));
+        log.push(1, BTreeMap::new());
+        log
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let json = log().to_json();
+        assert!(json.contains("\"0x"));
+        assert_eq!(WitnessLog::from_json(&json).unwrap(), log());
+    }
+
+    #[test]
+    fn replays_assignments() {
+        let log = log();
+        assert_eq!(log.assignments[0].witnesses, vec![2, 3]);
+
+        let expected = WitnessMap::from(BTreeMap::from([
+            (Witness(1), FieldElement::from(3_u128)),
+            (Witness(2), -FieldElement::one()),
+            (Witness(3), FieldElement::one()),
+        ]));
+        assert_eq!(log.witness_map(), expected);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut other_version = log();
+        other_version.version = WITNESS_LOG_VERSION + 1;
+
+        let error = WitnessLog::from_json(&other_version.to_json()).unwrap_err();
+        assert!(matches!(
+            error,
+            WitnessLogError::VersionMismatch { found, expected: WITNESS_LOG_VERSION }
+                if found == WITNESS_LOG_VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+//! Formats for persisting a [`WitnessMap`].
+//!
+//! The compact binary format is the one used for the `.tr` witness files written by
+//! `nargo execute`. The JSON format is intended for interoperating with external tools.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+use acvm::acir::native_types::{Witness, WitnessMap, WitnessMapError};
+use acvm::FieldElement;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WitnessSerializationError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Binary(#[from] WitnessMapError),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid witness index `{0}`")]
+    InvalidWitnessIndex(String),
+
+    #[error("Invalid value `{value}` for witness {index}, expected a hex encoded field element")]
+    InvalidFieldElement { index: u32, value: String },
+}
+
+/// A format in which a [`WitnessMap`] can be written out and read back.
+pub trait WitnessSerializer {
+    fn serialize(
+        &self,
+        witness_map: &WitnessMap,
+        writer: &mut impl Write,
+    ) -> Result<(), WitnessSerializationError>;
+
+    fn deserialize(&self, reader: &mut impl Read) -> Result<WitnessMap, WitnessSerializationError>;
+}
+
+/// The witness map serialized with bincode and then gzip compressed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinaryWitnessSerializer;
+
+impl WitnessSerializer for BinaryWitnessSerializer {
+    fn serialize(
+        &self,
+        witness_map: &WitnessMap,
+        writer: &mut impl Write,
+    ) -> Result<(), WitnessSerializationError> {
+        let buf: Vec<u8> = witness_map.clone().try_into()?;
+        writer.write_all(&buf)?;
+        Ok(())
+    }
+
+    fn deserialize(&self, reader: &mut impl Read) -> Result<WitnessMap, WitnessSerializationError> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        Ok(WitnessMap::try_from(buf.as_slice())?)
+    }
+}
+
+/// A JSON object mapping each witness index to its value as a hex string,
+/// e.g. `{"1": "0x01", "2": "0x2a"}`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonWitnessSerializer;
+
+impl WitnessSerializer for JsonWitnessSerializer {
+    fn serialize(
+        &self,
+        witness_map: &WitnessMap,
+        writer: &mut impl Write,
+    ) -> Result<(), WitnessSerializationError> {
+        let json: BTreeMap<u32, String> = witness_map
+            .clone()
+            .into_iter()
+            .map(|(witness, value)| (witness.witness_index(), format!("0x{}", value.to_hex())))
+            .collect();
+        serde_json::to_writer_pretty(writer, &json)?;
+        Ok(())
+    }
+
+    fn deserialize(&self, reader: &mut impl Read) -> Result<WitnessMap, WitnessSerializationError> {
+        let json: BTreeMap<String, String> = serde_json::from_reader(reader)?;
+
+        let mut witness_map = BTreeMap::new();
+        for (index, value) in json {
+            let index: u32 = index
+                .parse()
+                .map_err(|_| WitnessSerializationError::InvalidWitnessIndex(index.clone()))?;
+            let field = FieldElement::from_hex(&value)
+                .ok_or(WitnessSerializationError::InvalidFieldElement { index, value })?;
+            witness_map.insert(Witness(index), field);
+        }
+        Ok(WitnessMap::from(witness_map))
+    }
+}
+
+/// Writes `witness_map` to `writer` in the default binary format.
+pub fn write_witness(
+    witness_map: &WitnessMap,
+    writer: &mut impl Write,
+) -> Result<(), WitnessSerializationError> {
+    BinaryWitnessSerializer.serialize(witness_map, writer)
+}
+
+/// Reads a witness map written in the default binary format from `reader`.
+pub fn read_witness(reader: &mut impl Read) -> Result<WitnessMap, WitnessSerializationError> {
+    BinaryWitnessSerializer.deserialize(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::native_types::{Witness, WitnessMap};
+    use acvm::FieldElement;
+
+    use super::{BinaryWitnessSerializer, JsonWitnessSerializer, WitnessSerializer};
+
+    fn witness_map() -> WitnessMap {
+        WitnessMap::from(BTreeMap::from([
+            (Witness(1), FieldElement::from(1_u128)),
+            (Witness(3), FieldElement::from(42_u128)),
+            (Witness(4), -FieldElement::one()),
+        ]))
+    }
+
+    fn round_trip(serializer: impl WitnessSerializer) -> WitnessMap {
+        let mut buf = Vec::new();
+        serializer.serialize(&witness_map(), &mut buf).unwrap();
+        serializer.deserialize(&mut buf.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        assert_eq!(round_trip(BinaryWitnessSerializer), witness_map());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        assert_eq!(round_trip(JsonWitnessSerializer), witness_map());
+    }
+
+    #[test]
+    fn json_rejects_invalid_witnesses() {
+        let invalid = [r#"{"x": "0x01"}"#, r#"{"1": "not hex"}"#, r#"["0x01"]"#];
+        for json in invalid {
+            assert!(JsonWitnessSerializer.deserialize(&mut json.as_bytes()).is_err());
+        }
+    }
+}
@@ -3,8 +3,9 @@ use acvm::acir::{circuit::Circuit, native_types::WitnessMap};
 use acvm::pwg::ErrorLocation;
 use clap::Args;
 use nargo::constants::PROVER_INPUT_FILE;
+use nargo::ops::{ExecutionOptions, DEFAULT_TIMESTAMP};
 use nargo::package::Package;
-use nargo::NargoError;
+use nargo::{FieldDisplayMode, NargoError};
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
 use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::{Abi, InputMap};
@@ -13,8 +14,13 @@ use noirc_errors::{debug_info::DebugInfo, CustomDiagnostic};
 use noirc_frontend::graph::CrateName;
 use noirc_frontend::hir::Context;
 
+use std::path::PathBuf;
+
 use super::compile_cmd::compile_package;
-use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
+use super::fs::{
+    inputs::read_inputs_from_file, merkle_trees::read_merkle_trees_from_file,
+    witness::save_witness_to_dir,
+};
 use super::NargoConfig;
 use crate::backends::Backend;
 use crate::errors::CliError;
@@ -37,10 +43,46 @@ pub(crate) struct ExecuteCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    /// Display field elements in execution errors in hexadecimal rather than decimal
+    #[clap(long)]
+    hex_fields: bool,
+
+    #[clap(flatten)]
+    execution_args: ExecutionArgs,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
 
+/// Arguments controlling how a program is executed
+#[derive(Debug, Clone, Args)]
+pub(crate) struct ExecutionArgs {
+    /// The timestamp returned by each call to `std::now` while executing the program
+    #[clap(long, default_value_t = DEFAULT_TIMESTAMP)]
+    timestamp: u64,
+
+    /// A JSON file of the Merkle trees opened by calls to `std::merkle_open`, each given as its
+    /// layers of hex encoded nodes from the leaves up to the root
+    #[clap(long)]
+    merkle_trees: Option<PathBuf>,
+}
+
+impl ExecutionArgs {
+    pub(crate) fn execution_options(&self) -> Result<ExecutionOptions, CliError> {
+        let merkle_trees = match &self.merkle_trees {
+            Some(path) => read_merkle_trees_from_file(path)?,
+            None => Vec::new(),
+        };
+        Ok(ExecutionOptions {
+            show_output: true,
+            timestamp: self.timestamp,
+            overflow_diagnostics: true,
+            merkle_trees,
+            ..Default::default()
+        })
+    }
+}
+
 pub(crate) fn run(
     backend: &Backend,
     args: ExecuteCommand,
@@ -53,9 +95,19 @@ pub(crate) fn run(
     let workspace = resolve_workspace_from_toml(&toml_path, selection)?;
     let witness_dir = &workspace.target_directory_path();
 
+    let field_display =
+        if args.hex_fields { FieldDisplayMode::Hex } else { FieldDisplayMode::Decimal };
+    let execution_options = args.execution_args.execution_options()?;
+
     for package in &workspace {
-        let (return_value, solved_witness) =
-            execute_package(backend, package, &args.prover_name, &args.compile_options)?;
+        let (return_value, solved_witness) = execute_package(
+            backend,
+            package,
+            &args.prover_name,
+            &args.compile_options,
+            &execution_options,
+            field_display,
+        )?;
 
         println!("[{}] Circuit witness successfully solved", package.name);
         if let Some(return_value) = return_value {
@@ -75,6 +127,8 @@ fn execute_package(
     package: &Package,
     prover_name: &str,
     compile_options: &CompileOptions,
+    execution_options: &ExecutionOptions,
+    field_display: FieldDisplayMode,
 ) -> Result<(Option<InputValue>, WitnessMap), CliError> {
     let (context, compiled_program) = compile_package(backend, package, compile_options)?;
     let CompiledProgram { abi, circuit, debug } = compiled_program;
@@ -82,8 +136,15 @@ fn execute_package(
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &abi)?;
-    let solved_witness =
-        execute_program(backend, circuit, &abi, &inputs_map, Some((debug, context)))?;
+    let solved_witness = execute_program(
+        backend,
+        circuit,
+        &abi,
+        &inputs_map,
+        Some((debug, context)),
+        execution_options,
+        field_display,
+    )?;
     let public_abi = abi.public_abi();
     let (_, return_value) = public_abi.decode(&solved_witness)?;
 
@@ -92,16 +153,23 @@ fn execute_package(
 
 /// There are certain errors that contain an [acvm::pwg::ErrorLocation].
 /// We need to determine whether the error location has been resolving during execution.
-/// If the location has been resolved we return the contained [OpcodeLocation].
+/// If the location has been resolved we return the contained [OpcodeLocation], along with the
+/// message to report at it.
 fn extract_opcode_error_from_nargo_error(
     nargo_err: &NargoError,
-) -> Option<(OpcodeLocation, &acvm::pwg::OpcodeResolutionError)> {
+    field_display: FieldDisplayMode,
+) -> Option<(OpcodeLocation, String)> {
     let solving_err = match nargo_err {
         nargo::NargoError::SolvingError(err) => err,
+        nargo::NargoError::ArithmeticOverflow { opcode_index, bit_size, operation } => {
+            let operation = operation.display_with(field_display);
+            let message = format!("Integer overflow: {operation} does not fit in {bit_size} bits");
+            return Some((OpcodeLocation::Acir(*opcode_index), message));
+        }
         _ => return None,
     };
 
-    match solving_err {
+    let opcode_location = match solving_err {
         acvm::pwg::OpcodeResolutionError::IndexOutOfBounds {
             opcode_location: error_location,
             ..
@@ -112,46 +180,33 @@ fn extract_opcode_error_from_nargo_error(
             ErrorLocation::Unresolved => {
                 unreachable!("Cannot resolve index for unsatisfied constraint")
             }
-            ErrorLocation::Resolved(opcode_location) => Some((*opcode_location, solving_err)),
+            ErrorLocation::Resolved(opcode_location) => *opcode_location,
         },
-        _ => None,
-    }
+        _ => return None,
+    };
+
+    let message = match solving_err {
+        acvm::pwg::OpcodeResolutionError::IndexOutOfBounds { index, array_size, .. } => {
+            format!("Index out of bounds, array has size {array_size:?}, but index was {index:?}")
+        }
+        _ => "Failed constraint".into(),
+    };
+    Some((opcode_location, message))
 }
 
 /// Resolve an [OpcodeLocation] using debug information generated during compilation
 /// to determine an opcode's call stack. Then report the error using the resolved
 /// call stack and any other relevant error information returned from the ACVM.
 fn report_error_with_opcode_location(
-    opcode_err_info: Option<(OpcodeLocation, &acvm::pwg::OpcodeResolutionError)>,
+    opcode_err_info: Option<(OpcodeLocation, String)>,
     debug: &DebugInfo,
     context: &Context,
 ) {
-    if let Some((opcode_location, opcode_err)) = opcode_err_info {
+    if let Some((opcode_location, message)) = opcode_err_info {
         if let Some(locations) = debug.opcode_location(&opcode_location) {
             // The location of the error itself will be the location at the top
             // of the call stack (the last item in the Vec).
             if let Some(location) = locations.last() {
-                let message = match opcode_err {
-                    acvm::pwg::OpcodeResolutionError::IndexOutOfBounds {
-                        index,
-                        array_size,
-                        ..
-                    } => {
-                        format!(
-                            "Index out of bounds, array has size {array_size:?}, but index was {index:?}"
-                        )
-                    }
-                    acvm::pwg::OpcodeResolutionError::UnsatisfiedConstrain { .. } => {
-                        "Failed constraint".into()
-                    }
-                    _ => {
-                        // All other errors that do not have corresponding opcode locations
-                        // should not be reported in this method.
-                        // If an error with an opcode location is not handled in this match statement
-                        // the basic message attached to the original error from the ACVM should be reported.
-                        return;
-                    }
-                };
                 CustomDiagnostic::simple_error(message, String::new(), location.span)
                     .in_file(location.file)
                     .with_call_stack(locations)
@@ -167,23 +222,29 @@ pub(crate) fn execute_program(
     abi: &Abi,
     inputs_map: &InputMap,
     debug_data: Option<(DebugInfo, Context)>,
+    options: &ExecutionOptions,
+    field_display: FieldDisplayMode,
 ) -> Result<WitnessMap, CliError> {
     #[allow(deprecated)]
     let blackbox_solver = acvm::blackbox_solver::BarretenbergSolver::new();
 
     let initial_witness = abi.encode(inputs_map, None)?;
 
-    let solved_witness_err =
-        nargo::ops::execute_circuit(&blackbox_solver, circuit, initial_witness, true);
+    let solved_witness_err = nargo::ops::execute_circuit_with_options(
+        &blackbox_solver,
+        circuit,
+        initial_witness,
+        options,
+    );
     match solved_witness_err {
-        Ok(solved_witness) => Ok(solved_witness),
+        Ok(output) => Ok(output.witness),
         Err(err) => {
             if let Some((debug, context)) = debug_data {
-                let opcode_err_info = extract_opcode_error_from_nargo_error(&err);
+                let opcode_err_info = extract_opcode_error_from_nargo_error(&err, field_display);
                 report_error_with_opcode_location(opcode_err_info, &debug, &context);
             }
 
-            Err(crate::errors::CliError::NargoError(err))
+            Err(crate::errors::CliError::ExecutionError(err, field_display))
         }
     }
 }
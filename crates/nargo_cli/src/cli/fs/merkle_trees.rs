@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use acvm::FieldElement;
+use nargo::ops::MerkleTree;
+
+use crate::errors::FilesystemError;
+
+/// Reads the Merkle trees stored at `path` as a JSON array of trees, each of which is an array
+/// of its layers from the leaves up to the root, with every node a hex encoded field element.
+pub(crate) fn read_merkle_trees_from_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<MerkleTree>, FilesystemError> {
+    let path = path.as_ref();
+    let invalid = |reason: String| FilesystemError::MerkleTreesNotValid(path.to_path_buf(), reason);
+
+    let contents = std::fs::read_to_string(path)
+        .map_err(|_| FilesystemError::PathNotValid(path.to_path_buf()))?;
+    let trees: Vec<Vec<Vec<String>>> =
+        serde_json::from_str(&contents).map_err(|error| invalid(error.to_string()))?;
+
+    trees
+        .into_iter()
+        .map(|layers| {
+            let layers = layers
+                .into_iter()
+                .map(|nodes| {
+                    nodes
+                        .iter()
+                        .map(|node| {
+                            FieldElement::from_hex(node)
+                                .ok_or_else(|| invalid(format!("{node} is not a field element")))
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            MerkleTree::from_layers(layers).map_err(|error| invalid(error.to_string()))
+        })
+        .collect()
+}
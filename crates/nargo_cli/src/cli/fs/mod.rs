@@ -7,6 +7,7 @@ use std::{
 use crate::errors::FilesystemError;
 
 pub(super) mod inputs;
+pub(super) mod merkle_trees;
 pub(super) mod program;
 pub(super) mod proof;
 pub(super) mod witness;
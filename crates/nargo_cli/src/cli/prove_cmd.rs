@@ -3,7 +3,9 @@ use std::path::{Path, PathBuf};
 use clap::Args;
 use nargo::artifacts::program::PreprocessedProgram;
 use nargo::constants::{PROVER_INPUT_FILE, VERIFIER_INPUT_FILE};
+use nargo::ops::ExecutionOptions;
 use nargo::package::Package;
+use nargo::FieldDisplayMode;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
 use noirc_abi::input_parser::Format;
 use noirc_driver::CompileOptions;
@@ -16,7 +18,11 @@ use super::fs::{
     proof::save_proof_to_dir,
 };
 use super::NargoConfig;
-use crate::{backends::Backend, cli::execute_cmd::execute_program, errors::CliError};
+use crate::{
+    backends::Backend,
+    cli::execute_cmd::{execute_program, ExecutionArgs},
+    errors::CliError,
+};
 
 // TODO(#1388): pull this from backend.
 const BACKEND_IDENTIFIER: &str = "acvm-backend-barretenberg";
@@ -44,6 +50,9 @@ pub(crate) struct ProveCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    #[clap(flatten)]
+    execution_args: ExecutionArgs,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -59,6 +68,7 @@ pub(crate) fn run(
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
     let workspace = resolve_workspace_from_toml(&toml_path, selection)?;
     let proof_dir = workspace.proofs_directory_path();
+    let execution_options = args.execution_args.execution_options()?;
 
     for package in &workspace {
         let circuit_build_path = workspace.package_build_path(package);
@@ -72,6 +82,7 @@ pub(crate) fn run(
             circuit_build_path,
             args.verify,
             &args.compile_options,
+            &execution_options,
         )?;
     }
 
@@ -88,6 +99,7 @@ pub(crate) fn prove_package(
     circuit_build_path: PathBuf,
     check_proof: bool,
     compile_options: &CompileOptions,
+    execution_options: &ExecutionOptions,
 ) -> Result<(), CliError> {
     let (preprocessed_program, debug_data) = if circuit_build_path.exists() {
         let program = read_program_from_file(circuit_build_path)?;
@@ -109,7 +121,15 @@ pub(crate) fn prove_package(
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &abi)?;
 
-    let solved_witness = execute_program(backend, bytecode.clone(), &abi, &inputs_map, debug_data)?;
+    let solved_witness = execute_program(
+        backend,
+        bytecode.clone(),
+        &abi,
+        &inputs_map,
+        debug_data,
+        execution_options,
+        FieldDisplayMode::default(),
+    )?;
 
     // Write public inputs into Verifier.toml
     let public_abi = abi.public_abi();
@@ -1,7 +1,7 @@
 use acvm::acir::native_types::WitnessMapError;
 use acvm_backend_barretenberg::BackendError;
 use hex::FromHexError;
-use nargo::NargoError;
+use nargo::{FieldDisplayMode, NargoError};
 use nargo_toml::ManifestError;
 use noirc_abi::errors::{AbiError, InputParserError};
 use noirc_errors::reporter::ReportedErrors;
@@ -19,6 +19,8 @@ pub(crate) enum FilesystemError {
         " Error: cannot find {0}.toml file.\n Expected location: {1:?} \n Please generate this file at the expected location."
     )]
     MissingTomlFile(String, PathBuf),
+    #[error("Error: could not read Merkle trees from {}: {1}", .0.display())]
+    MerkleTreesNotValid(PathBuf, String),
 
     /// Input parsing error
     #[error(transparent)]
@@ -57,6 +59,11 @@ pub(crate) enum CliError {
     #[error(transparent)]
     NargoError(#[from] NargoError),
 
+    /// Error from Nargo while executing a program, with field elements displayed in the mode
+    /// chosen for execution
+    #[error("{}", .0.display_with(*.1))]
+    ExecutionError(NargoError, FieldDisplayMode),
+
     /// Error from Manifest
     #[error(transparent)]
     ManifestError(#[from] ManifestError),
@@ -53,3 +53,12 @@ pub enum AbiError {
     #[error("No return value is expected but received {0:?}")]
     UnexpectedReturnValue(InputValue),
 }
+
+/// An input within a batch which couldn't be encoded.
+#[derive(Debug, Error)]
+#[error("Input {index} of the batch could not be encoded: {source}")]
+pub struct EncodeError {
+    /// The position of the input within the batch
+    pub index: usize,
+    pub source: AbiError,
+}
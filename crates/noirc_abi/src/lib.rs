@@ -3,13 +3,18 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
-use std::{collections::BTreeMap, str};
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    str,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use acvm::{
     acir::native_types::{Witness, WitnessMap},
     FieldElement,
 };
-use errors::AbiError;
+use errors::{AbiError, EncodeError};
 use input_parser::InputValue;
 use iter_extended::{try_btree_map, try_vecmap, vecmap};
 use noirc_frontend::{hir::Context, Signedness, Type, TypeBinding, TypeVariableKind, Visibility};
@@ -168,6 +173,7 @@ impl AbiType {
             Type::Forall(..) => unreachable!(),
             Type::Function(_, _, _) => unreachable!(),
             Type::MutableReference(_) => unreachable!("&mut cannot be used in the abi"),
+            Type::Opaque(..) => unreachable!("opaque types cannot be used in the abi"),
             Type::NotConstant => unreachable!(),
         }
     }
@@ -423,6 +429,55 @@ impl Abi {
     }
 }
 
+/// Encodes each of a batch of inputs as described in `abi` into a `WitnessMap`, for when proving
+/// a circuit for many inputs at once. The inputs are validated and encoded in parallel, and
+/// encoding stops at the first input which can't be encoded.
+pub fn encode_inputs_batch(
+    abi: &Abi,
+    inputs: Vec<InputMap>,
+) -> Result<Vec<WitnessMap>, EncodeError> {
+    let threads = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = ((inputs.len() + threads - 1) / threads).max(1);
+    // The earliest input known to fail, as inputs after it needn't be encoded
+    let first_failure = AtomicUsize::new(usize::MAX);
+
+    let encoded_chunks: Vec<Result<Vec<WitnessMap>, EncodeError>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = inputs
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk_index, chunk)| {
+                let first_failure = &first_failure;
+                scope.spawn(move || {
+                    let mut witness_maps = Vec::with_capacity(chunk.len());
+                    for (offset, input_map) in chunk.iter().enumerate() {
+                        let index = chunk_index * chunk_size + offset;
+                        if index > first_failure.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        match abi.encode(input_map, None) {
+                            Ok(witness_map) => witness_maps.push(witness_map),
+                            Err(source) => {
+                                first_failure.fetch_min(index, Ordering::Relaxed);
+                                return Err(EncodeError { index, source });
+                            }
+                        }
+                    }
+                    Ok(witness_maps)
+                })
+            })
+            .collect();
+        vecmap(handles, |handle| handle.join().expect("input encoding thread panicked"))
+    });
+
+    // A chunk only stops early if an earlier input failed, so the first error in the batch is
+    // always that of the earliest chunk to fail
+    let mut witness_maps = Vec::with_capacity(inputs.len());
+    for encoded_chunk in encoded_chunks {
+        witness_maps.extend(encoded_chunk?);
+    }
+    Ok(witness_maps)
+}
+
 fn decode_value(
     field_iterator: &mut impl Iterator<Item = FieldElement>,
     value_type: &AbiType,
@@ -483,7 +538,10 @@ mod test {
 
     use acvm::{acir::native_types::Witness, FieldElement};
 
-    use crate::{input_parser::InputValue, Abi, AbiParameter, AbiType, AbiVisibility, InputMap};
+    use crate::{
+        encode_inputs_batch, errors::AbiError, input_parser::InputValue, Abi, AbiParameter,
+        AbiType, AbiVisibility, InputMap,
+    };
 
     #[test]
     fn witness_encoding_roundtrip() {
@@ -531,4 +589,35 @@ mod test {
         // We also decode the return value (we can do this immediately as we know it shares a witness with an input).
         assert_eq!(return_value.unwrap(), reconstructed_inputs["thing2"]);
     }
+
+    #[test]
+    fn batch_encoding_reports_the_first_failure() {
+        let abi = Abi {
+            parameters: vec![AbiParameter {
+                name: "x".to_string(),
+                typ: AbiType::Field,
+                visibility: AbiVisibility::Private,
+            }],
+            param_witnesses: BTreeMap::from([("x".to_string(), vec![Witness(1)])]),
+            return_type: None,
+            return_witnesses: Vec::new(),
+        };
+        let input = |value: u128| -> InputMap {
+            BTreeMap::from([("x".to_string(), InputValue::Field(FieldElement::from(value)))])
+        };
+
+        let inputs: Vec<InputMap> = (0..100).map(input).collect();
+        let witness_maps = encode_inputs_batch(&abi, inputs).unwrap();
+        assert_eq!(witness_maps.len(), 100);
+        for (value, witness_map) in witness_maps.iter().enumerate() {
+            assert_eq!(witness_map.get(&Witness(1)), Some(&FieldElement::from(value as u128)));
+        }
+
+        let mut inputs: Vec<InputMap> = (0..100).map(input).collect();
+        inputs[42] = BTreeMap::new();
+        inputs[77] = BTreeMap::new();
+        let error = encode_inputs_batch(&abi, inputs).unwrap_err();
+        assert_eq!(error.index, 42);
+        assert!(matches!(error.source, AbiError::MissingParam(name) if name == "x"));
+    }
 }
@@ -0,0 +1,129 @@
+//! Resolution of trait bounds and trait methods against many impls of the same trait, both in
+//! the Ast's trait registry and in the resolved impls the type checker consults, whose dispatch
+//! tables narrow them down to the impls for the type's head. Checks of resolved impls are
+//! measured both on a fresh set of impls and once cached, as repeated checks of the same
+//! constraint are during type checking.
+use std::rc::Rc;
+
+use arena::Index;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use noirc_errors::Span;
+use noirc_frontend::ast::trait_registry::{available_methods, satisfies, TraitRegistry};
+use noirc_frontend::graph::CrateId;
+use noirc_frontend::hir::def_map::{LocalModuleId, ModuleId};
+use noirc_frontend::hir::trait_impls::TraitImplementations;
+use noirc_frontend::hir_def::function::HirTraitConstraint;
+use noirc_frontend::node_interner::{FuncId, StructId, TraitId};
+use noirc_frontend::{
+    parse_program, Shared, StructType, TraitBound, Type, TypeBinding, TypeVariableId,
+    UnresolvedType, UnresolvedTypeData,
+};
+
+/// A module declaring `trait Eq` along with `impl_count` structs which each implement it.
+fn many_impls(impl_count: usize) -> String {
+    let mut src = "trait Eq { fn eq(self, other: Self) -> bool; }\n".to_string();
+    for index in 0..impl_count {
+        src.push_str(&format!(
+            "struct S{index} {{}}\nimpl Eq for S{index} {{ fn eq(self, other: Self) -> bool {{ true }} }}\n"
+        ));
+    }
+    src
+}
+
+fn parse_type(typ: &str) -> UnresolvedType {
+    let (program, _) = parse_program(&format!("global x: {typ} = 0;"));
+    program.globals[0].r#type.clone()
+}
+
+fn criterion_trait_dispatch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trait_dispatch");
+    for impl_count in [10, 100, 1000] {
+        let (program, _) = parse_program(&many_impls(impl_count));
+        let registry = TraitRegistry::from_module(&program);
+        let typ = parse_type(&format!("S{}", impl_count - 1));
+        let UnresolvedTypeData::Named(mut path, _) = parse_type("Eq").typ else {
+            unreachable!("trait names are parsed as named types")
+        };
+        let bound =
+            TraitBound { trait_name: path.pop(), trait_generics: Vec::new(), negative: false };
+
+        group.bench_with_input(BenchmarkId::new("satisfies", impl_count), &impl_count, |b, _| {
+            b.iter(|| satisfies(&typ, &bound, &registry))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("available_methods", impl_count),
+            &impl_count,
+            |b, _| b.iter(|| available_methods(&typ, &registry)),
+        );
+    }
+    group.finish();
+}
+
+fn module_id(index: usize) -> ModuleId {
+    ModuleId { krate: CrateId::Root(0), local_id: LocalModuleId(Index::from_raw_parts(index, 0)) }
+}
+
+/// The resolved impls of a trait `Eq` for `impl_count` structs and for `[T; 2] where T: Eq`,
+/// along with the trait's id and the type of the last struct.
+fn many_resolved_impls(impl_count: usize) -> (TraitImplementations, TraitId, Type) {
+    let trait_id = TraitId(module_id(impl_count));
+    let mut impls = TraitImplementations::default();
+    let mut typ = Type::Error;
+    for index in 0..impl_count {
+        let name = format!("S{index}").into();
+        let struct_type =
+            StructType::new(StructId(module_id(index)), name, Span::default(), vec![], vec![]);
+        typ = Type::Struct(Shared::new(struct_type), vec![]);
+
+        let impl_index = impls.add(trait_id, typ.clone(), Vec::new());
+        impls.add_method(impl_index, "eq".to_string(), FuncId::dummy_id());
+    }
+
+    let binding = Shared::new(TypeBinding::Unbound(TypeVariableId(0)));
+    let generic = Type::NamedGeneric(binding, Rc::new("T".to_string()));
+    let where_clause = vec![HirTraitConstraint { typ: generic.clone(), trait_id }];
+    impls.add(trait_id, pair(generic), where_clause);
+    (impls, trait_id, typ)
+}
+
+fn pair(element: Type) -> Type {
+    Type::Array(Box::new(Type::Constant(2)), Box::new(element))
+}
+
+fn criterion_trait_implementations(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trait_implementations");
+    for impl_count in [10, 100, 1000] {
+        let (impls, trait_id, typ) = many_resolved_impls(impl_count);
+        // Each level of nesting is checked against the where clause of the impl for arrays
+        let nested = (0..8).fold(typ.clone(), |typ, _| pair(typ));
+
+        group.bench_with_input(BenchmarkId::new("implements", impl_count), &impl_count, |b, _| {
+            b.iter(|| impls.implements(trait_id, &typ))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("implements_nested_uncached", impl_count),
+            &impl_count,
+            |b, _| {
+                b.iter_batched_ref(
+                    || many_resolved_impls(impl_count),
+                    |(impls, trait_id, _)| impls.implements(*trait_id, &nested),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("implements_nested_cached", impl_count),
+            &impl_count,
+            |b, _| b.iter(|| impls.implements(trait_id, &nested)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("select_method", impl_count),
+            &impl_count,
+            |b, _| b.iter(|| impls.select_method(&typ, "eq").is_ok()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_trait_dispatch, criterion_trait_implementations);
+criterion_main!(benches);
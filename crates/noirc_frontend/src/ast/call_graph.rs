@@ -0,0 +1,249 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::visitor::{walk_expression, Visitor},
+    parser::ParsedModule,
+    BlockExpression, Expression, ExpressionKind, NoirTrait, TraitImplItem, TraitItem,
+};
+
+/// A trait method, either as declared by its trait or as implemented for a particular type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MethodId {
+    pub trait_name: String,
+    /// The type the method is implemented for, or `None` for the trait's own declaration and
+    /// default body
+    pub impl_type: Option<String>,
+    pub method_name: String,
+}
+
+impl MethodId {
+    fn declared(trait_name: &str, method_name: &str) -> MethodId {
+        MethodId {
+            trait_name: trait_name.to_string(),
+            impl_type: None,
+            method_name: method_name.to_string(),
+        }
+    }
+}
+
+/// Builds the call graph between the trait methods of `module` and its inline submodules.
+///
+/// Each default body of a trait method and each method of a trait impl is mapped to the trait
+/// methods it calls, in the order they are first called. Callees are always the trait's
+/// declaration of the method (with `impl_type: None`) since which impl is called is only known
+/// once types have been resolved. Calls are resolved syntactically:
+/// - `Self::method(..)` and `self.method(..)` call the enclosing trait's method if it declares one,
+/// - `Trait::method(..)` calls the method of `Trait`,
+/// - `value.method(..)` calls the method of the only trait declaring a method of that name.
+///
+/// Any other call, including one to a method declared by several traits, is ignored.
+/// Recursive methods are recorded as calling themselves.
+pub fn trait_call_graph(module: &ParsedModule) -> HashMap<MethodId, Vec<MethodId>> {
+    let mut traits = HashMap::new();
+    collect_traits(module, &mut traits);
+
+    let mut graph = HashMap::new();
+    build_call_graph(module, &traits, &mut graph);
+    graph
+}
+
+/// Returns every method in `graph` reachable from `roots`, including the roots themselves.
+pub fn reachable_methods<'a>(
+    graph: &'a HashMap<MethodId, Vec<MethodId>>,
+    roots: impl IntoIterator<Item = &'a MethodId>,
+) -> HashSet<&'a MethodId> {
+    let mut reachable = HashSet::new();
+    let mut stack: Vec<_> = roots.into_iter().collect();
+    while let Some(method) = stack.pop() {
+        // Methods already reached have had their callees pushed, which breaks cycles
+        if reachable.insert(method) {
+            stack.extend(graph.get(method).into_iter().flatten());
+        }
+    }
+    reachable
+}
+
+/// Maps each trait name to the names of the methods it declares.
+type TraitMethods = HashMap<String, HashSet<String>>;
+
+fn collect_traits(module: &ParsedModule, traits: &mut TraitMethods) {
+    for noir_trait in &module.traits {
+        let methods = traits.entry(noir_trait.name.0.contents.clone()).or_default();
+        methods.extend(trait_methods(noir_trait));
+    }
+    for submodule in &module.submodules {
+        collect_traits(&submodule.contents, traits);
+    }
+}
+
+fn trait_methods(noir_trait: &NoirTrait) -> impl Iterator<Item = String> + '_ {
+    noir_trait.items.iter().filter_map(|item| match item {
+        TraitItem::Function { name, .. } => Some(name.0.contents.clone()),
+        _ => None,
+    })
+}
+
+fn build_call_graph(
+    module: &ParsedModule,
+    traits: &TraitMethods,
+    graph: &mut HashMap<MethodId, Vec<MethodId>>,
+) {
+    for noir_trait in &module.traits {
+        let trait_name = &noir_trait.name.0.contents;
+        for item in &noir_trait.items {
+            if let TraitItem::Function { name, body: Some(body), .. } = item {
+                let caller = MethodId::declared(trait_name, &name.0.contents);
+                graph.insert(caller, calls_in(body, trait_name, traits));
+            }
+        }
+    }
+
+    for trait_impl in &module.trait_impls {
+        let trait_name = &trait_impl.trait_name.0.contents;
+        for item in &trait_impl.items {
+            if let TraitImplItem::Function(function) = item {
+                let caller = MethodId {
+                    trait_name: trait_name.clone(),
+                    impl_type: Some(trait_impl.object_type.to_string()),
+                    method_name: function.name().to_string(),
+                };
+                graph.insert(caller, calls_in(&function.def.body, trait_name, traits));
+            }
+        }
+    }
+
+    for submodule in &module.submodules {
+        build_call_graph(&submodule.contents, traits, graph);
+    }
+}
+
+fn calls_in(body: &BlockExpression, trait_name: &str, traits: &TraitMethods) -> Vec<MethodId> {
+    let mut collector = CallCollector { trait_name, traits, calls: Vec::new() };
+    collector.visit_block(body);
+    collector.calls
+}
+
+struct CallCollector<'a> {
+    /// The trait whose method is being walked
+    trait_name: &'a str,
+    traits: &'a TraitMethods,
+    calls: Vec<MethodId>,
+}
+
+impl<'a> CallCollector<'a> {
+    fn declares(&self, trait_name: &str, method_name: &str) -> bool {
+        self.traits.get(trait_name).map_or(false, |methods| methods.contains(method_name))
+    }
+
+    fn resolve_method_call(&self, is_self: bool, method_name: &str) -> Option<MethodId> {
+        if is_self && self.declares(self.trait_name, method_name) {
+            return Some(MethodId::declared(self.trait_name, method_name));
+        }
+
+        let mut declaring_traits =
+            self.traits.iter().filter(|(_, methods)| methods.contains(method_name));
+        match (declaring_traits.next(), declaring_traits.next()) {
+            (Some((trait_name, _)), None) => Some(MethodId::declared(trait_name, method_name)),
+            _ => None,
+        }
+    }
+
+    fn resolve_path_call(&self, segments: &[String]) -> Option<MethodId> {
+        let [trait_name, method_name] = segments else {
+            return None;
+        };
+        let trait_name = if trait_name == "Self" { self.trait_name } else { trait_name.as_str() };
+        self.declares(trait_name, method_name).then(|| MethodId::declared(trait_name, method_name))
+    }
+
+    fn record(&mut self, callee: Option<MethodId>) {
+        if let Some(callee) = callee {
+            if !self.calls.contains(&callee) {
+                self.calls.push(callee);
+            }
+        }
+    }
+}
+
+impl<'a> Visitor for CallCollector<'a> {
+    fn visit_expression(&mut self, expression: &Expression) {
+        match &expression.kind {
+            ExpressionKind::MethodCall(call) => {
+                let is_self = matches!(
+                    &call.object.kind,
+                    ExpressionKind::Variable(path) if path.as_string() == "self"
+                );
+                let callee = self.resolve_method_call(is_self, &call.method_name.0.contents);
+                self.record(callee);
+            }
+            ExpressionKind::Call(call) => {
+                if let ExpressionKind::Variable(path) = &call.func.kind {
+                    let segments: Vec<_> =
+                        path.segments.iter().map(|segment| segment.0.contents.clone()).collect();
+                    let callee = self.resolve_path_call(&segments);
+                    self.record(callee);
+                }
+            }
+            _ => (),
+        }
+        walk_expression(self, expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reachable_methods, trait_call_graph, MethodId};
+    use crate::parse_program;
+
+    fn method(trait_name: &str, impl_type: Option<&str>, method_name: &str) -> MethodId {
+        MethodId {
+            trait_name: trait_name.to_string(),
+            impl_type: impl_type.map(ToString::to_string),
+            method_name: method_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn builds_trait_method_call_graph() {
+        let src = "
+            trait Shape {
+                fn area(self) -> Field;
+                fn double_area(self) -> Field { self.area() * 2 }
+                fn quadruple_area(self) -> Field { Self::double_area(self) * 2 }
+                fn countdown(self, n: Field) -> Field {
+                    if n == 0 { self.area() } else { self.countdown(n - 1) }
+                }
+            }
+            trait Named {
+                fn name(self) -> Field;
+            }
+            struct Square { side: Field }
+            impl Shape for Square {
+                fn area(self) -> Field { self.side * self.side + Named::name(self) }
+            }
+            fn helper() {}
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let graph = trait_call_graph(&program);
+        assert_eq!(graph.len(), 4);
+
+        let area = method("Shape", None, "area");
+        let double_area = method("Shape", None, "double_area");
+        let quadruple_area = method("Shape", None, "quadruple_area");
+        let countdown = method("Shape", None, "countdown");
+
+        assert_eq!(graph[&double_area], vec![area.clone()]);
+        assert_eq!(graph[&quadruple_area], vec![double_area.clone()]);
+        assert_eq!(graph[&countdown], vec![area.clone(), countdown.clone()]);
+        assert_eq!(
+            graph[&method("Shape", Some("Square"), "area")],
+            vec![method("Named", None, "name")]
+        );
+
+        let reachable = reachable_methods(&graph, [&quadruple_area, &countdown]);
+        assert_eq!(reachable.len(), 4);
+        assert!(reachable.contains(&area));
+    }
+}
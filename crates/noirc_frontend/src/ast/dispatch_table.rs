@@ -0,0 +1,224 @@
+//! An index of trait impls by the trait implemented and the outermost type constructor of their
+//! object type, so that the impls which may apply to a type are found without scanning every
+//! impl of the trait.
+//!
+//! An impl can only apply to a type with the same head as its object type, such as `Foo` for
+//! `Foo<T>` or arrays for `[T; 3]`. Blanket impls over one of their own generics, as in
+//! `impl<T> Eq for T`, may apply to a type with any head and so are kept apart from the buckets
+//! of their trait and merged into whichever bucket is looked up.
+//!
+//! The table only holds the position of each impl within a list kept by its owner, so that impls
+//! are stored once however many buckets they are a candidate for. Heads are [`TypeHead`]s of
+//! unresolved types by default, but the table can be keyed on any head, such as that of a
+//! resolved type.
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{Ident, Signedness, TraitImpl, UnresolvedType, UnresolvedTypeData};
+
+/// The outermost type constructor of a type, ignoring any types or lengths within it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TypeHead {
+    Field,
+    Integer(Signedness, u32),
+    Bool,
+    Unit,
+    /// An array with a length, as in `[T; N]`
+    Array,
+    /// An array without a length, as in `[T]`
+    Slice,
+    String,
+    FormatString,
+    Tuple(usize),
+    Function,
+    MutableReference,
+    /// A struct, type alias or generic, identified by the last segment of its path as when
+    /// matching impls
+    Named(String),
+}
+
+impl TypeHead {
+    /// Returns the head of `typ`, or `None` if it isn't known, as for trait objects, opaque
+    /// types and projections, or types which failed to parse.
+    pub fn of(typ: &UnresolvedType) -> Option<TypeHead> {
+        use UnresolvedTypeData::*;
+
+        let head = match &typ.typ {
+            FieldElement => TypeHead::Field,
+            Integer(sign, bits) => TypeHead::Integer(*sign, *bits),
+            Bool => TypeHead::Bool,
+            Unit => TypeHead::Unit,
+            Array(Some(_), _) => TypeHead::Array,
+            Array(None, _) => TypeHead::Slice,
+            String(_) => TypeHead::String,
+            FormatString(..) => TypeHead::FormatString,
+            Tuple(elements) => TypeHead::Tuple(elements.len()),
+            Function(..) => TypeHead::Function,
+            MutableReference(_) => TypeHead::MutableReference,
+            Named(path, _) => TypeHead::Named(path.segments.last()?.0.contents.clone()),
+            Expression(_)
+            | TraitObject(_)
+            | Opaque { .. }
+            | Projection { .. }
+            | Unspecified
+            | Error => return None,
+        };
+        Some(head)
+    }
+
+    /// Returns the head of `object_type`, the object type of an impl with the given generics,
+    /// or `None` if the impl may apply to types of any head.
+    pub fn of_impl(object_type: &UnresolvedType, impl_generics: &[Ident]) -> Option<TypeHead> {
+        match TypeHead::of(object_type)? {
+            TypeHead::Named(name)
+                if impl_generics.iter().any(|generic| generic.0.contents == name) =>
+            {
+                None
+            }
+            head => Some(head),
+        }
+    }
+}
+
+/// Trait impls bucketed by the trait they implement, identified by a key such as its name or
+/// id, and the head of their object type, such as a [`TypeHead`].
+///
+/// Each impl is identified by its index within a list kept by the owner of the table, and its
+/// trait's impls must be inserted in increasing order of index. Candidates are returned in
+/// that order.
+#[derive(Debug, Clone)]
+pub struct DispatchTable<K, H = TypeHead> {
+    traits: HashMap<K, TraitDispatch<H>>,
+}
+
+#[derive(Debug, Clone)]
+struct TraitDispatch<H> {
+    /// Every impl of the trait
+    impls: Vec<usize>,
+    /// The impls with an object type of each head
+    buckets: HashMap<H, Vec<usize>>,
+    /// The impls which may apply to types of any head
+    blanket_impls: Vec<usize>,
+}
+
+impl<K, H> Default for DispatchTable<K, H> {
+    fn default() -> Self {
+        DispatchTable { traits: HashMap::new() }
+    }
+}
+
+impl<H> Default for TraitDispatch<H> {
+    fn default() -> Self {
+        TraitDispatch { impls: Vec::new(), buckets: HashMap::new(), blanket_impls: Vec::new() }
+    }
+}
+
+impl DispatchTable<String> {
+    /// Creates a table of `impls` keyed on the names of their traits, taking the heads of their
+    /// object types as written. Impls are identified by their index within `impls`.
+    pub fn build(impls: &[TraitImpl]) -> DispatchTable<String> {
+        let mut table = DispatchTable::default();
+        for (index, trait_impl) in impls.iter().enumerate() {
+            let head = TypeHead::of_impl(&trait_impl.object_type, &trait_impl.impl_generics);
+            table.insert(trait_impl.trait_name.0.contents.clone(), index, head);
+        }
+        table
+    }
+}
+
+impl<K: Eq + Hash, H: Eq + Hash> DispatchTable<K, H> {
+    /// Adds the impl at `index` of the trait `trait_key`, bucketed by `head`, the head of its
+    /// object type once normalized, or as a blanket impl if this is `None`.
+    pub fn insert(&mut self, trait_key: K, index: usize, head: Option<H>) {
+        let dispatch = self.traits.entry(trait_key).or_default();
+        debug_assert!(dispatch.impls.last().map_or(true, |last| *last < index));
+
+        dispatch.impls.push(index);
+        match head {
+            Some(head) => dispatch.buckets.entry(head).or_default().push(index),
+            None => dispatch.blanket_impls.push(index),
+        }
+    }
+
+    /// Returns the impls of `trait_key` which may apply to a type with the given head, or every
+    /// impl of the trait if the head isn't known.
+    pub fn candidates<Q>(&self, trait_key: &Q, head: Option<&H>) -> Candidates<'_>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let Some(dispatch) = self.traits.get(trait_key) else { return Candidates::default() };
+        match head {
+            Some(head) => Candidates {
+                bucket: dispatch.buckets.get(head).map_or(&[], Vec::as_slice),
+                blanket_impls: &dispatch.blanket_impls,
+            },
+            None => Candidates { bucket: &dispatch.impls, blanket_impls: &[] },
+        }
+    }
+}
+
+/// The indices of the impls returned by [`DispatchTable::candidates`], merging the bucket of the
+/// head looked up with the blanket impls of the trait.
+#[derive(Debug, Clone, Default)]
+pub struct Candidates<'a> {
+    bucket: &'a [usize],
+    blanket_impls: &'a [usize],
+}
+
+impl Iterator for Candidates<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let from_bucket = match (self.bucket.first(), self.blanket_impls.first()) {
+            (Some(bucket_index), Some(blanket_index)) => bucket_index < blanket_index,
+            (bucket_index, _) => bucket_index.is_some(),
+        };
+        let list = if from_bucket { &mut self.bucket } else { &mut self.blanket_impls };
+        let (index, rest) = list.split_first()?;
+        *list = rest;
+        Some(*index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iter_extended::vecmap;
+
+    use super::{DispatchTable, TypeHead};
+    use crate::parse_program;
+
+    #[test]
+    fn buckets_impls_by_trait_and_type_head() {
+        let src = "
+            impl Eq for Foo {}
+            impl<T> Eq for Bar<T> {}
+            impl<T> Eq for T where T: Copy {}
+            impl<T> Eq for [T; 2] {}
+            impl Default for Foo {}
+            impl Eq for Baz {}
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+        let table = DispatchTable::build(&program.trait_impls);
+
+        let candidates = |trait_name, head: Option<TypeHead>| {
+            vecmap(table.candidates(trait_name, head.as_ref()), |index| {
+                program.trait_impls[index].object_type.to_string()
+            })
+        };
+        let named = |name: &str| Some(TypeHead::Named(name.to_string()));
+
+        assert_eq!(candidates("Eq", named("Foo")), vec!["Foo", "T"]);
+        assert_eq!(candidates("Eq", named("Bar")), vec!["Bar<T>", "T"]);
+        // The blanket impl was added before the impl for `Baz`
+        assert_eq!(candidates("Eq", named("Baz")), vec!["T", "Baz"]);
+        assert_eq!(candidates("Eq", Some(TypeHead::Array)), vec!["T", "[T; 2]"]);
+        assert_eq!(candidates("Eq", Some(TypeHead::Bool)), vec!["T"]);
+        assert_eq!(candidates("Eq", None), vec!["Foo", "Bar<T>", "T", "[T; 2]", "Baz"]);
+        assert_eq!(candidates("Default", named("Foo")), vec!["Foo"]);
+        assert_eq!(candidates("Default", named("Bar")), Vec::<String>::new());
+        assert_eq!(candidates("Ord", named("Foo")), Vec::<String>::new());
+    }
+}
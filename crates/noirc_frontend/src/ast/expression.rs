@@ -2,8 +2,8 @@ use std::fmt::Display;
 
 use crate::token::{Attribute, Token};
 use crate::{
-    Distinctness, Ident, Path, Pattern, Recoverable, Statement, TraitConstraint, UnresolvedType,
-    UnresolvedTypeData, Visibility,
+    Distinctness, GenericPredicate, Ident, Path, Pattern, Recoverable, Statement, TraitConstraint,
+    UnresolvedType, UnresolvedTypeData, Visibility,
 };
 use acvm::FieldElement;
 use iter_extended::vecmap;
@@ -354,6 +354,11 @@ pub struct FunctionDefinition {
     // XXX: Currently we only have one attribute defined. If more attributes are needed per function, we can make this a vector and make attribute definition more expressive
     pub attribute: Option<Attribute>,
 
+    /// True if this function was defined with the 'pub' keyword. This is only allowed on the
+    /// methods of inherent impls, which are private to the module defining them unless marked
+    /// `pub`. The methods of trait impls are always as visible as their trait.
+    pub is_pub: bool,
+
     /// True if this function was defined with the 'open' keyword
     pub is_open: bool,
 
@@ -367,6 +372,8 @@ pub struct FunctionDefinition {
     pub body: BlockExpression,
     pub span: Span,
     pub where_clause: Vec<TraitConstraint>,
+    /// The comparisons on numeric generics in the where clause, such as `N > 0`
+    pub where_predicates: Vec<GenericPredicate>,
     pub return_type: FunctionReturnType,
     pub return_visibility: Visibility,
     pub return_distinctness: Distinctness,
@@ -644,6 +651,7 @@ impl FunctionDefinition {
         FunctionDefinition {
             name: name.clone(),
             attribute: None,
+            is_pub: false,
             is_open: false,
             is_internal: false,
             is_unconstrained: false,
@@ -652,6 +660,7 @@ impl FunctionDefinition {
             body: body.clone(),
             span: name.span(),
             where_clause: where_clause.to_vec(),
+            where_predicates: Vec::new(),
             return_type: return_type.clone(),
             return_visibility: Visibility::Private,
             return_distinctness: Distinctness::DuplicationAllowed,
@@ -669,7 +678,8 @@ impl Display for FunctionDefinition {
             format!("{name}: {visibility} {type}")
         });
 
-        let where_clause = vecmap(&self.where_clause, ToString::to_string);
+        let mut where_clause = vecmap(&self.where_clause, ToString::to_string);
+        where_clause.extend(self.where_predicates.iter().map(ToString::to_string));
         let where_clause_str = if !where_clause.is_empty() {
             format!("where {}", where_clause.join(", "))
         } else {
@@ -93,7 +93,10 @@ impl From<FunctionDefinition> for NoirFunction {
             Some(Attribute::Test { .. }) => FunctionKind::Normal,
             Some(Attribute::Oracle(_)) => FunctionKind::Oracle,
             Some(Attribute::Deprecated(_)) | None => FunctionKind::Normal,
-            Some(Attribute::Custom(_)) => FunctionKind::Normal,
+            Some(Attribute::Derive(_))
+            | Some(Attribute::Cfg(_))
+            | Some(Attribute::Inline(_))
+            | Some(Attribute::Custom(_)) => FunctionKind::Normal,
         };
 
         NoirFunction { def: fd, kind }
@@ -4,19 +4,37 @@
 //!
 //! Noir's Ast is produced by the parser and taken as input to name resolution,
 //! where it is converted into the Hir (defined in the hir_def module).
+mod call_graph;
+pub mod dispatch_table;
 mod expression;
 mod function;
+mod partition;
+mod rename;
+mod respan;
+mod self_substitution;
 mod statement;
 mod structure;
+mod trait_format;
+mod trait_json;
+mod trait_merge;
+pub mod trait_registry;
 mod traits;
 mod type_alias;
+pub mod visitor;
 
+pub use call_graph::*;
 pub use expression::*;
 pub use function::*;
+pub use partition::*;
+pub use rename::*;
+pub use respan::*;
 
 use noirc_errors::Span;
 pub use statement::*;
 pub use structure::*;
+pub use trait_format::*;
+pub use trait_json::*;
+pub use trait_merge::*;
 pub use traits::*;
 pub use type_alias::*;
 
@@ -47,6 +65,33 @@ pub enum UnresolvedTypeData {
     /// &mut T
     MutableReference(Box<UnresolvedType>),
 
+    /// A trait object, as in `dyn Baz`, whose concrete type is only known at runtime.
+    ///
+    /// Only the syntax and the object safety of the trait are checked so far. The plan for
+    /// executing trait objects is to lower each one to a pair of its value, erased to a
+    /// pointer, and a vtable: a tuple holding a function value for each method of the trait,
+    /// in declaration order, built for each concrete type at the point it is coerced into the
+    /// trait object. Method calls would then index the vtable and pass the value as `self`.
+    TraitObject(TraitBound),
+
+    /// An opaque type, as in `impl Iterator`, which may only be the return type of a trait
+    /// method. Each impl of the method returns its own concrete type satisfying the bounds,
+    /// while callers may only use the methods of the traits named.
+    ///
+    /// The concrete type is only known when an impl's method is checked against the trait's
+    /// declaration, and is filled in by the def collector for the impl's copy of the method.
+    Opaque {
+        bounds: Vec<TraitBound>,
+        concrete_type: Option<Box<UnresolvedType>>,
+    },
+
+    /// An associated type of a trait impl, as in `<Bar as Baz>::Assoc`
+    Projection {
+        object_type: Box<UnresolvedType>,
+        trait_name: Ident,
+        item_name: Ident,
+    },
+
     // Note: Tuples have no visibility, instead each of their elements may have one.
     Tuple(Vec<UnresolvedType>),
 
@@ -104,13 +149,19 @@ impl std::fmt::Display for UnresolvedTypeData {
                 Signedness::Signed => write!(f, "i{num_bits}"),
                 Signedness::Unsigned => write!(f, "u{num_bits}"),
             },
-            Named(s, args) => {
+            Named(path, args) => {
+                // Types are written as in source, without the `plain::` of a displayed path
+                if path.kind != PathKind::Plain {
+                    write!(f, "{}::", path.kind)?;
+                }
+                let segments = vecmap(&path.segments, ToString::to_string);
+                write!(f, "{}", segments.join("::"))?;
+
                 let args = vecmap(args, |arg| ToString::to_string(&arg.typ));
-                if args.is_empty() {
-                    write!(f, "{s}")
-                } else {
-                    write!(f, "{}<{}>", s, args.join(", "))
+                if !args.is_empty() {
+                    write!(f, "<{}>", args.join(", "))?;
                 }
+                Ok(())
             }
             Tuple(elements) => {
                 let elements = vecmap(elements, ToString::to_string);
@@ -138,6 +189,13 @@ impl std::fmt::Display for UnresolvedTypeData {
                 }
             }
             MutableReference(element) => write!(f, "&mut {element}"),
+            TraitObject(bound) => write!(f, "dyn {bound}"),
+            Opaque { bounds, .. } => {
+                write!(f, "impl {}", vecmap(bounds, ToString::to_string).join(" + "))
+            }
+            Projection { object_type, trait_name, item_name } => {
+                write!(f, "<{object_type} as {trait_name}>::{item_name}")
+            }
             Unit => write!(f, "()"),
             Error => write!(f, "error"),
             Unspecified => write!(f, "unspecified"),
@@ -171,6 +229,87 @@ impl UnresolvedType {
     pub fn unspecified() -> UnresolvedType {
         UnresolvedType { typ: UnresolvedTypeData::Unspecified, span: None }
     }
+
+    /// True if this type is `Self`, such as in the constraint `Self: Eq`.
+    pub fn is_self_type(&self) -> bool {
+        match &self.typ {
+            UnresolvedTypeData::Named(path, args) => {
+                args.is_empty() && path.segments.len() == 1 && path.segments[0].0.contents == "Self"
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns every name within this type which may refer to a generic, in the order they
+    /// appear. These are the single-segment paths without generic arguments, whether used as a
+    /// type or as an array or string length. Whether each one is a generic or some other named
+    /// type is only known once it has been resolved.
+    pub fn referenced_generics(&self) -> Vec<&Ident> {
+        let mut names = Vec::new();
+        self.collect_referenced_generics(&mut names);
+        names
+    }
+
+    fn collect_referenced_generics<'a>(&'a self, names: &mut Vec<&'a Ident>) {
+        match &self.typ {
+            UnresolvedTypeData::Named(path, args) => {
+                match (path.segments.as_slice(), path.kind) {
+                    ([name], PathKind::Plain) if args.is_empty() => names.push(name),
+                    _ => (),
+                }
+                for arg in args {
+                    arg.collect_referenced_generics(names);
+                }
+            }
+            UnresolvedTypeData::Array(length, element) => {
+                if let Some(length) = length {
+                    length.collect_referenced_generics(names);
+                }
+                element.collect_referenced_generics(names);
+            }
+            UnresolvedTypeData::Expression(length) => length.collect_referenced_generics(names),
+            UnresolvedTypeData::String(Some(length)) => length.collect_referenced_generics(names),
+            UnresolvedTypeData::FormatString(length, elements) => {
+                length.collect_referenced_generics(names);
+                elements.collect_referenced_generics(names);
+            }
+            UnresolvedTypeData::MutableReference(element) => {
+                element.collect_referenced_generics(names);
+            }
+            UnresolvedTypeData::Projection { object_type, .. } => {
+                object_type.collect_referenced_generics(names);
+            }
+            UnresolvedTypeData::TraitObject(bound) => {
+                for generic in &bound.trait_generics {
+                    generic.collect_referenced_generics(names);
+                }
+            }
+            UnresolvedTypeData::Opaque { bounds, .. } => {
+                for generic in bounds.iter().flat_map(|bound| &bound.trait_generics) {
+                    generic.collect_referenced_generics(names);
+                }
+            }
+            UnresolvedTypeData::Tuple(elements) => {
+                for element in elements {
+                    element.collect_referenced_generics(names);
+                }
+            }
+            UnresolvedTypeData::Function(args, ret, env) => {
+                for arg in args {
+                    arg.collect_referenced_generics(names);
+                }
+                ret.collect_referenced_generics(names);
+                env.collect_referenced_generics(names);
+            }
+            UnresolvedTypeData::FieldElement
+            | UnresolvedTypeData::Integer(..)
+            | UnresolvedTypeData::Bool
+            | UnresolvedTypeData::String(None)
+            | UnresolvedTypeData::Unit
+            | UnresolvedTypeData::Unspecified
+            | UnresolvedTypeData::Error => (),
+        }
+    }
 }
 
 impl UnresolvedTypeData {
@@ -209,6 +348,21 @@ impl UnresolvedTypeExpression {
         })
     }
 
+    fn collect_referenced_generics<'a>(&'a self, names: &mut Vec<&'a Ident>) {
+        match self {
+            UnresolvedTypeExpression::Variable(path) => {
+                if let ([name], PathKind::Plain) = (path.segments.as_slice(), path.kind) {
+                    names.push(name);
+                }
+            }
+            UnresolvedTypeExpression::Constant(..) => (),
+            UnresolvedTypeExpression::BinaryOperation(lhs, _, rhs, _) => {
+                lhs.collect_referenced_generics(names);
+                rhs.collect_referenced_generics(names);
+            }
+        }
+    }
+
     pub fn span(&self) -> Span {
         match self {
             UnresolvedTypeExpression::Variable(path) => path.span(),
@@ -0,0 +1,78 @@
+use crate::{parser::ParsedModule, NoirTrait, TraitImpl, TypeImpl};
+
+/// Splits the trait-related items of `module` into its trait declarations, its trait impls and
+/// its inherent impls, in that order.
+///
+/// Items of inline submodules (`mod foo { ... }`) are included alongside those of `module`, with
+/// each group kept in source order. Submodules declared in other files (`mod foo;`) are not
+/// visited since their contents are not part of `module`.
+pub fn partition_trait_items(
+    module: &ParsedModule,
+) -> (Vec<NoirTrait>, Vec<TraitImpl>, Vec<TypeImpl>) {
+    let mut traits = Vec::new();
+    let mut trait_impls = Vec::new();
+    let mut type_impls = Vec::new();
+    collect_trait_items(module, &mut traits, &mut trait_impls, &mut type_impls);
+
+    // Submodules are stored apart from the items around them, so a stable sort on the
+    // position of each item restores the order they were written in.
+    traits.sort_by_key(|noir_trait| noir_trait.span.start());
+    trait_impls.sort_by_key(|trait_impl| trait_impl.object_type_span.start());
+    type_impls.sort_by_key(|type_impl| type_impl.type_span.start());
+
+    (traits, trait_impls, type_impls)
+}
+
+fn collect_trait_items(
+    module: &ParsedModule,
+    traits: &mut Vec<NoirTrait>,
+    trait_impls: &mut Vec<TraitImpl>,
+    type_impls: &mut Vec<TypeImpl>,
+) {
+    traits.extend(module.traits.iter().cloned());
+    trait_impls.extend(module.trait_impls.iter().cloned());
+    type_impls.extend(module.impls.iter().cloned());
+
+    for submodule in &module.submodules {
+        collect_trait_items(&submodule.contents, traits, trait_impls, type_impls);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::partition_trait_items;
+    use crate::parse_program;
+
+    #[test]
+    fn partitions_items_in_source_order() {
+        let src = "
+            struct Foo {}
+            trait First {}
+            impl Foo { fn new() -> Self { Foo {} } }
+            impl First for Foo {}
+            mod inner {
+                trait Second {}
+                impl Second for Field {}
+                impl crate::Foo { fn other() {} }
+            }
+            trait Third {}
+            impl Third for Foo {}
+            fn main() {}
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let (traits, trait_impls, type_impls) = partition_trait_items(&program);
+
+        let names: Vec<_> = traits.iter().map(|noir_trait| noir_trait.name.to_string()).collect();
+        assert_eq!(names, vec!["First", "Second", "Third"]);
+
+        let names: Vec<_> =
+            trait_impls.iter().map(|trait_impl| trait_impl.trait_name.to_string()).collect();
+        assert_eq!(names, vec!["First", "Second", "Third"]);
+
+        assert_eq!(type_impls.len(), 2);
+        assert_eq!(type_impls[0].methods[0].name(), "new");
+        assert_eq!(type_impls[1].methods[0].name(), "other");
+    }
+}
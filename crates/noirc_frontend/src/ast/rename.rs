@@ -0,0 +1,186 @@
+use crate::{
+    parser::ParsedModule, Ident, NoirFunction, PathKind, TraitBound, TraitConstraint,
+    TraitImplItem, TraitItem,
+};
+
+/// Renames the trait `from` declared in `module` to `to`, along with every reference to it as a
+/// trait bound or as the trait of a trait impl. Returns the number of references renamed, not
+/// counting the trait's declaration.
+///
+/// `module` is treated as the crate root. References within a submodule are only renamed if the
+/// submodule imports the trait with `use crate::from;` (or `use from;`), in which case the import
+/// is renamed as well. Submodules which declare their own trait named `from` are left untouched,
+/// as are those which import the trait under an alias beyond renaming the import itself.
+/// Submodules declared in other files (`mod foo;`) must be renamed separately.
+///
+/// Nothing is renamed if `module` does not declare a trait named `from`.
+pub fn rename_trait(module: &mut ParsedModule, from: &str, to: &str) -> usize {
+    let Some(noir_trait) =
+        module.traits.iter_mut().find(|noir_trait| noir_trait.name.0.contents == from)
+    else {
+        return 0;
+    };
+    noir_trait.name.0.contents = to.to_string();
+
+    let mut renamer = TraitRenamer { from, to, count: 0 };
+    renamer.rename_references(module);
+    renamer.rename_submodules(module);
+    renamer.count
+}
+
+struct TraitRenamer<'a> {
+    from: &'a str,
+    to: &'a str,
+    count: usize,
+}
+
+impl<'a> TraitRenamer<'a> {
+    fn rename_submodules(&mut self, module: &mut ParsedModule) {
+        for submodule in &mut module.submodules {
+            let module = &mut submodule.contents;
+            let shadowed =
+                module.traits.iter().any(|noir_trait| noir_trait.name.0.contents == self.from);
+            if !shadowed && self.rename_import(module) {
+                self.rename_references(module);
+            }
+            self.rename_submodules(module);
+        }
+    }
+
+    /// Renames any import of the trait within `module`, returning true if the trait is imported
+    /// under its own name.
+    fn rename_import(&mut self, module: &mut ParsedModule) -> bool {
+        let import = module.imports.iter_mut().find(|import| {
+            matches!(import.path.kind, PathKind::Crate | PathKind::Plain)
+                && import.path.segments.len() == 1
+                && import.path.segments[0].0.contents == self.from
+        });
+
+        match import {
+            Some(import) => {
+                self.rename(&mut import.path.segments[0]);
+                import.alias.is_none()
+            }
+            None => false,
+        }
+    }
+
+    fn rename_references(&mut self, module: &mut ParsedModule) {
+        for noir_trait in &mut module.traits {
+            self.rename_bounds(&mut noir_trait.supertraits);
+            self.rename_where_clause(&mut noir_trait.where_clause);
+            for item in &mut noir_trait.items {
+                match item {
+                    TraitItem::Function { where_clause, .. } => {
+                        self.rename_where_clause(where_clause);
+                    }
+                    TraitItem::Type { bounds, where_clause, .. } => {
+                        self.rename_bounds(bounds);
+                        self.rename_where_clause(where_clause);
+                    }
+                    TraitItem::Constant { where_clause, .. } => {
+                        self.rename_where_clause(where_clause);
+                    }
+                }
+            }
+        }
+
+        for trait_impl in &mut module.trait_impls {
+            if trait_impl.trait_name.0.contents == self.from {
+                self.rename(&mut trait_impl.trait_name);
+            }
+            self.rename_where_clause(&mut trait_impl.where_clause);
+            for item in &mut trait_impl.items {
+                if let TraitImplItem::Function(function) = item {
+                    self.rename_function(function);
+                }
+            }
+        }
+
+        for type_impl in &mut module.impls {
+            type_impl.methods.iter_mut().for_each(|method| self.rename_function(method));
+        }
+        module.functions.iter_mut().for_each(|function| self.rename_function(function));
+    }
+
+    fn rename_function(&mut self, function: &mut NoirFunction) {
+        self.rename_where_clause(&mut function.def.where_clause);
+    }
+
+    fn rename_where_clause(&mut self, where_clause: &mut [TraitConstraint]) {
+        for constraint in where_clause {
+            self.rename_bound(&mut constraint.trait_bound);
+        }
+    }
+
+    fn rename_bounds(&mut self, bounds: &mut [TraitBound]) {
+        bounds.iter_mut().for_each(|bound| self.rename_bound(bound));
+    }
+
+    fn rename_bound(&mut self, bound: &mut TraitBound) {
+        if bound.trait_name.0.contents == self.from {
+            self.rename(&mut bound.trait_name);
+        }
+    }
+
+    fn rename(&mut self, ident: &mut Ident) {
+        ident.0.contents = self.to.to_string();
+        self.count += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rename_trait;
+    use crate::parse_program;
+
+    #[test]
+    fn renames_trait_references() {
+        let src = "
+            trait Eq { fn eq(self, other: Self) -> bool; }
+            trait Ord: Eq {}
+            trait Other {}
+            struct Foo {}
+            impl Eq for Foo { fn eq(self, other: Self) -> bool { true } }
+            impl Other for Foo {}
+            fn equal<T>(a: T, b: T) -> bool where T: Eq + Other { a.eq(b) }
+
+            mod imported {
+                use crate::Eq;
+                impl Eq for Field { fn eq(self, other: Self) -> bool { true } }
+            }
+            mod shadowed {
+                trait Eq {}
+                impl Eq for Field {}
+            }
+            mod unrelated {
+                fn foo<T>(x: T) where T: Eq {}
+            }
+        ";
+        let (mut program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        // The supertrait, both impls, the where clause constraint and the import
+        assert_eq!(rename_trait(&mut program, "Eq", "PartialEq"), 5);
+
+        assert_eq!(program.traits[0].name.0.contents, "PartialEq");
+        assert_eq!(program.traits[1].supertraits[0].trait_name.0.contents, "PartialEq");
+        assert_eq!(program.trait_impls[0].trait_name.0.contents, "PartialEq");
+        assert_eq!(program.trait_impls[1].trait_name.0.contents, "Other");
+
+        let where_clause = &program.functions[0].def.where_clause;
+        assert_eq!(where_clause[0].trait_bound.trait_name.0.contents, "PartialEq");
+        assert_eq!(where_clause[1].trait_bound.trait_name.0.contents, "Other");
+
+        let submodule = |index: usize| &program.submodules[index].contents;
+        assert_eq!(submodule(0).imports[0].path.segments[0].0.contents, "PartialEq");
+        assert_eq!(submodule(0).trait_impls[0].trait_name.0.contents, "PartialEq");
+        assert_eq!(submodule(1).traits[0].name.0.contents, "Eq");
+        assert_eq!(submodule(1).trait_impls[0].trait_name.0.contents, "Eq");
+
+        let where_clause = &submodule(2).functions[0].def.where_clause;
+        assert_eq!(where_clause[0].trait_bound.trait_name.0.contents, "Eq");
+
+        assert_eq!(rename_trait(&mut program, "Missing", "Other"), 0);
+    }
+}
@@ -0,0 +1,319 @@
+use noirc_errors::{Span, Spanned};
+
+use crate::{
+    ArrayLiteral, BlockExpression, Expression, ExpressionKind, FunctionReturnType, Ident, LValue,
+    Literal, Path, Pattern, Statement, TraitBound, TraitConstraint, TraitItem, UnresolvedType,
+    UnresolvedTypeData, UnresolvedTypeExpression,
+};
+
+/// Overwrites the span of an AST node, and of every node nested within it, with a single span.
+///
+/// This is used when code is copied into a new location, e.g. by a macro, so that any errors
+/// within the copied code are reported where it was expanded rather than where it was written.
+pub trait Respan {
+    fn respan(&mut self, span: Span);
+}
+
+impl<T: Respan> Respan for Vec<T> {
+    fn respan(&mut self, span: Span) {
+        self.iter_mut().for_each(|node| node.respan(span));
+    }
+}
+
+impl<T: Respan> Respan for Option<T> {
+    fn respan(&mut self, span: Span) {
+        if let Some(node) = self {
+            node.respan(span);
+        }
+    }
+}
+
+impl<T: Respan> Respan for Box<T> {
+    fn respan(&mut self, span: Span) {
+        self.as_mut().respan(span);
+    }
+}
+
+impl<A: Respan, B: Respan> Respan for (A, B) {
+    fn respan(&mut self, span: Span) {
+        self.0.respan(span);
+        self.1.respan(span);
+    }
+}
+
+impl Respan for Ident {
+    fn respan(&mut self, span: Span) {
+        self.0 = Spanned::from(span, std::mem::take(&mut self.0.contents));
+    }
+}
+
+impl Respan for Path {
+    fn respan(&mut self, span: Span) {
+        self.segments.respan(span);
+    }
+}
+
+impl Respan for UnresolvedType {
+    fn respan(&mut self, new_span: Span) {
+        self.typ.respan(new_span);
+        // Omitted types have no span and should stay that way
+        if let Some(span) = &mut self.span {
+            *span = new_span;
+        }
+    }
+}
+
+impl Respan for UnresolvedTypeData {
+    fn respan(&mut self, span: Span) {
+        match self {
+            UnresolvedTypeData::Array(length, element) => {
+                length.respan(span);
+                element.respan(span);
+            }
+            UnresolvedTypeData::Expression(expression) => expression.respan(span),
+            UnresolvedTypeData::String(length) => length.respan(span),
+            UnresolvedTypeData::FormatString(length, elements) => {
+                length.respan(span);
+                elements.respan(span);
+            }
+            UnresolvedTypeData::Named(path, generics) => {
+                path.respan(span);
+                generics.respan(span);
+            }
+            UnresolvedTypeData::MutableReference(element) => element.respan(span),
+            UnresolvedTypeData::TraitObject(bound) => bound.respan(span),
+            UnresolvedTypeData::Opaque { bounds, concrete_type } => {
+                bounds.respan(span);
+                concrete_type.respan(span);
+            }
+            UnresolvedTypeData::Projection { object_type, trait_name, item_name } => {
+                object_type.respan(span);
+                trait_name.respan(span);
+                item_name.respan(span);
+            }
+            UnresolvedTypeData::Tuple(elements) => elements.respan(span),
+            UnresolvedTypeData::Function(arguments, ret, env) => {
+                arguments.respan(span);
+                ret.respan(span);
+                env.respan(span);
+            }
+            UnresolvedTypeData::FieldElement
+            | UnresolvedTypeData::Integer(..)
+            | UnresolvedTypeData::Bool
+            | UnresolvedTypeData::Unit
+            | UnresolvedTypeData::Unspecified
+            | UnresolvedTypeData::Error => (),
+        }
+    }
+}
+
+impl Respan for UnresolvedTypeExpression {
+    fn respan(&mut self, new_span: Span) {
+        match self {
+            UnresolvedTypeExpression::Variable(path) => path.respan(new_span),
+            UnresolvedTypeExpression::Constant(_, span) => *span = new_span,
+            UnresolvedTypeExpression::BinaryOperation(lhs, _, rhs, span) => {
+                lhs.respan(new_span);
+                rhs.respan(new_span);
+                *span = new_span;
+            }
+        }
+    }
+}
+
+impl Respan for FunctionReturnType {
+    fn respan(&mut self, new_span: Span) {
+        match self {
+            FunctionReturnType::Default(span) => *span = new_span,
+            FunctionReturnType::Ty(typ, span) => {
+                typ.respan(new_span);
+                *span = new_span;
+            }
+        }
+    }
+}
+
+impl Respan for TraitConstraint {
+    fn respan(&mut self, span: Span) {
+        self.typ.respan(span);
+        self.trait_bound.respan(span);
+    }
+}
+
+impl Respan for TraitBound {
+    fn respan(&mut self, span: Span) {
+        self.trait_name.respan(span);
+        self.trait_generics.respan(span);
+    }
+}
+
+impl Respan for BlockExpression {
+    fn respan(&mut self, span: Span) {
+        self.0.respan(span);
+    }
+}
+
+impl Respan for Statement {
+    fn respan(&mut self, span: Span) {
+        match self {
+            Statement::Let(let_statement) => {
+                let_statement.pattern.respan(span);
+                let_statement.r#type.respan(span);
+                let_statement.expression.respan(span);
+            }
+            Statement::Constrain(constrain) => constrain.0.respan(span),
+            Statement::Expression(expression) | Statement::Semi(expression) => {
+                expression.respan(span);
+            }
+            Statement::Assign(assign) => {
+                assign.lvalue.respan(span);
+                assign.expression.respan(span);
+            }
+            Statement::Error => (),
+        }
+    }
+}
+
+impl Respan for Pattern {
+    fn respan(&mut self, new_span: Span) {
+        match self {
+            Pattern::Identifier(name) => name.respan(new_span),
+            Pattern::Mutable(pattern, span) => {
+                pattern.respan(new_span);
+                *span = new_span;
+            }
+            Pattern::Tuple(patterns, span) => {
+                patterns.respan(new_span);
+                *span = new_span;
+            }
+            Pattern::Struct(path, fields, span) => {
+                path.respan(new_span);
+                fields.respan(new_span);
+                *span = new_span;
+            }
+        }
+    }
+}
+
+impl Respan for LValue {
+    fn respan(&mut self, span: Span) {
+        match self {
+            LValue::Ident(name) => name.respan(span),
+            LValue::MemberAccess { object, field_name } => {
+                object.respan(span);
+                field_name.respan(span);
+            }
+            LValue::Index { array, index } => {
+                array.respan(span);
+                index.respan(span);
+            }
+            LValue::Dereference(lvalue) => lvalue.respan(span),
+        }
+    }
+}
+
+impl Respan for Expression {
+    fn respan(&mut self, span: Span) {
+        self.span = span;
+
+        match &mut self.kind {
+            ExpressionKind::Literal(Literal::Array(ArrayLiteral::Standard(elements))) => {
+                elements.respan(span);
+            }
+            ExpressionKind::Literal(Literal::Array(ArrayLiteral::Repeated {
+                repeated_element,
+                length,
+            })) => {
+                repeated_element.respan(span);
+                length.respan(span);
+            }
+            ExpressionKind::Literal(_) => (),
+            ExpressionKind::Block(block) => block.respan(span),
+            ExpressionKind::Prefix(prefix) => prefix.rhs.respan(span),
+            ExpressionKind::Index(index) => {
+                index.collection.respan(span);
+                index.index.respan(span);
+            }
+            ExpressionKind::Call(call) => {
+                call.func.respan(span);
+                call.arguments.respan(span);
+            }
+            ExpressionKind::MethodCall(call) => {
+                call.object.respan(span);
+                call.method_name.respan(span);
+                call.arguments.respan(span);
+            }
+            ExpressionKind::Constructor(constructor) => {
+                constructor.type_name.respan(span);
+                constructor.fields.respan(span);
+            }
+            ExpressionKind::MemberAccess(access) => {
+                access.lhs.respan(span);
+                access.rhs.respan(span);
+            }
+            ExpressionKind::Cast(cast) => {
+                cast.lhs.respan(span);
+                cast.r#type.respan(span);
+            }
+            ExpressionKind::Infix(infix) => {
+                infix.lhs.respan(span);
+                infix.operator = Spanned::from(span, infix.operator.contents);
+                infix.rhs.respan(span);
+            }
+            ExpressionKind::For(for_loop) => {
+                for_loop.identifier.respan(span);
+                for_loop.start_range.respan(span);
+                for_loop.end_range.respan(span);
+                for_loop.block.respan(span);
+            }
+            ExpressionKind::If(if_expression) => {
+                if_expression.condition.respan(span);
+                if_expression.consequence.respan(span);
+                if_expression.alternative.respan(span);
+            }
+            ExpressionKind::Variable(path) => path.respan(span),
+            ExpressionKind::Tuple(elements) => elements.respan(span),
+            ExpressionKind::Lambda(lambda) => {
+                lambda.parameters.respan(span);
+                lambda.return_type.respan(span);
+                lambda.body.respan(span);
+            }
+            ExpressionKind::Error => (),
+        }
+    }
+}
+
+impl Respan for TraitItem {
+    fn respan(&mut self, span: Span) {
+        match self {
+            TraitItem::Function {
+                attributes: _,
+                name,
+                generics,
+                parameters,
+                return_type,
+                where_clause,
+                body,
+            } => {
+                name.respan(span);
+                generics.respan(span);
+                parameters.respan(span);
+                return_type.respan(span);
+                where_clause.respan(span);
+                body.respan(span);
+            }
+            TraitItem::Constant { name, typ, where_clause, default_value } => {
+                name.respan(span);
+                typ.respan(span);
+                where_clause.respan(span);
+                default_value.respan(span);
+            }
+            TraitItem::Type { name, generics, bounds, where_clause } => {
+                name.respan(span);
+                generics.respan(span);
+                bounds.respan(span);
+                where_clause.respan(span);
+            }
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    ArrayLiteral, BlockExpression, Expression, ExpressionKind, FunctionReturnType, LValue, Literal,
+    NoirFunction, Path, PathKind, Pattern, Respan, Statement, TraitBound, TraitConstraint,
+    TraitImpl, TraitImplItem, UnresolvedType, UnresolvedTypeData,
+};
+
+impl TraitImpl {
+    /// Returns a copy of this impl with every `Self` within its items, including their
+    /// signatures, bodies and associated type aliases, replaced by the impl's object type. The
+    /// impl's own where clause is rewritten in the same way.
+    ///
+    /// Where `Self` begins a path, as in `Self::new()` or `Self { x }`, it can only be replaced
+    /// if the object type is named by a path, and any generic arguments of the object type are
+    /// dropped as paths can't hold them. Such paths are left unchanged for other object types,
+    /// such as arrays or tuples.
+    pub fn specialize_self(&self) -> TraitImpl {
+        let object_path = match &self.object_type.typ {
+            UnresolvedTypeData::Named(path, _) => Some(path.clone()),
+            _ => None,
+        };
+        let with = SelfType { typ: &self.object_type, path: object_path.as_ref() };
+
+        let mut specialized = self.clone();
+        specialized.where_clause.substitute_self(&with);
+        for item in &mut specialized.items {
+            match item {
+                TraitImplItem::Function(function) => function.substitute_self(&with),
+                TraitImplItem::Constant(_, typ, value) => {
+                    typ.substitute_self(&with);
+                    value.substitute_self(&with);
+                }
+                TraitImplItem::Type { alias, .. } => alias.substitute_self(&with),
+            }
+        }
+        specialized
+    }
+}
+
+/// The type `Self` is replaced with, along with its path if it's a named type.
+struct SelfType<'a> {
+    typ: &'a UnresolvedType,
+    path: Option<&'a Path>,
+}
+
+/// Replaces `Self` within an AST node, and every node nested within it, with a concrete type.
+trait SubstituteSelf {
+    fn substitute_self(&mut self, with: &SelfType);
+}
+
+impl<T: SubstituteSelf> SubstituteSelf for Vec<T> {
+    fn substitute_self(&mut self, with: &SelfType) {
+        self.iter_mut().for_each(|node| node.substitute_self(with));
+    }
+}
+
+impl<T: SubstituteSelf> SubstituteSelf for Option<T> {
+    fn substitute_self(&mut self, with: &SelfType) {
+        if let Some(node) = self {
+            node.substitute_self(with);
+        }
+    }
+}
+
+impl<T: SubstituteSelf> SubstituteSelf for Box<T> {
+    fn substitute_self(&mut self, with: &SelfType) {
+        self.as_mut().substitute_self(with);
+    }
+}
+
+fn starts_with_self(path: &Path) -> bool {
+    path.kind == PathKind::Plain
+        && path.segments.first().map_or(false, |segment| segment.0.contents == "Self")
+}
+
+impl SubstituteSelf for Path {
+    fn substitute_self(&mut self, with: &SelfType) {
+        let Some(object_path) = with.path.filter(|_| starts_with_self(self)) else {
+            return;
+        };
+        let rest = self.segments.split_off(1);
+        let mut segments = object_path.segments.clone();
+        // Errors in the replaced segments should be reported where `Self` was written
+        segments.respan(self.segments[0].span());
+
+        self.kind = object_path.kind;
+        self.segments = segments;
+        self.segments.extend(rest);
+    }
+}
+
+impl SubstituteSelf for UnresolvedType {
+    fn substitute_self(&mut self, with: &SelfType) {
+        if let UnresolvedTypeData::Named(path, generics) = &self.typ {
+            if generics.is_empty() && path.segments.len() == 1 && starts_with_self(path) {
+                let mut typ = with.typ.clone();
+                if let Some(span) = self.span {
+                    typ.respan(span);
+                }
+                *self = typ;
+                return;
+            }
+        }
+
+        match &mut self.typ {
+            UnresolvedTypeData::Array(_, element)
+            | UnresolvedTypeData::MutableReference(element)
+            | UnresolvedTypeData::Projection { object_type: element, .. } => {
+                element.substitute_self(with);
+            }
+            UnresolvedTypeData::FormatString(_, elements) => elements.substitute_self(with),
+            UnresolvedTypeData::Named(path, generics) => {
+                path.substitute_self(with);
+                generics.substitute_self(with);
+            }
+            UnresolvedTypeData::TraitObject(bound) => bound.substitute_self(with),
+            UnresolvedTypeData::Opaque { bounds, concrete_type } => {
+                bounds.substitute_self(with);
+                concrete_type.substitute_self(with);
+            }
+            UnresolvedTypeData::Tuple(elements) => elements.substitute_self(with),
+            UnresolvedTypeData::Function(arguments, ret, env) => {
+                arguments.substitute_self(with);
+                ret.substitute_self(with);
+                env.substitute_self(with);
+            }
+            UnresolvedTypeData::FieldElement
+            | UnresolvedTypeData::Integer(..)
+            | UnresolvedTypeData::Bool
+            | UnresolvedTypeData::Expression(_)
+            | UnresolvedTypeData::String(_)
+            | UnresolvedTypeData::Unit
+            | UnresolvedTypeData::Unspecified
+            | UnresolvedTypeData::Error => (),
+        }
+    }
+}
+
+impl SubstituteSelf for FunctionReturnType {
+    fn substitute_self(&mut self, with: &SelfType) {
+        if let FunctionReturnType::Ty(typ, _) = self {
+            typ.substitute_self(with);
+        }
+    }
+}
+
+impl SubstituteSelf for TraitConstraint {
+    fn substitute_self(&mut self, with: &SelfType) {
+        self.typ.substitute_self(with);
+        self.trait_bound.substitute_self(with);
+    }
+}
+
+impl SubstituteSelf for TraitBound {
+    fn substitute_self(&mut self, with: &SelfType) {
+        self.trait_generics.substitute_self(with);
+    }
+}
+
+impl SubstituteSelf for NoirFunction {
+    fn substitute_self(&mut self, with: &SelfType) {
+        let def = &mut self.def;
+        for (pattern, typ, _) in &mut def.parameters {
+            pattern.substitute_self(with);
+            typ.substitute_self(with);
+        }
+        def.return_type.substitute_self(with);
+        def.where_clause.substitute_self(with);
+        def.body.substitute_self(with);
+    }
+}
+
+impl SubstituteSelf for BlockExpression {
+    fn substitute_self(&mut self, with: &SelfType) {
+        self.0.substitute_self(with);
+    }
+}
+
+impl SubstituteSelf for Statement {
+    fn substitute_self(&mut self, with: &SelfType) {
+        match self {
+            Statement::Let(let_statement) => {
+                let_statement.pattern.substitute_self(with);
+                let_statement.r#type.substitute_self(with);
+                let_statement.expression.substitute_self(with);
+            }
+            Statement::Constrain(constrain) => constrain.0.substitute_self(with),
+            Statement::Expression(expression) | Statement::Semi(expression) => {
+                expression.substitute_self(with);
+            }
+            Statement::Assign(assign) => {
+                assign.lvalue.substitute_self(with);
+                assign.expression.substitute_self(with);
+            }
+            Statement::Error => (),
+        }
+    }
+}
+
+impl SubstituteSelf for Pattern {
+    fn substitute_self(&mut self, with: &SelfType) {
+        match self {
+            Pattern::Identifier(_) => (),
+            Pattern::Mutable(pattern, _) => pattern.substitute_self(with),
+            Pattern::Tuple(patterns, _) => patterns.substitute_self(with),
+            Pattern::Struct(path, fields, _) => {
+                path.substitute_self(with);
+                fields.iter_mut().for_each(|(_, pattern)| pattern.substitute_self(with));
+            }
+        }
+    }
+}
+
+impl SubstituteSelf for LValue {
+    fn substitute_self(&mut self, with: &SelfType) {
+        match self {
+            LValue::Ident(_) => (),
+            LValue::MemberAccess { object, .. } => object.substitute_self(with),
+            LValue::Index { array, index } => {
+                array.substitute_self(with);
+                index.substitute_self(with);
+            }
+            LValue::Dereference(lvalue) => lvalue.substitute_self(with),
+        }
+    }
+}
+
+impl SubstituteSelf for Expression {
+    fn substitute_self(&mut self, with: &SelfType) {
+        match &mut self.kind {
+            ExpressionKind::Literal(Literal::Array(ArrayLiteral::Standard(elements))) => {
+                elements.substitute_self(with);
+            }
+            ExpressionKind::Literal(Literal::Array(ArrayLiteral::Repeated {
+                repeated_element,
+                length,
+            })) => {
+                repeated_element.substitute_self(with);
+                length.substitute_self(with);
+            }
+            ExpressionKind::Literal(_) => (),
+            ExpressionKind::Block(block) => block.substitute_self(with),
+            ExpressionKind::Prefix(prefix) => prefix.rhs.substitute_self(with),
+            ExpressionKind::Index(index) => {
+                index.collection.substitute_self(with);
+                index.index.substitute_self(with);
+            }
+            ExpressionKind::Call(call) => {
+                call.func.substitute_self(with);
+                call.arguments.substitute_self(with);
+            }
+            ExpressionKind::MethodCall(call) => {
+                call.object.substitute_self(with);
+                call.arguments.substitute_self(with);
+            }
+            ExpressionKind::Constructor(constructor) => {
+                constructor.type_name.substitute_self(with);
+                constructor.fields.iter_mut().for_each(|(_, field)| field.substitute_self(with));
+            }
+            ExpressionKind::MemberAccess(access) => access.lhs.substitute_self(with),
+            ExpressionKind::Cast(cast) => {
+                cast.lhs.substitute_self(with);
+                cast.r#type.substitute_self(with);
+            }
+            ExpressionKind::Infix(infix) => {
+                infix.lhs.substitute_self(with);
+                infix.rhs.substitute_self(with);
+            }
+            ExpressionKind::For(for_loop) => {
+                for_loop.start_range.substitute_self(with);
+                for_loop.end_range.substitute_self(with);
+                for_loop.block.substitute_self(with);
+            }
+            ExpressionKind::If(if_expression) => {
+                if_expression.condition.substitute_self(with);
+                if_expression.consequence.substitute_self(with);
+                if_expression.alternative.substitute_self(with);
+            }
+            ExpressionKind::Variable(path) => path.substitute_self(with),
+            ExpressionKind::Tuple(elements) => elements.substitute_self(with),
+            ExpressionKind::Lambda(lambda) => {
+                for (pattern, typ) in &mut lambda.parameters {
+                    pattern.substitute_self(with);
+                    typ.substitute_self(with);
+                }
+                lambda.return_type.substitute_self(with);
+                lambda.body.substitute_self(with);
+            }
+            ExpressionKind::Error => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_program, TraitImplItem};
+
+    #[test]
+    fn replaces_self_with_the_object_type() {
+        let src = "
+            impl Default for Point {
+                fn default() -> Self {
+                    let origin: Self = Self { x: 0, y: 0 };
+                    Self::check(origin)
+                }
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let specialized = program.trait_impls[0].specialize_self();
+        let TraitImplItem::Function(function) = &specialized.items[0] else {
+            panic!("expected a method");
+        };
+        assert_eq!(function.def.return_type.to_string(), "Point");
+
+        let displayed = specialized.to_string();
+        assert!(!displayed.contains("Self"), "{displayed}");
+        assert!(displayed.contains("Point::check"), "{displayed}");
+    }
+}
@@ -0,0 +1,136 @@
+use iter_extended::vecmap;
+
+use crate::{NoirTrait, TraitImpl, TypeImpl};
+
+/// The number of blank lines [`format_trait_section_with`] writes between declarations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlankLines {
+    /// Between two declarations of the same kind, such as two traits
+    pub within_group: usize,
+    /// Between the last declaration of one kind and the first declaration of the next
+    pub between_groups: usize,
+}
+
+impl Default for BlankLines {
+    fn default() -> Self {
+        BlankLines { within_group: 1, between_groups: 1 }
+    }
+}
+
+/// Formats the traits and impls of a module, separating each declaration from the next by a
+/// single blank line. See [`format_trait_section_with`].
+pub fn format_trait_section(
+    traits: &[NoirTrait],
+    type_impls: &[TypeImpl],
+    trait_impls: &[TraitImpl],
+) -> String {
+    format_trait_section_with(traits, type_impls, trait_impls, BlankLines::default())
+}
+
+/// Formats the traits and impls of a module as the trait-related section of a formatted file,
+/// with each declaration written by its `Display` impl and ending in a newline.
+///
+/// Traits come first, then inherent impls, then trait impls, each in the order given. Trait
+/// impls are then grouped by their trait, in the order of the traits in `traits`, followed by
+/// impls of traits declared elsewhere. Kinds of declaration which are missing are skipped along
+/// with the blank lines which would separate them.
+pub fn format_trait_section_with(
+    traits: &[NoirTrait],
+    type_impls: &[TypeImpl],
+    trait_impls: &[TraitImpl],
+    blank_lines: BlankLines,
+) -> String {
+    let trait_position = |trait_impl: &TraitImpl| {
+        traits
+            .iter()
+            .position(|noir_trait| noir_trait.name.0.contents == trait_impl.trait_name.0.contents)
+            .unwrap_or(traits.len())
+    };
+    let mut trait_impls: Vec<&TraitImpl> = trait_impls.iter().collect();
+    trait_impls.sort_by_key(|trait_impl| trait_position(trait_impl));
+
+    let groups = [
+        vecmap(traits, ToString::to_string),
+        vecmap(type_impls, ToString::to_string),
+        vecmap(trait_impls, ToString::to_string),
+    ];
+
+    let within_group = "\n".repeat(blank_lines.within_group + 1);
+    let between_groups = "\n".repeat(blank_lines.between_groups + 1);
+    let groups: Vec<String> = groups
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .map(|group| group.join(&within_group))
+        .collect();
+
+    let mut section = groups.join(&between_groups);
+    if !section.is_empty() {
+        section.push('\n');
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_trait_section, format_trait_section_with, BlankLines};
+    use crate::parse_program;
+
+    #[test]
+    fn formats_a_module_trait_section() {
+        let src = "
+            trait Default {
+                fn default() -> Self;
+            }
+
+            impl Container for Pair {
+                type Item = Field;
+            }
+
+            trait Container: Default {
+                type Item;
+                let SIZE: u32;
+                fn get(self, index: u32) -> Self::Item;
+            }
+
+            impl Pair {}
+
+            impl Default for Pair {
+                type Marker = Field;
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let formatted = format_trait_section(&program.traits, &program.impls, &program.trait_impls);
+        let expected = [
+            "trait Default {",
+            "    fn default() -> Self;",
+            "}",
+            "",
+            "trait Container: Default {",
+            "    type Item;",
+            "    let SIZE: u32;",
+            "    fn get(self: Self, index: u32) -> Self::Item;",
+            "}",
+            "",
+            "impl Pair {",
+            "}",
+            "",
+            "impl Default for Pair {",
+            "    type Marker = Field;",
+            "}",
+            "",
+            "impl Container for Pair {",
+            "    type Item = Field;",
+            "}",
+            "",
+        ];
+        assert_eq!(formatted, expected.join("\n"));
+
+        let blank_lines = BlankLines { within_group: 0, between_groups: 2 };
+        let compact = format_trait_section_with(&program.traits, &program.impls, &[], blank_lines);
+        assert!(compact.contains("}\ntrait Container"), "{compact}");
+        assert!(compact.ends_with("}\n\n\nimpl Pair {\n}\n"), "{compact}");
+        assert_eq!(format_trait_section(&[], &[], &[]), "");
+    }
+}
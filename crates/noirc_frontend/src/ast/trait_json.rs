@@ -0,0 +1,167 @@
+use iter_extended::vecmap;
+use serde_json::{json, Value};
+
+use crate::{FunctionReturnType, NoirTrait, TraitItem};
+
+/// The version of the schema written by [`trait_to_json`]. This must be bumped whenever the
+/// schema changes in a way which could break its consumers, such as renaming or removing a field.
+pub const TRAIT_JSON_VERSION: u32 = 1;
+
+/// Describes the public contract of `trait_def` as JSON, for tools such as documentation
+/// generators which don't parse Noir themselves. Only the AST is used, so no names are resolved
+/// and each type is given as it was written, e.g.
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "name": "Container",
+///   "generics": ["T"],
+///   "supertraits": ["Eq"],
+///   "where_clause": ["T: Default"],
+///   "items": [
+///     { "kind": "type", "name": "Item", "generics": [], "bounds": [], "where_clause": [],
+///       "has_default": false },
+///     { "kind": "constant", "name": "SIZE", "type": "u32", "where_clause": [],
+///       "has_default": true },
+///     { "kind": "function", "name": "get", "generics": [],
+///       "parameters": [{ "name": "self", "type": "Self" }], "return_type": "T",
+///       "where_clause": [], "has_default": false }
+///   ]
+/// }
+/// ```
+///
+/// Items are listed in source order, and `return_type` is `null` for functions which don't
+/// declare one.
+pub fn trait_to_json(trait_def: &NoirTrait) -> Value {
+    let items = vecmap(&trait_def.items, |item| match item {
+        TraitItem::Function {
+            name, generics, parameters, return_type, where_clause, body, ..
+        } => {
+            let parameters = vecmap(
+                parameters,
+                |(parameter, typ)| json!({ "name": parameter.to_string(), "type": typ.to_string() }),
+            );
+            let return_type = match return_type {
+                FunctionReturnType::Default(_) => None,
+                FunctionReturnType::Ty(typ, _) => Some(typ.to_string()),
+            };
+            json!({
+                "kind": "function",
+                "name": name.to_string(),
+                "generics": to_strings(generics),
+                "parameters": parameters,
+                "return_type": return_type,
+                "where_clause": to_strings(where_clause),
+                "has_default": body.is_some(),
+            })
+        }
+        TraitItem::Constant { name, typ, where_clause, default_value } => json!({
+            "kind": "constant",
+            "name": name.to_string(),
+            "type": typ.to_string(),
+            "where_clause": to_strings(where_clause),
+            "has_default": default_value.is_some(),
+        }),
+        // Associated types can't have defaults, but the field is kept so every item has one
+        TraitItem::Type { name, generics, bounds, where_clause } => json!({
+            "kind": "type",
+            "name": name.to_string(),
+            "generics": to_strings(generics),
+            "bounds": to_strings(bounds),
+            "where_clause": to_strings(where_clause),
+            "has_default": false,
+        }),
+    });
+
+    json!({
+        "version": TRAIT_JSON_VERSION,
+        "name": trait_def.name.to_string(),
+        "generics": to_strings(&trait_def.generics),
+        "supertraits": to_strings(&trait_def.supertraits),
+        "where_clause": to_strings(&trait_def.where_clause),
+        "items": items,
+    })
+}
+
+fn to_strings<T: ToString>(items: &[T]) -> Vec<String> {
+    vecmap(items, ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{trait_to_json, TRAIT_JSON_VERSION};
+    use crate::parse_program;
+
+    #[test]
+    fn describes_each_item() {
+        let src = "
+            trait Container<T>: Eq where T: Default {
+                type Item: Default;
+                let SIZE: u32 = 4;
+                fn get(self, index: u32) -> T;
+                fn is_empty(self) -> bool { Self::SIZE == 0 }
+                fn clear(self) {}
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let expected = json!({
+            "version": TRAIT_JSON_VERSION,
+            "name": "Container",
+            "generics": ["T"],
+            "supertraits": ["Eq"],
+            "where_clause": ["T: Default"],
+            "items": [
+                {
+                    "kind": "type",
+                    "name": "Item",
+                    "generics": [],
+                    "bounds": ["Default"],
+                    "where_clause": [],
+                    "has_default": false,
+                },
+                {
+                    "kind": "constant",
+                    "name": "SIZE",
+                    "type": "u32",
+                    "where_clause": [],
+                    "has_default": true,
+                },
+                {
+                    "kind": "function",
+                    "name": "get",
+                    "generics": [],
+                    "parameters": [
+                        { "name": "self", "type": "Self" },
+                        { "name": "index", "type": "u32" },
+                    ],
+                    "return_type": "T",
+                    "where_clause": [],
+                    "has_default": false,
+                },
+                {
+                    "kind": "function",
+                    "name": "is_empty",
+                    "generics": [],
+                    "parameters": [{ "name": "self", "type": "Self" }],
+                    "return_type": "bool",
+                    "where_clause": [],
+                    "has_default": true,
+                },
+                {
+                    "kind": "function",
+                    "name": "clear",
+                    "generics": [],
+                    "parameters": [{ "name": "self", "type": "Self" }],
+                    "return_type": null,
+                    "where_clause": [],
+                    "has_default": true,
+                },
+            ],
+        });
+        assert_eq!(trait_to_json(&program.traits[0]), expected);
+    }
+}
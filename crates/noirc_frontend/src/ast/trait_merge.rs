@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::{Ident, NoirTrait, TraitConstraint, TraitItem};
+
+/// Two traits being merged by [`merge_traits`] which both declare an item of the same name, but
+/// with different signatures or of different kinds.
+#[derive(Debug, Clone, Error)]
+#[error("`{}` is declared as `{first}` by one trait and `{second}` by the other", .name.0.contents)]
+pub struct MergeConflict {
+    pub name: Ident,
+    /// The signature of the item in the first trait, without any default
+    pub first: String,
+    /// The signature of the item in the second trait, without any default
+    pub second: String,
+}
+
+/// Combines the traits `a` and `b` into a new trait named `new_name`, which declares the items of
+/// `a` followed by those of `b` in source order. Generics, supertraits and where clause
+/// constraints are likewise combined, leaving out any repeating one from `a`. Generics are
+/// matched by name, so a generic declared by both traits becomes a single generic.
+///
+/// An item declared by both traits is only kept once if its signature is the same in each,
+/// ignoring spans, attributes, and the order of its where clause. The declaration from `a` is
+/// kept, taking the default from `b` if `a` doesn't provide one. Items whose signatures differ
+/// are reported as a [`MergeConflict`]. The new trait's span is that of `a`.
+pub fn merge_traits(
+    a: &NoirTrait,
+    b: &NoirTrait,
+    new_name: Ident,
+) -> Result<NoirTrait, MergeConflict> {
+    let mut items = a.items.clone();
+    for item in &b.items {
+        let existing = items.iter_mut().find(|existing| existing.name() == item.name());
+        let Some(existing) = existing else {
+            items.push(item.clone());
+            continue;
+        };
+
+        let (first, second) = (signature(existing), signature(item));
+        if first != second {
+            let name = existing.name().clone();
+            return Err(MergeConflict { name, first, second });
+        }
+        match (existing, item) {
+            (
+                TraitItem::Function { body: body @ None, .. },
+                TraitItem::Function { body: default, .. },
+            ) => *body = default.clone(),
+            (
+                TraitItem::Constant { default_value: value @ None, .. },
+                TraitItem::Constant { default_value: default, .. },
+            ) => *value = default.clone(),
+            _ => (),
+        }
+    }
+
+    Ok(NoirTrait {
+        name: new_name,
+        generics: union(&a.generics, &b.generics),
+        supertraits: union(&a.supertraits, &b.supertraits),
+        where_clause: union(&a.where_clause, &b.where_clause),
+        span: a.span,
+        items,
+    })
+}
+
+/// Formats `item` without its attributes or default, and with its where clause sorted, so
+/// that items with the same signature are formatted alike.
+fn signature(item: &TraitItem) -> String {
+    let sort = |where_clause: &mut Vec<TraitConstraint>| {
+        where_clause.sort_by_key(ToString::to_string);
+        where_clause.dedup_by_key(|constraint| constraint.to_string());
+    };
+
+    let mut item = item.clone();
+    match &mut item {
+        TraitItem::Function { attributes, where_clause, body, .. } => {
+            attributes.clear();
+            sort(where_clause);
+            *body = None;
+        }
+        TraitItem::Constant { where_clause, default_value, .. } => {
+            sort(where_clause);
+            *default_value = None;
+        }
+        TraitItem::Type { where_clause, .. } => sort(where_clause),
+    }
+    item.to_string()
+}
+
+/// The elements of `a` followed by those of `b`, leaving out any which were already given, as
+/// compared by their formatting so that spans are ignored.
+fn union<T: Clone + ToString>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut seen = HashSet::new();
+    a.iter().chain(b).filter(|element| seen.insert(element.to_string())).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use iter_extended::vecmap;
+    use noirc_errors::Span;
+
+    use super::merge_traits;
+    use crate::{parse_program, Ident, TraitItem};
+
+    #[test]
+    fn merges_items_and_reports_conflicts() {
+        let src = "
+            trait Read<T> where T: Default {
+                let SIZE: u32;
+                fn read(self) -> T;
+                fn len(self) -> u32;
+            }
+            trait Write<T> where T: Default, T: Eq {
+                let SIZE: u32 = 8;
+                fn write(self, value: T);
+                fn len(self) -> u32 { Self::SIZE }
+            }
+            trait Sized {
+                fn len(self) -> u64;
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+        let new_name = || Ident::new("ReadWrite".to_string(), Span::default());
+
+        let merged = merge_traits(&program.traits[0], &program.traits[1], new_name()).unwrap();
+        assert_eq!(merged.name.0.contents, "ReadWrite");
+        assert_eq!(vecmap(&merged.generics, |generic| generic.0.contents.clone()), vec!["T"]);
+        assert_eq!(vecmap(&merged.where_clause, ToString::to_string), vec!["T: Default", "T: Eq"]);
+
+        let names = vecmap(&merged.items, |item| item.name().0.contents.clone());
+        assert_eq!(names, vec!["SIZE", "read", "len", "write"]);
+        // Duplicates take the default of either trait
+        assert!(matches!(&merged.items[0], TraitItem::Constant { default_value: Some(_), .. }));
+        assert!(matches!(&merged.items[2], TraitItem::Function { body: Some(_), .. }));
+
+        let conflict =
+            merge_traits(&program.traits[0], &program.traits[2], new_name()).unwrap_err();
+        assert_eq!(conflict.name.0.contents, "len");
+        assert!(conflict.first.contains("-> u32"), "{}", conflict.first);
+        assert!(conflict.second.contains("-> u64"), "{}", conflict.second);
+    }
+}
@@ -0,0 +1,1570 @@
+//! The trait registry indexes the traits and trait impls declared in a program's Ast so that
+//! questions such as "does this type implement this trait?" can be answered without running
+//! name resolution or type checking.
+//!
+//! The registry also indexes inherent impls so that the methods available on a type can be
+//! listed, such as for autocompletion.
+//!
+//! Since the Ast is unresolved, traits and types are identified by name alone. Any single
+//! segment type name which isn't a struct or type alias known to the registry is assumed to
+//! be a generic.
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use iter_extended::vecmap;
+use thiserror::Error;
+
+use super::dispatch_table::{DispatchTable, TypeHead};
+use crate::{
+    BinaryTypeOperator, FunctionReturnType, Ident, NoirTrait, NoirTypeAlias, ParsedModule,
+    TraitBound, TraitConstraint, TraitImpl, TraitImplItem, TraitItem, TypeImpl, UnresolvedType,
+    UnresolvedTypeData, UnresolvedTypeExpression,
+};
+
+/// How deeply [`satisfies`] may recurse through where clauses and supertraits before giving up.
+pub const DEFAULT_SATISFACTION_DEPTH: usize = 32;
+
+/// A check of a trait bound which recursed more deeply than permitted, as happens with impls
+/// such as `impl<T> Foo for Box<T> where Box<Box<T>>: Foo` whose where clauses require ever
+/// larger types to satisfy the same bound.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("checking trait bounds recursed more than {limit} times: {}", .chain.join(", requiring "))]
+pub struct TraitResolutionOverflow {
+    pub limit: usize,
+    /// Each `type: bound` checked, from the original query to the one exceeding the limit
+    pub chain: Vec<String>,
+}
+
+/// The answer to whether a type satisfies a trait bound.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SatisfactionResult {
+    Yes,
+    No,
+    /// The answer depends on how some generic type is instantiated, or on information
+    /// which isn't available from the Ast. The reason is meant to be shown to users.
+    Unknown(String),
+}
+
+impl SatisfactionResult {
+    fn from_bool(result: bool) -> SatisfactionResult {
+        if result {
+            SatisfactionResult::Yes
+        } else {
+            SatisfactionResult::No
+        }
+    }
+
+    /// Combines two results which must both hold.
+    fn and(self, other: impl FnOnce() -> SatisfactionResult) -> SatisfactionResult {
+        match self {
+            SatisfactionResult::No => SatisfactionResult::No,
+            SatisfactionResult::Yes => other(),
+            SatisfactionResult::Unknown(reason) => match other() {
+                SatisfactionResult::No => SatisfactionResult::No,
+                _ => SatisfactionResult::Unknown(reason),
+            },
+        }
+    }
+
+    /// The result of the opposite question, as for a negative bound.
+    fn negate(self) -> SatisfactionResult {
+        match self {
+            SatisfactionResult::Yes => SatisfactionResult::No,
+            SatisfactionResult::No => SatisfactionResult::Yes,
+            unknown @ SatisfactionResult::Unknown(_) => unknown,
+        }
+    }
+
+    /// Combines two results of which either may hold.
+    fn or(self, other: impl FnOnce() -> SatisfactionResult) -> SatisfactionResult {
+        match self {
+            SatisfactionResult::Yes => SatisfactionResult::Yes,
+            SatisfactionResult::No => other(),
+            SatisfactionResult::Unknown(reason) => match other() {
+                SatisfactionResult::Yes => SatisfactionResult::Yes,
+                _ => SatisfactionResult::Unknown(reason),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TraitRegistry {
+    traits: HashMap<String, NoirTrait>,
+
+    /// Maps the name of each trait to all of its impls
+    impls: HashMap<String, Vec<TraitImpl>>,
+
+    /// The index of each impl within `impls`, bucketed by the head of its object type once
+    /// normalized
+    dispatch: DispatchTable<String>,
+
+    type_impls: Vec<TypeImpl>,
+
+    /// The names of every struct and type alias, used to tell concrete types from generics
+    type_names: HashSet<String>,
+
+    type_aliases: AliasTable,
+}
+
+/// Maps the name of each type alias to its definition.
+pub type AliasTable = HashMap<String, NoirTypeAlias>;
+
+impl TraitRegistry {
+    /// Creates a registry of every trait, trait impl and type within `module` and its submodules.
+    pub fn from_module(module: &ParsedModule) -> TraitRegistry {
+        let mut registry = TraitRegistry::default();
+        registry.add_module(module);
+        registry
+    }
+
+    /// Adds every trait, trait impl and type within `module` and its submodules.
+    /// Modules declared in other files (`mod foo;`) must be added separately.
+    pub fn add_module(&mut self, module: &ParsedModule) {
+        // Aliases are added first so that impls are dispatched on the types they expand to
+        // without rebuilding the dispatch table
+        for type_alias in &module.type_aliases {
+            self.add_type_alias(type_alias.clone());
+        }
+        for noir_trait in &module.traits {
+            self.add_trait(noir_trait.clone());
+        }
+        for trait_impl in &module.trait_impls {
+            self.add_impl(trait_impl.clone());
+        }
+        for type_impl in &module.impls {
+            self.add_type_impl(type_impl.clone());
+        }
+        for noir_struct in &module.types {
+            self.add_type_name(&noir_struct.name);
+        }
+        for submodule in &module.submodules {
+            self.add_module(&submodule.contents);
+        }
+    }
+
+    pub fn add_trait(&mut self, noir_trait: NoirTrait) {
+        self.traits.insert(noir_trait.name.0.contents.clone(), noir_trait);
+    }
+
+    pub fn add_impl(&mut self, trait_impl: TraitImpl) {
+        let trait_name = trait_impl.trait_name.0.contents.clone();
+        let head = self.impl_head(&trait_impl);
+        let impls = self.impls.entry(trait_name.clone()).or_default();
+        self.dispatch.insert(trait_name, impls.len(), head);
+        impls.push(trait_impl);
+    }
+
+    pub fn add_type_impl(&mut self, type_impl: TypeImpl) {
+        self.type_impls.push(type_impl);
+    }
+
+    /// Adds a type alias, which is expanded wherever it is used before types are compared.
+    ///
+    /// Impls are dispatched on their object types with aliases expanded, so adding an alias
+    /// once impls have been added rebuilds the index of impls.
+    pub fn add_type_alias(&mut self, type_alias: NoirTypeAlias) {
+        self.add_type_name(&type_alias.name);
+        self.type_aliases.insert(type_alias.name.0.contents.clone(), type_alias);
+
+        if !self.impls.is_empty() {
+            let mut dispatch = DispatchTable::default();
+            for (trait_name, impls) in &self.impls {
+                for (index, trait_impl) in impls.iter().enumerate() {
+                    dispatch.insert(trait_name.clone(), index, self.impl_head(trait_impl));
+                }
+            }
+            self.dispatch = dispatch;
+        }
+    }
+
+    /// Marks `name` as a concrete type rather than a generic.
+    pub fn add_type_name(&mut self, name: &Ident) {
+        self.type_names.insert(name.0.contents.clone());
+    }
+
+    pub fn get_trait(&self, trait_name: &str) -> Option<&NoirTrait> {
+        self.traits.get(trait_name)
+    }
+
+    pub fn traits(&self) -> impl Iterator<Item = &NoirTrait> {
+        self.traits.values()
+    }
+
+    pub fn impls_of(&self, trait_name: &str) -> &[TraitImpl] {
+        self.impls.get(trait_name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the head `trait_impl` is dispatched on, that of its object type once normalized,
+    /// or `None` if it is a blanket impl.
+    fn impl_head(&self, trait_impl: &TraitImpl) -> Option<TypeHead> {
+        TypeHead::of_impl(&self.normalize(&trait_impl.object_type), &trait_impl.impl_generics)
+    }
+
+    /// Returns the impls of `trait_name` which may apply to `typ`, a normalized type, in the
+    /// order they were added. Impls whose object types have a different head are left out,
+    /// unless `typ` is a generic or its head isn't known.
+    fn candidate_impls(&self, trait_name: &str, typ: &UnresolvedType) -> Vec<&TraitImpl> {
+        let head = TypeHead::of(typ).filter(|_| self.generic_name(typ).is_none());
+        let impls = self.impls_of(trait_name);
+        self.dispatch.candidates(trait_name, head.as_ref()).map(|index| &impls[index]).collect()
+    }
+
+    /// Returns every trait impl which may apply to `typ`, a normalized type, ordered by the name
+    /// of the trait implemented.
+    fn all_candidate_impls(&self, typ: &UnresolvedType) -> Vec<&TraitImpl> {
+        let mut trait_names: Vec<_> = self.impls.keys().collect();
+        trait_names.sort();
+        trait_names
+            .into_iter()
+            .flat_map(|trait_name| self.candidate_impls(trait_name, typ))
+            .collect()
+    }
+
+    /// Returns the name of `typ` if it is a generic type, as far as the registry can tell.
+    fn generic_name<'a>(&self, typ: &'a UnresolvedType) -> Option<&'a str> {
+        match &typ.typ {
+            UnresolvedTypeData::Named(path, generics)
+                if generics.is_empty() && path.segments.len() == 1 =>
+            {
+                let name = path.segments[0].0.contents.as_str();
+                (!self.type_names.contains(name)).then_some(name)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns whether `typ` satisfies `bound` given the traits and impls in `registry`.
+///
+/// A type satisfies a bound if an impl of the bound's trait applies to it, including blanket
+/// impls such as `impl<T> Eq for [T; 2] where T: Eq`, or if it satisfies any trait which has
+/// the bound's trait as a supertrait. This only reads from the registry so may be called as
+/// often as needed.
+///
+/// A negative bound such as `!Eq` is satisfied by exactly the types which don't satisfy `Eq`.
+/// The registry is assumed to hold every impl, so a type is only known not to implement a
+/// trait once no impl of it could apply.
+///
+/// Checks recursing more than [`DEFAULT_SATISFACTION_DEPTH`] times are given up on as
+/// [`SatisfactionResult::Unknown`]. See [`satisfies_within_depth`] to report them instead.
+pub fn satisfies(
+    typ: &UnresolvedType,
+    bound: &TraitBound,
+    registry: &TraitRegistry,
+) -> SatisfactionResult {
+    let mut search = SatisfactionSearch::new(DEFAULT_SATISFACTION_DEPTH);
+    satisfies_at_depth(typ, bound, registry, &mut search)
+}
+
+/// Returns whether `typ` satisfies `bound` like [`satisfies`], but fails with the chain of
+/// bounds being checked if that requires recursing through where clauses and supertraits more
+/// than `max_depth` times.
+pub fn satisfies_within_depth(
+    typ: &UnresolvedType,
+    bound: &TraitBound,
+    registry: &TraitRegistry,
+    max_depth: usize,
+) -> Result<SatisfactionResult, TraitResolutionOverflow> {
+    let mut search = SatisfactionSearch::new(max_depth);
+    let result = satisfies_at_depth(typ, bound, registry, &mut search);
+    match search.overflow {
+        Some(chain) => Err(TraitResolutionOverflow { limit: max_depth, chain }),
+        None => Ok(result),
+    }
+}
+
+/// The state of a single check of a trait bound, recording the bounds being checked so that
+/// a check recursing too deeply can be reported.
+struct SatisfactionSearch {
+    max_depth: usize,
+    /// Each `type: bound` being checked, from the original query inwards
+    chain: Vec<String>,
+    /// The chain as it was when the check first recursed more than `max_depth` times, if it did
+    overflow: Option<Vec<String>>,
+}
+
+impl SatisfactionSearch {
+    fn new(max_depth: usize) -> SatisfactionSearch {
+        SatisfactionSearch { max_depth, chain: Vec::new(), overflow: None }
+    }
+}
+
+/// Returns whether the type constrained by `constraint` satisfies its trait bound, as for
+/// each constraint within a where clause. The constrained type may be any type, including
+/// arrays and tuples such as in `where [T; N]: Serialize`.
+pub fn satisfies_constraint(
+    constraint: &TraitConstraint,
+    registry: &TraitRegistry,
+) -> SatisfactionResult {
+    satisfies(&constraint.typ, &constraint.trait_bound, registry)
+}
+
+fn satisfies_at_depth(
+    typ: &UnresolvedType,
+    bound: &TraitBound,
+    registry: &TraitRegistry,
+    search: &mut SatisfactionSearch,
+) -> SatisfactionResult {
+    let goal = format!("{typ}: {bound}");
+    if search.chain.len() > search.max_depth {
+        if search.overflow.is_none() {
+            let chain = search.chain.iter().cloned().chain(std::iter::once(goal.clone()));
+            search.overflow = Some(chain.collect());
+        }
+        return SatisfactionResult::Unknown(format!(
+            "reached the recursion limit while checking `{goal}`"
+        ));
+    }
+
+    if bound.negative {
+        let positive = TraitBound { negative: false, ..bound.clone() };
+        return satisfies_at_depth(typ, &positive, registry, search).negate();
+    }
+
+    search.chain.push(goal);
+    let result = satisfies_positive_bound(typ, bound, registry, search);
+    search.chain.pop();
+    result
+}
+
+fn satisfies_positive_bound(
+    typ: &UnresolvedType,
+    bound: &TraitBound,
+    registry: &TraitRegistry,
+    search: &mut SatisfactionSearch,
+) -> SatisfactionResult {
+    let typ = &registry.normalize(typ);
+
+    let trait_name = &bound.trait_name.0.contents;
+    if registry.get_trait(trait_name).is_none() {
+        return SatisfactionResult::Unknown(format!("trait `{trait_name}` is not defined"));
+    }
+
+    let mut result = SatisfactionResult::No;
+    for trait_impl in registry.candidate_impls(trait_name, typ) {
+        result = result.or(|| impl_applies(trait_impl, typ, bound, registry, search));
+    }
+
+    // Implementing a trait requires implementing its supertraits, so satisfying any
+    // subtrait of the bound implies satisfying the bound itself.
+    for subtrait in registry.traits() {
+        // Supertraits of generic traits would need the trait's generics substituted
+        if !subtrait.generics.is_empty() {
+            continue;
+        }
+
+        let implies_bound =
+            subtrait.supertraits.iter().any(|supertrait| same_bound(supertrait, bound));
+        if implies_bound {
+            let subtrait_bound = TraitBound {
+                trait_name: subtrait.name.clone(),
+                trait_generics: Vec::new(),
+                negative: false,
+            };
+            result = result.or(|| satisfies_at_depth(typ, &subtrait_bound, registry, search));
+        }
+    }
+
+    result
+}
+
+/// Returns every bound implied by `bound` through supertraits, such as `PartialOrd` and `Eq` for
+/// `Ord` given `trait Ord: PartialOrd` and `trait PartialOrd: Eq`. The generics of each trait are
+/// substituted along the way, so `Ord<Field>` implies `PartialOrd<Field>` given
+/// `trait Ord<T>: PartialOrd<T>`.
+///
+/// Each implied bound is listed once, nearest first, and `bound` itself is never included even
+/// if supertraits form a cycle back to it. Negative bounds and traits which aren't in the
+/// registry imply nothing.
+pub fn supertrait_closure(bound: &TraitBound, registry: &TraitRegistry) -> Vec<TraitBound> {
+    let mut closure: Vec<TraitBound> = Vec::new();
+    if bound.negative {
+        return closure;
+    }
+
+    let mut queue = VecDeque::from([bound.clone()]);
+    while let Some(current) = queue.pop_front() {
+        let Some(noir_trait) = registry.get_trait(&current.trait_name.0.contents) else {
+            continue;
+        };
+
+        let bindings: HashMap<_, _> = noir_trait
+            .generics
+            .iter()
+            .map(|generic| generic.0.contents.clone())
+            .zip(current.trait_generics.iter().cloned())
+            .collect();
+
+        for supertrait in &noir_trait.supertraits {
+            let implied = TraitBound {
+                trait_generics: vecmap(&supertrait.trait_generics, |generic| {
+                    substitute_alias_generics(generic, &bindings)
+                }),
+                ..supertrait.clone()
+            };
+
+            // Bounds already reached have had their supertraits queued, which breaks cycles
+            let reached = same_bound(&implied, bound)
+                || closure.iter().any(|existing| same_bound(existing, &implied));
+            if !reached {
+                closure.push(implied.clone());
+                queue.push_back(implied);
+            }
+        }
+    }
+    closure
+}
+
+/// Checks whether `trait_impl` implements `bound` for `typ`, including its where clause.
+fn impl_applies(
+    trait_impl: &TraitImpl,
+    typ: &UnresolvedType,
+    bound: &TraitBound,
+    registry: &TraitRegistry,
+    search: &mut SatisfactionSearch,
+) -> SatisfactionResult {
+    if trait_impl.trait_generics.len() != bound.trait_generics.len() {
+        return SatisfactionResult::No;
+    }
+
+    let mut matcher =
+        ImplMatcher { registry, generics: &trait_impl.impl_generics, bindings: HashMap::new() };
+
+    let mut result = matcher.match_type(&registry.normalize(&trait_impl.object_type), typ);
+    for (pattern, actual) in trait_impl.trait_generics.iter().zip(&bound.trait_generics) {
+        let (pattern, actual) = (registry.normalize(pattern), registry.normalize(actual));
+        result = result.and(|| matcher.match_type(&pattern, &actual));
+    }
+
+    result.and(|| where_clause_holds(&trait_impl.where_clause, &matcher, registry, search))
+}
+
+/// Why [`select_impl`] couldn't choose an impl.
+#[derive(Debug, Clone)]
+pub enum ImplSelectionError<'a> {
+    /// No impl applies to the type
+    NotImplemented,
+    /// Several impls apply to the type and none is more specific than the others
+    Ambiguous(Vec<&'a TraitImpl>),
+    /// Whether an impl at least as specific as those which apply does apply isn't known
+    Unknown(String),
+}
+
+/// Selects the impl of `bound` which applies to `typ`, preferring the most specific impl when
+/// several apply, as with `impl Foo for u32` and `impl<T> Foo for T` for `u32`.
+///
+/// Impls whose object types have more concrete positions are more specific, so that
+/// `impl<T> Foo for [T; 2]` is preferred over `impl<T> Foo for T`, and `impl Foo for [Field; 2]`
+/// over both. Selection fails if there is more than one most specific impl, or if it isn't
+/// known whether some impl at least as specific applies. Only impls of the bound's trait itself
+/// are selected, so types satisfying a bound through a subtrait have no impl of it, nor do
+/// negative bounds.
+pub fn select_impl<'a>(
+    typ: &UnresolvedType,
+    bound: &TraitBound,
+    registry: &'a TraitRegistry,
+) -> Result<&'a TraitImpl, ImplSelectionError<'a>> {
+    if bound.negative {
+        return Err(ImplSelectionError::NotImplemented);
+    }
+
+    let typ = &registry.normalize(typ);
+    let specificity = |trait_impl: &TraitImpl| {
+        specificity(&registry.normalize(&trait_impl.object_type), &trait_impl.impl_generics)
+    };
+
+    let mut applicable = Vec::new();
+    let mut unknown = Vec::new();
+    for trait_impl in registry.candidate_impls(&bound.trait_name.0.contents, typ) {
+        let mut search = SatisfactionSearch::new(DEFAULT_SATISFACTION_DEPTH);
+        match impl_applies(trait_impl, typ, bound, registry, &mut search) {
+            SatisfactionResult::Yes => applicable.push(trait_impl),
+            SatisfactionResult::No => (),
+            SatisfactionResult::Unknown(reason) => unknown.push((specificity(trait_impl), reason)),
+        }
+    }
+
+    let Some(most_specific) = applicable.iter().map(|trait_impl| specificity(trait_impl)).max()
+    else {
+        return Err(match unknown.into_iter().next() {
+            Some((_, reason)) => ImplSelectionError::Unknown(reason),
+            None => ImplSelectionError::NotImplemented,
+        });
+    };
+    if let Some((_, reason)) = unknown.into_iter().find(|(rank, _)| *rank >= most_specific) {
+        return Err(ImplSelectionError::Unknown(reason));
+    }
+
+    let mut candidates: Vec<_> = applicable
+        .into_iter()
+        .filter(|trait_impl| specificity(trait_impl) == most_specific)
+        .collect();
+    if candidates.len() == 1 {
+        Ok(candidates.remove(0))
+    } else {
+        Err(ImplSelectionError::Ambiguous(candidates))
+    }
+}
+
+/// Returns the number of positions within `typ`, the object type of an impl with the given
+/// generics, which are concrete rather than one of the generics, as used by [`select_impl`].
+/// `[T; 2]` has two such positions: the array and its length.
+fn specificity(typ: &UnresolvedType, generics: &[Ident]) -> usize {
+    use UnresolvedTypeData::*;
+
+    let is_generic =
+        |name: &Ident| generics.iter().any(|generic| generic.0.contents == name.0.contents);
+    let all = |types: &[UnresolvedType]| -> usize {
+        types.iter().map(|typ| specificity(typ, generics)).sum()
+    };
+    let length = |length: &UnresolvedTypeExpression| match length {
+        UnresolvedTypeExpression::Variable(path)
+            if path.segments.len() == 1 && is_generic(&path.segments[0]) =>
+        {
+            0
+        }
+        _ => 1,
+    };
+
+    match &typ.typ {
+        Named(path, args)
+            if args.is_empty() && path.segments.len() == 1 && is_generic(&path.segments[0]) =>
+        {
+            0
+        }
+        Named(_, args) => 1 + all(args),
+        Array(array_length, element) => {
+            1 + array_length.as_ref().map_or(0, length) + specificity(element, generics)
+        }
+        String(string_length) => 1 + string_length.as_ref().map_or(0, length),
+        FormatString(string_length, elements) => {
+            1 + length(string_length) + specificity(elements, generics)
+        }
+        Tuple(elements) => 1 + all(elements),
+        Function(args, ret, env) => {
+            1 + all(args) + specificity(ret, generics) + specificity(env, generics)
+        }
+        MutableReference(element) => 1 + specificity(element, generics),
+        _ => 1,
+    }
+}
+
+/// An impl found by [`find_impl`].
+#[derive(Debug, Clone, Copy)]
+pub struct FoundImpl<'a> {
+    pub trait_impl: &'a TraitImpl,
+    /// Whether the impl is generic over its object type, as with `impl<T> Foo for T` or
+    /// `impl<T> Foo for [T; 2]`, rather than written for the type it was found for
+    pub is_blanket: bool,
+}
+
+/// Returns the impl of `bound` which applies to `typ`, chosen as by [`select_impl`], or `None`
+/// if no single impl is known to apply. This is the impl whose methods are called through the
+/// bound, so tooling can navigate from a use of a trait to the source of its impl.
+pub fn find_impl<'a>(
+    typ: &UnresolvedType,
+    bound: &TraitBound,
+    registry: &'a TraitRegistry,
+) -> Option<FoundImpl<'a>> {
+    let trait_impl = select_impl(typ, bound, registry).ok()?;
+    let impl_generics: HashSet<&str> =
+        trait_impl.impl_generics.iter().map(|generic| generic.0.contents.as_str()).collect();
+    let is_blanket = trait_impl
+        .object_type
+        .referenced_generics()
+        .iter()
+        .any(|name| impl_generics.contains(name.0.contents.as_str()));
+    Some(FoundImpl { trait_impl, is_blanket })
+}
+
+/// Checks each constraint of `where_clause` once the generics bound by `matcher` are substituted.
+fn where_clause_holds(
+    where_clause: &[TraitConstraint],
+    matcher: &ImplMatcher,
+    registry: &TraitRegistry,
+    search: &mut SatisfactionSearch,
+) -> SatisfactionResult {
+    let mut result = SatisfactionResult::Yes;
+    for constraint in where_clause {
+        if result == SatisfactionResult::No {
+            break;
+        }
+
+        let typ = matcher.substitute(&constraint.typ);
+        let bound = TraitBound {
+            trait_name: constraint.trait_bound.trait_name.clone(),
+            trait_generics: vecmap(&constraint.trait_bound.trait_generics, |generic| {
+                matcher.substitute(generic)
+            }),
+            negative: constraint.trait_bound.negative,
+        };
+        result = result.and(|| satisfies_at_depth(&typ, &bound, registry, search));
+    }
+
+    result
+}
+
+/// A method which may be called on a type, as returned by [`available_methods`].
+#[derive(Debug, Clone)]
+pub struct MethodInfo {
+    pub name: Ident,
+    /// The method's signature with the generics of its impl replaced by the types they are bound
+    /// to, e.g. `fn get(self: Self) -> u8` for `impl<T> Box<T> { fn get(self) -> T }` on `Box<u8>`
+    pub signature: String,
+    pub source: MethodSource,
+}
+
+/// The impl which provides a method.
+#[derive(Debug, Clone)]
+pub enum MethodSource {
+    /// The method is declared within `impl object_type { ... }`
+    TypeImpl { object_type: UnresolvedType },
+    /// The method is declared within, or is a default method inherited by,
+    /// `impl trait_name for object_type { ... }`
+    TraitImpl { trait_name: Ident, object_type: UnresolvedType },
+}
+
+/// Returns every method available on `typ` from the impls and trait impls in `registry`.
+///
+/// A method is included if the object type of its impl matches `typ` and the impl's where
+/// clause is satisfied. Methods whose own where clause cannot be satisfied are skipped, though
+/// methods whose where clause depends on generics, such as the method's own generics, are kept
+/// since they may still be callable. Trait impls also provide each default method of their
+/// trait which they don't override.
+///
+/// Inherent methods are listed first, in declaration order, followed by trait methods ordered
+/// by trait name.
+pub fn available_methods(typ: &UnresolvedType, registry: &TraitRegistry) -> Vec<MethodInfo> {
+    let mut methods = Vec::new();
+    let typ = &registry.normalize(typ);
+    let mut search = SatisfactionSearch::new(DEFAULT_SATISFACTION_DEPTH);
+
+    for type_impl in &registry.type_impls {
+        let mut matcher =
+            ImplMatcher { registry, generics: &type_impl.generics, bindings: HashMap::new() };
+        let object_type = registry.normalize(&type_impl.object_type);
+        if matcher.match_type(&object_type, typ) != SatisfactionResult::Yes {
+            continue;
+        }
+
+        for method in &type_impl.methods {
+            let def = &method.def;
+            if where_clause_holds(&def.where_clause, &matcher, registry, &mut search)
+                == SatisfactionResult::No
+            {
+                continue;
+            }
+
+            let parameters = vecmap(&def.parameters, |(pattern, typ, _)| {
+                format!("{pattern}: {}", matcher.substitute(typ))
+            });
+            methods.push(MethodInfo {
+                name: def.name.clone(),
+                signature: signature(
+                    &def.name,
+                    &def.generics,
+                    parameters,
+                    &def.return_type,
+                    &matcher,
+                ),
+                source: MethodSource::TypeImpl { object_type: type_impl.object_type.clone() },
+            });
+        }
+    }
+
+    for trait_impl in registry.all_candidate_impls(typ) {
+        let mut matcher =
+            ImplMatcher { registry, generics: &trait_impl.impl_generics, bindings: HashMap::new() };
+        let applies = matcher
+            .match_type(&registry.normalize(&trait_impl.object_type), typ)
+            .and(|| where_clause_holds(&trait_impl.where_clause, &matcher, registry, &mut search));
+        if applies != SatisfactionResult::Yes {
+            continue;
+        }
+
+        let source = MethodSource::TraitImpl {
+            trait_name: trait_impl.trait_name.clone(),
+            object_type: trait_impl.object_type.clone(),
+        };
+
+        for item in &trait_impl.items {
+            let TraitImplItem::Function(method) = item else { continue };
+            let def = &method.def;
+            if where_clause_holds(&def.where_clause, &matcher, registry, &mut search)
+                == SatisfactionResult::No
+            {
+                continue;
+            }
+
+            let parameters = vecmap(&def.parameters, |(pattern, typ, _)| {
+                format!("{pattern}: {}", matcher.substitute(typ))
+            });
+            methods.push(MethodInfo {
+                name: def.name.clone(),
+                signature: signature(
+                    &def.name,
+                    &def.generics,
+                    parameters,
+                    &def.return_type,
+                    &matcher,
+                ),
+                source: source.clone(),
+            });
+        }
+
+        let Some(noir_trait) = registry.get_trait(&trait_impl.trait_name.0.contents) else {
+            continue;
+        };
+
+        // Default methods are written in terms of the trait's generics, which are bound to the
+        // generics the impl gives the trait.
+        let trait_generics =
+            vecmap(&trait_impl.trait_generics, |generic| matcher.substitute(generic));
+        let trait_matcher = ImplMatcher {
+            registry,
+            generics: &noir_trait.generics,
+            bindings: noir_trait
+                .generics
+                .iter()
+                .map(|generic| generic.0.contents.clone())
+                .zip(trait_generics)
+                .collect(),
+        };
+
+        for item in &noir_trait.items {
+            let TraitItem::Function {
+                name,
+                generics,
+                parameters,
+                return_type,
+                where_clause,
+                body,
+                ..
+            } = item
+            else {
+                continue;
+            };
+            let overridden = trait_impl.items.iter().any(
+                |item| matches!(item, TraitImplItem::Function(method) if &method.def.name == name),
+            );
+            if body.is_none() || overridden {
+                continue;
+            }
+            if where_clause_holds(where_clause, &trait_matcher, registry, &mut search)
+                == SatisfactionResult::No
+            {
+                continue;
+            }
+
+            let parameters = vecmap(parameters, |(name, typ)| {
+                format!("{name}: {}", trait_matcher.substitute(typ))
+            });
+            methods.push(MethodInfo {
+                name: name.clone(),
+                signature: signature(name, generics, parameters, return_type, &trait_matcher),
+                source: source.clone(),
+            });
+        }
+    }
+
+    methods
+}
+
+fn signature(
+    name: &Ident,
+    generics: &[Ident],
+    parameters: Vec<String>,
+    return_type: &FunctionReturnType,
+    matcher: &ImplMatcher,
+) -> String {
+    let generics = if generics.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", vecmap(generics, ToString::to_string).join(", "))
+    };
+
+    let return_type = match return_type {
+        FunctionReturnType::Default(_) => String::new(),
+        FunctionReturnType::Ty(typ, _) => format!(" -> {}", matcher.substitute(typ)),
+    };
+
+    format!("fn {name}{generics}({}){return_type}", parameters.join(", "))
+}
+
+/// Returns every pair of impls of the same trait in `registry` which may both apply to some
+/// type, as determined by [`impls_may_overlap`].
+pub fn overlapping_impls(registry: &TraitRegistry) -> Vec<(&TraitImpl, &TraitImpl)> {
+    let mut overlapping = Vec::new();
+    for (trait_name, impls) in &registry.impls {
+        // The registry may contain impls of traits it doesn't know about
+        if registry.get_trait(trait_name).is_none() {
+            continue;
+        }
+        for (index, a) in impls.iter().enumerate() {
+            for b in &impls[index + 1..] {
+                if impls_may_overlap(a, b, registry) {
+                    overlapping.push((a, b));
+                }
+            }
+        }
+    }
+    overlapping
+}
+
+/// Returns whether two impls of the same trait may both apply to some type.
+///
+/// Impls are disjoint if their object types and trait generics can never match the same type,
+/// or if one impl requires a constraint to hold which the other requires not to hold, as with
+/// `impl<T> Foo for T where T: Bar` and `impl<T> Foo for T where T: !Bar`. Impls are otherwise
+/// assumed to overlap, even if their where clauses could never both be satisfied.
+pub fn impls_may_overlap(a: &TraitImpl, b: &TraitImpl, registry: &TraitRegistry) -> bool {
+    if a.trait_name != b.trait_name || a.trait_generics.len() != b.trait_generics.len() {
+        return false;
+    }
+
+    let mut unifier = Unifier { registry, a, b, bindings: HashMap::new() };
+    let unifies = unifier
+        .unify(&registry.normalize(&a.object_type), &registry.normalize(&b.object_type))
+        && a.trait_generics
+            .iter()
+            .zip(&b.trait_generics)
+            .all(|(x, y)| unifier.unify(&registry.normalize(x), &registry.normalize(y)));
+    if !unifies {
+        return false;
+    }
+
+    // Rewrite `a`'s where clause in terms of `b`'s generics to compare the two
+    let matcher = ImplMatcher { registry, generics: &a.impl_generics, bindings: unifier.bindings };
+    let excludes = |constraint_a: &TraitConstraint, constraint_b: &TraitConstraint| {
+        let bound_a = &constraint_a.trait_bound;
+        let bound_b = &constraint_b.trait_bound;
+        bound_a.negative != bound_b.negative
+            && bound_a.trait_name == bound_b.trait_name
+            && bound_a.trait_generics.len() == bound_b.trait_generics.len()
+            && bound_a
+                .trait_generics
+                .iter()
+                .zip(&bound_b.trait_generics)
+                .all(|(x, y)| same_type(&matcher.substitute(x), y))
+            && same_type(&matcher.substitute(&constraint_a.typ), &constraint_b.typ)
+    };
+
+    !a.where_clause.iter().any(|constraint_a| {
+        b.where_clause.iter().any(|constraint_b| excludes(constraint_a, constraint_b))
+    })
+}
+
+/// Checks whether the types written in two impls could ever be the same type, binding the
+/// generics of impl `a` to the parts of `b`'s types they correspond to.
+struct Unifier<'a> {
+    registry: &'a TraitRegistry,
+    a: &'a TraitImpl,
+    b: &'a TraitImpl,
+    bindings: HashMap<String, UnresolvedType>,
+}
+
+impl<'a> Unifier<'a> {
+    fn impl_generic<'t>(&self, typ: &'t UnresolvedType, trait_impl: &TraitImpl) -> Option<&'t str> {
+        self.registry.generic_name(typ).filter(|name| {
+            trait_impl.impl_generics.iter().any(|generic| generic.0.contents == *name)
+        })
+    }
+
+    fn unify(&mut self, a: &UnresolvedType, b: &UnresolvedType) -> bool {
+        use UnresolvedTypeData::*;
+
+        if let Some(name) = self.impl_generic(a, self.a) {
+            return match self.bindings.get(name) {
+                // A generic of `b` may stand for any type, so only concrete types can conflict
+                Some(binding) => {
+                    same_type(binding, b)
+                        || self.impl_generic(binding, self.b).is_some()
+                        || self.impl_generic(b, self.b).is_some()
+                }
+                None => {
+                    self.bindings.insert(name.to_string(), b.clone());
+                    true
+                }
+            };
+        }
+        if self.impl_generic(b, self.b).is_some() {
+            return true;
+        }
+
+        match (&a.typ, &b.typ) {
+            (Array(_, element_a), Array(_, element_b)) => self.unify(element_a, element_b),
+            (MutableReference(element_a), MutableReference(element_b)) => {
+                self.unify(element_a, element_b)
+            }
+            (Tuple(elements_a), Tuple(elements_b)) => {
+                elements_a.len() == elements_b.len()
+                    && elements_a.iter().zip(elements_b).all(|(x, y)| self.unify(x, y))
+            }
+            (Named(path_a, generics_a), Named(path_b, generics_b)) => {
+                path_a.segments.last() == path_b.segments.last()
+                    && generics_a.len() == generics_b.len()
+                    && generics_a.iter().zip(generics_b).all(|(x, y)| self.unify(x, y))
+            }
+            _ => same_type(a, b),
+        }
+    }
+}
+
+impl TraitRegistry {
+    fn normalize(&self, typ: &UnresolvedType) -> UnresolvedType {
+        normalize_type(typ, &self.type_aliases)
+    }
+}
+
+/// Rewrites `typ` into a canonical form, so that types which are written differently but are
+/// equivalent compare equal:
+/// - Each type alias in `aliases` is expanded, with the alias' generics replaced by the
+///   arguments it is given. Aliases referring to each other are expanded as far as
+///   `DEFAULT_SATISFACTION_DEPTH`, so cyclic aliases are left partly expanded.
+/// - An empty tuple is written as the unit type.
+/// - Array and string lengths made up only of constants are evaluated, so `[T; 2 + 1]` becomes
+///   `[T; 3]`.
+pub fn normalize_type(typ: &UnresolvedType, aliases: &AliasTable) -> UnresolvedType {
+    normalize_at_depth(typ, aliases, 0)
+}
+
+fn normalize_at_depth(typ: &UnresolvedType, aliases: &AliasTable, depth: usize) -> UnresolvedType {
+    use UnresolvedTypeData::*;
+
+    let normalize = |typ: &UnresolvedType| normalize_at_depth(typ, aliases, depth);
+    let normalized = match &typ.typ {
+        Named(path, generics) => {
+            let alias = (path.segments.len() == 1 && depth < DEFAULT_SATISFACTION_DEPTH)
+                .then(|| aliases.get(&path.segments[0].0.contents))
+                .flatten()
+                .filter(|alias| alias.generics.len() == generics.len());
+
+            if let Some(alias) = alias {
+                let bindings = alias
+                    .generics
+                    .iter()
+                    .map(|generic| generic.0.contents.clone())
+                    .zip(generics.iter().cloned())
+                    .collect();
+                let expanded = substitute_alias_generics(&alias.typ, &bindings);
+                return normalize_at_depth(&expanded, aliases, depth + 1);
+            }
+            Named(path.clone(), vecmap(generics, normalize))
+        }
+        Tuple(elements) if elements.is_empty() => Unit,
+        Tuple(elements) => Tuple(vecmap(elements, normalize)),
+        Array(length, element) => {
+            Array(length.as_ref().map(evaluate_length), Box::new(normalize(element)))
+        }
+        String(length) => String(length.as_ref().map(evaluate_length)),
+        FormatString(length, elements) => {
+            FormatString(evaluate_length(length), Box::new(normalize(elements)))
+        }
+        MutableReference(element) => MutableReference(Box::new(normalize(element))),
+        Function(arguments, ret, env) => Function(
+            vecmap(arguments, normalize),
+            Box::new(normalize(ret)),
+            Box::new(normalize(env)),
+        ),
+        Projection { object_type, trait_name, item_name } => Projection {
+            object_type: Box::new(normalize(object_type)),
+            trait_name: trait_name.clone(),
+            item_name: item_name.clone(),
+        },
+        other => other.clone(),
+    };
+    UnresolvedType { typ: normalized, span: typ.span }
+}
+
+/// Replaces each generic of a type alias within its definition `typ` with the argument bound to
+/// it in `bindings`.
+pub(crate) fn substitute_alias_generics(
+    typ: &UnresolvedType,
+    bindings: &HashMap<String, UnresolvedType>,
+) -> UnresolvedType {
+    use UnresolvedTypeData::*;
+
+    let substitute = |typ: &UnresolvedType| substitute_alias_generics(typ, bindings);
+    let substitute_length = |length: &UnresolvedTypeExpression| match length {
+        UnresolvedTypeExpression::Variable(path) if path.segments.len() == 1 => {
+            match bindings.get(&path.segments[0].0.contents).map(|binding| &binding.typ) {
+                Some(Expression(expression)) => expression.clone(),
+                _ => length.clone(),
+            }
+        }
+        other => other.clone(),
+    };
+
+    let substituted = match &typ.typ {
+        Named(path, generics) if path.segments.len() == 1 && generics.is_empty() => {
+            if let Some(binding) = bindings.get(&path.segments[0].0.contents) {
+                return binding.clone();
+            }
+            typ.typ.clone()
+        }
+        Named(path, generics) => Named(path.clone(), vecmap(generics, substitute)),
+        Tuple(elements) => Tuple(vecmap(elements, substitute)),
+        Array(length, element) => {
+            Array(length.as_ref().map(substitute_length), Box::new(substitute(element)))
+        }
+        String(length) => String(length.as_ref().map(substitute_length)),
+        MutableReference(element) => MutableReference(Box::new(substitute(element))),
+        Function(arguments, ret, env) => Function(
+            vecmap(arguments, substitute),
+            Box::new(substitute(ret)),
+            Box::new(substitute(env)),
+        ),
+        other => other.clone(),
+    };
+    UnresolvedType { typ: substituted, span: typ.span }
+}
+
+/// Evaluates `length` to a constant if it is made up only of constants.
+fn evaluate_length(length: &UnresolvedTypeExpression) -> UnresolvedTypeExpression {
+    let UnresolvedTypeExpression::BinaryOperation(lhs, operator, rhs, span) = length else {
+        return length.clone();
+    };
+
+    let (lhs, rhs) = (evaluate_length(lhs), evaluate_length(rhs));
+    match (&lhs, &rhs) {
+        (UnresolvedTypeExpression::Constant(a, _), UnresolvedTypeExpression::Constant(b, _))
+            if !(*b == 0
+                && matches!(
+                    operator,
+                    BinaryTypeOperator::Division | BinaryTypeOperator::Modulo
+                )) =>
+        {
+            UnresolvedTypeExpression::Constant(operator.function()(*a, *b), *span)
+        }
+        _ => UnresolvedTypeExpression::BinaryOperation(
+            Box::new(lhs),
+            *operator,
+            Box::new(rhs),
+            *span,
+        ),
+    }
+}
+
+fn same_bound(a: &TraitBound, b: &TraitBound) -> bool {
+    a.negative == b.negative
+        && a.trait_name == b.trait_name
+        && a.trait_generics.len() == b.trait_generics.len()
+        && a.trait_generics.iter().zip(&b.trait_generics).all(|(a, b)| same_type(a, b))
+}
+
+/// Compares two types structurally, ignoring their spans.
+fn same_type(a: &UnresolvedType, b: &UnresolvedType) -> bool {
+    a.typ.to_string() == b.typ.to_string()
+}
+
+/// Matches the object type of an impl against a concrete type, binding the impl's generics.
+struct ImplMatcher<'a> {
+    registry: &'a TraitRegistry,
+    generics: &'a [Ident],
+    bindings: HashMap<String, UnresolvedType>,
+}
+
+impl<'a> ImplMatcher<'a> {
+    fn is_impl_generic(&self, name: &str) -> bool {
+        self.generics.iter().any(|generic| generic.0.contents == name)
+    }
+
+    fn match_type(
+        &mut self,
+        pattern: &UnresolvedType,
+        actual: &UnresolvedType,
+    ) -> SatisfactionResult {
+        use UnresolvedTypeData::*;
+
+        if let Some(name) = self.registry.generic_name(pattern) {
+            if self.is_impl_generic(name) {
+                return match self.bindings.get(name) {
+                    Some(binding) if same_type(binding, actual) => SatisfactionResult::Yes,
+                    Some(binding) => self.mismatch(binding, actual),
+                    None => {
+                        self.bindings.insert(name.to_string(), actual.clone());
+                        SatisfactionResult::Yes
+                    }
+                };
+            }
+        }
+
+        if let Some(name) = self.registry.generic_name(actual) {
+            return SatisfactionResult::Unknown(format!("`{name}` is a generic type"));
+        }
+
+        match (&pattern.typ, &actual.typ) {
+            (Error | Unspecified, _) | (_, Error | Unspecified) => {
+                SatisfactionResult::Unknown("type could not be determined".to_string())
+            }
+            (FieldElement, FieldElement) | (Bool, Bool) | (Unit, Unit) => SatisfactionResult::Yes,
+            (Integer(sign_a, bits_a), Integer(sign_b, bits_b)) => {
+                SatisfactionResult::from_bool(sign_a == sign_b && bits_a == bits_b)
+            }
+            (Array(length_a, element_a), Array(length_b, element_b)) => self
+                .match_length(length_a.as_ref(), length_b.as_ref())
+                .and(|| self.match_type(element_a, element_b)),
+            (String(length_a), String(length_b)) => {
+                self.match_length(length_a.as_ref(), length_b.as_ref())
+            }
+            (MutableReference(element_a), MutableReference(element_b)) => {
+                self.match_type(element_a, element_b)
+            }
+            (Tuple(elements_a), Tuple(elements_b)) if elements_a.len() == elements_b.len() => {
+                let mut result = SatisfactionResult::Yes;
+                for (a, b) in elements_a.iter().zip(elements_b) {
+                    result = result.and(|| self.match_type(a, b));
+                }
+                result
+            }
+            (Named(path_a, generics_a), Named(path_b, generics_b))
+                if path_a.segments.last() == path_b.segments.last()
+                    && generics_a.len() == generics_b.len() =>
+            {
+                let mut result = SatisfactionResult::Yes;
+                for (a, b) in generics_a.iter().zip(generics_b) {
+                    result = result.and(|| self.match_type(a, b));
+                }
+                result
+            }
+            _ => SatisfactionResult::No,
+        }
+    }
+
+    fn match_length(
+        &mut self,
+        pattern: Option<&UnresolvedTypeExpression>,
+        actual: Option<&UnresolvedTypeExpression>,
+    ) -> SatisfactionResult {
+        match (pattern, actual) {
+            (None, None) => SatisfactionResult::Yes,
+            (Some(UnresolvedTypeExpression::Variable(path)), Some(_))
+                if path.segments.len() == 1
+                    && self.is_impl_generic(&path.segments[0].0.contents) =>
+            {
+                SatisfactionResult::Yes
+            }
+            (Some(_), Some(UnresolvedTypeExpression::Variable(path))) => {
+                SatisfactionResult::Unknown(format!("array length `{path}` is generic"))
+            }
+            (Some(a), Some(b)) => SatisfactionResult::from_bool(a.to_string() == b.to_string()),
+            _ => SatisfactionResult::No,
+        }
+    }
+
+    /// Two different types were bound to the same impl generic
+    fn mismatch(&self, a: &UnresolvedType, b: &UnresolvedType) -> SatisfactionResult {
+        match self.registry.generic_name(a).or_else(|| self.registry.generic_name(b)) {
+            Some(name) => SatisfactionResult::Unknown(format!("`{name}` is a generic type")),
+            None => SatisfactionResult::No,
+        }
+    }
+
+    /// Replaces each impl generic within `typ` with the type bound to it.
+    fn substitute(&self, typ: &UnresolvedType) -> UnresolvedType {
+        use UnresolvedTypeData::*;
+
+        if let Some(binding) =
+            self.registry.generic_name(typ).and_then(|name| self.bindings.get(name))
+        {
+            return binding.clone();
+        }
+
+        let substituted = match &typ.typ {
+            Array(length, element) => Array(length.clone(), Box::new(self.substitute(element))),
+            MutableReference(element) => MutableReference(Box::new(self.substitute(element))),
+            Tuple(elements) => Tuple(vecmap(elements, |element| self.substitute(element))),
+            Named(path, generics) => {
+                Named(path.clone(), vecmap(generics, |generic| self.substitute(generic)))
+            }
+            other => other.clone(),
+        };
+        UnresolvedType { typ: substituted, span: typ.span }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        available_methods, find_impl, normalize_type, overlapping_impls, satisfies,
+        satisfies_constraint, satisfies_within_depth, select_impl, supertrait_closure, AliasTable,
+        ImplSelectionError, MethodSource, SatisfactionResult, TraitRegistry,
+    };
+    use crate::{parse_program, TraitBound, TraitConstraint, UnresolvedType};
+
+    fn registry(src: &str) -> TraitRegistry {
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+        TraitRegistry::from_module(&program)
+    }
+
+    /// Parses `typ` by declaring it as the type of a global
+    fn parse_type(typ: &str) -> UnresolvedType {
+        let (program, errors) = parse_program(&format!("global x: {typ} = 0;"));
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+        program.globals[0].r#type.clone()
+    }
+
+    /// Parses a trait bound, which is negative if it begins with `!`
+    fn bound(trait_name: &str) -> TraitBound {
+        let (negative, trait_name) = match trait_name.strip_prefix('!') {
+            Some(trait_name) => (true, trait_name),
+            None => (false, trait_name),
+        };
+        let typ = parse_type(trait_name);
+        match typ.typ {
+            crate::UnresolvedTypeData::Named(mut path, trait_generics) => {
+                TraitBound { trait_name: path.pop(), trait_generics, negative }
+            }
+            _ => unreachable!("trait names are parsed as named types"),
+        }
+    }
+
+    fn check(registry: &TraitRegistry, typ: &str, trait_name: &str) -> SatisfactionResult {
+        satisfies(&parse_type(typ), &bound(trait_name), registry)
+    }
+
+    #[test]
+    fn concrete_impls() {
+        let registry = registry(
+            "trait Eq {}
+            struct Foo {}
+            struct Bar {}
+            impl Eq for Foo {}",
+        );
+
+        assert_eq!(check(&registry, "Foo", "Eq"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "Bar", "Eq"), SatisfactionResult::No);
+        assert_eq!(check(&registry, "Field", "Eq"), SatisfactionResult::No);
+    }
+
+    #[test]
+    fn blanket_impls() {
+        let registry = registry(
+            "trait Eq {}
+            trait Wrap<T> {}
+            struct Foo {}
+            struct Box<T> {}
+            impl Eq for Foo {}
+            impl<T, N> Eq for [T; N] where T: Eq {}
+            impl<T> Wrap<T> for Box<T> {}",
+        );
+
+        assert_eq!(check(&registry, "[Foo; 2]", "Eq"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "[[Foo; 2]; 3]", "Eq"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "[Field; 2]", "Eq"), SatisfactionResult::No);
+        assert_eq!(check(&registry, "Box<u8>", "Wrap<u8>"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "Box<u8>", "Wrap<u16>"), SatisfactionResult::No);
+    }
+
+    /// Parses the where clause of a function generic over `T` and `N`
+    fn parse_where_clause(where_clause: &str) -> Vec<TraitConstraint> {
+        let (mut program, errors) = parse_program(&format!("fn foo<T, N>() {where_clause} {{}}"));
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+        program.functions.remove(0).def.where_clause
+    }
+
+    #[test]
+    fn compound_type_constraints() {
+        let registry = registry(
+            "trait Eq {}
+            trait Serialize {}
+            struct Foo {}
+            struct Bar {}
+            impl Eq for Foo {}
+            impl Serialize for Field {}
+            impl<T, N> Serialize for [T; N] where T: Serialize {}
+            impl<A, B> Eq for (A, B) where A: Eq, B: Eq {}",
+        );
+
+        let satisfied = parse_where_clause(
+            "where [Field; N]: Serialize, [[Field; 2]; 3]: Serialize, (Foo, Foo): Eq",
+        );
+        for constraint in &satisfied {
+            assert_eq!(satisfies_constraint(constraint, &registry), SatisfactionResult::Yes);
+        }
+
+        let violated =
+            parse_where_clause("where [Foo; N]: Serialize, (Foo, Bar): Eq, (Foo, Foo, Foo): Eq");
+        for constraint in &violated {
+            assert_eq!(satisfies_constraint(constraint, &registry), SatisfactionResult::No);
+        }
+
+        let unknown = parse_where_clause("where [T; 2]: Serialize, (Foo, T): Eq");
+        for constraint in &unknown {
+            let result = satisfies_constraint(constraint, &registry);
+            assert!(matches!(result, SatisfactionResult::Unknown(_)));
+        }
+    }
+
+    #[test]
+    fn supertrait_implications() {
+        let registry = registry(
+            "trait Eq {}
+            trait Ord: Eq {}
+            struct Foo {}
+            impl Ord for Foo {}",
+        );
+
+        assert_eq!(check(&registry, "Foo", "Eq"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "Field", "Eq"), SatisfactionResult::No);
+    }
+
+    #[test]
+    fn generics_are_unknown() {
+        let registry = registry(
+            "trait Eq {}
+            trait Default {}
+            struct Foo {}
+            impl Eq for Foo {}
+            impl<T> Default for T {}",
+        );
+
+        assert!(matches!(check(&registry, "T", "Eq"), SatisfactionResult::Unknown(_)));
+        assert!(matches!(check(&registry, "[T; 2]", "Eq"), SatisfactionResult::Unknown(_)));
+        assert!(matches!(check(&registry, "Foo", "Hash"), SatisfactionResult::Unknown(_)));
+
+        // A blanket impl over every type applies even to generics
+        assert_eq!(check(&registry, "T", "Default"), SatisfactionResult::Yes);
+    }
+
+    #[test]
+    fn self_referential_bounds_hit_the_depth_limit() {
+        let registry = registry(
+            "trait Foo {}
+            struct Wrapper<T> {}
+            impl<T> Foo for Wrapper<T> where Wrapper<Wrapper<T>>: Foo {}",
+        );
+
+        let overflow =
+            satisfies_within_depth(&parse_type("Wrapper<u8>"), &bound("Foo"), &registry, 3)
+                .unwrap_err();
+        assert_eq!(overflow.limit, 3);
+        assert_eq!(
+            overflow.chain,
+            vec![
+                "Wrapper<u8>: Foo",
+                "Wrapper<Wrapper<u8>>: Foo",
+                "Wrapper<Wrapper<Wrapper<u8>>>: Foo",
+                "Wrapper<Wrapper<Wrapper<Wrapper<u8>>>>: Foo",
+                "Wrapper<Wrapper<Wrapper<Wrapper<Wrapper<u8>>>>>: Foo",
+            ]
+        );
+        assert!(matches!(check(&registry, "Wrapper<u8>", "Foo"), SatisfactionResult::Unknown(_)));
+
+        // Checks which finish within the limit are unaffected by it
+        let result = satisfies_within_depth(&parse_type("u8"), &bound("Foo"), &registry, 3);
+        assert_eq!(result, Ok(SatisfactionResult::No));
+    }
+
+    #[test]
+    fn available_methods_across_impls() {
+        let registry = registry(
+            "trait Eq { fn eq(self, other: Self) -> bool; }
+            trait Describe { fn describe(self) -> Field { 0 }; fn id(self) -> Field { 1 }; }
+            struct Foo {}
+            struct Bar {}
+            struct Box<T> {}
+            impl<T> Box<T> {
+                fn get(self) -> T { self.0 }
+                fn compare(self, other: Self) -> bool where T: Eq { true }
+            }
+            impl Box<Field> { fn field(self) -> Field { 0 } }
+            impl Eq for Foo { fn eq(self, other: Self) -> bool { true } }
+            impl<T> Describe for Box<T> where T: Eq { fn id(self) -> Field { 2 } }",
+        );
+
+        let methods = |typ: &str| {
+            let methods = available_methods(&parse_type(typ), &registry);
+            methods.into_iter().map(|method| method.signature).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            methods("Box<Foo>"),
+            vec![
+                "fn get(self: Self) -> Foo",
+                "fn compare(self: Self, other: Self) -> bool",
+                "fn id(self: Self) -> Field",
+                "fn describe(self: Self) -> Field",
+            ]
+        );
+
+        // `Bar` is not `Eq`, so the conditional method and trait impl are unavailable
+        assert_eq!(methods("Box<Bar>"), vec!["fn get(self: Self) -> Bar"]);
+        assert_eq!(
+            methods("Box<Field>"),
+            vec!["fn get(self: Self) -> Field", "fn field(self: Self) -> Field"]
+        );
+        assert_eq!(methods("Foo"), vec!["fn eq(self: Self, other: Self) -> bool"]);
+
+        let sources = available_methods(&parse_type("Foo"), &registry);
+        assert!(matches!(
+            &sources[0].source,
+            MethodSource::TraitImpl { trait_name, .. } if trait_name.0.contents == "Eq"
+        ));
+    }
+
+    #[test]
+    fn negative_bounds_make_impls_disjoint() {
+        let registry = registry(
+            "trait Eq {}
+            trait Describe {}
+            struct Foo {}
+            struct Bar {}
+            impl Eq for Foo {}
+            impl<T> Describe for Box<T> where T: Eq {}
+            impl<U> Describe for Box<U> where U: !Eq {}
+            impl<T> Describe for [T; 2] where T: Eq {}
+            impl<T> Describe for [T; 2] {}
+            struct Box<T> {}",
+        );
+
+        assert_eq!(check(&registry, "Box<Foo>", "Describe"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "Box<Bar>", "Describe"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "Bar", "!Eq"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "Foo", "!Eq"), SatisfactionResult::No);
+        assert!(matches!(check(&registry, "T", "!Eq"), SatisfactionResult::Unknown(_)));
+
+        let violated = parse_where_clause("where Foo: !Eq");
+        assert_eq!(satisfies_constraint(&violated[0], &registry), SatisfactionResult::No);
+
+        // Only the array impls overlap, as the `Box` impls require opposite bounds on `T`
+        let overlapping = overlapping_impls(&registry);
+        assert_eq!(overlapping.len(), 1);
+        assert_eq!(overlapping[0].0.object_type.to_string(), "[T; 2]");
+    }
+
+    #[test]
+    fn impls_on_type_aliases() {
+        let registry = registry(
+            "trait Eq {}
+            trait Describe {}
+            struct Foo {}
+            struct Box<T> {}
+            type Point = (Field, Field);
+            type Wrapper<T> = Box<T>;
+            type Grid<T, N> = [[T; N]; N];
+            impl Eq for Point {}
+            impl Describe for Wrapper<Foo> {}
+            impl Eq for Grid<Foo, 2> {}",
+        );
+
+        // Uses of the aliased types match impls written in terms of their aliases
+        assert_eq!(check(&registry, "(Field, Field)", "Eq"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "Box<Foo>", "Describe"), SatisfactionResult::Yes);
+        assert_eq!(check(&registry, "Box<Field>", "Describe"), SatisfactionResult::No);
+        assert_eq!(check(&registry, "[[Foo; 2]; 1 + 1]", "Eq"), SatisfactionResult::Yes);
+
+        // And uses of an alias match impls on the type it aliases
+        assert_eq!(check(&registry, "Wrapper<Foo>", "Describe"), SatisfactionResult::Yes);
+    }
+
+    #[test]
+    fn aliases_added_after_impls_are_dispatched_on() {
+        let mut registry = registry("trait Eq {} impl Eq for Point {}");
+        assert_eq!(check(&registry, "(Field, Field)", "Eq"), SatisfactionResult::No);
+
+        let (program, errors) = parse_program("type Point = (Field, Field);");
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+        registry.add_type_alias(program.type_aliases[0].clone());
+        assert_eq!(check(&registry, "(Field, Field)", "Eq"), SatisfactionResult::Yes);
+    }
+
+    #[test]
+    fn normalizes_types() {
+        let (program, errors) = parse_program("type Pair<T> = (T, T); type Cycle = Cycle;");
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+        let aliases: AliasTable = program
+            .type_aliases
+            .into_iter()
+            .map(|alias| (alias.name.0.contents.clone(), alias))
+            .collect();
+
+        let normalize = |typ: &str| normalize_type(&parse_type(typ), &aliases).to_string();
+        assert_eq!(normalize("Pair<[u8; 2 * 3]>"), "([u8; 6], [u8; 6])");
+        assert_eq!(normalize("[Field; 2 / 0]"), parse_type("[Field; 2 / 0]").to_string());
+        assert_eq!(normalize("Cycle"), "Cycle");
+    }
+
+    #[test]
+    fn selects_the_most_specific_impl() {
+        let registry = registry(
+            "trait Foo {}
+            trait Bar {}
+            impl Foo for u32 {}
+            impl<T> Foo for T {}
+            impl<T> Bar for T {}
+            impl<U> Bar for U {}
+            trait Baz {}
+            impl<T> Baz for T {}
+            impl<T> Baz for [T; 2] {}
+            impl Baz for [Field; 2] {}",
+        );
+        let select = |typ, trait_name| select_impl(&parse_type(typ), &bound(trait_name), &registry);
+        let selected_type = |typ, trait_name| {
+            let selected = select(typ, trait_name).expect("an impl should be selected");
+            selected.object_type.to_string()
+        };
+
+        assert_eq!(selected_type("u32", "Foo"), "u32");
+        assert_eq!(selected_type("Field", "Foo"), "T");
+
+        // Both generic impls have one generic, but an array of two is more specific
+        assert_eq!(selected_type("[u8; 2]", "Baz"), "[T; 2]");
+        assert_eq!(selected_type("[Field; 2]", "Baz"), "[Field; 2]");
+        assert_eq!(selected_type("[u8; 3]", "Baz"), "T");
+
+        assert!(matches!(
+            select("u32", "Bar"),
+            Err(ImplSelectionError::Ambiguous(candidates)) if candidates.len() == 2
+        ));
+        assert!(matches!(select("u32", "!Foo"), Err(ImplSelectionError::NotImplemented)));
+    }
+
+    #[test]
+    fn finds_the_impl_used_for_a_type() {
+        let registry = registry(
+            "trait Eq {}
+            struct Foo {}
+            struct Bar {}
+            impl Eq for Foo {}
+            impl<T, N> Eq for [T; N] where T: Eq {}
+            impl<T> Eq for (T, Bar) where T: Eq {}",
+        );
+        let find = |typ| find_impl(&parse_type(typ), &bound("Eq"), &registry);
+
+        let found = find("Foo").expect("an impl should be found");
+        assert_eq!(found.trait_impl.object_type.to_string(), "Foo");
+        assert!(!found.is_blanket);
+
+        let found = find("[Foo; 3]").expect("an impl should be found");
+        assert_eq!(found.trait_impl.object_type.to_string(), "[T; N]");
+        assert!(found.is_blanket);
+
+        assert!(find("(Foo, Bar)").expect("an impl should be found").is_blanket);
+        assert!(find("[Bar; 3]").is_none());
+        assert!(find("Field").is_none());
+    }
+
+    #[test]
+    fn supertrait_closure_substitutes_generics() {
+        let registry = registry(
+            "trait Eq<T> {}
+            trait PartialOrd<T>: Eq<T> {}
+            trait Ord<T>: PartialOrd<T> + Eq<T> {}
+            trait Ping: Pong {}
+            trait Pong: Ping {}",
+        );
+        let closure = |trait_name: &str| {
+            let closure = supertrait_closure(&bound(trait_name), &registry);
+            closure.iter().map(ToString::to_string).collect::<Vec<_>>()
+        };
+
+        assert_eq!(closure("Ord<Field>"), vec!["PartialOrd<Field>", "Eq<Field>"]);
+        assert_eq!(closure("PartialOrd<u8>"), vec!["Eq<u8>"]);
+        assert!(closure("Eq<Field>").is_empty());
+
+        // Cycles are only followed once
+        assert_eq!(closure("Ping"), vec!["Pong"]);
+    }
+}
@@ -1,19 +1,26 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::Display;
 
 use iter_extended::vecmap;
 use noirc_errors::Span;
 
 use crate::{
-    BlockExpression, Expression, FunctionReturnType, Ident, NoirFunction, UnresolvedGenerics,
-    UnresolvedType,
+    ast::trait_registry::substitute_alias_generics,
+    ast::visitor::{walk_expression, Visitor},
+    token::{Attribute, IntType, Keyword},
+    BinaryOpKind, BlockExpression, Expression, ExpressionKind, FunctionReturnType, Ident,
+    NoirFunction, Respan, UnresolvedGenerics, UnresolvedType, UnresolvedTypeData,
 };
 
 /// AST node for trait definitions:
-/// `trait name<generics> { ... items ... }`
+/// `trait name<generics>: supertraits { ... items ... }`
 #[derive(Clone, Debug)]
 pub struct NoirTrait {
     pub name: Ident,
     pub generics: Vec<Ident>,
+    /// Traits which every implementor of this trait must also implement,
+    /// as in `trait Ord: Eq { ... }`
+    pub supertraits: Vec<TraitBound>,
     pub where_clause: Vec<TraitConstraint>,
     pub span: Span,
     pub items: Vec<TraitItem>,
@@ -24,6 +31,7 @@ pub struct NoirTrait {
 #[derive(Clone, Debug)]
 pub enum TraitItem {
     Function {
+        attributes: Vec<Attribute>,
         name: Ident,
         generics: Vec<Ident>,
         parameters: Vec<(Ident, UnresolvedType)>,
@@ -34,10 +42,17 @@ pub enum TraitItem {
     Constant {
         name: Ident,
         typ: UnresolvedType,
+        /// The constraints under which impls must define the constant, as in
+        /// `let SIZE: u32 where Self: Sized;`
+        where_clause: Vec<TraitConstraint>,
         default_value: Option<Expression>,
     },
     Type {
         name: Ident,
+        /// The generics of a generic associated type, as in `type Out<T>;`
+        generics: Vec<Ident>,
+        bounds: Vec<TraitBound>,
+        where_clause: Vec<TraitConstraint>,
     },
 }
 
@@ -45,6 +60,7 @@ pub enum TraitItem {
 /// `impl object_type<generics> { ... methods ... }`
 #[derive(Clone, Debug)]
 pub struct TypeImpl {
+    pub attributes: Vec<Attribute>,
     pub object_type: UnresolvedType,
     pub type_span: Span,
     pub generics: UnresolvedGenerics,
@@ -55,6 +71,7 @@ pub struct TypeImpl {
 /// `impl trait_name<trait_generics> for object_type where where_clauses { ... items ... }`
 #[derive(Clone, Debug)]
 pub struct TraitImpl {
+    pub attributes: Vec<Attribute>,
     pub impl_generics: UnresolvedGenerics,
 
     pub trait_name: Ident,
@@ -66,6 +83,80 @@ pub struct TraitImpl {
     pub where_clause: Vec<TraitConstraint>,
 
     pub items: Vec<TraitImplItem>,
+
+    /// Where this impl was generated from, or `None` if it was written by hand
+    pub provenance: Option<Provenance>,
+}
+
+/// The macro which generated an item, such as a trait impl produced by `#[derive(Eq)]`, and the
+/// item it was generated from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Provenance {
+    /// The macro's name as written in its attribute, such as `derive(Eq)`
+    pub macro_name: String,
+    /// The item the macro was applied to. Its span points at the item's name.
+    pub source_item: Ident,
+}
+
+/// Either kind of impl, so that inherent and trait impls can be handled uniformly
+#[derive(Clone, Debug)]
+pub enum AnyImpl {
+    Inherent(TypeImpl),
+    Trait(TraitImpl),
+}
+
+impl AnyImpl {
+    /// The type the impl's methods are defined on
+    pub fn object_type(&self) -> &UnresolvedType {
+        match self {
+            AnyImpl::Inherent(type_impl) => &type_impl.object_type,
+            AnyImpl::Trait(trait_impl) => &trait_impl.object_type,
+        }
+    }
+
+    /// The generics declared on the impl itself, as in `impl<T>`
+    pub fn generics(&self) -> &UnresolvedGenerics {
+        match self {
+            AnyImpl::Inherent(type_impl) => &type_impl.generics,
+            AnyImpl::Trait(trait_impl) => &trait_impl.impl_generics,
+        }
+    }
+
+    /// The methods defined by the impl. Associated constants and types of trait impls are
+    /// skipped.
+    pub fn methods(&self) -> Vec<&NoirFunction> {
+        match self {
+            AnyImpl::Inherent(type_impl) => type_impl.methods.iter().collect(),
+            AnyImpl::Trait(trait_impl) => trait_impl
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    TraitImplItem::Function(function) => Some(function),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// The trait implemented, or `None` for an inherent impl
+    pub fn trait_name(&self) -> Option<&Ident> {
+        match self {
+            AnyImpl::Inherent(_) => None,
+            AnyImpl::Trait(trait_impl) => Some(&trait_impl.trait_name),
+        }
+    }
+}
+
+impl From<TypeImpl> for AnyImpl {
+    fn from(type_impl: TypeImpl) -> AnyImpl {
+        AnyImpl::Inherent(type_impl)
+    }
+}
+
+impl From<TraitImpl> for AnyImpl {
+    fn from(trait_impl: TraitImpl) -> AnyImpl {
+        AnyImpl::Trait(trait_impl)
+    }
 }
 
 /// Represents a simple trait constraint such as `where Foo: TraitY<U, V>`
@@ -80,24 +171,361 @@ pub struct TraitConstraint {
     pub trait_bound: TraitBound,
 }
 
+/// Returns true if the where clauses `a` and `b` require the same constraints, regardless of the
+/// order they are given in or how often each is repeated. Constraints are compared ignoring their
+/// spans, and as bounds such as `T: A + B` are split by the parser, this is equivalent to
+/// `T: B, T: A`.
+pub fn where_clauses_equivalent(a: &[TraitConstraint], b: &[TraitConstraint]) -> bool {
+    let canonicalize = |where_clause: &[TraitConstraint]| -> BTreeSet<String> {
+        where_clause.iter().map(ToString::to_string).collect()
+    };
+    canonicalize(a) == canonicalize(b)
+}
+
 /// Represents a single trait bound, such as `TraitX` or `TraitY<U, V>`
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct TraitBound {
     pub trait_name: Ident,
     pub trait_generics: Vec<UnresolvedType>,
+    /// Whether this is a negative bound such as `!TraitX`, which holds only for types that
+    /// don't implement the trait
+    pub negative: bool,
+}
+
+/// The name of the bound satisfied by functions and closures of a given signature, as in
+/// `F: Fn(Field) -> Field`. There is no trait of this name, and such bounds are checked by
+/// treating the bounded generic as a function type.
+pub const FN_TRAIT_NAME: &str = "Fn";
+
+impl TraitBound {
+    /// Returns the parameter and return types of a bound such as `Fn(Field) -> Field`, or `None`
+    /// for any other bound.
+    pub fn fn_signature(&self) -> Option<(&[UnresolvedType], &UnresolvedType)> {
+        if self.trait_name.0.contents != FN_TRAIT_NAME || self.negative {
+            return None;
+        }
+        match self.trait_generics.as_slice() {
+            [UnresolvedType { typ: UnresolvedTypeData::Function(args, ret, _), .. }] => {
+                Some((args, ret))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A comparison between a numeric generic and an integer in a function's where clause, such as
+/// `where N > 0`, which must hold for each value the generic is instantiated with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenericPredicate {
+    pub generic: Ident,
+    /// One of the comparison operators `==`, `!=`, `<`, `<=`, `>` or `>=`
+    pub operator: BinaryOpKind,
+    pub value: u64,
+    pub span: Span,
+}
+
+impl GenericPredicate {
+    /// Returns true if the predicate holds when its generic is instantiated with `generic_value`.
+    pub fn holds(&self, generic_value: u64) -> bool {
+        match self.operator {
+            BinaryOpKind::Equal => generic_value == self.value,
+            BinaryOpKind::NotEqual => generic_value != self.value,
+            BinaryOpKind::Less => generic_value < self.value,
+            BinaryOpKind::LessEqual => generic_value <= self.value,
+            BinaryOpKind::Greater => generic_value > self.value,
+            BinaryOpKind::GreaterEqual => generic_value >= self.value,
+            operator => unreachable!("Non-comparison operator {operator} in generic predicate"),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum TraitImplItem {
     Function(NoirFunction),
     Constant(Ident, UnresolvedType, Expression),
-    Type { name: Ident, alias: UnresolvedType },
+    Type {
+        name: Ident,
+        /// The generics of a generic associated type, as in `type Out<T> = Wrap<T>;`
+        generics: Vec<Ident>,
+        alias: UnresolvedType,
+    },
+}
+
+/// A reason a trait can't be used as a trait object such as `dyn Trait`, naming the item of the
+/// trait responsible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ObjectSafetyViolation {
+    /// A method without a `self` receiver, which can't be called through a trait object
+    AssociatedFunction(Ident),
+    /// A method with generics of its own, which would need a vtable entry for each of the
+    /// types it may be instantiated with
+    GenericMethod(Ident),
+}
+
+impl ObjectSafetyViolation {
+    /// The item of the trait which violates object safety.
+    pub fn item(&self) -> &Ident {
+        match self {
+            ObjectSafetyViolation::AssociatedFunction(name)
+            | ObjectSafetyViolation::GenericMethod(name) => name,
+        }
+    }
+}
+
+impl Display for ObjectSafetyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectSafetyViolation::AssociatedFunction(name) => {
+                write!(f, "associated function `{name}` has no `self` receiver")
+            }
+            ObjectSafetyViolation::GenericMethod(name) => {
+                write!(f, "method `{name}` has generic parameters")
+            }
+        }
+    }
+}
+
+impl NoirTrait {
+    /// Returns each item of this trait which prevents it from being used as a trait object, in
+    /// declaration order. The trait is object safe if there are none.
+    pub fn object_safety_violations(&self) -> Vec<ObjectSafetyViolation> {
+        let mut violations = Vec::new();
+        for item in &self.items {
+            let TraitItem::Function { name, generics, parameters, .. } = item else { continue };
+            let has_receiver =
+                parameters.first().map_or(false, |(parameter, _)| parameter.0.contents == "self");
+            if !has_receiver {
+                violations.push(ObjectSafetyViolation::AssociatedFunction(name.clone()));
+            }
+            if !generics.is_empty() {
+                violations.push(ObjectSafetyViolation::GenericMethod(name.clone()));
+            }
+        }
+        violations
+    }
+
+    /// Returns each of this trait's generics which isn't referenced by its where clause, its
+    /// supertraits, or the signature or where clause of any of its items, in declaration order.
+    /// Implementors must still choose an argument for such a generic, though nothing depends on
+    /// it.
+    ///
+    /// References are found with [`UnresolvedType::referenced_generics`], so within an item
+    /// declaring a generic of the same name, the name refers to the item's generic instead.
+    pub fn phantom_generics(&self) -> Vec<Ident> {
+        let no_generics: &[Ident] = &[];
+        let mut types: Vec<(&UnresolvedType, &[Ident])> = Vec::new();
+        types.extend(constraint_types(&self.where_clause).map(|typ| (typ, no_generics)));
+        types.extend(
+            self.supertraits
+                .iter()
+                .flat_map(|bound| &bound.trait_generics)
+                .map(|typ| (typ, no_generics)),
+        );
+        for item in &self.items {
+            match item {
+                TraitItem::Function { generics, parameters, return_type, where_clause, .. } => {
+                    let return_type = match return_type {
+                        FunctionReturnType::Default(_) => None,
+                        FunctionReturnType::Ty(typ, _) => Some(typ),
+                    };
+                    let signature = parameters
+                        .iter()
+                        .map(|(_, typ)| typ)
+                        .chain(return_type)
+                        .chain(constraint_types(where_clause));
+                    types.extend(signature.map(|typ| (typ, generics.as_slice())));
+                }
+                TraitItem::Constant { typ, where_clause, .. } => {
+                    let signature = std::iter::once(typ).chain(constraint_types(where_clause));
+                    types.extend(signature.map(|typ| (typ, no_generics)));
+                }
+                TraitItem::Type { generics, bounds, where_clause, .. } => {
+                    let signature = bounds
+                        .iter()
+                        .flat_map(|bound| &bound.trait_generics)
+                        .chain(constraint_types(where_clause));
+                    types.extend(signature.map(|typ| (typ, generics.as_slice())));
+                }
+            }
+        }
+
+        let referenced: HashSet<&str> = types
+            .into_iter()
+            .flat_map(|(typ, shadowing)| {
+                let names = typ.referenced_generics().into_iter();
+                names.filter(move |name| !shadowing.contains(*name))
+            })
+            .map(|name| name.0.contents.as_str())
+            .collect();
+        let is_phantom = |generic: &&Ident| !referenced.contains(generic.0.contents.as_str());
+        self.generics.iter().filter(is_phantom).cloned().collect()
+    }
+
+    /// Formats this trait like its [`Display`] impl, but with its items in a canonical order
+    /// rather than their source order: associated types, then constants, then methods, each
+    /// sorted by name. Traits which differ only in the order of their items are formatted alike.
+    pub fn display_canonical(&self) -> String {
+        let mut canonical = self.clone();
+        canonical.items.sort_by(|a, b| a.canonical_key().cmp(&b.canonical_key()));
+        canonical.to_string()
+    }
+}
+
+impl TraitImpl {
+    /// Returns every constraint a type must satisfy to use this impl of `trait_def`: the impl's
+    /// own where clause, followed by the trait's where clause and a constraint for each of its
+    /// supertraits. Constraints from the trait are given in terms of the impl, with `Self`
+    /// replaced by the impl's object type and each of the trait's generics by the impl's
+    /// argument for it. Constraints repeating an earlier one, ignoring spans, are left out.
+    pub fn effective_constraints(&self, trait_def: &NoirTrait) -> Vec<TraitConstraint> {
+        let mut bindings: HashMap<_, _> = trait_def
+            .generics
+            .iter()
+            .map(|generic| generic.0.contents.clone())
+            .zip(self.trait_generics.iter().cloned())
+            .collect();
+        bindings.insert("Self".to_string(), self.object_type.clone());
+
+        let substitute = |typ: &UnresolvedType| substitute_alias_generics(typ, &bindings);
+        let substitute_bound = |bound: &TraitBound| TraitBound {
+            trait_generics: vecmap(&bound.trait_generics, substitute),
+            ..bound.clone()
+        };
+
+        let trait_constraints = trait_def.where_clause.iter().map(|constraint| TraitConstraint {
+            typ: substitute(&constraint.typ),
+            trait_bound: substitute_bound(&constraint.trait_bound),
+        });
+        let supertrait_constraints =
+            trait_def.supertraits.iter().map(|supertrait| TraitConstraint {
+                typ: self.object_type.clone(),
+                trait_bound: substitute_bound(supertrait),
+            });
+
+        let mut seen = HashSet::new();
+        self.where_clause
+            .iter()
+            .cloned()
+            .chain(trait_constraints)
+            .chain(supertrait_constraints)
+            .filter(|constraint| seen.insert(constraint.to_string()))
+            .collect()
+    }
+}
+
+impl TraitItem {
+    pub fn name(&self) -> &Ident {
+        match self {
+            TraitItem::Function { name, .. }
+            | TraitItem::Constant { name, .. }
+            | TraitItem::Type { name, .. } => name,
+        }
+    }
+
+    /// The key items are sorted on by [`NoirTrait::display_canonical`].
+    fn canonical_key(&self) -> (u8, &str) {
+        match self {
+            TraitItem::Type { name, .. } => (0, &name.0.contents),
+            TraitItem::Constant { name, .. } => (1, &name.0.contents),
+            TraitItem::Function { name, .. } => (2, &name.0.contents),
+        }
+    }
+
+    /// Returns this item with every span within it replaced by `span`.
+    /// See [`Respan`] for more details.
+    pub fn with_span(mut self, span: Span) -> TraitItem {
+        self.respan(span);
+        self
+    }
+
+    /// Returns the methods of `trait_def` which an impl must implement to use this method's
+    /// default body, such as `cmp` for a default `partial_cmp` which calls `self.cmp(other)`.
+    ///
+    /// These are the methods without a default body which are called as `self.method(..)` or
+    /// `Self::method(..)`, either by this method's default body or by the default body of
+    /// another method it calls, in the order they are first reached. Items other than methods
+    /// with a default body require nothing.
+    pub fn required_siblings(&self, trait_def: &NoirTrait) -> Vec<Ident> {
+        let TraitItem::Function { name, body: Some(body), .. } = self else {
+            return Vec::new();
+        };
+
+        let methods: HashMap<&str, (&Ident, Option<&BlockExpression>)> = trait_def
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                TraitItem::Function { name, body, .. } => {
+                    Some((name.0.contents.as_str(), (name, body.as_ref())))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut required = Vec::new();
+        let mut visited = HashSet::from([name.0.contents.as_str()]);
+        let mut bodies = vec![body];
+        while let Some(body) = bodies.pop() {
+            let mut calls = SiblingCalls::default();
+            calls.visit_block(body);
+
+            // Bodies are searched in the order they are called
+            let mut called_bodies = Vec::new();
+            for method_name in calls.0 {
+                let Some((method_name, (name, body))) = methods.get_key_value(method_name.as_str())
+                else {
+                    continue;
+                };
+                if !visited.insert(*method_name) {
+                    continue;
+                }
+                match body {
+                    Some(body) => called_bodies.push(*body),
+                    None => required.push((*name).clone()),
+                }
+            }
+            bodies.extend(called_bodies.into_iter().rev());
+        }
+        required
+    }
+}
+
+/// Records the names of the methods called as `self.method(..)` or `Self::method(..)`.
+#[derive(Default)]
+struct SiblingCalls(Vec<String>);
+
+impl Visitor for SiblingCalls {
+    fn visit_expression(&mut self, expression: &Expression) {
+        match &expression.kind {
+            ExpressionKind::MethodCall(call) => {
+                if matches!(&call.object.kind, ExpressionKind::Variable(path) if path.as_string() == "self")
+                {
+                    self.0.push(call.method_name.0.contents.clone());
+                }
+            }
+            ExpressionKind::Call(call) => {
+                if let ExpressionKind::Variable(path) = &call.func.kind {
+                    if let [self_type, method_name] = path.segments.as_slice() {
+                        if self_type.0.contents == "Self" {
+                            self.0.push(method_name.0.contents.clone());
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+        walk_expression(self, expression);
+    }
 }
 
 impl Display for TypeImpl {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let generics = vecmap(&self.generics, |generic| generic.to_string());
-        let generics = if generics.is_empty() { "".into() } else { generics.join(", ") };
+        let generics =
+            if generics.is_empty() { "".into() } else { format!("<{}>", generics.join(", ")) };
+
+        for attribute in &self.attributes {
+            writeln!(f, "{attribute}")?;
+        }
 
         writeln!(f, "impl{} {} {{", generics, self.object_type)?;
 
@@ -114,10 +542,18 @@ impl Display for TypeImpl {
 
 impl Display for NoirTrait {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let generics = vecmap(&self.generics, |generic| generic.to_string());
-        let generics = if generics.is_empty() { "".into() } else { generics.join(", ") };
+        let generics = vecmap(&self.generics, |generic| Escaped(generic).to_string());
+        let generics =
+            if generics.is_empty() { "".into() } else { format!("<{}>", generics.join(", ")) };
 
-        writeln!(f, "trait {}{} {{", self.name, generics)?;
+        let supertraits = vecmap(&self.supertraits, |supertrait| supertrait.to_string());
+        let supertraits = if supertraits.is_empty() {
+            "".into()
+        } else {
+            format!(": {}", supertraits.join(" + "))
+        };
+
+        writeln!(f, "trait {}{}{} {{", Escaped(&self.name), generics, supertraits)?;
 
         for item in self.items.iter() {
             let item = item.to_string();
@@ -133,37 +569,79 @@ impl Display for NoirTrait {
 impl Display for TraitItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TraitItem::Function { name, generics, parameters, return_type, where_clause, body } => {
-                let generics = vecmap(generics, |generic| generic.to_string());
-                let parameters = vecmap(parameters, |(name, typ)| format!("{name}: {typ}"));
-                let where_clause = vecmap(where_clause, ToString::to_string);
+            TraitItem::Function {
+                attributes,
+                name,
+                generics,
+                parameters,
+                return_type,
+                where_clause,
+                body,
+            } => {
+                for attribute in attributes {
+                    write!(f, "{attribute} ")?;
+                }
 
-                let generics = generics.join(", ");
-                let parameters = parameters.join(", ");
-                let where_clause = where_clause.join(", ");
+                write!(f, "fn {}", Escaped(name))?;
 
-                write!(
-                    f,
-                    "fn {name}<{}>({}) -> {} where {}",
-                    generics, parameters, return_type, where_clause
-                )?;
+                if !generics.is_empty() {
+                    let generics = vecmap(generics, |generic| Escaped(generic).to_string());
+                    write!(f, "<{}>", generics.join(", "))?;
+                }
+
+                let parameters =
+                    vecmap(parameters, |(name, typ)| format!("{}: {typ}", Escaped(name)));
+                write!(f, "({})", parameters.join(", "))?;
+
+                if let FunctionReturnType::Ty(..) = return_type {
+                    write!(f, " -> {return_type}")?;
+                }
+
+                if !where_clause.is_empty() {
+                    let where_clause = vecmap(where_clause, ToString::to_string);
+                    write!(f, " where {}", where_clause.join(", "))?;
+                }
 
                 if let Some(body) = body {
-                    write!(f, "{}", body)
+                    write!(f, " {body}")
                 } else {
                     write!(f, ";")
                 }
             }
-            TraitItem::Constant { name, typ, default_value } => {
-                write!(f, "let {}: {}", name, typ)?;
+            TraitItem::Constant { name, typ, where_clause, default_value } => {
+                write!(f, "let {}: {}", Escaped(name), typ)?;
+
+                if !where_clause.is_empty() {
+                    let where_clause = vecmap(where_clause, ToString::to_string);
+                    write!(f, " where {}", where_clause.join(", "))?;
+                }
 
                 if let Some(default_value) = default_value {
-                    write!(f, "{};", default_value)
+                    write!(f, " = {default_value};")
                 } else {
                     write!(f, ";")
                 }
             }
-            TraitItem::Type { name } => write!(f, "type {name};"),
+            TraitItem::Type { name, generics, bounds, where_clause } => {
+                write!(f, "type {}", Escaped(name))?;
+
+                if !generics.is_empty() {
+                    let generics = vecmap(generics, |generic| Escaped(generic).to_string());
+                    write!(f, "<{}>", generics.join(", "))?;
+                }
+
+                if !bounds.is_empty() {
+                    let bounds = vecmap(bounds, ToString::to_string);
+                    write!(f, ": {}", bounds.join(" + "))?;
+                }
+
+                if !where_clause.is_empty() {
+                    let where_clause = vecmap(where_clause, ToString::to_string);
+                    write!(f, " where {}", where_clause.join(", "))?;
+                }
+
+                write!(f, ";")
+            }
         }
     }
 }
@@ -174,8 +652,21 @@ impl Display for TraitConstraint {
     }
 }
 
+impl Display for GenericPredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.generic, self.operator, self.value)
+    }
+}
+
 impl Display for TraitBound {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.negative {
+            write!(f, "!")?;
+        }
+        if let Some((args, ret)) = self.fn_signature() {
+            let args = vecmap(args, ToString::to_string);
+            return write!(f, "{}({}) -> {ret}", self.trait_name, args.join(", "));
+        }
         let generics = vecmap(&self.trait_generics, |generic| generic.to_string());
         if !generics.is_empty() {
             write!(f, "{}<{}>", self.trait_name, generics.join(", "))
@@ -188,9 +679,14 @@ impl Display for TraitBound {
 impl Display for TraitImpl {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let generics = vecmap(&self.trait_generics, |generic| generic.to_string());
-        let generics = generics.join(", ");
+        let generics =
+            if generics.is_empty() { "".into() } else { format!("<{}>", generics.join(", ")) };
+
+        for attribute in &self.attributes {
+            writeln!(f, "{attribute}")?;
+        }
 
-        writeln!(f, "impl {}<{}> for {} {{", self.trait_name, generics, self.object_type)?;
+        writeln!(f, "impl {}{} for {} {{", Escaped(&self.trait_name), generics, self.object_type)?;
 
         for item in self.items.iter() {
             let item = item.to_string();
@@ -203,14 +699,278 @@ impl Display for TraitImpl {
     }
 }
 
+impl Display for AnyImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnyImpl::Inherent(type_impl) => type_impl.fmt(f),
+            AnyImpl::Trait(trait_impl) => trait_impl.fmt(f),
+        }
+    }
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "generated by #[{}] on {}", self.macro_name, self.source_item)
+    }
+}
+
 impl Display for TraitImplItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TraitImplItem::Function(function) => function.fmt(f),
-            TraitImplItem::Type { name, alias } => write!(f, "type {name} = {alias};"),
+            TraitImplItem::Type { name, generics, alias } => {
+                let name = Escaped(name);
+                if generics.is_empty() {
+                    write!(f, "type {name} = {alias};")
+                } else {
+                    let generics = vecmap(generics, |generic| Escaped(generic).to_string());
+                    write!(f, "type {name}<{}> = {alias};", generics.join(", "))
+                }
+            }
             TraitImplItem::Constant(name, typ, value) => {
-                write!(f, "let {}: {} = {};", name, typ, value)
+                write!(f, "let {}: {} = {};", Escaped(name), typ, value)
+            }
+        }
+    }
+}
+
+/// Returns the constrained type and the trait's generics of each constraint in `where_clause`.
+fn constraint_types(where_clause: &[TraitConstraint]) -> impl Iterator<Item = &UnresolvedType> {
+    where_clause.iter().flat_map(|constraint| {
+        std::iter::once(&constraint.typ).chain(&constraint.trait_bound.trait_generics)
+    })
+}
+
+/// Displays an identifier so that it lexes back to the same identifier, writing names which
+/// would otherwise lex as a keyword or integer type as raw identifiers, e.g. `r#type`.
+struct Escaped<'a>(&'a Ident);
+
+impl Display for Escaped<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = &self.0 .0.contents;
+        let is_reserved = Keyword::lookup_keyword(name).is_some()
+            || !matches!(IntType::lookup_int_type(name, self.0.span()), Ok(None));
+        if is_reserved {
+            write!(f, "r#{name}")
+        } else {
+            write!(f, "{name}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iter_extended::vecmap;
+
+    use crate::{parse_program, where_clauses_equivalent, AnyImpl, TraitItem};
+
+    #[test]
+    fn phantom_generics_are_unreferenced() {
+        let src = "
+            trait Convert<A, B, C, D, E, N> where A: Default {
+                let SIZE: [Field; N];
+                fn convert(self) -> B;
+                fn with<C>(self, value: C) -> Self;
+                fn meet(self) where Self: Eq<D>;
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        // `C` is only referenced by `with`, which declares its own `C`
+        let phantom = vecmap(program.traits[0].phantom_generics(), |generic| generic.0.contents);
+        assert_eq!(phantom, vec!["C", "E"]);
+    }
+
+    #[test]
+    fn keyword_names_are_displayed_as_raw_identifiers() {
+        let src = "
+            trait Schema {
+                let r#type: Field;
+                fn r#struct(self) -> Field;
+                fn field_count(self) -> Field;
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let trait_def = &program.traits[0];
+        assert!(matches!(
+            &trait_def.items[1],
+            TraitItem::Function { name, .. } if name.0.contents == "struct"
+        ));
+
+        let displayed = trait_def.to_string();
+        assert!(displayed.contains("let r#type: Field;"), "{displayed}");
+        assert!(displayed.contains("fn r#struct(self: Self) -> Field;"), "{displayed}");
+        assert!(displayed.contains("fn field_count(self: Self) -> Field;"), "{displayed}");
+    }
+
+    #[test]
+    fn default_bodies_require_the_methods_they_call() {
+        let src = "
+            trait Ord {
+                fn cmp(self, other: Self) -> Field;
+                fn is_zero(self) -> bool;
+                fn partial_cmp(self, other: Self) -> Field { self.cmp(other) }
+                fn max(self, other: Self) -> Self {
+                    if Self::partial_cmp(self, other) == 1 { self } else { other }
+                }
+                fn min(self, other: Self) -> Self {
+                    if self.is_zero() | (self.max(other) == other) { self } else { other }
+                }
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let trait_def = &program.traits[0];
+        let required = |method_name: &str| {
+            let item = trait_def.items.iter().find(|item| {
+                matches!(item, TraitItem::Function { name, .. } if name.0.contents == method_name)
+            });
+            let required = item.expect("method should be declared").required_siblings(trait_def);
+            required.into_iter().map(|name| name.0.contents).collect::<Vec<_>>()
+        };
+
+        assert!(required("cmp").is_empty());
+        assert_eq!(required("partial_cmp"), vec!["cmp"]);
+        assert_eq!(required("max"), vec!["cmp"]);
+        assert_eq!(required("min"), vec!["is_zero", "cmp"]);
+    }
+
+    #[test]
+    fn object_safety_violations_name_their_items() {
+        let src = "
+            trait Shape {
+                fn area(self) -> Field;
+                fn scale<T>(self, factor: T) -> Self;
+                fn unit() -> Self;
+            }
+            trait Named {
+                fn name(self) -> Field;
+                fn describe(self) -> Field { self.name() }
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let violations =
+            vecmap(program.traits[0].object_safety_violations(), |violation| violation.to_string());
+        assert_eq!(
+            violations,
+            vec![
+                "method `scale` has generic parameters",
+                "associated function `unit` has no `self` receiver",
+            ]
+        );
+        assert!(program.traits[1].object_safety_violations().is_empty());
+    }
+
+    #[test]
+    fn effective_constraints_merge_impl_and_trait_where_clauses() {
+        let src = "
+            trait Container<T>: Sized where T: Eq, Self: Default {
+                fn get(self) -> T;
+            }
+            impl<U> Container<U> for Bag<U> where U: Eq, U: Hash {
+                fn get(self) -> U { self.item }
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let constraints = program.trait_impls[0].effective_constraints(&program.traits[0]);
+        let constraints = vecmap(constraints, |constraint| constraint.to_string());
+        assert_eq!(constraints, vec!["U: Eq", "U: Hash", "Bag<U>: Default", "Bag<U>: Sized"]);
+    }
+
+    #[test]
+    fn where_clauses_are_equivalent_regardless_of_order() {
+        let src = "
+            fn reordered<T, U>(t: T, u: U) where T: Eq, U: Hash, T: Default {}
+            fn same_order<T, U>(t: T, u: U) where U: Hash, T: Default, T: Eq {}
+            fn regrouped<T, U>(t: T, u: U) where T: Default + Eq, U: Hash, T: Eq {}
+            fn grouped<T, U>(t: T, u: U) where T: Eq + Default, U: Hash {}
+            fn missing<T, U>(t: T, u: U) where T: Eq + Default {}
+            fn negated<T, U>(t: T, u: U) where T: Eq + !Default, U: Hash {}
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let where_clauses = vecmap(&program.functions, |function| &function.def.where_clause);
+        for where_clause in &where_clauses[1..4] {
+            assert!(where_clauses_equivalent(where_clauses[0], where_clause));
+            assert!(where_clauses_equivalent(where_clause, where_clauses[0]));
+        }
+        assert!(!where_clauses_equivalent(where_clauses[0], where_clauses[4]));
+        assert!(!where_clauses_equivalent(where_clauses[0], where_clauses[5]));
+    }
+
+    #[test]
+    fn canonical_display_ignores_item_order() {
+        let src = "
+            trait Container {
+                fn len(self) -> u32;
+                let CAPACITY: u32 = 8;
+                type Item;
+                fn is_empty(self) -> bool { self.len() == 0 }
+                type Index;
+            }
+            trait Reordered {
+                type Index;
+                fn is_empty(self) -> bool { self.len() == 0 }
+                let CAPACITY: u32 = 8;
+                type Item;
+                fn len(self) -> u32;
+            }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let canonical = vecmap(&program.traits, |trait_def| {
+            trait_def.display_canonical().replacen("Reordered", "Container", 1)
+        });
+        assert_eq!(canonical[0], canonical[1]);
+        assert_ne!(program.traits[0].to_string(), program.traits[1].to_string());
+
+        let item_lines: Vec<_> = canonical[0].lines().skip(1).map(str::trim).collect();
+        assert!(item_lines[0].starts_with("type Index"));
+        assert!(item_lines[1].starts_with("type Item"));
+        assert!(item_lines[2].starts_with("let CAPACITY"));
+        assert!(item_lines[3].starts_with("fn is_empty"));
+    }
+
+    #[test]
+    fn inherent_and_trait_impls_are_viewed_alike() {
+        let src = "
+            struct Wrap<T> { value: T }
+            impl<T> Wrap<T> {
+                fn get(self) -> T { self.value }
+            }
+            trait Size {
+                type Unit;
+                fn size(self) -> Field;
+            }
+            impl<T> Size for Wrap<T> {
+                type Unit = Field;
+                fn size(self) -> Field { 1 }
             }
+        ";
+        let (program, errors) = parse_program(src);
+        assert!(!errors.iter().any(|error| error.is_error()), "{errors:?}");
+
+        let impls = [
+            AnyImpl::from(program.impls[0].clone()),
+            AnyImpl::from(program.trait_impls[0].clone()),
+        ];
+        for (any_impl, method_name) in impls.iter().zip(["get", "size"]) {
+            assert_eq!(any_impl.object_type().to_string(), "Wrap<T>");
+            assert_eq!(any_impl.generics().len(), 1);
+            let methods: Vec<_> = any_impl.methods().iter().map(|method| method.name()).collect();
+            assert_eq!(methods, vec![method_name]);
         }
+        assert!(impls[0].trait_name().is_none());
+        assert_eq!(impls[1].trait_name().map(ToString::to_string), Some("Size".to_string()));
     }
 }
@@ -0,0 +1,192 @@
+use crate::{
+    ArrayLiteral, BlockExpression, Expression, ExpressionKind, LValue, Literal, Path, Pattern,
+    Statement, TraitBound, UnresolvedType, UnresolvedTypeData,
+};
+
+/// A read-only traversal over the statements and expressions of the Ast.
+///
+/// Each `visit_*` method defaults to visiting the children of the node through the matching
+/// `walk_*` function, so implementors only need to override the methods for the nodes they are
+/// interested in, calling the `walk_*` function themselves if they still want to recurse.
+pub trait Visitor {
+    fn visit_block(&mut self, block: &BlockExpression) {
+        walk_block(self, block);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_type(&mut self, typ: &UnresolvedType) {
+        walk_type(self, typ);
+    }
+
+    /// Called for each path referenced by an expression, pattern or type.
+    fn visit_path(&mut self, _path: &Path) {}
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &BlockExpression) {
+    for statement in &block.0 {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Let(let_statement) => {
+            visitor.visit_pattern(&let_statement.pattern);
+            visitor.visit_type(&let_statement.r#type);
+            visitor.visit_expression(&let_statement.expression);
+        }
+        Statement::Constrain(constrain) => visitor.visit_expression(&constrain.0),
+        Statement::Expression(expression) | Statement::Semi(expression) => {
+            visitor.visit_expression(expression);
+        }
+        Statement::Assign(assign) => {
+            walk_lvalue(visitor, &assign.lvalue);
+            visitor.visit_expression(&assign.expression);
+        }
+        Statement::Error => (),
+    }
+}
+
+fn walk_lvalue<V: Visitor + ?Sized>(visitor: &mut V, lvalue: &LValue) {
+    match lvalue {
+        LValue::Ident(_) => (),
+        LValue::MemberAccess { object, .. } => walk_lvalue(visitor, object),
+        LValue::Index { array, index } => {
+            walk_lvalue(visitor, array);
+            visitor.visit_expression(index);
+        }
+        LValue::Dereference(lvalue) => walk_lvalue(visitor, lvalue),
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match &expression.kind {
+        ExpressionKind::Literal(Literal::Array(ArrayLiteral::Standard(elements))) => {
+            elements.iter().for_each(|element| visitor.visit_expression(element));
+        }
+        ExpressionKind::Literal(Literal::Array(ArrayLiteral::Repeated {
+            repeated_element,
+            length,
+        })) => {
+            visitor.visit_expression(repeated_element);
+            visitor.visit_expression(length);
+        }
+        ExpressionKind::Literal(_) => (),
+        ExpressionKind::Block(block) => visitor.visit_block(block),
+        ExpressionKind::Prefix(prefix) => visitor.visit_expression(&prefix.rhs),
+        ExpressionKind::Index(index) => {
+            visitor.visit_expression(&index.collection);
+            visitor.visit_expression(&index.index);
+        }
+        ExpressionKind::Call(call) => {
+            visitor.visit_expression(&call.func);
+            call.arguments.iter().for_each(|argument| visitor.visit_expression(argument));
+        }
+        ExpressionKind::MethodCall(call) => {
+            visitor.visit_expression(&call.object);
+            call.arguments.iter().for_each(|argument| visitor.visit_expression(argument));
+        }
+        ExpressionKind::Constructor(constructor) => {
+            visitor.visit_path(&constructor.type_name);
+            constructor.fields.iter().for_each(|(_, field)| visitor.visit_expression(field));
+        }
+        ExpressionKind::MemberAccess(access) => visitor.visit_expression(&access.lhs),
+        ExpressionKind::Cast(cast) => {
+            visitor.visit_expression(&cast.lhs);
+            visitor.visit_type(&cast.r#type);
+        }
+        ExpressionKind::Infix(infix) => {
+            visitor.visit_expression(&infix.lhs);
+            visitor.visit_expression(&infix.rhs);
+        }
+        ExpressionKind::For(for_loop) => {
+            visitor.visit_expression(&for_loop.start_range);
+            visitor.visit_expression(&for_loop.end_range);
+            visitor.visit_expression(&for_loop.block);
+        }
+        ExpressionKind::If(if_expression) => {
+            visitor.visit_expression(&if_expression.condition);
+            visitor.visit_expression(&if_expression.consequence);
+            if let Some(alternative) = &if_expression.alternative {
+                visitor.visit_expression(alternative);
+            }
+        }
+        ExpressionKind::Variable(path) => visitor.visit_path(path),
+        ExpressionKind::Tuple(elements) => {
+            elements.iter().for_each(|element| visitor.visit_expression(element));
+        }
+        ExpressionKind::Lambda(lambda) => {
+            for (pattern, typ) in &lambda.parameters {
+                visitor.visit_pattern(pattern);
+                visitor.visit_type(typ);
+            }
+            visitor.visit_type(&lambda.return_type);
+            visitor.visit_expression(&lambda.body);
+        }
+        ExpressionKind::Error => (),
+    }
+}
+
+pub fn walk_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &Pattern) {
+    match pattern {
+        Pattern::Identifier(_) => (),
+        Pattern::Mutable(pattern, _) => visitor.visit_pattern(pattern),
+        Pattern::Tuple(patterns, _) => {
+            patterns.iter().for_each(|pattern| visitor.visit_pattern(pattern));
+        }
+        Pattern::Struct(path, fields, _) => {
+            visitor.visit_path(path);
+            fields.iter().for_each(|(_, pattern)| visitor.visit_pattern(pattern));
+        }
+    }
+}
+
+pub fn walk_type<V: Visitor + ?Sized>(visitor: &mut V, typ: &UnresolvedType) {
+    match &typ.typ {
+        UnresolvedTypeData::Array(_, element)
+        | UnresolvedTypeData::MutableReference(element)
+        | UnresolvedTypeData::Projection { object_type: element, .. } => {
+            visitor.visit_type(element);
+        }
+        UnresolvedTypeData::FormatString(_, elements) => visitor.visit_type(elements),
+        UnresolvedTypeData::Named(path, generics) => {
+            visitor.visit_path(path);
+            generics.iter().for_each(|generic| visitor.visit_type(generic));
+        }
+        UnresolvedTypeData::Tuple(elements)
+        | UnresolvedTypeData::TraitObject(TraitBound { trait_generics: elements, .. }) => {
+            elements.iter().for_each(|element| visitor.visit_type(element));
+        }
+        UnresolvedTypeData::Opaque { bounds, concrete_type } => {
+            let generics = bounds.iter().flat_map(|bound| &bound.trait_generics);
+            generics.for_each(|generic| visitor.visit_type(generic));
+            if let Some(concrete_type) = concrete_type {
+                visitor.visit_type(concrete_type);
+            }
+        }
+        UnresolvedTypeData::Function(arguments, ret, env) => {
+            arguments.iter().for_each(|argument| visitor.visit_type(argument));
+            visitor.visit_type(ret);
+            visitor.visit_type(env);
+        }
+        UnresolvedTypeData::FieldElement
+        | UnresolvedTypeData::Integer(..)
+        | UnresolvedTypeData::Bool
+        | UnresolvedTypeData::Expression(_)
+        | UnresolvedTypeData::String(_)
+        | UnresolvedTypeData::Unit
+        | UnresolvedTypeData::Unspecified
+        | UnresolvedTypeData::Error => (),
+    }
+}
@@ -0,0 +1,360 @@
+//! Evaluation of the default values of trait constants.
+//!
+//! A default such as `let SIZE: u32 = 2 * 8 + 1;` is folded into an integer while the trait is
+//! resolved, so that later size computations can use its value directly. Defaults may use
+//! arithmetic and bitwise operators on integer literals and on the trait's other constants,
+//! referred to as `Self::NAME`. They are evaluated as unsigned 128-bit integers, so anything
+//! which overflows, divides by zero or is negative is rejected. The folded value must then fit
+//! in the integer type the constant is declared with.
+//!
+//! The constants of each trait impl are folded in the same way, with the impl's values taking
+//! the place of the trait's defaults. Every `Self::NAME` expression within the impl's methods is
+//! then replaced by the folded value, so the constants are fully resolved before any circuit is
+//! generated and each impl's methods use their own impl's values.
+use std::collections::{HashMap, HashSet};
+
+use acvm::FieldElement;
+use noirc_errors::Span;
+
+use crate::{
+    ArrayLiteral, BinaryOpKind, BlockExpression, Expression, ExpressionKind, Ident, LValue,
+    Literal, NoirFunction, NoirTrait, Signedness, Statement, TraitImpl, TraitImplItem, TraitItem,
+    UnresolvedType, UnresolvedTypeData,
+};
+
+use super::errors::DefCollectorErrorKind;
+
+/// Folds the default value of each constant of `trait_def`, returning the values of those which
+/// could be evaluated keyed on their names, along with an error for each which couldn't.
+pub(crate) fn fold_trait_constants(
+    trait_def: &NoirTrait,
+) -> (HashMap<String, u128>, Vec<DefCollectorErrorKind>) {
+    fold_constants(trait_constants(trait_def))
+}
+
+/// Folds the value of each constant of `trait_impl`, an impl of `trait_def`, returning the
+/// values of those which could be evaluated keyed on their names, along with an error for each
+/// value given by the impl which couldn't, or which doesn't fit in its type. Constants the impl
+/// doesn't define take the trait's default, whose errors have already been reported when
+/// folding the trait's constants.
+pub(crate) fn fold_impl_constants(
+    trait_def: &NoirTrait,
+    trait_impl: &TraitImpl,
+) -> (HashMap<String, u128>, Vec<DefCollectorErrorKind>) {
+    let mut constants = trait_constants(trait_def);
+    let mut impl_constants = HashSet::new();
+    for item in &trait_impl.items {
+        if let TraitImplItem::Constant(item_name, typ, value) = item {
+            let source = ConstantSource::Impl;
+            let constant = Constant { item_name, typ, value: Some(value), source };
+            constants.insert(item_name.0.contents.as_str(), constant);
+            impl_constants.insert(item_name.0.contents.as_str());
+        }
+    }
+
+    let (values, mut errors) = fold_constants(constants);
+    errors.retain(|error| match error {
+        DefCollectorErrorKind::NonConstantDefault { .. } => false,
+        DefCollectorErrorKind::ConstantOverflow { item_name, .. } => {
+            impl_constants.contains(item_name.0.contents.as_str())
+        }
+        _ => true,
+    });
+    (values, errors)
+}
+
+/// Replaces each `Self::NAME` expression within the body of `function` which names one of
+/// `values` with the constant's value.
+pub(crate) fn inline_constants(function: &mut NoirFunction, values: &HashMap<String, u128>) {
+    if !values.is_empty() {
+        ConstantInliner { values }.inline_block(&mut function.def.body);
+    }
+}
+
+/// Returns each constant of `trait_def` with its default value, keyed on its name.
+fn trait_constants(trait_def: &NoirTrait) -> HashMap<&str, Constant> {
+    trait_def
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Constant { name, typ, default_value, .. } => {
+                let source = ConstantSource::TraitDefault;
+                let value = default_value.as_ref();
+                let constant = Constant { item_name: name, typ, value, source };
+                Some((name.0.contents.as_str(), constant))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn fold_constants(
+    constants: HashMap<&str, Constant>,
+) -> (HashMap<String, u128>, Vec<DefCollectorErrorKind>) {
+    let mut folder = ConstantFolder {
+        constants,
+        values: HashMap::new(),
+        in_progress: HashSet::new(),
+        errors: Vec::new(),
+    };
+    let names: Vec<_> = folder.constants.keys().copied().collect();
+    for name in names {
+        folder.fold_constant(name);
+    }
+
+    let values = folder
+        .values
+        .into_iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value?)))
+        .collect();
+    (values, folder.errors)
+}
+
+/// Where the value of a constant being folded was given.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ConstantSource {
+    TraitDefault,
+    Impl,
+}
+
+#[derive(Clone, Copy)]
+struct Constant<'a> {
+    item_name: &'a Ident,
+    typ: &'a UnresolvedType,
+    value: Option<&'a Expression>,
+    source: ConstantSource,
+}
+
+struct ConstantFolder<'a> {
+    /// The name and value of each constant, keyed on its name
+    constants: HashMap<&'a str, Constant<'a>>,
+    /// The folded value of each constant, or `None` if it couldn't be folded
+    values: HashMap<&'a str, Option<u128>>,
+    /// The constants whose defaults are being folded, to detect defaults defined in terms of
+    /// themselves
+    in_progress: HashSet<&'a str>,
+    errors: Vec<DefCollectorErrorKind>,
+}
+
+impl<'a> ConstantFolder<'a> {
+    /// Folds the value of the constant `name`, reporting an error if it isn't constant.
+    fn fold_constant(&mut self, name: &'a str) -> Option<u128> {
+        if let Some(value) = self.values.get(name) {
+            return *value;
+        }
+
+        let Constant { item_name, typ, value, source } = self.constants[name];
+        let expr = value?;
+
+        self.in_progress.insert(name);
+        let value = match self.fold(expr) {
+            Ok(value) if fits_in(value, typ) => Some(value),
+            Ok(value) => {
+                self.errors.push(DefCollectorErrorKind::ConstantOverflow {
+                    item_name: item_name.clone(),
+                    typ: typ.to_string(),
+                    value,
+                    span: expr.span,
+                });
+                None
+            }
+            Err(Some(span)) => {
+                let item_name = item_name.clone();
+                self.errors.push(match source {
+                    ConstantSource::TraitDefault => {
+                        DefCollectorErrorKind::NonConstantDefault { item_name, span }
+                    }
+                    ConstantSource::Impl => {
+                        DefCollectorErrorKind::NonConstantImplValue { item_name, span }
+                    }
+                });
+                None
+            }
+            // The error has been reported for the constant referenced
+            Err(None) => None,
+        };
+        self.in_progress.remove(name);
+
+        self.values.insert(name, value);
+        value
+    }
+
+    /// Evaluates `expr`, returning the span of the part of it which couldn't be evaluated on
+    /// failure. No span is returned if `expr` refers to a constant which has already been
+    /// reported.
+    fn fold(&mut self, expr: &'a Expression) -> Result<u128, Option<Span>> {
+        let not_constant = Err(Some(expr.span));
+        match &expr.kind {
+            ExpressionKind::Literal(Literal::Integer(value)) => {
+                value.try_into_u128().ok_or(Some(expr.span))
+            }
+            ExpressionKind::Variable(path) => {
+                let name = match path.segments.as_slice() {
+                    [self_type, name] if self_type.0.contents == "Self" => name.0.contents.as_str(),
+                    _ => return not_constant,
+                };
+                let Some(constant) = self.constants.get(name) else {
+                    return not_constant;
+                };
+                // Constants without a default take their value from each impl
+                if constant.value.is_none() || self.in_progress.contains(name) {
+                    return not_constant;
+                }
+                self.fold_constant(name).ok_or(None)
+            }
+            ExpressionKind::Infix(infix) => {
+                let lhs = self.fold(&infix.lhs)?;
+                let rhs = self.fold(&infix.rhs)?;
+                let value = match infix.operator.contents {
+                    BinaryOpKind::Add => lhs.checked_add(rhs),
+                    BinaryOpKind::Subtract => lhs.checked_sub(rhs),
+                    BinaryOpKind::Multiply => lhs.checked_mul(rhs),
+                    BinaryOpKind::Divide => lhs.checked_div(rhs),
+                    BinaryOpKind::Modulo => lhs.checked_rem(rhs),
+                    BinaryOpKind::And => Some(lhs & rhs),
+                    BinaryOpKind::Or => Some(lhs | rhs),
+                    BinaryOpKind::Xor => Some(lhs ^ rhs),
+                    BinaryOpKind::ShiftLeft => {
+                        u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shl(rhs))
+                    }
+                    BinaryOpKind::ShiftRight => {
+                        u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shr(rhs))
+                    }
+                    BinaryOpKind::Equal
+                    | BinaryOpKind::NotEqual
+                    | BinaryOpKind::Less
+                    | BinaryOpKind::LessEqual
+                    | BinaryOpKind::Greater
+                    | BinaryOpKind::GreaterEqual => None,
+                };
+                value.ok_or(Some(expr.span))
+            }
+            _ => not_constant,
+        }
+    }
+}
+
+/// Whether `value` fits in `typ`, if it is an integer type. The values of other types are left
+/// to the type checker.
+fn fits_in(value: u128, typ: &UnresolvedType) -> bool {
+    let bits = match typ.typ {
+        UnresolvedTypeData::Integer(Signedness::Unsigned, bits) => bits,
+        // Folded values are never negative, so only need to fit below the sign bit
+        UnresolvedTypeData::Integer(Signedness::Signed, bits) => bits.saturating_sub(1),
+        _ => return true,
+    };
+    bits >= 128 || value >> bits == 0
+}
+
+struct ConstantInliner<'a> {
+    values: &'a HashMap<String, u128>,
+}
+
+impl<'a> ConstantInliner<'a> {
+    fn inline_block(&self, block: &mut BlockExpression) {
+        for statement in &mut block.0 {
+            self.inline_statement(statement);
+        }
+    }
+
+    fn inline_statement(&self, statement: &mut Statement) {
+        match statement {
+            Statement::Let(let_statement) => self.inline(&mut let_statement.expression),
+            Statement::Constrain(constrain) => self.inline(&mut constrain.0),
+            Statement::Expression(expression) | Statement::Semi(expression) => {
+                self.inline(expression);
+            }
+            Statement::Assign(assign) => {
+                self.inline_lvalue(&mut assign.lvalue);
+                self.inline(&mut assign.expression);
+            }
+            Statement::Error => (),
+        }
+    }
+
+    fn inline_lvalue(&self, lvalue: &mut LValue) {
+        match lvalue {
+            LValue::Ident(_) => (),
+            LValue::MemberAccess { object, .. } | LValue::Dereference(object) => {
+                self.inline_lvalue(object);
+            }
+            LValue::Index { array, index } => {
+                self.inline_lvalue(array);
+                self.inline(index);
+            }
+        }
+    }
+
+    fn inline_all(&self, expressions: &mut [Expression]) {
+        for expression in expressions {
+            self.inline(expression);
+        }
+    }
+
+    fn inline(&self, expr: &mut Expression) {
+        match &mut expr.kind {
+            ExpressionKind::Variable(path) => {
+                let value = match path.segments.as_slice() {
+                    [self_type, name] if self_type.0.contents == "Self" => {
+                        self.values.get(&name.0.contents)
+                    }
+                    _ => None,
+                };
+                if let Some(value) = value {
+                    let value = FieldElement::from(*value);
+                    expr.kind = ExpressionKind::Literal(Literal::Integer(value));
+                }
+            }
+            ExpressionKind::Literal(Literal::Array(ArrayLiteral::Standard(elements))) => {
+                self.inline_all(elements);
+            }
+            ExpressionKind::Literal(Literal::Array(ArrayLiteral::Repeated {
+                repeated_element,
+                length,
+            })) => {
+                self.inline(repeated_element);
+                self.inline(length);
+            }
+            ExpressionKind::Literal(_) | ExpressionKind::Error => (),
+            ExpressionKind::Block(block) => self.inline_block(block),
+            ExpressionKind::Prefix(prefix) => self.inline(&mut prefix.rhs),
+            ExpressionKind::Index(index) => {
+                self.inline(&mut index.collection);
+                self.inline(&mut index.index);
+            }
+            ExpressionKind::Call(call) => {
+                self.inline(&mut call.func);
+                self.inline_all(&mut call.arguments);
+            }
+            ExpressionKind::MethodCall(call) => {
+                self.inline(&mut call.object);
+                self.inline_all(&mut call.arguments);
+            }
+            ExpressionKind::Constructor(constructor) => {
+                for (_, field) in &mut constructor.fields {
+                    self.inline(field);
+                }
+            }
+            ExpressionKind::MemberAccess(access) => self.inline(&mut access.lhs),
+            ExpressionKind::Cast(cast) => self.inline(&mut cast.lhs),
+            ExpressionKind::Infix(infix) => {
+                self.inline(&mut infix.lhs);
+                self.inline(&mut infix.rhs);
+            }
+            ExpressionKind::For(for_loop) => {
+                self.inline(&mut for_loop.start_range);
+                self.inline(&mut for_loop.end_range);
+                self.inline(&mut for_loop.block);
+            }
+            ExpressionKind::If(if_expression) => {
+                self.inline(&mut if_expression.condition);
+                self.inline(&mut if_expression.consequence);
+                if let Some(alternative) = &mut if_expression.alternative {
+                    self.inline(alternative);
+                }
+            }
+            ExpressionKind::Tuple(elements) => self.inline_all(elements),
+            ExpressionKind::Lambda(lambda) => self.inline(&mut lambda.body),
+        }
+    }
+}
@@ -1,7 +1,8 @@
+use super::const_fold::fold_trait_constants;
 use super::dc_mod::collect_defs;
 use super::errors::{DefCollectorErrorKind, DuplicateType};
 use crate::graph::CrateId;
-use crate::hir::def_map::{CrateDefMap, LocalModuleId, ModuleId};
+use crate::hir::def_map::{CrateDefMap, LocalModuleId, ModuleDefId, ModuleId};
 use crate::hir::resolution::errors::ResolverError;
 use crate::hir::resolution::import::PathResolutionError;
 use crate::hir::resolution::resolver::Resolver;
@@ -9,19 +10,23 @@ use crate::hir::resolution::{
     import::{resolve_imports, ImportDirective},
     path_resolver::StandardPathResolver,
 };
-use crate::hir::type_check::{type_check_func, TypeChecker};
+use crate::hir::trait_impls::specificity;
+use crate::hir::type_check::{is_unknown, may_match, type_check_func, TypeCheckError, TypeChecker};
 use crate::hir::Context;
-use crate::node_interner::{FuncId, NodeInterner, StmtId, StructId, TraitId, TypeAliasId};
+use crate::node_interner::{
+    supports_primitive_methods, FuncId, NodeInterner, StmtId, StructId, TraitId, TypeAliasId,
+};
 use crate::{
     ExpressionKind, FunctionReturnType, Generics, Ident, LetStatement, Literal, NoirFunction,
-    NoirStruct, NoirTrait, NoirTypeAlias, ParsedModule, Shared, StructType, TraitItem,
-    TraitItemType, Type, TypeBinding, UnresolvedGenerics, UnresolvedType,
+    NoirStruct, NoirTrait, NoirTypeAlias, ParsedModule, Provenance, Shared, StructType,
+    TraitConstraint, TraitItem, TraitItemType, Type, TypeBinding, UnresolvedGenerics,
+    UnresolvedType, UnresolvedTypeData,
 };
 use fm::FileId;
 use iter_extended::vecmap;
 use noirc_errors::Span;
 use noirc_errors::{CustomDiagnostic, FileDiagnostic};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::vec;
 
@@ -29,6 +34,13 @@ use std::vec;
 pub struct UnresolvedFunctions {
     pub file_id: FileId,
     pub functions: Vec<(LocalModuleId, FuncId, NoirFunction)>,
+    /// The trait these functions implement, if they are the methods of a trait impl
+    pub trait_id: Option<TraitId>,
+    /// The constants of the implemented trait without a default which a trait impl doesn't
+    /// define. These are only missing if the impl meets their where clauses.
+    pub omitted_constants: Vec<Ident>,
+    /// The where clause of the trait impl these functions are the methods of, if any
+    pub where_clause: Vec<TraitConstraint>,
 }
 
 impl UnresolvedFunctions {
@@ -64,6 +76,19 @@ pub struct UnresolvedGlobal {
     pub stmt_def: LetStatement,
 }
 
+/// A trait impl whose trait isn't declared in the module of the impl, kept so that the trait can
+/// be looked up again once the module's imports have been resolved.
+#[derive(Clone)]
+pub struct UnfoundTraitImpl {
+    pub file_id: FileId,
+    pub module_id: LocalModuleId,
+    pub trait_name: Ident,
+    pub impl_generics: UnresolvedGenerics,
+    pub object_type: UnresolvedType,
+    pub object_type_span: Span,
+    pub provenance: Option<Provenance>,
+}
+
 /// Given a Crate root, collect all definitions in that crate
 pub struct DefCollector {
     pub(crate) def_map: CrateDefMap,
@@ -75,6 +100,8 @@ pub struct DefCollector {
     pub(crate) collected_globals: Vec<UnresolvedGlobal>,
     pub(crate) collected_impls: ImplMap,
     pub(crate) collected_traits_impls: ImplMap,
+    pub(crate) collected_associated_types: AssociatedTypeMap,
+    pub(crate) unfound_trait_impls: Vec<UnfoundTraitImpl>,
 }
 
 /// Maps the type and the module id in which the impl is defined to the functions contained in that
@@ -83,6 +110,14 @@ pub struct DefCollector {
 type ImplMap =
     HashMap<(UnresolvedType, LocalModuleId), Vec<(UnresolvedGenerics, Span, UnresolvedFunctions)>>;
 
+/// Maps the trait name, object type and name of each associated type defined by a trait impl
+/// in the crate to the type it is defined as. Object types are keyed on how they're written.
+pub(crate) type AssociatedTypeMap = HashMap<(String, String, String), UnresolvedType>;
+
+/// The most projections followed when normalizing a single type, guarding against
+/// associated types which are defined in terms of each other.
+const MAX_PROJECTION_DEPTH: usize = 32;
+
 impl DefCollector {
     fn new(def_map: CrateDefMap) -> DefCollector {
         DefCollector {
@@ -94,6 +129,8 @@ impl DefCollector {
             collected_traits: HashMap::new(),
             collected_impls: HashMap::new(),
             collected_traits_impls: HashMap::new(),
+            collected_associated_types: HashMap::new(),
+            unfound_trait_impls: vec![],
             collected_globals: vec![],
         }
     }
@@ -195,13 +232,33 @@ impl DefCollector {
 
         file_global_ids.append(&mut more_global_ids);
 
+        check_unfound_trait_impls(context, crate_id, def_collector.unfound_trait_impls, errors);
+
+        // Projections in impl object types can only be normalized once every trait impl in the
+        // crate has been collected
+        let associated_types = &def_collector.collected_associated_types;
+        let collected_impls = normalize_projected_impls(
+            context,
+            crate_id,
+            def_collector.collected_impls,
+            associated_types,
+            errors,
+        );
+        let collected_traits_impls = normalize_projected_impls(
+            context,
+            crate_id,
+            def_collector.collected_traits_impls,
+            associated_types,
+            errors,
+        );
+
         // Before we resolve any function symbols we must go through our impls and
         // re-collect the methods within into their proper module. This cannot be
         // done before resolution since we need to be able to resolve the type of the
         // impl since that determines the module we should collect into.
-        collect_impls(context, crate_id, &def_collector.collected_impls, errors);
+        collect_impls(context, crate_id, &collected_impls, false, errors);
 
-        collect_impls(context, crate_id, &def_collector.collected_traits_impls, errors);
+        collect_impls(context, crate_id, &collected_traits_impls, true, errors);
 
         // Lower each function in the crate. This is now possible since imports have been resolved
         let file_func_ids = resolve_free_functions(
@@ -217,7 +274,7 @@ impl DefCollector {
             &mut context.def_interner,
             crate_id,
             &context.def_maps,
-            def_collector.collected_impls,
+            collected_impls,
             errors,
         );
 
@@ -225,25 +282,82 @@ impl DefCollector {
             &mut context.def_interner,
             crate_id,
             &context.def_maps,
-            def_collector.collected_traits_impls,
+            collected_traits_impls,
             errors,
         );
 
         type_check_globals(&mut context.def_interner, file_global_ids, errors);
 
         // Type check all of the functions in the crate
-        type_check_functions(&mut context.def_interner, file_func_ids, errors);
-        type_check_functions(&mut context.def_interner, file_trait_impls_ids, errors);
-        type_check_functions(&mut context.def_interner, file_method_ids, errors);
+        let def_maps = &context.def_maps;
+        type_check_functions(&mut context.def_interner, def_maps, file_func_ids, errors);
+        type_check_functions(&mut context.def_interner, def_maps, file_trait_impls_ids, errors);
+        type_check_functions(&mut context.def_interner, def_maps, file_method_ids, errors);
+    }
+}
+
+/// Replaces each impl object type which is a projection, such as `<Bar as Baz>::Assoc`, with the
+/// type `Assoc` is defined as in the impl of `Baz` for `Bar`. Impls whose projection can't be
+/// resolved to a concrete type are reported and dropped.
+fn normalize_projected_impls(
+    context: &Context,
+    crate_id: CrateId,
+    impls: ImplMap,
+    associated_types: &AssociatedTypeMap,
+    errors: &mut Vec<FileDiagnostic>,
+) -> ImplMap {
+    let mut normalized = ImplMap::new();
+    for ((typ, module_id), methods) in impls {
+        match normalize_projection(&typ, associated_types, 0) {
+            Ok(typ) => normalized.entry((typ, module_id)).or_default().extend(methods),
+            Err(projection) => {
+                let file = context.def_maps[&crate_id].file_id(module_id);
+                for (_, span, _) in methods {
+                    let projection = projection.clone();
+                    let error = DefCollectorErrorKind::UnresolvedProjection { projection, span };
+                    errors.push(error.into_file_diagnostic(file));
+                }
+            }
+        }
+    }
+    normalized
+}
+
+/// Follows the projection `typ`, if it is one, to the type it names. Returns the projection
+/// which couldn't be followed if no impl in the crate defines it.
+fn normalize_projection(
+    typ: &UnresolvedType,
+    associated_types: &AssociatedTypeMap,
+    depth: usize,
+) -> Result<UnresolvedType, String> {
+    let UnresolvedTypeData::Projection { object_type, trait_name, item_name } = &typ.typ else {
+        return Ok(typ.clone());
+    };
+    if depth == MAX_PROJECTION_DEPTH {
+        return Err(typ.to_string());
+    }
+
+    let object_type = normalize_projection(object_type, associated_types, depth + 1)?;
+    let key =
+        (trait_name.0.contents.clone(), object_type.to_string(), item_name.0.contents.clone());
+    match associated_types.get(&key) {
+        Some(definition) => normalize_projection(definition, associated_types, depth + 1),
+        None => Err(typ.to_string()),
     }
 }
 
 /// Go through the list of impls and add each function within to the scope
 /// of the module defined by its type.
+///
+/// Trait impls may also be defined for tuple types or for any type, as blanket impls such as
+/// `impl<T> Foo for T`, and impls of any kind for primitive types within the stdlib or the root
+/// crate. These types have no module of their own, so their methods are only reachable through
+/// method call syntax.
 fn collect_impls(
     context: &mut Context,
     crate_id: CrateId,
     collected_impls: &ImplMap,
+    is_trait_impl: bool,
     errors: &mut Vec<FileDiagnostic>,
 ) {
     let interner = &mut context.def_interner;
@@ -259,6 +373,8 @@ fn collect_impls(
             let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
             resolver.add_generics(generics);
             let typ = resolver.resolve_type(unresolved_type.clone());
+            // The where clause is resolved again along with the impl's methods
+            resolver.resolve_impl_constraints(&unresolved.where_clause);
 
             extend_errors(errors, unresolved.file_id, resolver.take_errors());
 
@@ -292,8 +408,17 @@ fn collect_impls(
                         errors.push(err.into_file_diagnostic(unresolved.file_id));
                     }
                 }
-            // Prohibit defining impls for primitive types if we're not in the stdlib
-            } else if typ != Type::Error && !crate_id.is_stdlib() {
+            } else if is_trait_impl && matches!(typ, Type::Tuple(_) | Type::NamedGeneric(..)) {
+                // Tuple methods are added to the interner along with other primitive methods
+                // once resolved, and the methods of blanket impls are only found among the
+                // trait impls applying to a type, so there is nothing to declare here.
+            } else if supports_primitive_methods(&typ)
+                && (crate_id.is_stdlib() || crate_id.is_root())
+            {
+                // Methods on primitive types are likewise added to the interner once resolved.
+                // All crates share these methods, so only the stdlib and the crate being compiled
+                // may add them, and any clash between the two is reported as a duplicate.
+            } else if typ != Type::Error {
                 let span = *span;
                 let error = DefCollectorErrorKind::NonStructTypeInImpl { span };
                 errors.push(error.into_file_diagnostic(unresolved.file_id));
@@ -302,6 +427,73 @@ fn collect_impls(
     }
 }
 
+/// Reports an error for each trait impl whose trait couldn't be found when it was collected.
+///
+/// Impls are only collected for traits declared in the module of the impl, so the trait of each
+/// of these impls is either missing or imported. The impl is an orphan if the trait is
+/// from another crate and so is its object type: allowing these would let two crates provide
+/// conflicting impls of the same trait for the same type. Only structs are ever local, so impls
+/// of a foreign trait for primitives, tuples, arrays or the impl's own generics are orphans.
+fn check_unfound_trait_impls(
+    context: &mut Context,
+    crate_id: CrateId,
+    unfound_impls: Vec<UnfoundTraitImpl>,
+    errors: &mut Vec<FileDiagnostic>,
+) {
+    for unfound in unfound_impls {
+        let module = &context.def_maps[&crate_id].modules[unfound.module_id.0];
+        let trait_id = match module.find_name(&unfound.trait_name).types {
+            Some((ModuleDefId::TraitId(trait_id), _)) => Some(trait_id),
+            _ => None,
+        };
+
+        let is_orphan = match trait_id {
+            Some(trait_id) if trait_id.0.krate != crate_id => {
+                !is_local_type(context, crate_id, &unfound)
+            }
+            _ => false,
+        };
+
+        let error = if is_orphan {
+            DefCollectorErrorKind::OrphanImpl {
+                trait_name: unfound.trait_name,
+                type_name: unfound.object_type.to_string(),
+                span: unfound.object_type_span,
+            }
+        } else {
+            DefCollectorErrorKind::TraitNotFound {
+                trait_name: unfound.trait_name.to_string(),
+                span: unfound.trait_name.span(),
+            }
+        };
+        let mut diagnostic = error.into_file_diagnostic(unfound.file_id);
+
+        // Errors in generated impls point back at whatever generated them
+        if let Some(provenance) = &unfound.provenance {
+            diagnostic.diagnostic.add_note(provenance.to_string());
+        }
+        errors.push(diagnostic);
+    }
+}
+
+/// Returns true if the object type of `unfound` is a struct defined in the current crate.
+fn is_local_type(context: &mut Context, crate_id: CrateId, unfound: &UnfoundTraitImpl) -> bool {
+    let path_resolver =
+        StandardPathResolver::new(ModuleId { local_id: unfound.module_id, krate: crate_id });
+    let mut resolver = Resolver::new(
+        &mut context.def_interner,
+        &path_resolver,
+        &context.def_maps,
+        unfound.file_id,
+    );
+    resolver.add_generics(&unfound.impl_generics);
+    let typ = resolver.resolve_type(unfound.object_type.clone());
+
+    // The impl is rejected either way, so errors resolving its type would only add noise
+    let _ = resolver.take_errors();
+    get_struct_type(&typ).map_or(false, |struct_type| struct_type.borrow().id.0.krate == crate_id)
+}
+
 fn get_struct_type(typ: &Type) -> Option<&Shared<StructType>> {
     match typ {
         Type::Struct(definition, _) => Some(definition),
@@ -397,22 +589,74 @@ fn resolve_structs(
 }
 
 fn resolve_trait_types(
-    _context: &mut Context,
-    _crate_id: CrateId,
-    _unresolved_trait: &UnresolvedTrait,
-    _errors: &mut [FileDiagnostic],
+    context: &mut Context,
+    crate_id: CrateId,
+    unresolved_trait: &UnresolvedTrait,
+    errors: &mut Vec<FileDiagnostic>,
 ) -> Vec<TraitItemType> {
+    let interner = &mut context.def_interner;
+    let def_maps = &mut context.def_maps;
+
+    let path_resolver = StandardPathResolver::new(ModuleId {
+        local_id: unresolved_trait.module_id,
+        krate: crate_id,
+    });
+    let file = def_maps[&crate_id].file_id(unresolved_trait.module_id);
+
+    for item in &unresolved_trait.trait_def.items {
+        if let TraitItem::Type { bounds, where_clause, .. } = item {
+            let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
+            for bound in bounds {
+                resolver.check_trait_bound(bound);
+            }
+            resolver.check_trait_constraints(where_clause);
+            let new_errors = take_errors_filter_self_not_resolved(resolver);
+            extend_errors(errors, file, new_errors);
+        }
+    }
+
     // TODO
     vec![]
 }
 fn resolve_trait_constants(
-    _context: &mut Context,
-    _crate_id: CrateId,
-    _unresolved_trait: &UnresolvedTrait,
-    _errors: &mut [FileDiagnostic],
+    context: &mut Context,
+    crate_id: CrateId,
+    unresolved_trait: &UnresolvedTrait,
+    errors: &mut Vec<FileDiagnostic>,
 ) -> Vec<TraitItemType> {
-    // TODO
-    vec![]
+    let interner = &mut context.def_interner;
+    let def_maps = &mut context.def_maps;
+
+    let path_resolver = StandardPathResolver::new(ModuleId {
+        local_id: unresolved_trait.module_id,
+        krate: crate_id,
+    });
+    let file = def_maps[&crate_id].file_id(unresolved_trait.module_id);
+
+    let (default_values, fold_errors) = fold_trait_constants(&unresolved_trait.trait_def);
+    for error in fold_errors {
+        errors.push(error.into_file_diagnostic(file));
+    }
+
+    let mut res = vec![];
+    for item in &unresolved_trait.trait_def.items {
+        if let TraitItem::Constant { name, typ, where_clause, default_value: _ } = item {
+            let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
+            let ty = resolver.resolve_type(typ.clone());
+            resolver.check_trait_constraints(where_clause);
+            let self_constraints = resolver.resolve_self_constraints(where_clause);
+            res.push(TraitItemType::Constant {
+                name: name.clone(),
+                ty,
+                default_value: default_values.get(&name.0.contents).copied(),
+                self_constraints,
+                span: name.span(),
+            });
+            let new_errors = take_errors_filter_self_not_resolved(resolver);
+            extend_errors(errors, file, new_errors);
+        }
+    }
+    res
 }
 
 fn resolve_trait_methods(
@@ -434,35 +678,63 @@ fn resolve_trait_methods(
 
     for item in &unresolved_trait.trait_def.items {
         if let TraitItem::Function {
+            attributes: _,
             name,
-            generics: _,
+            generics,
             parameters,
             return_type,
-            where_clause: _,
+            where_clause,
             body: _,
         } = item
         {
             let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
+            resolver.add_generics(&unresolved_trait.trait_def.generics);
+            let mut method_generics = resolver.add_generics(generics);
+            resolver.resolve_fn_bounds(where_clause, &mut method_generics);
             let arguments = vecmap(parameters, |param| resolver.resolve_type(param.1.clone()));
             let resolved_return_type = match return_type {
                 FunctionReturnType::Default(_) => None,
+                // Each impl picks its own concrete type, so only the bounds can be resolved here
+                FunctionReturnType::Ty(
+                    UnresolvedType { typ: UnresolvedTypeData::Opaque { bounds, .. }, .. },
+                    _span,
+                ) => {
+                    resolver.resolve_opaque_bounds(bounds);
+                    Some(Type::Error)
+                }
                 FunctionReturnType::Ty(unresolved_type, _span) => {
                     Some(resolver.resolve_type(unresolved_type.clone()))
                 }
             };
             let name = name.clone();
-            // TODO
-            let generics: Generics = vec![];
             let span: Span = name.span();
             let f = TraitItemType::Function {
                 name,
-                generics,
+                generics: method_generics,
                 arguments,
                 return_type: resolved_return_type,
                 span,
             };
             res.push(f);
+
+            let declared_generics: HashSet<_> = unresolved_trait
+                .trait_def
+                .generics
+                .iter()
+                .chain(generics)
+                .map(|generic| generic.0.contents.as_str())
+                .collect();
+            let signature_types = parameters.iter().map(|(_, typ)| typ).chain(match return_type {
+                FunctionReturnType::Default(_) => None,
+                FunctionReturnType::Ty(typ, _) => Some(typ),
+            });
+            let undeclared_generics: Vec<_> = signature_types
+                .flat_map(UnresolvedType::referenced_generics)
+                .filter(|name| !declared_generics.contains(name.0.contents.as_str()))
+                .collect();
+
             let new_errors = take_errors_filter_self_not_resolved(resolver);
+            let new_errors = report_undeclared_generics(new_errors, &undeclared_generics);
             extend_errors(errors, file, new_errors);
         }
     }
@@ -483,6 +755,24 @@ fn take_errors_filter_self_not_resolved(resolver: Resolver<'_>) -> Vec<ResolverE
         .collect()
 }
 
+/// Replaces the errors for names in a trait method's signature which failed to resolve with
+/// [`ResolverError::UndeclaredGeneric`] if they were referenced as a generic but weren't declared
+/// by the method or its trait.
+fn report_undeclared_generics(
+    errors: Vec<ResolverError>,
+    undeclared_generics: &[&Ident],
+) -> Vec<ResolverError> {
+    vecmap(errors, |error| match error {
+        ResolverError::PathResolutionError(PathResolutionError::Unresolved(ident))
+            if undeclared_generics.iter().any(|generic| generic.span() == ident.span()) =>
+        {
+            let span = ident.span();
+            ResolverError::UndeclaredGeneric { name: ident.0.contents, span }
+        }
+        error => error,
+    })
+}
+
 /// Create the mappings from TypeId -> TraitType
 /// so that expressions can access the elements of traits
 fn resolve_traits(
@@ -503,12 +793,37 @@ fn resolve_traits(
         items.append(&mut resolve_trait_constants(context, crate_id, &unresolved_trait, errors));
         // 3. Trait Methods
         items.append(&mut resolve_trait_methods(context, crate_id, &unresolved_trait, errors));
+        let self_constraints =
+            resolve_self_constraints(context, crate_id, &unresolved_trait, errors);
         context.def_interner.update_trait(trait_id, |trait_def| {
             trait_def.set_items(items);
+            trait_def.set_self_constraints(self_constraints);
         });
     }
 }
 
+/// Resolves the traits required of `Self` by the where clause of `unresolved_trait`.
+fn resolve_self_constraints(
+    context: &mut Context,
+    crate_id: CrateId,
+    unresolved_trait: &UnresolvedTrait,
+    errors: &mut Vec<FileDiagnostic>,
+) -> Vec<TraitId> {
+    let path_resolver = StandardPathResolver::new(ModuleId {
+        local_id: unresolved_trait.module_id,
+        krate: crate_id,
+    });
+    let file = context.def_maps[&crate_id].file_id(unresolved_trait.module_id);
+    let mut resolver =
+        Resolver::new(&mut context.def_interner, &path_resolver, &context.def_maps, file);
+
+    let where_clause = &unresolved_trait.trait_def.where_clause;
+    resolver.check_trait_constraints(where_clause);
+    let self_constraints = resolver.resolve_self_constraints(where_clause);
+    extend_errors(errors, file, resolver.take_errors());
+    self_constraints
+}
+
 fn resolve_struct_fields(
     context: &mut Context,
     krate: CrateId,
@@ -557,6 +872,10 @@ fn resolve_impls(
     errors: &mut Vec<FileDiagnostic>,
 ) -> Vec<(FileId, FuncId)> {
     let mut file_method_ids = Vec::new();
+    // Trait impls can only be checked against their trait's constraints on `Self`, and their
+    // methods against the bounds of opaque return types, once every impl has been added
+    let mut implemented_traits = Vec::new();
+    let mut trait_methods = Vec::new();
 
     for ((unresolved_type, module_id), methods) in collected_impls {
         let path_resolver =
@@ -564,12 +883,31 @@ fn resolve_impls(
 
         let file = def_maps[&crate_id].file_id(module_id);
 
-        for (generics, _, functions) in methods {
+        for (generics, span, mut functions) in methods {
             let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
             resolver.add_generics(&generics);
             let generics = resolver.get_generics().to_vec();
+            // Errors resolving the impl's type and where clause were reported when collecting it
             let self_type = resolver.resolve_type(unresolved_type.clone());
+            let where_clause = resolver.resolve_impl_constraints(&functions.where_clause);
+
+            let mut impl_index = None;
+            if let Some(trait_id) = functions.trait_id {
+                if self_type != Type::Error {
+                    let trait_impls = interner.trait_implementations_mut();
+                    impl_index = Some(trait_impls.add(trait_id, self_type.clone(), where_clause));
+                    let omitted_constants = std::mem::take(&mut functions.omitted_constants);
+                    implemented_traits.push((
+                        trait_id,
+                        self_type.clone(),
+                        omitted_constants,
+                        span,
+                        file,
+                    ));
+                }
+            }
 
+            let is_trait_impl = functions.trait_id.is_some();
             let mut file_func_ids = resolve_function_set(
                 interner,
                 crate_id,
@@ -579,13 +917,29 @@ fn resolve_impls(
                 generics,
                 errors,
             );
+            if is_trait_impl {
+                trait_methods.extend(file_func_ids.iter().copied());
+            }
+            // Blanket impls have no type to add their methods to
+            let is_blanket_impl = matches!(self_type, Type::NamedGeneric(..));
             if self_type != Type::Error {
                 for (file_id, method_id) in &file_func_ids {
                     let method_name = interner.function_name(method_id).to_owned();
+                    if let Some(impl_index) = impl_index {
+                        interner.trait_implementations_mut().add_method(
+                            impl_index,
+                            method_name.clone(),
+                            *method_id,
+                        );
+                    }
+                    if is_blanket_impl {
+                        continue;
+                    }
 
-                    if let Some(first_fn) =
-                        interner.add_method(&self_type, method_name.clone(), *method_id)
-                    {
+                    let first_fn = interner.add_method(&self_type, method_name.clone(), *method_id);
+                    if let Some(first_fn) = first_fn.filter(|first_fn| {
+                        !is_overlapping_impl_method(interner, first_fn, method_id)
+                    }) {
                         let error = ResolverError::DuplicateDefinition {
                             name: method_name,
                             first_span: interner.function_ident(&first_fn).span(),
@@ -600,9 +954,165 @@ fn resolve_impls(
         }
     }
 
+    for (trait_id, self_type, omitted_constants, span, file) in implemented_traits {
+        check_self_constraints(interner, trait_id, &self_type, span, file, errors);
+        check_omitted_constants(
+            interner,
+            trait_id,
+            &self_type,
+            &omitted_constants,
+            span,
+            file,
+            errors,
+        );
+    }
+    for (file, method_id) in trait_methods {
+        check_opaque_return_type(interner, &method_id, file, errors);
+    }
+
     file_method_ids
 }
 
+/// True if `first_fn` and `second_fn` are methods of impls of the same trait which share a slot
+/// in the map of methods by type but can be told apart, as `impl Foo for u32` can from
+/// `impl Foo for Field`, and `impl<T> Foo for [T; 2]` from `impl Foo for [Field; 2]`. The method
+/// a call refers to is chosen among the impls applying to the object type, so these don't clash,
+/// unlike impls which may apply to the same type without either being more specific.
+fn is_overlapping_impl_method(
+    interner: &NodeInterner,
+    first_fn: &FuncId,
+    second_fn: &FuncId,
+) -> bool {
+    let trait_impls = interner.trait_implementations();
+    match (trait_impls.impl_of_method(first_fn), trait_impls.impl_of_method(second_fn)) {
+        (Some(first_impl), Some(second_impl)) => {
+            let (first_type, second_type) = (&first_impl.object_type, &second_impl.object_type);
+            first_impl.trait_id == second_impl.trait_id
+                && (specificity(first_type) != specificity(second_type)
+                    || !may_match(first_type, second_type))
+        }
+        _ => false,
+    }
+}
+
+/// Checks that the concrete type returned by `method_id`, a method of a trait impl, implements
+/// each trait bounding its return type if that is an opaque type.
+fn check_opaque_return_type(
+    interner: &NodeInterner,
+    method_id: &FuncId,
+    file: FileId,
+    errors: &mut Vec<FileDiagnostic>,
+) {
+    let meta = interner.function_meta(method_id);
+    let Type::Opaque(traits, opaque_type, concrete_type) = meta.return_type() else { return };
+    if is_unknown(concrete_type) {
+        return;
+    }
+
+    let span = match &meta.return_type {
+        FunctionReturnType::Default(span) | FunctionReturnType::Ty(_, span) => *span,
+    };
+    for required_trait in traits {
+        if !check_implements(interner, *required_trait, concrete_type, span, file, errors) {
+            let error = DefCollectorErrorKind::UnsatisfiedOpaqueReturnType {
+                method_name: interner.function_name(method_id).to_owned(),
+                opaque_type: opaque_type.to_string(),
+                required_trait: interner.get_trait(*required_trait).borrow().name.to_string(),
+                typ: concrete_type.to_string(),
+                span,
+            };
+            errors.push(error.into_file_diagnostic(file));
+        }
+    }
+}
+
+/// Checks that `self_type` implements each trait required of `Self` by the trait `trait_id`
+/// which it implements.
+fn check_self_constraints(
+    interner: &NodeInterner,
+    trait_id: TraitId,
+    self_type: &Type,
+    span: Span,
+    file: FileId,
+    errors: &mut Vec<FileDiagnostic>,
+) {
+    let trait_def = interner.get_trait(trait_id);
+    let trait_def = trait_def.borrow();
+    for required_trait in &trait_def.self_constraints {
+        if !check_implements(interner, *required_trait, self_type, span, file, errors) {
+            let error = DefCollectorErrorKind::UnsatisfiedSelfConstraint {
+                trait_name: trait_def.name.to_string(),
+                required_trait: interner.get_trait(*required_trait).borrow().name.to_string(),
+                typ: self_type.to_string(),
+                span,
+            };
+            errors.push(error.into_file_diagnostic(file));
+        }
+    }
+}
+
+/// Reports each of `omitted_constants`, the constants without a default which an impl of
+/// `trait_id` for `self_type` doesn't define, unless `self_type` doesn't implement one of the
+/// traits the constant's where clause requires of `Self`.
+fn check_omitted_constants(
+    interner: &NodeInterner,
+    trait_id: TraitId,
+    self_type: &Type,
+    omitted_constants: &[Ident],
+    span: Span,
+    file: FileId,
+    errors: &mut Vec<FileDiagnostic>,
+) {
+    let trait_def = interner.get_trait(trait_id);
+    let trait_def = trait_def.borrow();
+    for constant_name in omitted_constants {
+        let self_constraints = trait_def.items.iter().find_map(|item| match item {
+            TraitItemType::Constant { name, self_constraints, .. } if name == constant_name => {
+                Some(self_constraints)
+            }
+            _ => None,
+        });
+        let Some(self_constraints) = self_constraints else { continue };
+
+        let is_required = self_constraints.iter().all(|required_trait| {
+            check_implements(interner, *required_trait, self_type, span, file, errors)
+        });
+        if is_required {
+            let error = DefCollectorErrorKind::TraitMissedConstantImplementation {
+                trait_name: trait_def.name.clone(),
+                constant_name: constant_name.clone(),
+                trait_impl_span: span,
+            };
+            errors.push(error.into_file_diagnostic(file));
+        }
+    }
+}
+
+/// Returns whether `typ` implements `trait_id`. A check which recurses too deeply through the
+/// where clauses of impls is reported at `span`, and the trait is then taken to be implemented
+/// so that it isn't also reported as missing.
+fn check_implements(
+    interner: &NodeInterner,
+    trait_id: TraitId,
+    typ: &Type,
+    span: Span,
+    file: FileId,
+    errors: &mut Vec<FileDiagnostic>,
+) -> bool {
+    match interner.trait_implementations().implements(trait_id, typ) {
+        Ok(implemented) => implemented,
+        Err(overflow) => {
+            let error = TypeCheckError::TraitResolutionOverflow {
+                limit: overflow.limit,
+                chain: overflow.describe_chain(interner),
+                span,
+            };
+            extend_errors(errors, file, [error]);
+            true
+        }
+    }
+}
+
 fn resolve_free_functions(
     interner: &mut NodeInterner,
     crate_id: CrateId,
@@ -638,6 +1148,14 @@ fn resolve_function_set(
     errors: &mut Vec<FileDiagnostic>,
 ) -> Vec<(FileId, FuncId)> {
     let file_id = unresolved_functions.file_id;
+    // The methods of trait impls are as visible as the trait itself, while the methods of
+    // inherent impls are private to their module unless marked `pub`
+    let is_inherent_impl = self_type.is_some() && unresolved_functions.trait_id.is_none();
+    let self_methods = if self_type.is_some() {
+        vecmap(&unresolved_functions.functions, |(_, _, func)| func.name().to_owned())
+    } else {
+        Vec::new()
+    };
 
     vecmap(unresolved_functions.functions, |(mod_id, func_id, func)| {
         let module_id = ModuleId { krate: crate_id, local_id: mod_id };
@@ -650,8 +1168,15 @@ fn resolve_function_set(
         // TypeVariables for the same generic, causing it to instantiate incorrectly.
         resolver.set_generics(impl_generics.clone());
         resolver.set_self_type(self_type.clone());
+        resolver.set_self_methods(self_methods.clone());
 
-        let (hir_func, func_meta, errs) = resolver.resolve_function(func, func_id, module_id);
+        let is_pub = func.def.is_pub;
+        if is_pub && !is_inherent_impl {
+            let error = ResolverError::PubOutsideInherentImpl { ident: func.name_ident().clone() };
+            errors.push(error.into_file_diagnostic(file_id));
+        }
+        let (hir_func, mut func_meta, errs) = resolver.resolve_function(func, func_id, module_id);
+        func_meta.is_private = is_inherent_impl && !is_pub;
         interner.push_fn_meta(func_meta, func_id);
         interner.update_fn(func_id, hir_func);
         extend_errors(errors, file_id, errs);
@@ -661,10 +1186,11 @@ fn resolve_function_set(
 
 fn type_check_functions(
     interner: &mut NodeInterner,
+    def_maps: &HashMap<CrateId, CrateDefMap>,
     file_func_ids: Vec<(FileId, FuncId)>,
     errors: &mut Vec<FileDiagnostic>,
 ) {
     for (file, func) in file_func_ids {
-        extend_errors(errors, file, type_check_func(interner, func));
+        extend_errors(errors, file, type_check_func(interner, def_maps, func));
     }
 }
@@ -1,18 +1,27 @@
+use std::collections::{HashMap, HashSet};
+
 use fm::FileId;
-use noirc_errors::{FileDiagnostic, Location};
+use iter_extended::vecmap;
+use noirc_errors::{FileDiagnostic, Location, Span};
 
 use crate::{
+    ast::visitor::{walk_expression, walk_statement, Visitor},
     graph::CrateId,
     hir::def_collector::dc_crate::{UnresolvedStruct, UnresolvedTrait},
     node_interner::{StructId, TraitId},
     parser::SubModule,
-    FunctionDefinition, FunctionReturnType, Ident, LetStatement, NoirFunction, NoirStruct,
-    NoirTrait, NoirTypeAlias, ParsedModule, TraitImpl, TraitImplItem, TraitItem, TypeImpl,
-    UnresolvedType,
+    token::{Attribute, InlineHint},
+    BinaryOpKind, Expression, ExpressionKind, FunctionDefinition, FunctionReturnType, Ident,
+    LValue, LetStatement, NoirFunction, NoirStruct, NoirTrait, NoirTypeAlias, ParsedModule, Path,
+    PathKind, Statement, TraitBound, TraitConstraint, TraitImpl, TraitImplItem, TraitItem,
+    TypeImpl, UnresolvedType, UnresolvedTypeData,
 };
 
 use super::{
-    dc_crate::{DefCollector, UnresolvedFunctions, UnresolvedGlobal, UnresolvedTypeAlias},
+    const_fold::{fold_impl_constants, inline_constants},
+    dc_crate::{
+        DefCollector, UnfoundTraitImpl, UnresolvedFunctions, UnresolvedGlobal, UnresolvedTypeAlias,
+    },
     errors::{DefCollectorErrorKind, DuplicateType},
 };
 use crate::hir::def_map::{parse_file, LocalModuleId, ModuleData, ModuleDefId, ModuleId};
@@ -31,13 +40,24 @@ struct ModCollector<'a> {
 /// This performs the entirety of the definition collection phase of the name resolution pass.
 pub fn collect_defs(
     def_collector: &mut DefCollector,
-    ast: ParsedModule,
+    mut ast: ParsedModule,
     file_id: FileId,
     module_id: LocalModuleId,
     crate_id: CrateId,
     context: &mut Context,
     errors: &mut Vec<FileDiagnostic>,
 ) {
+    // Drop any impls disabled by a `#[cfg(...)]` attribute under the active features
+    let features = &context.cfg_features;
+    let is_enabled = |attributes: &[Attribute]| {
+        attributes.iter().all(|attribute| attribute.is_cfg_enabled(features))
+    };
+    ast.impls.retain(|r#impl| is_enabled(&r#impl.attributes));
+    ast.trait_impls.retain(|trait_impl| is_enabled(&trait_impl.attributes));
+
+    // Generate the impls requested by `#[derive(...)]` so they're collected with the rest
+    context.derive_registry.expand(&mut ast, file_id, errors);
+
     let mut collector = ModCollector { def_collector, file_id, module_id };
 
     // First resolve the module declarations
@@ -68,7 +88,13 @@ pub fn collect_defs(
 
     collector.collect_trait_impls(context, ast.trait_impls, errors);
 
-    collector.collect_impls(context, ast.impls);
+    collector.collect_impls(context, ast.impls, errors);
+}
+
+/// Compares two types by how they're written, as the spans nested within tuples and arrays
+/// would otherwise never match between a trait and its impls.
+fn same_type(a: &UnresolvedTypeData, b: &UnresolvedTypeData) -> bool {
+    a.to_string() == b.to_string()
 }
 
 fn check_trait_method_implementation_parameters(
@@ -88,7 +114,7 @@ fn check_trait_method_implementation_parameters(
     }
     for (count, (parameter, typ, _abi_vis)) in impl_method.def.parameters.iter().enumerate() {
         let (_expected_name, expected_type) = &expected_parameters[count];
-        if typ.typ != expected_type.typ {
+        if !same_type(&typ.typ, &expected_type.typ) {
             return Err(DefCollectorErrorKind::MismatchTraitImlementationParameter {
                 trait_name: trait_name.to_owned(),
                 expected_type: expected_type.clone(),
@@ -105,7 +131,7 @@ fn check_trait_method_implementation_return_type(
     impl_method: &NoirFunction,
     trait_name: &str,
 ) -> Result<(), DefCollectorErrorKind> {
-    if expected_return_type.get_type() == impl_method.def.return_type.get_type() {
+    if same_type(expected_return_type.get_type(), impl_method.def.return_type.get_type()) {
         Ok(())
     } else {
         Err(DefCollectorErrorKind::MismatchTraitImplementationReturnType {
@@ -115,12 +141,31 @@ fn check_trait_method_implementation_return_type(
     }
 }
 
+/// Checks `impl_method` against the declaration of the same method in `r#trait`, returning
+/// the method with each `Self::Name` projection in its signature replaced by the impl's
+/// definition of the associated type `Name`. Projections in the trait's declaration are
+/// replaced in the same way before the two signatures are compared.
 fn check_trait_method_implementation(
     r#trait: &NoirTrait,
     impl_method: &NoirFunction,
-) -> Result<(), DefCollectorErrorKind> {
+    associated_types: &HashMap<&str, &UnresolvedType>,
+) -> Result<NoirFunction, DefCollectorErrorKind> {
+    let missing_associated_type = |item_name| DefCollectorErrorKind::MissingAssociatedType {
+        trait_name: r#trait.name.clone(),
+        item_name,
+        impl_method: impl_method.name_ident().clone(),
+    };
+    let project = |typ: &UnresolvedType| {
+        project_associated_types(typ, associated_types).map_err(missing_associated_type)
+    };
+    let project_return_type = |return_type: &FunctionReturnType| match return_type {
+        FunctionReturnType::Default(span) => Ok(FunctionReturnType::Default(*span)),
+        FunctionReturnType::Ty(typ, span) => Ok(FunctionReturnType::Ty(project(typ)?, *span)),
+    };
+
     for item in &r#trait.items {
         if let TraitItem::Function {
+            attributes: _,
             name,
             generics: _,
             parameters,
@@ -130,18 +175,39 @@ fn check_trait_method_implementation(
         } = item
         {
             if name.0.contents == impl_method.def.name.0.contents {
+                let parameters = parameters
+                    .iter()
+                    .map(|(name, typ)| Ok((name.clone(), project(typ)?)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let return_type = project_return_type(return_type)?;
+
+                let mut impl_method = impl_method.clone();
+                for (_, typ, _) in &mut impl_method.def.parameters {
+                    *typ = project(&*typ)?;
+                }
+                impl_method.def.return_type = project_return_type(&impl_method.def.return_type)?;
+
                 // name matches, check for parameters - count and type, return type
                 check_trait_method_implementation_parameters(
-                    parameters,
-                    impl_method,
-                    &r#trait.name.0.contents,
-                )?;
-                check_trait_method_implementation_return_type(
-                    return_type,
-                    impl_method,
+                    &parameters,
+                    &impl_method,
                     &r#trait.name.0.contents,
                 )?;
-                return Ok(());
+                match &return_type {
+                    FunctionReturnType::Ty(
+                        UnresolvedType {
+                            typ: UnresolvedTypeData::Opaque { bounds, concrete_type: None },
+                            ..
+                        },
+                        _,
+                    ) => hide_concrete_return_type(&mut impl_method, bounds),
+                    _ => check_trait_method_implementation_return_type(
+                        &return_type,
+                        &impl_method,
+                        &r#trait.name.0.contents,
+                    )?,
+                }
+                return Ok(impl_method);
             }
         }
     }
@@ -152,7 +218,857 @@ fn check_trait_method_implementation(
     })
 }
 
+/// Replaces the return type of `impl_method`, which implements a trait method returning an
+/// opaque type with `bounds`, with that opaque type so that callers only see the methods of the
+/// traits bounding it. The impl's own return type becomes the opaque type's concrete type,
+/// which is checked against the bounds once every impl has been resolved.
+fn hide_concrete_return_type(impl_method: &mut NoirFunction, bounds: &[TraitBound]) {
+    let (concrete_type, span) = match &impl_method.def.return_type {
+        FunctionReturnType::Default(span) => {
+            (UnresolvedType::without_span(UnresolvedTypeData::Unit), *span)
+        }
+        FunctionReturnType::Ty(typ, span) => (typ.clone(), *span),
+    };
+    let opaque_type = UnresolvedTypeData::Opaque {
+        bounds: bounds.to_vec(),
+        concrete_type: Some(Box::new(concrete_type)),
+    };
+    impl_method.def.return_type = FunctionReturnType::Ty(opaque_type.with_span(span), span);
+}
+
+/// Replaces each `Self::Name` projection within `typ` with its definition in
+/// `associated_types`, returning the name of the first associated type without one.
+///
+/// Projections within a definition are replaced as well. Those forming a cycle are replaced
+/// with an error type as the cycle is reported separately by [`check_impl_type_cycles`].
+fn project_associated_types(
+    typ: &UnresolvedType,
+    associated_types: &HashMap<&str, &UnresolvedType>,
+) -> Result<UnresolvedType, String> {
+    fn project(
+        typ: &UnresolvedType,
+        associated_types: &HashMap<&str, &UnresolvedType>,
+        expanding: &mut Vec<String>,
+    ) -> Result<UnresolvedType, String> {
+        let project_all = |types: &[UnresolvedType], expanding: &mut Vec<String>| {
+            types
+                .iter()
+                .map(|typ| project(typ, associated_types, expanding))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let projected = match &typ.typ {
+            UnresolvedTypeData::Named(path, generics)
+                if generics.is_empty()
+                    && path.segments.len() == 2
+                    && path.segments[0].0.contents == "Self" =>
+            {
+                let item_name = &path.segments[1].0.contents;
+                let definition =
+                    associated_types.get(item_name.as_str()).ok_or_else(|| item_name.clone())?;
+                if expanding.contains(item_name) {
+                    UnresolvedTypeData::Error
+                } else {
+                    expanding.push(item_name.clone());
+                    let definition = project(definition, associated_types, expanding)?;
+                    expanding.pop();
+                    definition.typ
+                }
+            }
+            UnresolvedTypeData::Named(path, generics) => {
+                UnresolvedTypeData::Named(path.clone(), project_all(generics, expanding)?)
+            }
+            UnresolvedTypeData::Tuple(elements) => {
+                UnresolvedTypeData::Tuple(project_all(elements, expanding)?)
+            }
+            UnresolvedTypeData::Function(arguments, ret, env) => {
+                let arguments = project_all(arguments, expanding)?;
+                let ret = project(ret, associated_types, expanding)?;
+                let env = project(env, associated_types, expanding)?;
+                UnresolvedTypeData::Function(arguments, Box::new(ret), Box::new(env))
+            }
+            UnresolvedTypeData::Array(length, element) => {
+                let element = project(element, associated_types, expanding)?;
+                UnresolvedTypeData::Array(length.clone(), Box::new(element))
+            }
+            UnresolvedTypeData::FormatString(length, element) => {
+                let element = project(element, associated_types, expanding)?;
+                UnresolvedTypeData::FormatString(length.clone(), Box::new(element))
+            }
+            UnresolvedTypeData::MutableReference(element) => {
+                let element = project(element, associated_types, expanding)?;
+                UnresolvedTypeData::MutableReference(Box::new(element))
+            }
+            other => other.clone(),
+        };
+        Ok(UnresolvedType { typ: projected, span: typ.span })
+    }
+
+    project(typ, associated_types, &mut Vec::new())
+}
+
+/// Returns true if `a` and `b` bound the same type by the same trait with the same generics,
+/// whether or not either bound is negative. Spans are ignored when comparing constraints.
+fn same_bounded_trait(a: &TraitConstraint, b: &TraitConstraint) -> bool {
+    a.typ.typ == b.typ.typ
+        && a.trait_bound.trait_name == b.trait_bound.trait_name
+        && a.trait_bound.trait_generics.len() == b.trait_bound.trait_generics.len()
+        && a.trait_bound
+            .trait_generics
+            .iter()
+            .zip(&b.trait_bound.trait_generics)
+            .all(|(a, b)| a.typ == b.typ)
+}
+
+/// Returns each constraint in `where_clause` which repeats a constraint listed before it,
+/// such as the second bound in `where T: Eq, T: Eq`.
+///
+/// Bounds implied through a supertrait of another listed bound are not detected here.
+fn redundant_trait_constraints(where_clause: &[TraitConstraint]) -> Vec<&TraitConstraint> {
+    where_clause
+        .iter()
+        .enumerate()
+        .filter(|(index, constraint)| {
+            where_clause[..*index].iter().any(|previous| {
+                same_bounded_trait(previous, constraint)
+                    && previous.trait_bound.negative == constraint.trait_bound.negative
+            })
+        })
+        .map(|(_, constraint)| constraint)
+        .collect()
+}
+
+/// Returns each pair of constraints in `where_clause` which no type can satisfy together, such
+/// as `where T: Eq, T: !Eq`, with the constraint listed first first.
+///
+/// Only a positive and a negative bound of the same trait on the same type are detected here.
+fn contradictory_trait_constraints(
+    where_clause: &[TraitConstraint],
+) -> Vec<(&TraitConstraint, &TraitConstraint)> {
+    let mut contradictions = Vec::new();
+    for (index, constraint) in where_clause.iter().enumerate() {
+        for previous in &where_clause[..index] {
+            if same_bounded_trait(previous, constraint)
+                && previous.trait_bound.negative != constraint.trait_bound.negative
+            {
+                contradictions.push((previous, constraint));
+            }
+        }
+    }
+    contradictions
+}
+
+/// Returns the span of `constraint` from its type to its trait's name.
+fn constraint_span(constraint: &TraitConstraint) -> Span {
+    match constraint.typ.span {
+        Some(span) => span.merge(constraint.trait_bound.trait_name.span()),
+        None => constraint.trait_bound.trait_name.span(),
+    }
+}
+
+/// Collects the item name of every `Self::name` path visited.
+#[derive(Default)]
+struct SelfItemReferences(Vec<Ident>);
+
+impl Visitor for SelfItemReferences {
+    fn visit_path(&mut self, path: &Path) {
+        if let [self_type, item_name] = &path.segments[..] {
+            if self_type.0.contents == "Self" {
+                self.0.push(item_name.clone());
+            }
+        }
+    }
+}
+
+/// Returns an error for each `Self::name` path within the default method bodies of
+/// `trait_def` which does not name one of the trait's own items.
+fn check_trait_default_bodies(trait_def: &NoirTrait) -> Vec<DefCollectorErrorKind> {
+    let item_names = vecmap(&trait_def.items, |item| match item {
+        TraitItem::Function { name, .. }
+        | TraitItem::Constant { name, .. }
+        | TraitItem::Type { name, .. } => name,
+    });
+
+    let mut references = SelfItemReferences::default();
+    for item in &trait_def.items {
+        if let TraitItem::Function { body: Some(body), .. } = item {
+            references.visit_block(body);
+        }
+    }
+
+    references
+        .0
+        .into_iter()
+        .filter(|item_name| !item_names.contains(&item_name))
+        .map(|item_name| DefCollectorErrorKind::NoSuchAssociatedItem {
+            trait_name: trait_def.name.clone(),
+            item_name,
+        })
+        .collect()
+}
+
+/// Records whether `self` is referenced anywhere within the nodes visited.
+#[derive(Default)]
+struct UsesSelf(bool);
+
+impl Visitor for UsesSelf {
+    fn visit_statement(&mut self, statement: &Statement) {
+        if let Statement::Assign(assign) = statement {
+            let mut lvalue = &assign.lvalue;
+            let root = loop {
+                match lvalue {
+                    LValue::Ident(ident) => break ident,
+                    LValue::MemberAccess { object: inner, .. }
+                    | LValue::Index { array: inner, .. }
+                    | LValue::Dereference(inner) => lvalue = inner,
+                }
+            };
+            self.0 |= root.0.contents == "self";
+        }
+        walk_statement(self, statement);
+    }
+
+    fn visit_path(&mut self, path: &Path) {
+        if let [name] = &path.segments[..] {
+            self.0 |= name.0.contents == "self";
+        }
+    }
+}
+
+/// Returns a warning for each default method of `trait_def` which declares a `self` receiver
+/// but never uses it. This can be silenced with `#[allow(unused_self)]` on the method.
+fn check_unused_self(trait_def: &NoirTrait) -> Vec<DefCollectorErrorKind> {
+    let mut warnings = Vec::new();
+    for item in &trait_def.items {
+        let TraitItem::Function { attributes, name, parameters, body: Some(body), .. } = item
+        else {
+            continue;
+        };
+        let Some((receiver, _)) = parameters.first().filter(|(name, _)| name.0.contents == "self")
+        else {
+            continue;
+        };
+        let allowed = attributes.iter().any(|attribute| {
+            matches!(attribute, Attribute::Custom(attribute) if attribute == "allow(unused_self)")
+        });
+        if allowed {
+            continue;
+        }
+
+        let mut uses_self = UsesSelf::default();
+        uses_self.visit_block(body);
+        if !uses_self.0 {
+            warnings.push(DefCollectorErrorKind::UnusedSelf {
+                method_name: name.clone(),
+                receiver_span: receiver.span(),
+            });
+        }
+    }
+    warnings
+}
+
+/// Returns a warning for each default method of `trait_def` with an `#[inline(...)]` hint which
+/// is overruled by how it is compiled. Default methods are constrained, and constrained code is
+/// always inlined into its caller, so `#[inline(never)]` has no effect on them.
+fn check_inline_hints(trait_def: &NoirTrait) -> Vec<DefCollectorErrorKind> {
+    let mut warnings = Vec::new();
+    for item in &trait_def.items {
+        let TraitItem::Function { attributes, name, body: Some(_), .. } = item else {
+            continue;
+        };
+        for hint in attributes.iter().filter_map(Attribute::inline_hint) {
+            if hint == InlineHint::Never {
+                warnings.push(DefCollectorErrorKind::IgnoredInlineHint {
+                    method_name: name.clone(),
+                    hint,
+                    reason: "constrained functions are always inlined into their callers",
+                });
+            }
+        }
+    }
+    warnings
+}
+
+/// Returns a warning for each trait bound a default method of `trait_def` needs but doesn't
+/// declare, such as `T: Ord` for a body calling `x.cmp(y)` on a parameter `x: T`. Without the
+/// bound, the error would only be reported for each impl using the default body.
+///
+/// Bodies are walked syntactically, so only calls to methods of parameters whose type is one of
+/// the trait's or method's generics, and calls of the form `T::method(..)`, are considered. A
+/// call requires the bound on the trait declaring the method, provided it is the only trait in
+/// `traits` which does. Bounds declared by the method's or trait's where clause satisfy the
+/// call, as do bounds on any of their supertraits.
+fn check_missing_where_bounds(
+    trait_def: &NoirTrait,
+    traits: &[NoirTrait],
+) -> Vec<DefCollectorErrorKind> {
+    let declaring_traits = declaring_traits(traits);
+    let supertraits = supertrait_names(traits);
+
+    let mut warnings = Vec::new();
+    for item in &trait_def.items {
+        let TraitItem::Function {
+            name, generics, parameters, where_clause, body: Some(body), ..
+        } = item
+        else {
+            continue;
+        };
+
+        let all_generics: Vec<&Ident> = trait_def.generics.iter().chain(generics).collect();
+
+        let mut declared = HashSet::new();
+        for constraint in trait_def.where_clause.iter().chain(where_clause) {
+            let Some(generic) = generic_type_name(&constraint.typ, &all_generics) else { continue };
+            let bound = constraint.trait_bound.trait_name.0.contents.as_str();
+            for trait_name in implied_traits(bound, &supertraits) {
+                declared.insert((generic.as_str(), trait_name));
+            }
+        }
+
+        let mut calls = GenericMethodCalls {
+            generics: all_generics.clone(),
+            parameters: parameters
+                .iter()
+                .filter_map(|(parameter, typ)| {
+                    Some((parameter.0.contents.as_str(), generic_type_name(typ, &all_generics)?))
+                })
+                .collect(),
+            calls: Vec::new(),
+        };
+        calls.visit_block(body);
+
+        let mut reported = HashSet::new();
+        for (generic, called_method, span) in calls.calls {
+            let Some([trait_name]) =
+                declaring_traits.get(called_method.as_str()).map(Vec::as_slice)
+            else {
+                continue;
+            };
+            if !declared.contains(&(generic.as_str(), *trait_name))
+                && reported.insert((generic.clone(), *trait_name))
+            {
+                warnings.push(DefCollectorErrorKind::MissingWhereBound {
+                    method_name: name.clone(),
+                    bound: format!("{generic}: {trait_name}"),
+                    span,
+                });
+            }
+        }
+    }
+    warnings
+}
+
+/// Maps the name of each method declared by one of `traits` to the names of the traits which
+/// declare it.
+fn declaring_traits(traits: &[NoirTrait]) -> HashMap<&str, Vec<&str>> {
+    let mut declaring_traits: HashMap<&str, Vec<&str>> = HashMap::new();
+    for noir_trait in traits {
+        for item in &noir_trait.items {
+            if let TraitItem::Function { name, .. } = item {
+                let trait_name = noir_trait.name.0.contents.as_str();
+                declaring_traits.entry(&name.0.contents).or_default().push(trait_name);
+            }
+        }
+    }
+    declaring_traits
+}
+
+/// Maps the name of each of `traits` to the names of its direct supertraits.
+fn supertrait_names(traits: &[NoirTrait]) -> HashMap<&str, Vec<&str>> {
+    traits
+        .iter()
+        .map(|noir_trait| {
+            let supertraits =
+                vecmap(&noir_trait.supertraits, |bound| bound.trait_name.0.contents.as_str());
+            (noir_trait.name.0.contents.as_str(), supertraits)
+        })
+        .collect()
+}
+
+/// Returns `bound` along with every trait it implies through its supertraits, transitively.
+fn implied_traits<'a>(
+    bound: &'a str,
+    supertraits: &HashMap<&str, Vec<&'a str>>,
+) -> HashSet<&'a str> {
+    let mut implied = HashSet::new();
+    let mut stack = vec![bound];
+    while let Some(trait_name) = stack.pop() {
+        if implied.insert(trait_name) {
+            stack.extend(supertraits.get(trait_name).into_iter().flatten().copied());
+        }
+    }
+    implied
+}
+
+/// Returns a warning for each bound an associated type of `trait_def` needs but doesn't
+/// declare, such as `Eq` for `type Item;` when a default method compares a parameter
+/// `x: Self::Item` with `==`. Without the bound, the error would only be reported for each impl
+/// using the default body.
+///
+/// Bodies are walked syntactically, as in [`check_missing_where_bounds`]. Only parameters whose
+/// type is `Self::Name` for one of the trait's non-generic associated types are considered.
+/// Comparing such a parameter with `==` or `!=` requires `Eq`, and with `<`, `<=`, `>` or `>=`
+/// requires `Ord`. Calling a method on it requires the trait declaring the method, provided it
+/// is the only trait in `traits` which does. Bounds on the associated type, and constraints on
+/// `Self::Name` in the trait's or method's where clause, satisfy the requirement, as do any of
+/// their supertraits.
+fn check_associated_type_bounds(
+    trait_def: &NoirTrait,
+    traits: &[NoirTrait],
+) -> Vec<DefCollectorErrorKind> {
+    let associated_types: Vec<&Ident> = trait_def
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Type { name, generics, .. } if generics.is_empty() => Some(name),
+            _ => None,
+        })
+        .collect();
+    if associated_types.is_empty() {
+        return Vec::new();
+    }
+
+    let declaring_traits = declaring_traits(traits);
+    let supertraits = supertrait_names(traits);
+
+    let mut trait_bounds = self_item_constraints(&trait_def.where_clause, &associated_types);
+    for item in &trait_def.items {
+        if let TraitItem::Type { name, bounds, .. } = item {
+            trait_bounds.extend(bounds.iter().map(|bound| (name, bound)));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let mut reported = HashSet::new();
+    for item in &trait_def.items {
+        let TraitItem::Function { parameters, where_clause, body: Some(body), .. } = item else {
+            continue;
+        };
+
+        let method_bounds = self_item_constraints(where_clause, &associated_types);
+        let mut declared = HashSet::new();
+        for (item_name, bound) in trait_bounds.iter().chain(&method_bounds) {
+            for trait_name in implied_traits(&bound.trait_name.0.contents, &supertraits) {
+                declared.insert((item_name.0.contents.as_str(), trait_name));
+            }
+        }
+
+        let mut uses = AssociatedTypeUses {
+            parameters: parameters
+                .iter()
+                .filter_map(|(parameter, typ)| {
+                    Some((parameter.0.contents.as_str(), self_item_name(typ, &associated_types)?))
+                })
+                .collect(),
+            declaring_traits: &declaring_traits,
+            uses: Vec::new(),
+        };
+        uses.visit_block(body);
+
+        for (item_name, trait_name, span) in uses.uses {
+            let key = (item_name.0.contents.as_str(), trait_name);
+            if !declared.contains(&key) && reported.insert(key) {
+                warnings.push(DefCollectorErrorKind::MissingAssociatedTypeBound {
+                    trait_name: trait_def.name.clone(),
+                    item_name: item_name.clone(),
+                    bound: trait_name.to_string(),
+                    span,
+                });
+            }
+        }
+    }
+    warnings
+}
+
+/// If `typ` is `Self::Name` for one of `associated_types`, returns that associated type.
+fn self_item_name<'a>(typ: &UnresolvedType, associated_types: &[&'a Ident]) -> Option<&'a Ident> {
+    let UnresolvedTypeData::Named(path, args) = &typ.typ else { return None };
+    let [self_type, item_name] = path.segments.as_slice() else { return None };
+    if path.kind != PathKind::Plain || !args.is_empty() || self_type.0.contents != "Self" {
+        return None;
+    }
+    associated_types.iter().find(|name| name.0.contents == item_name.0.contents).copied()
+}
+
+/// Returns the constraints of `where_clause` on `Self::Name` for one of `associated_types`, as
+/// the associated type and its bound.
+fn self_item_constraints<'a>(
+    where_clause: &'a [TraitConstraint],
+    associated_types: &[&'a Ident],
+) -> Vec<(&'a Ident, &'a TraitBound)> {
+    where_clause
+        .iter()
+        .filter_map(|constraint| {
+            let item_name = self_item_name(&constraint.typ, associated_types)?;
+            Some((item_name, &constraint.trait_bound))
+        })
+        .collect()
+}
+
+/// Collects the traits required of associated types by how parameters of those types are used
+/// within the nodes visited, as the associated type, the trait and the span of the use.
+struct AssociatedTypeUses<'a> {
+    /// The associated type of each parameter whose type is `Self::Name`, keyed on the
+    /// parameter's name
+    parameters: HashMap<&'a str, &'a Ident>,
+    declaring_traits: &'a HashMap<&'a str, Vec<&'a str>>,
+    uses: Vec<(&'a Ident, &'a str, Span)>,
+}
+
+impl<'a> AssociatedTypeUses<'a> {
+    fn associated_type(&self, expression: &Expression) -> Option<&'a Ident> {
+        let ExpressionKind::Variable(path) = &expression.kind else { return None };
+        self.parameters.get(path.as_ident()?.0.contents.as_str()).copied()
+    }
+}
+
+impl<'a> Visitor for AssociatedTypeUses<'a> {
+    fn visit_expression(&mut self, expression: &Expression) {
+        match &expression.kind {
+            ExpressionKind::Infix(infix) => {
+                let trait_name = match infix.operator.contents {
+                    BinaryOpKind::Equal | BinaryOpKind::NotEqual => Some("Eq"),
+                    BinaryOpKind::Less
+                    | BinaryOpKind::LessEqual
+                    | BinaryOpKind::Greater
+                    | BinaryOpKind::GreaterEqual => Some("Ord"),
+                    _ => None,
+                };
+                let item_name =
+                    self.associated_type(&infix.lhs).or_else(|| self.associated_type(&infix.rhs));
+                if let (Some(trait_name), Some(item_name)) = (trait_name, item_name) {
+                    self.uses.push((item_name, trait_name, expression.span));
+                }
+            }
+            ExpressionKind::MethodCall(call) => {
+                let declaring_traits = self.declaring_traits;
+                let method_name = call.method_name.0.contents.as_str();
+                if let (Some(item_name), Some([trait_name])) = (
+                    self.associated_type(&call.object),
+                    declaring_traits.get(method_name).map(Vec::as_slice),
+                ) {
+                    self.uses.push((item_name, *trait_name, expression.span));
+                }
+            }
+            _ => (),
+        }
+        walk_expression(self, expression);
+    }
+}
+
+/// Returns a warning for each method of `trait_def` sharing its name with a method of one of
+/// its supertraits, directly or through their own supertraits, when the two signatures differ.
+/// A method with the same signature is taken to be an intended override.
+///
+/// Only supertraits among `traits` are walked. Signatures are compared as written, so
+/// parameter types naming a supertrait's generics are not substituted first.
+fn check_shadowed_supertrait_methods(
+    trait_def: &NoirTrait,
+    traits: &[NoirTrait],
+) -> Vec<DefCollectorErrorKind> {
+    let find_trait =
+        |name: &str| traits.iter().find(|noir_trait| noir_trait.name.0.contents == name);
+
+    let mut closure = Vec::new();
+    let mut visited = HashSet::from([trait_def.name.0.contents.as_str()]);
+    let mut stack = vec![trait_def];
+    while let Some(noir_trait) = stack.pop() {
+        for bound in &noir_trait.supertraits {
+            let name = bound.trait_name.0.contents.as_str();
+            if let Some(supertrait) = find_trait(name).filter(|_| visited.insert(name)) {
+                closure.push(supertrait);
+                stack.push(supertrait);
+            }
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for item in &trait_def.items {
+        let TraitItem::Function { name, parameters, return_type, .. } = item else { continue };
+        let signature = method_signature(parameters, return_type);
+        for supertrait in &closure {
+            for supertrait_item in &supertrait.items {
+                let TraitItem::Function {
+                    name: supertrait_method, parameters, return_type, ..
+                } = supertrait_item
+                else {
+                    continue;
+                };
+                if supertrait_method != name {
+                    continue;
+                }
+                let supertrait_signature = method_signature(parameters, return_type);
+                if supertrait_signature != signature {
+                    warnings.push(DefCollectorErrorKind::ShadowedSupertraitMethod {
+                        method_name: name.clone(),
+                        signature: signature.clone(),
+                        supertrait_name: supertrait.name.0.contents.clone(),
+                        supertrait_signature,
+                        supertrait_span: supertrait_method.span(),
+                    });
+                }
+            }
+        }
+    }
+    warnings
+}
+
+/// Returns the signature of a trait method as written, e.g. `fn(Self, u32) -> bool`.
+fn method_signature(
+    parameters: &[(Ident, UnresolvedType)],
+    return_type: &FunctionReturnType,
+) -> String {
+    let parameters = vecmap(parameters, |(_, typ)| typ.to_string()).join(", ");
+    match return_type {
+        FunctionReturnType::Default(_) => format!("fn({parameters})"),
+        FunctionReturnType::Ty(typ, _) => format!("fn({parameters}) -> {typ}"),
+    }
+}
+
+/// Returns the name of `typ` if it is one of `generics`.
+fn generic_type_name<'a>(typ: &'a UnresolvedType, generics: &[&Ident]) -> Option<&'a String> {
+    match &typ.typ {
+        UnresolvedTypeData::Named(path, args) if args.is_empty() => {
+            let type_name = &path.as_ident()?.0.contents;
+            generics.iter().any(|generic| &generic.0.contents == type_name).then_some(type_name)
+        }
+        _ => None,
+    }
+}
+
+/// Collects the calls to methods on values of generic types within the nodes visited, as the
+/// generic type, the method called and the span of the call.
+struct GenericMethodCalls<'a> {
+    generics: Vec<&'a Ident>,
+    /// The type of each parameter whose type is a generic, keyed on the parameter's name
+    parameters: HashMap<&'a str, &'a String>,
+    calls: Vec<(String, String, Span)>,
+}
+
+impl<'a> Visitor for GenericMethodCalls<'a> {
+    fn visit_expression(&mut self, expression: &Expression) {
+        match &expression.kind {
+            ExpressionKind::MethodCall(call) => {
+                if let ExpressionKind::Variable(path) = &call.object.kind {
+                    let generic = path
+                        .as_ident()
+                        .and_then(|ident| self.parameters.get(ident.0.contents.as_str()));
+                    if let Some(generic) = generic {
+                        let method_name = call.method_name.0.contents.clone();
+                        self.calls.push(((*generic).clone(), method_name, expression.span));
+                    }
+                }
+            }
+            ExpressionKind::Call(call) => {
+                if let ExpressionKind::Variable(path) = &call.func.kind {
+                    if let [type_name, method_name] = path.segments.as_slice() {
+                        let type_name = &type_name.0.contents;
+                        if self.generics.iter().any(|generic| &generic.0.contents == type_name) {
+                            let generic = type_name.clone();
+                            let method_name = method_name.0.contents.clone();
+                            self.calls.push((generic, method_name, expression.span));
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+        walk_expression(self, expression);
+    }
+}
+
+/// Returns an error for each associated constant or type of `trait_def` which has the same name
+/// as one of the trait's generics, as in `trait Foo<N> { let N: u32; }`.
+fn check_trait_items_shadowing_generics(trait_def: &NoirTrait) -> Vec<DefCollectorErrorKind> {
+    let item_names = trait_def.items.iter().filter_map(|item| match item {
+        TraitItem::Constant { name, .. } | TraitItem::Type { name, .. } => Some(name),
+        TraitItem::Function { .. } => None,
+    });
+    items_shadowing_generics(&trait_def.generics, item_names)
+}
+
+/// Returns an error for each associated constant or type of `trait_impl` which has the same
+/// name as one of the impl's generics, as in `impl<T> Foo for Bar<T> { type T = Field; }`.
+fn check_impl_items_shadowing_generics(trait_impl: &TraitImpl) -> Vec<DefCollectorErrorKind> {
+    let item_names = trait_impl.items.iter().filter_map(|item| match item {
+        TraitImplItem::Constant(name, ..) | TraitImplItem::Type { name, .. } => Some(name),
+        TraitImplItem::Function(_) => None,
+    });
+    items_shadowing_generics(&trait_impl.impl_generics, item_names)
+}
+
+/// Returns an error for each associated constant or type of `trait_impl` which isn't declared by
+/// `trait_def`. Methods missing from the trait are reported as their signatures are checked.
+fn check_impl_items_in_trait(
+    trait_impl: &TraitImpl,
+    trait_def: &NoirTrait,
+) -> Vec<DefCollectorErrorKind> {
+    let declared: HashSet<&str> = trait_def
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Constant { name, .. } | TraitItem::Type { name, .. } => {
+                Some(name.0.contents.as_str())
+            }
+            TraitItem::Function { .. } => None,
+        })
+        .collect();
+
+    let item_names = trait_impl.items.iter().filter_map(|item| match item {
+        TraitImplItem::Constant(name, ..) | TraitImplItem::Type { name, .. } => Some(name),
+        TraitImplItem::Function(_) => None,
+    });
+    item_names
+        .filter(|name| !declared.contains(name.0.contents.as_str()))
+        .map(|name| DefCollectorErrorKind::ItemNotInTrait {
+            trait_name: trait_def.name.clone(),
+            item_name: name.clone(),
+        })
+        .collect()
+}
+
+/// Returns an error for each associated type of `trait_impl` given a different number of generics
+/// than it is declared with by `trait_def`, as in `type Out = Field;` for `type Out<T>;`. Since
+/// generics aren't marked as types or numeric, only their number is compared.
+fn check_associated_type_generics(
+    trait_impl: &TraitImpl,
+    trait_def: &NoirTrait,
+) -> Vec<DefCollectorErrorKind> {
+    let declared: HashMap<&str, usize> = trait_def
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Type { name, generics, .. } => {
+                Some((name.0.contents.as_str(), generics.len()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    for item in &trait_impl.items {
+        let TraitImplItem::Type { name, generics, .. } = item else { continue };
+        // Items missing from the trait are reported separately
+        let Some(expected) = declared.get(name.0.contents.as_str()) else { continue };
+        if generics.len() != *expected {
+            errors.push(DefCollectorErrorKind::AssociatedTypeGenericCountMismatch {
+                trait_name: trait_def.name.clone(),
+                item_name: name.clone(),
+                expected: *expected,
+                found: generics.len(),
+            });
+        }
+    }
+    errors
+}
+
+/// Returns an error for each cycle of associated types in `trait_impl` which are defined in
+/// terms of each other, such as `type Item = Self::Item;`, as these could never be resolved.
+fn check_impl_type_cycles(trait_impl: &TraitImpl) -> Vec<DefCollectorErrorKind> {
+    /// Searches depth first from `alias` for a chain of projections leading back to `target`,
+    /// pushing each associated type along the chain onto `path`.
+    fn reaches_target(
+        target: &Ident,
+        alias: &UnresolvedType,
+        aliases: &HashMap<&str, &UnresolvedType>,
+        path: &mut Vec<Ident>,
+        visited: &mut HashSet<String>,
+    ) -> bool {
+        let mut references = SelfItemReferences::default();
+        references.visit_type(alias);
+
+        for projection in references.0 {
+            let Some(projected_alias) = aliases.get(projection.0.contents.as_str()) else {
+                continue;
+            };
+
+            let is_new = visited.insert(projection.0.contents.clone());
+            let is_target = projection == *target;
+            path.push(projection);
+
+            if is_target
+                || (is_new && reaches_target(target, projected_alias, aliases, path, visited))
+            {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    let aliases: HashMap<&str, &UnresolvedType> = trait_impl
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitImplItem::Type { name, alias, .. } => Some((name.0.contents.as_str(), alias)),
+            _ => None,
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    let mut in_reported_cycle = HashSet::new();
+
+    for item in &trait_impl.items {
+        let TraitImplItem::Type { name, alias, .. } = item else { continue };
+        if in_reported_cycle.contains(&name.0.contents) {
+            continue;
+        }
+
+        let mut cycle = vec![name.clone()];
+        if reaches_target(name, alias, &aliases, &mut cycle, &mut HashSet::new()) {
+            in_reported_cycle.extend(cycle.iter().map(|item| item.0.contents.clone()));
+            errors.push(DefCollectorErrorKind::CyclicAssociatedType {
+                item_name: name.clone(),
+                cycle: vecmap(cycle, |item| item.0.contents),
+            });
+        }
+    }
+
+    errors
+}
+
+fn items_shadowing_generics<'a>(
+    generics: &[Ident],
+    item_names: impl Iterator<Item = &'a Ident>,
+) -> Vec<DefCollectorErrorKind> {
+    item_names
+        .filter_map(|item_name| {
+            let generic = generics.iter().find(|generic| *generic == item_name)?;
+            Some(DefCollectorErrorKind::ItemShadowsGeneric {
+                item_name: item_name.clone(),
+                generic: generic.clone(),
+            })
+        })
+        .collect()
+}
+
 impl<'a> ModCollector<'a> {
+    /// Warns about every constraint in `where_clause` that is already required by another one,
+    /// and reports every pair of constraints which contradict each other.
+    fn check_where_clause(
+        &self,
+        where_clause: &[TraitConstraint],
+        errors: &mut Vec<FileDiagnostic>,
+    ) {
+        for constraint in redundant_trait_constraints(where_clause) {
+            let warning = DefCollectorErrorKind::RedundantTraitConstraint {
+                constraint: constraint.to_string(),
+                span: constraint_span(constraint),
+            };
+            errors.push(warning.into_file_diagnostic(self.file_id));
+        }
+
+        for (first, second) in contradictory_trait_constraints(where_clause) {
+            let error = DefCollectorErrorKind::ContradictoryTraitConstraints {
+                first: first.to_string(),
+                first_span: constraint_span(first),
+                second: second.to_string(),
+                second_span: constraint_span(second),
+            };
+            errors.push(error.into_file_diagnostic(self.file_id));
+        }
+    }
+
     fn collect_globals(
         &mut self,
         context: &mut Context,
@@ -188,12 +1104,24 @@ impl<'a> ModCollector<'a> {
         }
     }
 
-    fn collect_impls(&mut self, context: &mut Context, impls: Vec<TypeImpl>) {
+    fn collect_impls(
+        &mut self,
+        context: &mut Context,
+        impls: Vec<TypeImpl>,
+        errors: &mut Vec<FileDiagnostic>,
+    ) {
         for r#impl in impls {
-            let mut unresolved_functions =
-                UnresolvedFunctions { file_id: self.file_id, functions: Vec::new() };
+            let mut unresolved_functions = UnresolvedFunctions {
+                file_id: self.file_id,
+                functions: Vec::new(),
+                trait_id: None,
+                omitted_constants: Vec::new(),
+                where_clause: Vec::new(),
+            };
 
             for method in r#impl.methods {
+                self.check_where_clause(&method.def.where_clause, errors);
+
                 let func_id = context.def_interner.push_empty_fn();
                 context.def_interner.push_function_definition(method.name().to_owned(), func_id);
                 unresolved_functions.push_fn(self.module_id, func_id, method);
@@ -212,43 +1140,101 @@ impl<'a> ModCollector<'a> {
         errors: &mut Vec<FileDiagnostic>,
     ) {
         for trait_impl in impls {
-            let trait_name = trait_impl.trait_name.clone();
-            let module = &self.def_collector.def_map.modules[self.module_id.0];
-            match module.find_name(&trait_name).types {
-                Some((module_def_id, _visibility)) => {
-                    if let Some(collected_trait) = self.get_unresolved_trait(module_def_id) {
-                        let trait_def = collected_trait.trait_def.clone();
-                        let collected_implementations = self.collect_trait_implementations(
-                            context,
-                            &trait_impl,
-                            &trait_def,
-                            errors,
-                        );
-
-                        let impl_type_span = trait_impl.object_type_span;
-                        let impl_generics = trait_impl.impl_generics.clone();
-                        let impl_object_type = trait_impl.object_type.clone();
-                        let key = (impl_object_type, self.module_id);
-                        self.def_collector.collected_traits_impls.entry(key).or_default().push((
-                            impl_generics,
-                            impl_type_span,
-                            collected_implementations,
-                        ));
-                    } else {
-                        let error = DefCollectorErrorKind::NotATrait {
-                            not_a_trait_name: trait_name.clone(),
-                        };
-                        errors.push(error.into_file_diagnostic(self.file_id));
-                    }
+            let first_error = errors.len();
+            self.collect_trait_impl(context, &trait_impl, errors);
+
+            // Errors in generated impls point back at whatever generated them
+            if let Some(provenance) = &trait_impl.provenance {
+                for error in &mut errors[first_error..] {
+                    error.diagnostic.add_note(provenance.to_string());
                 }
-                None => {
-                    let error = DefCollectorErrorKind::TraitNotFound {
-                        trait_name: trait_name.to_string(),
-                        span: trait_name.span(),
-                    };
+            }
+        }
+    }
+
+    fn collect_trait_impl(
+        &mut self,
+        context: &mut Context,
+        trait_impl: &TraitImpl,
+        errors: &mut Vec<FileDiagnostic>,
+    ) {
+        self.check_where_clause(&trait_impl.where_clause, errors);
+        for error in check_impl_items_shadowing_generics(trait_impl) {
+            errors.push(error.into_file_diagnostic(self.file_id));
+        }
+        for error in check_impl_type_cycles(trait_impl) {
+            errors.push(error.into_file_diagnostic(self.file_id));
+        }
+
+        let trait_name = trait_impl.trait_name.clone();
+        self.collect_associated_types(trait_impl);
+
+        let module = &self.def_collector.def_map.modules[self.module_id.0];
+        match module.find_name(&trait_name).types {
+            Some((module_def_id, _visibility)) => {
+                if let Some(collected_trait) = self.get_unresolved_trait(module_def_id) {
+                    let trait_def = collected_trait.trait_def.clone();
+                    let trait_module = collected_trait.module_id;
+                    let mut collected_implementations = self.collect_trait_implementations(
+                        context,
+                        trait_impl,
+                        &trait_def,
+                        trait_module,
+                        errors,
+                    );
+                    collected_implementations.trait_id = module_def_id.as_trait();
+                    collected_implementations.where_clause = trait_impl.where_clause.clone();
+
+                    let impl_type_span = trait_impl.object_type_span;
+                    let impl_generics = trait_impl.impl_generics.clone();
+                    let impl_object_type = trait_impl.object_type.clone();
+                    let key = (impl_object_type, self.module_id);
+                    self.def_collector.collected_traits_impls.entry(key).or_default().push((
+                        impl_generics,
+                        impl_type_span,
+                        collected_implementations,
+                    ));
+                } else {
+                    let error =
+                        DefCollectorErrorKind::NotATrait { not_a_trait_name: trait_name.clone() };
                     errors.push(error.into_file_diagnostic(self.file_id));
                 }
             }
+            // The trait may be imported, so it can only be looked up once imports are resolved
+            None => self.def_collector.unfound_trait_impls.push(UnfoundTraitImpl {
+                file_id: self.file_id,
+                module_id: self.module_id,
+                trait_name,
+                impl_generics: trait_impl.impl_generics.clone(),
+                object_type: trait_impl.object_type.clone(),
+                object_type_span: trait_impl.object_type_span,
+                provenance: trait_impl.provenance.clone(),
+            }),
+        }
+    }
+
+    /// Records the associated types defined by `trait_impl` so projections onto them can be
+    /// normalized once the crate has been collected.
+    fn collect_associated_types(&mut self, trait_impl: &TraitImpl) {
+        let aliases: HashMap<&str, &UnresolvedType> = trait_impl
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                TraitImplItem::Type { name, alias, .. } => Some((name.0.contents.as_str(), alias)),
+                _ => None,
+            })
+            .collect();
+
+        for (name, alias) in &aliases {
+            // A reference to an associated type missing from the impl is left for the resolver
+            let alias =
+                project_associated_types(alias, &aliases).unwrap_or_else(|_| (*alias).clone());
+            let key = (
+                trait_impl.trait_name.0.contents.clone(),
+                trait_impl.object_type.to_string(),
+                name.to_string(),
+            );
+            self.def_collector.collected_associated_types.insert(key, alias);
         }
     }
 
@@ -259,25 +1245,58 @@ impl<'a> ModCollector<'a> {
         }
     }
 
+    /// Collects the methods of `trait_impl`, an impl of `trait_def`, along with a copy of the
+    /// default method of the trait for each method the impl doesn't define. The default methods
+    /// are resolved in `trait_module`, the module declaring the trait, so that the functions
+    /// they call are those in scope where they are written rather than where they are used.
     fn collect_trait_implementations(
         &mut self,
         context: &mut Context,
         trait_impl: &TraitImpl,
         trait_def: &NoirTrait,
+        trait_module: LocalModuleId,
         errors: &mut Vec<FileDiagnostic>,
     ) -> UnresolvedFunctions {
-        let mut unresolved_functions =
-            UnresolvedFunctions { file_id: self.file_id, functions: Vec::new() };
+        let mut unresolved_functions = UnresolvedFunctions {
+            file_id: self.file_id,
+            functions: Vec::new(),
+            trait_id: None,
+            omitted_constants: Vec::new(),
+            where_clause: Vec::new(),
+        };
+
+        for error in check_impl_items_in_trait(trait_impl, trait_def) {
+            errors.push(error.into_file_diagnostic(self.file_id));
+        }
+        for error in check_associated_type_generics(trait_impl, trait_def) {
+            errors.push(error.into_file_diagnostic(self.file_id));
+        }
+        let (constant_values, fold_errors) = fold_impl_constants(trait_def, trait_impl);
+        for error in fold_errors {
+            errors.push(error.into_file_diagnostic(self.file_id));
+        }
+
+        let associated_types: HashMap<&str, &UnresolvedType> = trait_impl
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                TraitImplItem::Type { name, alias, .. } => Some((name.0.contents.as_str(), alias)),
+                _ => None,
+            })
+            .collect();
 
         for item in &trait_impl.items {
             if let TraitImplItem::Function(impl_method) = item {
-                match check_trait_method_implementation(trait_def, impl_method) {
-                    Ok(()) => {
+                self.check_where_clause(&impl_method.def.where_clause, errors);
+
+                match check_trait_method_implementation(trait_def, impl_method, &associated_types) {
+                    Ok(mut impl_method) => {
+                        inline_constants(&mut impl_method, &constant_values);
                         let func_id = context.def_interner.push_empty_fn();
                         context
                             .def_interner
                             .push_function_definition(impl_method.name().to_owned(), func_id);
-                        unresolved_functions.push_fn(self.module_id, func_id, impl_method.clone());
+                        unresolved_functions.push_fn(self.module_id, func_id, impl_method);
                     }
                     Err(error) => {
                         errors.push(error.into_file_diagnostic(self.file_id));
@@ -288,6 +1307,7 @@ impl<'a> ModCollector<'a> {
 
         for item in &trait_def.items {
             if let TraitItem::Function {
+                attributes,
                 name,
                 generics,
                 parameters,
@@ -306,7 +1326,7 @@ impl<'a> ModCollector<'a> {
                             let method_name = name.0.contents.clone();
                             let func_id = context.def_interner.push_empty_fn();
                             context.def_interner.push_function_definition(method_name, func_id);
-                            let impl_method = NoirFunction::normal(FunctionDefinition::normal(
+                            let mut impl_method = NoirFunction::normal(FunctionDefinition::normal(
                                 name,
                                 generics,
                                 parameters,
@@ -314,7 +1334,13 @@ impl<'a> ModCollector<'a> {
                                 where_clause,
                                 return_type,
                             ));
-                            unresolved_functions.push_fn(self.module_id, func_id, impl_method);
+                            // The copy keeps any inline hint given to the default method
+                            impl_method.def.attribute = attributes
+                                .iter()
+                                .find(|attribute| attribute.inline_hint().is_some())
+                                .cloned();
+                            inline_constants(&mut impl_method, &constant_values);
+                            unresolved_functions.push_fn(trait_module, func_id, impl_method);
                         }
                         None => {
                             let error = DefCollectorErrorKind::TraitMissedMethodImplementation {
@@ -328,6 +1354,24 @@ impl<'a> ModCollector<'a> {
                 }
             }
         }
+
+        // Whether these must be defined depends on their where clauses, which can only be
+        // checked once every impl has been collected
+        let defined_constants: HashSet<&str> = trait_impl
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                TraitImplItem::Constant(name, ..) => Some(name.0.contents.as_str()),
+                _ => None,
+            })
+            .collect();
+        for item in &trait_def.items {
+            if let TraitItem::Constant { name, default_value: None, .. } = item {
+                if !defined_constants.contains(name.0.contents.as_str()) {
+                    unresolved_functions.omitted_constants.push(name.clone());
+                }
+            }
+        }
         unresolved_functions
     }
 
@@ -337,10 +1381,17 @@ impl<'a> ModCollector<'a> {
         functions: Vec<NoirFunction>,
         errors: &mut Vec<FileDiagnostic>,
     ) {
-        let mut unresolved_functions =
-            UnresolvedFunctions { file_id: self.file_id, functions: Vec::new() };
+        let mut unresolved_functions = UnresolvedFunctions {
+            file_id: self.file_id,
+            functions: Vec::new(),
+            trait_id: None,
+            omitted_constants: Vec::new(),
+            where_clause: Vec::new(),
+        };
 
         for function in functions {
+            self.check_where_clause(&function.def.where_clause, errors);
+
             let name = function.name_ident().clone();
 
             // First create dummy function in the DefInterner
@@ -458,7 +1509,44 @@ impl<'a> ModCollector<'a> {
         krate: CrateId,
         errors: &mut Vec<FileDiagnostic>,
     ) {
+        for trait_definition in &traits {
+            for warning in check_missing_where_bounds(trait_definition, &traits) {
+                errors.push(warning.into_file_diagnostic(self.file_id));
+            }
+            for warning in check_associated_type_bounds(trait_definition, &traits) {
+                errors.push(warning.into_file_diagnostic(self.file_id));
+            }
+            for warning in check_shadowed_supertrait_methods(trait_definition, &traits) {
+                errors.push(warning.into_file_diagnostic(self.file_id));
+            }
+        }
+
         for trait_definition in traits {
+            self.check_where_clause(&trait_definition.where_clause, errors);
+            for item in &trait_definition.items {
+                let (TraitItem::Function { where_clause, .. }
+                | TraitItem::Constant { where_clause, .. }
+                | TraitItem::Type { where_clause, .. }) = item;
+                self.check_where_clause(where_clause, errors);
+            }
+            for error in check_trait_default_bodies(&trait_definition) {
+                errors.push(error.into_file_diagnostic(self.file_id));
+            }
+            for error in check_trait_items_shadowing_generics(&trait_definition) {
+                errors.push(error.into_file_diagnostic(self.file_id));
+            }
+            for generic in trait_definition.phantom_generics() {
+                let trait_name = trait_definition.name.clone();
+                let warning = DefCollectorErrorKind::PhantomTraitGeneric { trait_name, generic };
+                errors.push(warning.into_file_diagnostic(self.file_id));
+            }
+            for warning in check_unused_self(&trait_definition) {
+                errors.push(warning.into_file_diagnostic(self.file_id));
+            }
+            for warning in check_inline_hints(&trait_definition) {
+                errors.push(warning.into_file_diagnostic(self.file_id));
+            }
+
             let name = trait_definition.name.clone();
 
             // Create the corresponding module for the trait namespace
@@ -0,0 +1,392 @@
+//! Expansion of `#[derive(...)]` attributes on struct definitions.
+//!
+//! Each derivable trait is registered by name in a [`DeriveRegistry`] together with a generator
+//! which produces the trait implementation for a given struct. Expansion happens on the AST of
+//! each module before its definitions are collected, so generated impls are collected, resolved
+//! and type checked exactly like handwritten ones.
+use std::collections::HashMap;
+
+use acvm::FieldElement;
+use fm::FileId;
+use noirc_errors::{FileDiagnostic, Span, Spanned};
+
+use crate::{
+    token::Attribute, AssignStatement, BinaryOpKind, BinaryTypeOperator, BlockExpression,
+    Expression, ExpressionKind, ForExpression, FunctionDefinition, FunctionReturnType, Ident,
+    InfixExpression, LValue, Literal, NoirFunction, NoirStruct, ParsedModule, Path, Pattern,
+    Provenance, Statement, TraitBound, TraitConstraint, TraitImpl, TraitImplItem, UnresolvedType,
+    UnresolvedTypeData, UnresolvedTypeExpression,
+};
+
+use super::errors::DefCollectorErrorKind;
+
+/// Generates the implementation of a derivable trait for the given struct, or the error
+/// explaining why the trait can't be derived for it.
+pub type DeriveGenerator = fn(&NoirStruct) -> Result<TraitImpl, DefCollectorErrorKind>;
+
+/// Maps the name of each derivable trait to the generator used to implement it.
+///
+/// The default registry contains the builtin `Eq` and `Default` derives.
+#[derive(Clone)]
+pub struct DeriveRegistry {
+    generators: HashMap<String, DeriveGenerator>,
+}
+
+impl Default for DeriveRegistry {
+    fn default() -> Self {
+        let mut registry = DeriveRegistry::empty();
+        registry.register("Eq", derive_eq);
+        registry.register("Default", derive_default);
+        registry
+    }
+}
+
+impl DeriveRegistry {
+    /// Creates a registry without any derivable traits, not even the builtin ones.
+    pub fn empty() -> Self {
+        DeriveRegistry { generators: HashMap::new() }
+    }
+
+    /// Registers `generator` as the way to derive `trait_name`, returning the generator
+    /// previously registered for that trait, if any.
+    pub fn register(
+        &mut self,
+        trait_name: impl Into<String>,
+        generator: DeriveGenerator,
+    ) -> Option<DeriveGenerator> {
+        self.generators.insert(trait_name.into(), generator)
+    }
+
+    pub fn get(&self, trait_name: &str) -> Option<DeriveGenerator> {
+        self.generators.get(trait_name).copied()
+    }
+
+    /// Appends a trait impl to `ast` for every trait named in the `#[derive(...)]` attributes
+    /// of its structs. Traits without a registered generator are reported as errors.
+    pub fn expand(
+        &self,
+        ast: &mut ParsedModule,
+        file_id: FileId,
+        errors: &mut Vec<FileDiagnostic>,
+    ) {
+        for noir_struct in &ast.types {
+            for attribute in &noir_struct.attributes {
+                let Attribute::Derive(traits) = attribute else { continue };
+
+                for trait_name in traits {
+                    match self.get(trait_name).map(|generator| generator(noir_struct)) {
+                        Some(Ok(trait_impl)) => ast.trait_impls.push(trait_impl),
+                        Some(Err(error)) => errors.push(error.into_file_diagnostic(file_id)),
+                        None => {
+                            let error = DefCollectorErrorKind::UnknownDerive {
+                                trait_name: trait_name.clone(),
+                                span: noir_struct.name.span(),
+                            };
+                            errors.push(error.into_file_diagnostic(file_id));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Derives `fn eq(self, other: Self) -> bool` by comparing each field in declaration order.
+fn derive_eq(noir_struct: &NoirStruct) -> Result<TraitImpl, DefCollectorErrorKind> {
+    let span = noir_struct.span;
+
+    let mut comparisons = Vec::with_capacity(noir_struct.fields.len());
+    for (field, typ) in &noir_struct.fields {
+        let lhs = member_access(variable("self", span), field.clone());
+        let rhs = member_access(variable("other", span), field.clone());
+        let comparison =
+            equality(typ, lhs, rhs, 0).ok_or_else(|| unsupported_field("Eq", field, typ))?;
+        comparisons.push(comparison);
+    }
+
+    let body = comparisons
+        .into_iter()
+        .reduce(|acc, comparison| infix(acc, BinaryOpKind::And, comparison))
+        .unwrap_or_else(|| expression(ExpressionKind::boolean(true), span));
+
+    let parameters =
+        [(ident("self", span), self_type(span)), (ident("other", span), self_type(span))];
+    let return_type = UnresolvedType { typ: UnresolvedTypeData::Bool, span: Some(span) };
+
+    let method = noir_function("eq", &parameters, body, return_type, span);
+    Ok(derived_impl(noir_struct, "Eq", method))
+}
+
+/// Compares `lhs` and `rhs`, which have type `typ`, returning `None` if values of this type
+/// can't be compared. `depth` is the number of loops the comparison is nested within.
+///
+/// Values which may hold nested structs, or aliases, can't be compared with `==`, so these are
+/// compared with their own `eq` method, looping over arrays and comparing tuples elementwise.
+fn equality(
+    typ: &UnresolvedType,
+    lhs: Expression,
+    rhs: Expression,
+    depth: usize,
+) -> Option<Expression> {
+    if !contains_named_type(typ) {
+        return is_comparable(typ).then(|| infix(lhs, BinaryOpKind::Equal, rhs));
+    }
+
+    let span = lhs.span;
+    match &typ.typ {
+        UnresolvedTypeData::Named(..) => Some(method_call(lhs, "eq", vec![rhs])),
+        UnresolvedTypeData::Array(Some(_), element) => {
+            // `{ let mut equal = true; for i in 0..lhs.len() { equal = equal & ...; } equal }`
+            let index = format!("i{depth}");
+            let element_lhs = Expression::index(lhs.clone(), variable(&index, span), span);
+            let element_rhs = Expression::index(rhs, variable(&index, span), span);
+            let comparison = equality(element, element_lhs, element_rhs, depth + 1)?;
+            let assignment = Statement::Assign(AssignStatement {
+                lvalue: LValue::Ident(ident("equal", span)),
+                expression: infix(variable("equal", span), BinaryOpKind::And, comparison),
+            });
+            let for_loop = ExpressionKind::For(Box::new(ForExpression {
+                identifier: ident(&index, span),
+                start_range: expression(ExpressionKind::integer(FieldElement::zero()), span),
+                end_range: method_call(lhs, "len", vec![]),
+                block: expression(ExpressionKind::Block(BlockExpression(vec![assignment])), span),
+            }));
+            let equal = Pattern::Mutable(Box::new(Pattern::Identifier(ident("equal", span))), span);
+            let statements = vec![
+                Statement::new_let((
+                    (equal, UnresolvedType::unspecified()),
+                    expression(ExpressionKind::boolean(true), span),
+                )),
+                Statement::Expression(expression(for_loop, span)),
+                Statement::Expression(variable("equal", span)),
+            ];
+            Some(expression(ExpressionKind::Block(BlockExpression(statements)), span))
+        }
+        UnresolvedTypeData::Tuple(elements) => {
+            let mut comparisons = Vec::with_capacity(elements.len());
+            for (position, element) in elements.iter().enumerate() {
+                let position = ident(&position.to_string(), span);
+                let element_lhs = member_access(lhs.clone(), position.clone());
+                let element_rhs = member_access(rhs.clone(), position);
+                comparisons.push(equality(element, element_lhs, element_rhs, depth)?);
+            }
+            comparisons
+                .into_iter()
+                .reduce(|acc, comparison| infix(acc, BinaryOpKind::And, comparison))
+        }
+        _ => None,
+    }
+}
+
+/// Whether `typ` is, or holds, a named type such as a struct, alias or generic.
+fn contains_named_type(typ: &UnresolvedType) -> bool {
+    match &typ.typ {
+        UnresolvedTypeData::Named(..) => true,
+        UnresolvedTypeData::Array(_, element) => contains_named_type(element),
+        UnresolvedTypeData::Tuple(elements) => elements.iter().any(contains_named_type),
+        _ => false,
+    }
+}
+
+/// Whether values of `typ`, which holds no named types, can be compared with `==`.
+fn is_comparable(typ: &UnresolvedType) -> bool {
+    match &typ.typ {
+        UnresolvedTypeData::FieldElement
+        | UnresolvedTypeData::Integer(..)
+        | UnresolvedTypeData::Bool
+        | UnresolvedTypeData::Unit
+        | UnresolvedTypeData::String(Some(_)) => true,
+        UnresolvedTypeData::Array(Some(_), element) => is_comparable(element),
+        UnresolvedTypeData::Tuple(elements) => elements.iter().all(is_comparable),
+        _ => false,
+    }
+}
+
+/// Derives `fn default() -> Self` by building `Self` out of a default value for each field.
+fn derive_default(noir_struct: &NoirStruct) -> Result<TraitImpl, DefCollectorErrorKind> {
+    let span = noir_struct.span;
+
+    let mut fields = Vec::with_capacity(noir_struct.fields.len());
+    for (field, typ) in &noir_struct.fields {
+        let value =
+            default_value(typ, span).ok_or_else(|| unsupported_field("Default", field, typ))?;
+        fields.push((field.clone(), value));
+    }
+
+    let body = expression(
+        ExpressionKind::constructor((Path::from_single("Self".into(), span), fields)),
+        span,
+    );
+
+    let method = noir_function("default", &[], body, self_type(span), span);
+    Ok(derived_impl(noir_struct, "Default", method))
+}
+
+/// The default value of a field of type `typ`, or `None` if it has no default. Integers default
+/// to zero, booleans to false and compound types are built from the defaults of their elements.
+/// Named types are expected to implement `Default` themselves.
+fn default_value(typ: &UnresolvedType, span: Span) -> Option<Expression> {
+    let value = match &typ.typ {
+        UnresolvedTypeData::FieldElement | UnresolvedTypeData::Integer(..) => {
+            expression(ExpressionKind::integer(FieldElement::zero()), span)
+        }
+        UnresolvedTypeData::Bool => expression(ExpressionKind::boolean(false), span),
+        UnresolvedTypeData::Unit => expression(ExpressionKind::Literal(Literal::Unit), span),
+        UnresolvedTypeData::Array(Some(length), element) => {
+            let element = default_value(element, span)?;
+            let length = type_expression_to_expression(length, span);
+            expression(ExpressionKind::repeated_array(element, length), span)
+        }
+        UnresolvedTypeData::Tuple(elements) => {
+            let elements = elements
+                .iter()
+                .map(|element| default_value(element, span))
+                .collect::<Option<_>>()?;
+            expression(ExpressionKind::Tuple(elements), span)
+        }
+        UnresolvedTypeData::Named(path, _) => {
+            let mut path = path.clone();
+            path.segments.push(ident("default", span));
+            Expression::call(expression(ExpressionKind::Variable(path), span), vec![], span)
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn unsupported_field(
+    trait_name: &str,
+    field: &Ident,
+    typ: &UnresolvedType,
+) -> DefCollectorErrorKind {
+    DefCollectorErrorKind::UnsupportedDeriveField {
+        trait_name: trait_name.to_string(),
+        field_type: typ.to_string(),
+        span: typ.span.unwrap_or_else(|| field.span()),
+    }
+}
+
+fn type_expression_to_expression(length: &UnresolvedTypeExpression, span: Span) -> Expression {
+    match length {
+        UnresolvedTypeExpression::Variable(path) => {
+            expression(ExpressionKind::Variable(path.clone()), span)
+        }
+        UnresolvedTypeExpression::Constant(value, _) => {
+            expression(ExpressionKind::integer(FieldElement::from(*value as u128)), span)
+        }
+        UnresolvedTypeExpression::BinaryOperation(lhs, operator, rhs, _) => {
+            let operator = match operator {
+                BinaryTypeOperator::Addition => BinaryOpKind::Add,
+                BinaryTypeOperator::Subtraction => BinaryOpKind::Subtract,
+                BinaryTypeOperator::Multiplication => BinaryOpKind::Multiply,
+                BinaryTypeOperator::Division => BinaryOpKind::Divide,
+                BinaryTypeOperator::Modulo => BinaryOpKind::Modulo,
+            };
+            let lhs = type_expression_to_expression(lhs, span);
+            let rhs = type_expression_to_expression(rhs, span);
+            infix(lhs, operator, rhs)
+        }
+    }
+}
+
+/// Wraps `method` in `impl<generics> trait_name for StructName<generics> where T: trait_name`,
+/// requiring each generic of the struct to implement the derived trait as well.
+fn derived_impl(noir_struct: &NoirStruct, trait_name: &str, method: NoirFunction) -> TraitImpl {
+    let span = noir_struct.span;
+
+    let generic_type = |generic: &Ident| UnresolvedType {
+        typ: UnresolvedTypeData::Named(Path::from_ident(generic.clone()), vec![]),
+        span: Some(generic.span()),
+    };
+
+    let struct_generics = noir_struct.generics.iter().map(generic_type).collect();
+    let object_type = UnresolvedType {
+        typ: UnresolvedTypeData::Named(Path::from_ident(noir_struct.name.clone()), struct_generics),
+        span: Some(span),
+    };
+
+    let where_clause = noir_struct
+        .generics
+        .iter()
+        .map(|generic| TraitConstraint {
+            typ: generic_type(generic),
+            trait_bound: TraitBound {
+                trait_name: ident(trait_name, span),
+                trait_generics: vec![],
+                negative: false,
+            },
+        })
+        .collect();
+
+    TraitImpl {
+        attributes: vec![],
+        impl_generics: noir_struct.generics.clone(),
+        trait_name: ident(trait_name, span),
+        trait_generics: vec![],
+        object_type,
+        object_type_span: span,
+        where_clause,
+        items: vec![TraitImplItem::Function(method)],
+        provenance: Some(Provenance {
+            macro_name: format!("derive({trait_name})"),
+            source_item: noir_struct.name.clone(),
+        }),
+    }
+}
+
+fn noir_function(
+    name: &str,
+    parameters: &[(Ident, UnresolvedType)],
+    body: Expression,
+    return_type: UnresolvedType,
+    span: Span,
+) -> NoirFunction {
+    let body = BlockExpression(vec![Statement::Expression(body)]);
+    let return_type = FunctionReturnType::Ty(return_type, span);
+    let def = FunctionDefinition::normal(
+        &ident(name, span),
+        &Vec::new(),
+        parameters,
+        &body,
+        &[],
+        &return_type,
+    );
+    NoirFunction::normal(def)
+}
+
+fn ident(name: &str, span: Span) -> Ident {
+    Ident::new(name.to_string(), span)
+}
+
+fn expression(kind: ExpressionKind, span: Span) -> Expression {
+    Expression::new(kind, span)
+}
+
+fn variable(name: &str, span: Span) -> Expression {
+    expression(ExpressionKind::Variable(Path::from_single(name.to_string(), span)), span)
+}
+
+fn self_type(span: Span) -> UnresolvedType {
+    let typ = UnresolvedTypeData::Named(Path::from_single("Self".to_string(), span), vec![]);
+    UnresolvedType { typ, span: Some(span) }
+}
+
+fn member_access(lhs: Expression, field: Ident) -> Expression {
+    let span = lhs.span;
+    Expression::member_access_or_method_call(lhs, (field, None), span)
+}
+
+fn method_call(object: Expression, method_name: &str, arguments: Vec<Expression>) -> Expression {
+    let span = object.span;
+    Expression::member_access_or_method_call(
+        object,
+        (ident(method_name, span), Some(arguments)),
+        span,
+    )
+}
+
+fn infix(lhs: Expression, operator: BinaryOpKind, rhs: Expression) -> Expression {
+    let span = lhs.span.merge(rhs.span);
+    let operator = Spanned::from(span, operator);
+    expression(ExpressionKind::Infix(Box::new(InfixExpression { lhs, operator, rhs })), span)
+}
@@ -1,7 +1,9 @@
 use crate::hir::resolution::import::PathResolutionError;
+use crate::token::InlineHint;
 use crate::Ident;
 use crate::UnresolvedType;
 
+use iter_extended::vecmap;
 use noirc_errors::CustomDiagnostic as Diagnostic;
 use noirc_errors::FileDiagnostic;
 use noirc_errors::Span;
@@ -49,12 +51,91 @@ pub enum DefCollectorErrorKind {
     },
     #[error("Method is not defined in trait")]
     MethodNotInTrait { trait_name: Ident, impl_method: Ident },
+    #[error("Associated item is not defined in trait")]
+    ItemNotInTrait { trait_name: Ident, item_name: Ident },
     #[error("Only traits can be implemented")]
     NotATrait { not_a_trait_name: Ident },
     #[error("Trait not found")]
     TraitNotFound { trait_name: String, span: Span },
     #[error("Missing Trait method implementation")]
     TraitMissedMethodImplementation { trait_name: Ident, method_name: Ident, trait_impl_span: Span },
+    #[error("Missing Trait constant implementation")]
+    TraitMissedConstantImplementation {
+        trait_name: Ident,
+        constant_name: Ident,
+        trait_impl_span: Span,
+    },
+    #[error("Trait cannot be derived")]
+    UnknownDerive { trait_name: String, span: Span },
+    #[error("Trait cannot be derived for the type of a field")]
+    UnsupportedDeriveField { trait_name: String, field_type: String, span: Span },
+    #[error("Redundant trait constraint")]
+    RedundantTraitConstraint { constraint: String, span: Span },
+    #[error("Contradictory trait constraints")]
+    ContradictoryTraitConstraints {
+        first: String,
+        first_span: Span,
+        second: String,
+        second_span: Span,
+    },
+    #[error("No associated item with this name in trait")]
+    NoSuchAssociatedItem { trait_name: Ident, item_name: Ident },
+    #[error("Associated item shadows a generic")]
+    ItemShadowsGeneric { item_name: Ident, generic: Ident },
+    #[error("Associated type is defined in terms of itself")]
+    CyclicAssociatedType { item_name: Ident, cycle: Vec<String> },
+    #[error("Trait generic is never used")]
+    PhantomTraitGeneric { trait_name: Ident, generic: Ident },
+    #[error("Unused self receiver")]
+    UnusedSelf { method_name: Ident, receiver_span: Span },
+    #[error("Associated type is not defined in impl")]
+    MissingAssociatedType { trait_name: Ident, item_name: String, impl_method: Ident },
+    #[error("Unresolved associated type projection")]
+    UnresolvedProjection { projection: String, span: Span },
+    #[error("Default value of trait constant is not constant")]
+    NonConstantDefault { item_name: Ident, span: Span },
+    #[error("Non-constant value of associated constant in trait impl")]
+    NonConstantImplValue { item_name: Ident, span: Span },
+    #[error("Value of trait constant does not fit in its type")]
+    ConstantOverflow { item_name: Ident, typ: String, value: u128, span: Span },
+    #[error("Orphan trait impl")]
+    OrphanImpl { trait_name: Ident, type_name: String, span: Span },
+    #[error("Associated type has the wrong number of generics")]
+    AssociatedTypeGenericCountMismatch {
+        trait_name: Ident,
+        item_name: Ident,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Inline hint is ignored")]
+    IgnoredInlineHint { method_name: Ident, hint: InlineHint, reason: &'static str },
+    #[error("Default method needs a trait bound it doesn't declare")]
+    MissingWhereBound { method_name: Ident, bound: String, span: Span },
+    #[error("Associated type is used in a way its bounds don't allow")]
+    MissingAssociatedTypeBound { trait_name: Ident, item_name: Ident, bound: String, span: Span },
+    #[error("Trait method shadows a supertrait method with a different signature")]
+    ShadowedSupertraitMethod {
+        method_name: Ident,
+        signature: String,
+        supertrait_name: String,
+        supertrait_signature: String,
+        supertrait_span: Span,
+    },
+    #[error("Trait impl does not satisfy a constraint on `Self`")]
+    UnsatisfiedSelfConstraint {
+        trait_name: String,
+        required_trait: String,
+        typ: String,
+        span: Span,
+    },
+    #[error("Trait method returns a type which does not satisfy its opaque return type")]
+    UnsatisfiedOpaqueReturnType {
+        method_name: String,
+        opaque_type: String,
+        required_trait: String,
+        typ: String,
+        span: Span,
+    },
 }
 
 impl DefCollectorErrorKind {
@@ -109,7 +190,7 @@ impl From<DefCollectorErrorKind> for Diagnostic {
             DefCollectorErrorKind::PathResolutionError(error) => error.into(),
             DefCollectorErrorKind::NonStructTypeInImpl { span } => Diagnostic::simple_error(
                 "Non-struct type used in impl".into(),
-                "Only struct types may have implementation methods".into(),
+                "Only struct types and the primitive types of the crate being compiled may have implementation methods".into(),
                 span,
             ),
             DefCollectorErrorKind::ForeignImpl { span, type_name } => Diagnostic::simple_error(
@@ -163,8 +244,44 @@ impl From<DefCollectorErrorKind> for Diagnostic {
                 let trait_name = trait_name.0.contents;
                 let impl_method_span = impl_method.span();
                 let impl_method_name = impl_method.0.contents;
-                let primary_message = format!("method with name {impl_method_name} is not part of trait {trait_name}, therefore it can't be implemented");
-                Diagnostic::simple_error(primary_message, "".to_owned(), impl_method_span)
+                Diagnostic::simple_error(
+                    format!("method `{impl_method_name}` is not a member of trait `{trait_name}`"),
+                    "methods which aren't part of the trait belong in an `impl` of the type itself"
+                        .to_owned(),
+                    impl_method_span,
+                )
+            }
+            DefCollectorErrorKind::NonConstantDefault { item_name, span } => {
+                Diagnostic::simple_error(
+                    format!("default value of `{item_name}` is not a compile-time constant"),
+                    "only arithmetic on integer literals and the trait's constants can be evaluated"
+                        .into(),
+                    span,
+                )
+            }
+            DefCollectorErrorKind::NonConstantImplValue { item_name, span } => {
+                Diagnostic::simple_error(
+                    format!("value of `{item_name}` is not a compile-time constant"),
+                    "only arithmetic on integer literals and the trait's constants can be evaluated"
+                        .into(),
+                    span,
+                )
+            }
+            DefCollectorErrorKind::ConstantOverflow { item_name, typ, value, span } => {
+                Diagnostic::simple_error(
+                    format!("value of `{item_name}` does not fit in its type `{typ}`"),
+                    format!("this evaluates to {value}, which overflows `{typ}`"),
+                    span,
+                )
+            }
+            DefCollectorErrorKind::ItemNotInTrait { trait_name, item_name } => {
+                let span = item_name.span();
+                let item_name = item_name.0.contents;
+                Diagnostic::simple_error(
+                    format!("associated item `{item_name}` is not a member of trait `{trait_name}`"),
+                    format!("`{item_name}` is not declared in trait `{trait_name}`"),
+                    span,
+                )
             }
             DefCollectorErrorKind::TraitMissedMethodImplementation {
                 trait_name,
@@ -182,6 +299,22 @@ impl From<DefCollectorErrorKind> for Diagnostic {
                     trait_impl_span,
                 )
             }
+            DefCollectorErrorKind::TraitMissedConstantImplementation {
+                trait_name,
+                constant_name,
+                trait_impl_span,
+            } => {
+                let trait_name = trait_name.0.contents;
+                let constant_name = constant_name.0.contents;
+                let primary_message = format!(
+                    "constant `{constant_name}` from trait `{trait_name}` is not implemented"
+                );
+                Diagnostic::simple_error(
+                    primary_message,
+                    format!("Please implement {constant_name} here"),
+                    trait_impl_span,
+                )
+            }
             DefCollectorErrorKind::NotATrait { not_a_trait_name } => {
                 let span = not_a_trait_name.0.span();
                 let name = &not_a_trait_name.0.contents;
@@ -191,6 +324,213 @@ impl From<DefCollectorErrorKind> for Diagnostic {
                     span,
                 )
             }
+            DefCollectorErrorKind::UnknownDerive { trait_name, span } => Diagnostic::simple_error(
+                format!("Trait `{trait_name}` cannot be derived"),
+                format!("No derive is registered for `{trait_name}`"),
+                span,
+            ),
+            DefCollectorErrorKind::UnsupportedDeriveField { trait_name, field_type, span } => {
+                Diagnostic::simple_error(
+                    format!("Cannot derive `{trait_name}` for a field of type `{field_type}`"),
+                    format!("`{trait_name}` can't be derived for values of this type"),
+                    span,
+                )
+            }
+            DefCollectorErrorKind::RedundantTraitConstraint { constraint, span } => {
+                Diagnostic::simple_warning(
+                    format!("Redundant trait constraint `{constraint}`"),
+                    "This constraint is already required by an earlier one".to_string(),
+                    span,
+                )
+            }
+            DefCollectorErrorKind::ContradictoryTraitConstraints {
+                first,
+                first_span,
+                second,
+                second_span,
+            } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("Contradictory trait constraints `{first}` and `{second}`"),
+                    "No type can satisfy this constraint along with an earlier one".to_string(),
+                    second_span,
+                );
+                diag.add_secondary(format!("`{first}` is required here"), first_span);
+                diag
+            }
+            DefCollectorErrorKind::NoSuchAssociatedItem { trait_name, item_name } => {
+                let span = item_name.span();
+                let item_name = item_name.0.contents;
+                Diagnostic::simple_error(
+                    format!("no associated item `{item_name}` on this trait"),
+                    format!("`{item_name}` is not declared in trait `{trait_name}`"),
+                    span,
+                )
+            }
+            DefCollectorErrorKind::ItemShadowsGeneric { item_name, generic } => {
+                let name = &item_name.0.contents;
+                let mut diag = Diagnostic::simple_error(
+                    format!("`{name}` is declared as both a generic and an associated item"),
+                    format!("associated item `{name}` declared here"),
+                    item_name.span(),
+                );
+                diag.add_secondary(format!("generic `{name}` declared here"), generic.span());
+                diag
+            }
+            DefCollectorErrorKind::CyclicAssociatedType { item_name, cycle } => {
+                let cycle = vecmap(cycle, |name| format!("Self::{name}"));
+                Diagnostic::simple_error(
+                    format!("associated type `{item_name}` is defined in terms of itself"),
+                    format!("cycle found: {}", cycle.join(" -> ")),
+                    item_name.span(),
+                )
+            }
+            DefCollectorErrorKind::MissingAssociatedType { trait_name, item_name, impl_method } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("associated type `{item_name}` is not defined in this impl of trait `{trait_name}`"),
+                    format!("the signature of `{impl_method}` uses `Self::{item_name}`"),
+                    impl_method.span(),
+                );
+                diag.add_note(format!("add `type {item_name} = ...;` to the impl"));
+                diag
+            }
+            DefCollectorErrorKind::UnresolvedProjection { projection, span } => {
+                Diagnostic::simple_error(
+                    format!("could not resolve `{projection}` to a concrete type"),
+                    "no trait impl in the current crate defines this associated type".into(),
+                    span,
+                )
+            }
+            DefCollectorErrorKind::OrphanImpl { trait_name, type_name, span } => {
+                let mut diag = Diagnostic::simple_error(
+                    "cannot implement a trait from another crate for a type from another crate"
+                        .into(),
+                    format!(
+                        "neither `{trait_name}` nor `{type_name}` is defined in the current crate"
+                    ),
+                    span,
+                );
+                diag.add_note(
+                    "define the trait or wrap the type in a struct within the current crate".into(),
+                );
+                diag
+            }
+            DefCollectorErrorKind::UnsatisfiedSelfConstraint {
+                trait_name,
+                required_trait,
+                typ,
+                span,
+            } => {
+                let secondary = format!(
+                    "`{trait_name}` can only be implemented by types implementing `{required_trait}`"
+                );
+                let mut diag = Diagnostic::simple_error(
+                    format!("the trait bound `{typ}: {required_trait}` is not satisfied"),
+                    secondary,
+                    span,
+                );
+                diag.add_note(format!("required by `Self: {required_trait}` on `{trait_name}`"));
+                diag
+            }
+            DefCollectorErrorKind::UnsatisfiedOpaqueReturnType {
+                method_name,
+                opaque_type,
+                required_trait,
+                typ,
+                span,
+            } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("the trait bound `{typ}: {required_trait}` is not satisfied"),
+                    format!("`{method_name}` must return a type implementing `{required_trait}`"),
+                    span,
+                );
+                diag.add_note(format!("required by the return type `{opaque_type}` of the trait's `{method_name}`"));
+                diag
+            }
+            DefCollectorErrorKind::AssociatedTypeGenericCountMismatch {
+                trait_name,
+                item_name,
+                expected,
+                found,
+            } => {
+                let plural = if expected == 1 { "" } else { "s" };
+                Diagnostic::simple_error(
+                    format!(
+                        "associated type `{item_name}` does not match its declaration in trait `{trait_name}`"
+                    ),
+                    format!("expected {expected} generic{plural} but found {found}"),
+                    item_name.span(),
+                )
+            }
+            DefCollectorErrorKind::IgnoredInlineHint { method_name, hint, reason } => {
+                Diagnostic::simple_warning(
+                    format!("`#[inline({hint})]` on method `{method_name}` has no effect"),
+                    reason.to_string(),
+                    method_name.span(),
+                )
+            }
+            DefCollectorErrorKind::MissingWhereBound { method_name, bound, span } => {
+                Diagnostic::simple_warning(
+                    format!("default method `{method_name}` requires `{bound}`"),
+                    format!("consider adding `where {bound}`"),
+                    span,
+                )
+            }
+            DefCollectorErrorKind::MissingAssociatedTypeBound {
+                trait_name,
+                item_name,
+                bound,
+                span,
+            } => Diagnostic::simple_warning(
+                format!("associated type `{trait_name}::{item_name}` requires `{bound}`"),
+                format!("consider declaring `type {item_name}: {bound};`"),
+                span,
+            ),
+            DefCollectorErrorKind::ShadowedSupertraitMethod {
+                method_name,
+                signature,
+                supertrait_name,
+                supertrait_signature,
+                supertrait_span,
+            } => {
+                let mut diag = Diagnostic::simple_warning(
+                    format!(
+                        "method `{method_name}` shadows `{supertrait_name}::{method_name}` with a different signature"
+                    ),
+                    format!("`{signature}` is declared here"),
+                    method_name.span(),
+                );
+                diag.add_secondary(
+                    format!("`{supertrait_signature}` is declared by `{supertrait_name}` here"),
+                    supertrait_span,
+                );
+                diag.add_note(
+                    "if this is intended, confirm that callers should see both methods; otherwise consider renaming one of them".into(),
+                );
+                diag
+            }
+            DefCollectorErrorKind::PhantomTraitGeneric { trait_name, generic } => {
+                let mut diag = Diagnostic::simple_warning(
+                    format!("generic `{generic}` of trait `{trait_name}` is never used"),
+                    "no item, where clause or supertrait refers to it".to_string(),
+                    generic.span(),
+                );
+                diag.add_note(
+                    "implementors must still choose a type for it, though nothing depends on it"
+                        .into(),
+                );
+                diag
+            }
+            DefCollectorErrorKind::UnusedSelf { method_name, receiver_span } => {
+                let mut diag = Diagnostic::simple_warning(
+                    format!("method `{method_name}` never uses its `self` receiver"),
+                    format!(
+                        "consider removing `self` to make `{method_name}` an associated function"
+                    ),
+                    receiver_span,
+                );
+                diag.add_note("this warning can be silenced with `#[allow(unused_self)]`".into());
+                diag
+            }
         }
     }
 }
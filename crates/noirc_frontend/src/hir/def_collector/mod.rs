@@ -17,6 +17,8 @@
 //! are defined and linked, and convert the definition into Hir.
 //!
 //! These passes are performed sequentially (along with type checking afterward) in dc_crate.
+mod const_fold;
 pub mod dc_crate;
 pub mod dc_mod;
+pub mod derive;
 mod errors;
@@ -2,14 +2,16 @@ pub mod def_collector;
 pub mod def_map;
 pub mod resolution;
 pub mod scope;
+pub mod trait_impls;
 pub mod type_check;
 
 use crate::graph::{CrateGraph, CrateId, Dependency};
 use crate::hir_def::function::FuncMeta;
 use crate::node_interner::{FuncId, NodeInterner, StructId};
+use def_collector::derive::DeriveRegistry;
 use def_map::{Contract, CrateDefMap};
 use fm::FileManager;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use self::def_map::TestFunction;
 
@@ -25,6 +27,12 @@ pub struct Context {
     /// Maps a given (contract) module id to the next available storage slot
     /// for that contract.
     pub storage_slots: HashMap<def_map::ModuleId, StorageSlot>,
+
+    /// The traits which may be derived on structs via `#[derive(...)]`.
+    pub derive_registry: DeriveRegistry,
+
+    /// The features which are active when evaluating `#[cfg(...)]` attributes.
+    pub cfg_features: HashSet<String>,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -44,6 +52,8 @@ impl Context {
             crate_graph,
             file_manager,
             storage_slots: HashMap::new(),
+            derive_registry: DeriveRegistry::default(),
+            cfg_features: HashSet::new(),
         }
     }
 
@@ -40,6 +40,8 @@ pub enum ResolverError {
     UnnecessaryPub { ident: Ident, position: PubPosition },
     #[error("Required 'pub', main function must return public value")]
     NecessaryPub { ident: Ident },
+    #[error("'pub' is only allowed on the methods of inherent impls")]
+    PubOutsideInherentImpl { ident: Ident },
     #[error("'distinct' keyword can only be used with main method")]
     DistinctNotAllowed { ident: Ident },
     #[error("Missing expression for declared constant")]
@@ -62,6 +64,8 @@ pub enum ResolverError {
     GenericsOnSelfType { span: Span },
     #[error("Incorrect amount of arguments to generic type constructor")]
     IncorrectGenericCount { span: Span, struct_type: String, actual: usize, expected: usize },
+    #[error("Incorrect amount of generic arguments to trait")]
+    IncorrectTraitGenericCount { span: Span, trait_name: String, expected: usize, found: usize },
     #[error("{0}")]
     ParserError(Box<ParserError>),
     #[error("Function is not defined in a contract yet sets its contract visibility")]
@@ -76,6 +80,18 @@ pub enum ResolverError {
     NumericConstantInFormatString { name: String, span: Span },
     #[error("Closure environment must be a tuple or unit type")]
     InvalidClosureEnvironment { typ: Type, span: Span },
+    #[error("Associated type projection outside of an impl's object type")]
+    UnexpectedProjection { span: Span },
+    #[error("Trait is not object safe")]
+    NotObjectSafe { trait_name: String, reason: String, item_span: Span, span: Span },
+    #[error("Trait objects are not supported")]
+    TraitObjectsUnsupported { span: Span },
+    #[error("Opaque type outside of a trait method")]
+    UnexpectedOpaqueType { span: Span },
+    #[error("Use of undeclared generic")]
+    UndeclaredGeneric { name: String, span: Span },
+    #[error("Method used as a function")]
+    MethodUsedAsFunction { name: String, span: Span },
 }
 
 impl ResolverError {
@@ -194,6 +210,18 @@ impl From<ResolverError> for Diagnostic {
                 diag.add_note("The `pub` keyword is mandatory for the entry-point function return type because the verifier cannot retrieve private witness and thus the function will not be able to return a 'priv' value".to_owned());
                 diag
             }
+            ResolverError::PubOutsideInherentImpl { ident } => {
+                let name = &ident.0.contents;
+
+                let mut diag = Diagnostic::simple_error(
+                    format!("Invalid `pub` keyword on function {name}"),
+                    "invalid pub on function".to_string(),
+                    ident.0.span(),
+                );
+
+                diag.add_note("Free functions and the methods of traits are always visible, so `pub` is only allowed on the methods of inherent impls, which are private to the module defining them without it".to_owned());
+                diag
+            }
             ResolverError::DistinctNotAllowed { ident } => {
                 let name = &ident.0.contents;
 
@@ -263,6 +291,15 @@ impl From<ResolverError> for Diagnostic {
                     span,
                 )
             }
+            ResolverError::IncorrectTraitGenericCount { span, trait_name, expected, found } => {
+                let expected_plural = if expected == 1 { "" } else { "s" };
+
+                Diagnostic::simple_error(
+                    format!("trait `{trait_name}` expects {expected} generic argument{expected_plural}, found {found}"),
+                    "Incorrect number of generic arguments".into(),
+                    span,
+                )
+            }
             ResolverError::ParserError(error) => (*error).into(),
             ResolverError::ContractFunctionTypeInNormalFunction { span } => Diagnostic::simple_error(
                 "Only functions defined within contracts can set their contract function type".into(),
@@ -288,6 +325,40 @@ impl From<ResolverError> for Diagnostic {
             ResolverError::InvalidClosureEnvironment { span, typ } => Diagnostic::simple_error(
                 format!("{typ} is not a valid closure environment type"),
                 "Closure environment must be a tuple or unit type".to_string(), span),
+            ResolverError::UnexpectedProjection { span } => Diagnostic::simple_error(
+                "associated type projections are not supported here".into(),
+                "projections may currently only be used as the object type of an impl".into(),
+                span,
+            ),
+            ResolverError::NotObjectSafe { trait_name, reason, item_span, span } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("trait `{trait_name}` cannot be made into an object"),
+                    format!("`{trait_name}` is not object safe"),
+                    span,
+                );
+                diag.add_secondary(format!("{reason}, so `{trait_name}` is not object safe"), item_span);
+                diag
+            }
+            ResolverError::TraitObjectsUnsupported { span } => Diagnostic::simple_error(
+                "trait objects are not yet supported".into(),
+                "consider using a generic constrained by the trait instead".into(),
+                span,
+            ),
+            ResolverError::UnexpectedOpaqueType { span } => Diagnostic::simple_error(
+                "`impl Trait` types are only supported as the return type of trait methods".into(),
+                "each impl of the method must return a concrete type instead".into(),
+                span,
+            ),
+            ResolverError::UndeclaredGeneric { name, span } => Diagnostic::simple_error(
+                format!("use of undeclared generic `{name}`"),
+                format!("consider adding `{name}` to the generics of the method or its trait"),
+                span,
+            ),
+            ResolverError::MethodUsedAsFunction { name, span } => Diagnostic::simple_error(
+                format!("cannot find function `{name}` in this scope"),
+                format!("`{name}` is a method of `Self`, consider calling it as `self.{name}(..)`"),
+                span,
+            ),
         }
     }
 }
@@ -34,10 +34,10 @@ use crate::{
     Statement,
 };
 use crate::{
-    ArrayLiteral, ContractFunctionType, Distinctness, Generics, LValue, NoirStruct, NoirTypeAlias,
-    Path, Pattern, Shared, StructType, Trait, Type, TypeAliasType, TypeBinding, TypeVariable,
-    UnaryOp, UnresolvedGenerics, UnresolvedType, UnresolvedTypeData, UnresolvedTypeExpression,
-    Visibility, ERROR_IDENT,
+    ArrayLiteral, ContractFunctionType, Distinctness, GenericPredicate, Generics, LValue,
+    NoirStruct, NoirTypeAlias, Path, Pattern, Shared, StructType, Trait, TraitBound,
+    TraitConstraint, Type, TypeAliasType, TypeBinding, TypeVariable, UnaryOp, UnresolvedGenerics,
+    UnresolvedType, UnresolvedTypeData, UnresolvedTypeExpression, Visibility, ERROR_IDENT,
 };
 use fm::FileId;
 use iter_extended::vecmap;
@@ -47,7 +47,7 @@ use crate::hir::scope::{
     Scope as GenericScope, ScopeForest as GenericScopeForest, ScopeTree as GenericScopeTree,
 };
 use crate::hir_def::{
-    function::{FuncMeta, HirFunction},
+    function::{FuncMeta, HirFunction, HirGenericPredicate, HirTraitConstraint},
     stmt::{HirConstrainStatement, HirLetStatement, HirStatement},
 };
 
@@ -83,12 +83,20 @@ pub struct Resolver<'a> {
     /// Set to the current type if we're resolving an impl
     self_type: Option<Type>,
 
+    /// The names of the methods of the impl being resolved, if any, so that calling one of them
+    /// as though it were a free function can be reported as such
+    self_methods: Vec<String>,
+
     /// Contains a mapping of the current struct or functions's generics to
     /// unique type variables if we're resolving a struct. Empty otherwise.
     /// This is a Vec rather than a map to preserve the order a functions generics
     /// were declared in.
     generics: Vec<(Rc<String>, TypeVariable, Span)>,
 
+    /// The function type each generic bounded by `Fn` in the current function's where clause is
+    /// resolved as, such as `fn(Field) -> Field` for `F: Fn(Field) -> Field`
+    fn_bounds: Vec<(String, Type)>,
+
     /// When resolving lambda expressions, we need to keep track of the variables
     /// that are captured. We do this in order to create the hidden environment
     /// parameter for the lambda function.
@@ -116,7 +124,9 @@ impl<'a> Resolver<'a> {
             scopes: ScopeForest::default(),
             interner,
             self_type: None,
+            self_methods: Vec::new(),
             generics: Vec::new(),
+            fn_bounds: Vec::new(),
             errors: Vec::new(),
             lambda_stack: Vec::new(),
             file,
@@ -127,6 +137,10 @@ impl<'a> Resolver<'a> {
         self.self_type = self_type;
     }
 
+    pub fn set_self_methods(&mut self, self_methods: Vec<String>) {
+        self.self_methods = self_methods;
+    }
+
     fn push_err(&mut self, err: ResolverError) {
         self.errors.push(err);
     }
@@ -392,6 +406,31 @@ impl<'a> Resolver<'a> {
             MutableReference(element) => {
                 Type::MutableReference(Box::new(self.resolve_type_inner(*element, new_variables)))
             }
+            TraitObject(bound) => {
+                let span = typ.span.unwrap_or_default();
+                self.resolve_trait_object(&bound, span);
+                Type::Error
+            }
+            // Opaque types written by the user have no concrete type, which is only filled in
+            // for the impls of trait methods returning them
+            Opaque { bounds, concrete_type: Some(concrete_type) } => {
+                let name = format!("impl {}", vecmap(&bounds, ToString::to_string).join(" + "));
+                let traits = self.resolve_opaque_bounds(&bounds);
+                let concrete_type = self.resolve_type_inner(*concrete_type, new_variables);
+                Type::Opaque(traits, Rc::new(name), Box::new(concrete_type))
+            }
+            Opaque { bounds, concrete_type: None } => {
+                self.resolve_opaque_bounds(&bounds);
+                let span = typ.span.unwrap_or_default();
+                self.push_err(ResolverError::UnexpectedOpaqueType { span });
+                Type::Error
+            }
+            // Projections in impl object types are normalized before they reach the resolver
+            Projection { .. } => {
+                let span = typ.span.unwrap_or_default();
+                self.push_err(ResolverError::UnexpectedProjection { span });
+                Type::Error
+            }
         }
     }
 
@@ -478,6 +517,9 @@ impl<'a> Resolver<'a> {
     fn lookup_generic_or_global_type(&mut self, path: &Path) -> Option<Type> {
         if path.segments.len() == 1 {
             let name = &path.last_segment().0.contents;
+            if let Some((_, typ)) = self.fn_bounds.iter().find(|(bounded, _)| bounded == name) {
+                return Some(typ.clone());
+            }
             if let Some((name, var, _)) = self.find_generic(name) {
                 return Some(Type::NamedGeneric(var.clone(), name.clone()));
             }
@@ -556,6 +598,15 @@ impl<'a> Resolver<'a> {
                 Err(error) => error,
             },
         };
+        // Methods are only found through `self`, so a bare `foo` naming one is reported as such
+        let error = match error {
+            ResolverError::VariableNotDeclared { name, span }
+                if self.self_methods.contains(&name) =>
+            {
+                ResolverError::MethodUsedAsFunction { name, span }
+            }
+            error => error,
+        };
         self.push_err(error);
         let id = DefinitionId::dummy_id();
         (HirIdent { location, id }, 0)
@@ -679,6 +730,8 @@ impl<'a> Resolver<'a> {
                 }
             });
 
+        self.resolve_fn_bounds(&func.def.where_clause, &mut generics);
+
         let mut parameters = vec![];
         let mut parameter_types = vec![];
 
@@ -722,6 +775,10 @@ impl<'a> Resolver<'a> {
             self.push_err(ResolverError::DistinctNotAllowed { ident: func.name_ident().clone() });
         }
 
+        self.check_trait_constraints(&func.def.where_clause);
+        let trait_constraints = self.resolve_trait_constraints(&func.def.where_clause);
+        let generic_predicates = self.resolve_generic_predicates(&func.def.where_predicates);
+
         if matches!(attributes, Some(Attribute::Test { .. })) && !parameters.is_empty() {
             self.push_err(ResolverError::TestFunctionHasParameters {
                 span: func.name_ident().span(),
@@ -744,8 +801,12 @@ impl<'a> Resolver<'a> {
             contract_function_type: self.handle_function_type(func),
             is_internal: self.handle_is_function_internal(func),
             is_unconstrained: func.def.is_unconstrained,
+            // Set when resolving the methods of an impl, as only those of inherent impls are private
+            is_private: false,
             location,
             typ,
+            trait_constraints,
+            generic_predicates,
             parameters: parameters.into(),
             return_type: func.def.return_type.clone(),
             return_visibility: func.def.return_visibility,
@@ -754,6 +815,213 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Checks that each trait bound in `where_clause` is given as many generic arguments as its
+    /// trait declares. Bounds on traits which cannot be found are left unchecked.
+    pub fn check_trait_constraints(&mut self, where_clause: &[TraitConstraint]) {
+        for constraint in where_clause {
+            self.check_trait_bound(&constraint.trait_bound);
+        }
+    }
+
+    /// Resolves each `Fn` bound on a generic in scope, such as `F: Fn(Field) -> Field`, so that
+    /// the generic is resolved as a function of that signature wherever it is used. Closures
+    /// passed for the generic are then checked against the signature like any other argument.
+    /// Each closure captures a different environment, so the environment of the function type
+    /// is a new generic which is appended to `new_variables`.
+    pub fn resolve_fn_bounds(
+        &mut self,
+        where_clause: &[TraitConstraint],
+        new_variables: &mut Generics,
+    ) {
+        for constraint in where_clause {
+            let Some((args, ret)) = constraint.trait_bound.fn_signature() else { continue };
+            let UnresolvedTypeData::Named(path, generics) = &constraint.typ.typ else { continue };
+            if !generics.is_empty() || path.segments.len() != 1 {
+                continue;
+            }
+            let name = path.segments[0].0.contents.clone();
+            if self.find_generic(&name).is_none()
+                || self.fn_bounds.iter().any(|(bounded, _)| *bounded == name)
+            {
+                continue;
+            }
+
+            let args = vecmap(args, |arg| self.resolve_type_inner(arg.clone(), new_variables));
+            let ret = Box::new(self.resolve_type_inner(ret.clone(), new_variables));
+
+            let id = self.interner.next_type_variable_id();
+            let typevar = Shared::new(TypeBinding::Unbound(id));
+            new_variables.push((id, typevar.clone()));
+            let env = Box::new(Type::NamedGeneric(typevar, Rc::new(format!("{name}Env"))));
+
+            self.fn_bounds.push((name, Type::Function(args, ret, env)));
+        }
+    }
+
+    /// Resolves each constraint of `where_clause` on a generic in scope, such as `T: Eq`, so that
+    /// it can be checked once the generic is instantiated. Negative bounds, constraints on any
+    /// other type and constraints naming traits which cannot be found are skipped.
+    fn resolve_trait_constraints(
+        &mut self,
+        where_clause: &[TraitConstraint],
+    ) -> Vec<HirTraitConstraint> {
+        let mut constraints = Vec::new();
+        for constraint in where_clause {
+            // `Fn` bounds are checked through the function type given by `resolve_fn_bounds`
+            if constraint.trait_bound.negative || constraint.trait_bound.fn_signature().is_some() {
+                continue;
+            }
+
+            let UnresolvedTypeData::Named(path, generics) = &constraint.typ.typ else { continue };
+            if !generics.is_empty() || path.segments.len() != 1 {
+                continue;
+            }
+            let Some((name, typevar, _)) = self.find_generic(&path.segments[0].0.contents) else {
+                continue;
+            };
+            let typ = Type::NamedGeneric(typevar.clone(), name.clone());
+
+            let path = Path::from_ident(constraint.trait_bound.trait_name.clone());
+            if let Ok(trait_id) = self.lookup::<TraitId>(path) {
+                constraints.push(HirTraitConstraint { typ, trait_id });
+            }
+        }
+        constraints
+    }
+
+    /// Resolves each constraint of `where_clause`, the where clause of a trait impl, on any type
+    /// such as `T: Eq` or `Wrapper<T>: Eq`, so that the impl is only taken to apply to a type
+    /// once its constraints hold for it. Negative bounds, `Fn` bounds and constraints naming
+    /// traits which cannot be found are skipped.
+    pub fn resolve_impl_constraints(
+        &mut self,
+        where_clause: &[TraitConstraint],
+    ) -> Vec<HirTraitConstraint> {
+        let mut constraints = Vec::new();
+        for constraint in where_clause {
+            if constraint.trait_bound.negative || constraint.trait_bound.fn_signature().is_some() {
+                continue;
+            }
+
+            let path = Path::from_ident(constraint.trait_bound.trait_name.clone());
+            if let Ok(trait_id) = self.lookup::<TraitId>(path) {
+                let typ = self.resolve_type(constraint.typ.clone());
+                constraints.push(HirTraitConstraint { typ, trait_id });
+            }
+        }
+        constraints
+    }
+
+    /// Resolves the generic compared by each predicate of a where clause, such as `N > 0`, so
+    /// that it can be checked once the generic is instantiated.
+    fn resolve_generic_predicates(
+        &mut self,
+        predicates: &[GenericPredicate],
+    ) -> Vec<HirGenericPredicate> {
+        let mut resolved = Vec::new();
+        for predicate in predicates {
+            let name = &predicate.generic;
+            match self.find_generic(&name.0.contents) {
+                Some((generic_name, typevar, _)) => resolved.push(HirGenericPredicate {
+                    typ: Type::NamedGeneric(typevar.clone(), generic_name.clone()),
+                    predicate: predicate.clone(),
+                }),
+                None => self.push_err(ResolverError::UndeclaredGeneric {
+                    name: name.0.contents.clone(),
+                    span: name.span(),
+                }),
+            }
+        }
+        resolved
+    }
+
+    /// Resolves the trait of each constraint of `where_clause` on `Self`, such as `Self: Clone`,
+    /// which every type implementing the trait declaring the where clause must also implement.
+    /// Negative bounds and constraints naming traits which cannot be found are skipped.
+    pub fn resolve_self_constraints(&mut self, where_clause: &[TraitConstraint]) -> Vec<TraitId> {
+        let mut traits = Vec::new();
+        for constraint in where_clause {
+            if constraint.trait_bound.negative || !constraint.typ.is_self_type() {
+                continue;
+            }
+
+            let path = Path::from_ident(constraint.trait_bound.trait_name.clone());
+            if let Ok(trait_id) = self.lookup::<TraitId>(path) {
+                traits.push(trait_id);
+            }
+        }
+        traits
+    }
+
+    /// Checks that the trait of the trait object `dyn bound` exists and is object safe, reporting
+    /// each item which prevents it from being used as a trait object. Trait objects can't be
+    /// executed yet, so those of object safe traits are reported as unsupported instead.
+    fn resolve_trait_object(&mut self, bound: &TraitBound, span: Span) {
+        let path = Path::from_ident(bound.trait_name.clone());
+        let trait_id = match self.lookup::<TraitId>(path) {
+            Ok(trait_id) => trait_id,
+            Err(error) => {
+                self.push_err(error);
+                return;
+            }
+        };
+        self.check_trait_bound(bound);
+
+        let violations = self.get_trait(trait_id).borrow().object_safety_violations.clone();
+        if violations.is_empty() {
+            self.push_err(ResolverError::TraitObjectsUnsupported { span });
+        }
+        for violation in violations {
+            self.push_err(ResolverError::NotObjectSafe {
+                trait_name: bound.trait_name.to_string(),
+                reason: violation.to_string(),
+                item_span: violation.item().span(),
+                span,
+            });
+        }
+    }
+
+    /// Checks that `bound` is given as many generic arguments as its trait declares.
+    /// Bounds on traits which cannot be found are left unchecked.
+    /// Resolves the traits named by the bounds of an opaque type, as in `impl Iterator`.
+    pub fn resolve_opaque_bounds(&mut self, bounds: &[TraitBound]) -> Vec<TraitId> {
+        let mut traits = Vec::new();
+        for bound in bounds {
+            let path = Path::from_ident(bound.trait_name.clone());
+            match self.lookup::<TraitId>(path) {
+                Ok(trait_id) => traits.push(trait_id),
+                Err(error) => self.push_err(error),
+            }
+            self.check_trait_bound(bound);
+        }
+        traits
+    }
+
+    pub fn check_trait_bound(&mut self, bound: &TraitBound) {
+        if bound.fn_signature().is_some() {
+            return;
+        }
+        let path = Path::from_ident(bound.trait_name.clone());
+        let Ok(trait_id) = self.lookup::<TraitId>(path) else { return };
+
+        let expected = self.get_trait(trait_id).borrow().generics.len();
+        let found = bound.trait_generics.len();
+        if expected != found {
+            let span = bound
+                .trait_generics
+                .iter()
+                .filter_map(|generic| generic.span)
+                .fold(bound.trait_name.span(), Span::merge);
+            let trait_name = bound.trait_name.to_string();
+            self.push_err(ResolverError::IncorrectTraitGenericCount {
+                span,
+                trait_name,
+                expected,
+                found,
+            });
+        }
+    }
+
     /// True if the 'pub' keyword is allowed on parameters in this function
     fn pub_allowed(&self, func: &NoirFunction) -> bool {
         if self.in_contract() {
@@ -880,7 +1148,9 @@ impl<'a> Resolver<'a> {
                     }
                 }
             }
-            Type::MutableReference(element) => Self::find_numeric_generics_in_type(element, found),
+            Type::MutableReference(element) | Type::Opaque(_, _, element) => {
+                Self::find_numeric_generics_in_type(element, found);
+            }
             Type::String(length) => {
                 if let Type::NamedGeneric(type_variable, name) = length.as_ref() {
                     found.insert(name.to_string(), type_variable.clone());
@@ -1511,7 +1781,7 @@ mod test {
 
     use fm::FileId;
     use iter_extended::vecmap;
-    use noirc_errors::Location;
+    use noirc_errors::{Location, Span};
 
     use crate::hir::def_map::{ModuleData, ModuleId};
     use crate::hir::resolution::errors::ResolverError;
@@ -1523,11 +1793,11 @@ mod test {
     use crate::hir_def::function::HirFunction;
     use crate::hir_def::stmt::HirStatement;
     use crate::node_interner::{FuncId, NodeInterner};
-    use crate::ParsedModule;
     use crate::{
         hir::def_map::{CrateDefMap, LocalModuleId, ModuleDefId},
         parse_program, Path,
     };
+    use crate::{FunctionDefinition, NoirFunction, ParsedModule, TraitItem};
 
     use super::{PathResolver, Resolver};
 
@@ -1855,6 +2125,59 @@ mod test {
         }
     }
 
+    #[test]
+    fn resolve_respanned_trait_item_reports_call_site() {
+        let src = r#"
+            trait Foo {
+                fn foo() -> Field {
+                    x
+                }
+            }
+        "#;
+
+        let (mut program, mut interner, def_maps, file, path_resolver) =
+            init_src_code_resolution(src);
+
+        // Emulate a macro expanding the trait's default method at some other call site
+        let call_site = Span::inclusive(1000, 1003);
+        let item = program.traits.remove(0).items.remove(0).with_span(call_site);
+        let TraitItem::Function {
+            attributes: _,
+            name,
+            generics,
+            parameters,
+            return_type,
+            where_clause,
+            body: Some(body),
+        } = item
+        else {
+            panic!("Expected a trait method with a default body");
+        };
+        let func = NoirFunction::normal(FunctionDefinition::normal(
+            &name,
+            &generics,
+            &parameters,
+            &body,
+            &where_clause,
+            &return_type,
+        ));
+
+        let id = interner.push_fn(HirFunction::empty());
+        interner.push_function_definition(func.name().to_string(), id);
+
+        let resolver = Resolver::new(&mut interner, &path_resolver, &def_maps, file);
+        let (_, _, errors) = resolver.resolve_function(func, id, ModuleId::dummy_id());
+
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        match &errors[0] {
+            ResolverError::VariableNotDeclared { name, span } => {
+                assert_eq!(name, "x");
+                assert_eq!(*span, call_site);
+            }
+            other => panic!("Unexpected error: {:?}", other),
+        }
+    }
+
     #[test]
     fn resolve_basic_closure() {
         let src = r#"
@@ -0,0 +1,626 @@
+//! The resolved trait impls of the crates being compiled, which answer whether a type implements
+//! a trait when checking trait constraints, and which impl's method a method call refers to.
+//!
+//! Impls are stored once, in the order they are resolved, and indexed by a [`DispatchTable`] on
+//! their trait and the head of their object type so that only the impls which may apply to a
+//! type are matched against it.
+//!
+//! Both the object types of impls and the types matched against them are normalized first, so
+//! that types which are written differently but are the same match the same impls. Type aliases
+//! are already expanded when types are resolved, so `impl Foo for MyAlias` is matched as an impl
+//! for the aliased type, and normalizing leaves the bindings of type variables to be followed.
+//!
+//! An impl only applies to a type once its where clause holds for it, which is checked in turn
+//! against the impls of each trait required. Where clauses such as that of
+//! `impl<T> Foo for Wrapper<T> where Wrapper<Wrapper<T>>: Foo` require ever larger types to
+//! implement the same trait, so checks recursing more deeply than a limit are reported as a
+//! [`TraitResolutionOverflow`] instead.
+//!
+//! The same constraints are checked many times over, once for each call of a function with a
+//! where clause, so whether a fully known type implements a trait is cached until another impl
+//! is added.
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use iter_extended::vecmap;
+
+use super::type_check::is_unknown;
+use crate::ast::dispatch_table::{DispatchTable, TypeHead};
+use crate::hir_def::function::HirTraitConstraint;
+use crate::node_interner::{FuncId, NodeInterner, StructId, TraitId};
+use crate::{Type, TypeBinding, TypeBindings};
+
+/// How deeply checking whether a type implements a trait may recurse through the where clauses
+/// of impls, unless another limit is set with [`TraitImplementations::set_depth_limit`].
+pub const DEFAULT_RESOLUTION_DEPTH: usize = 32;
+
+/// A resolved impl of a trait.
+#[derive(Debug, Clone)]
+pub struct TraitImplementation {
+    pub trait_id: TraitId,
+    /// The type the trait is implemented for. Types of generic impls contain the impl's
+    /// generics, e.g. `[T; 2]` for `impl<T> Eq for [T; 2]`.
+    pub object_type: Type,
+    /// The constraints the impl's where clause places on its generics, as in `T: Eq` or
+    /// `Wrapper<T>: Eq`
+    pub where_clause: Vec<HirTraitConstraint>,
+    /// The impl's methods by name, including the trait's default methods it doesn't override
+    pub methods: HashMap<String, FuncId>,
+}
+
+/// A check of whether a type implements a trait which recursed through the where clauses of
+/// impls more deeply than the depth limit.
+#[derive(Debug, Clone)]
+pub struct TraitResolutionOverflow {
+    pub limit: usize,
+    /// Each trait checked and the type it was checked for, from the original check to the one
+    /// exceeding the limit
+    pub chain: Vec<(TraitId, Type)>,
+}
+
+impl TraitResolutionOverflow {
+    /// Describes the chain of checks, as in
+    /// `Wrapper<u8>: Foo, requiring Wrapper<Wrapper<u8>>: Foo`.
+    pub fn describe_chain(&self, interner: &NodeInterner) -> String {
+        let checks = vecmap(&self.chain, |(trait_id, typ)| {
+            format!("{typ}: {}", interner.get_trait(*trait_id).borrow().name)
+        });
+        checks.join(", requiring ")
+    }
+}
+
+/// Why [`TraitImplementations::select_method`] couldn't choose a method.
+#[derive(Debug, Clone)]
+pub enum MethodSelectionError<'a> {
+    /// The method is defined by several impls which apply to the type, none of which is more
+    /// specific than the others
+    Ambiguous(Vec<&'a TraitImplementation>),
+    /// Checking whether an impl defining the method applies to the type recursed too deeply
+    Overflow(TraitResolutionOverflow),
+}
+
+/// How many checks of whether a type implements a trait were answered by the cache of
+/// [`TraitImplementations`], for diagnosing how well it works.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// The outermost type constructor of a resolved type. Structs are told apart by their ids rather
+/// than by name as in the Ast.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ResolvedTypeHead {
+    Builtin(TypeHead),
+    Struct(StructId),
+}
+
+impl ResolvedTypeHead {
+    /// Returns the head of `typ`, a resolved type with its bindings followed, or `None` if the
+    /// head is still unknown or could be any head, as for type variables and generics. This is
+    /// also the head of an impl whose object type is `typ`.
+    fn of(typ: &Type) -> Option<ResolvedTypeHead> {
+        let head = match typ {
+            Type::FieldElement => TypeHead::Field,
+            Type::Integer(sign, bits) => TypeHead::Integer(*sign, *bits),
+            Type::Bool => TypeHead::Bool,
+            Type::Unit => TypeHead::Unit,
+            Type::Array(length, _) => match length.as_ref() {
+                Type::NotConstant => TypeHead::Slice,
+                // The length may yet be bound to that of a slice
+                Type::TypeVariable(..) => return None,
+                _ => TypeHead::Array,
+            },
+            Type::String(_) => TypeHead::String,
+            Type::FmtString(..) => TypeHead::FormatString,
+            Type::Tuple(elements) => TypeHead::Tuple(elements.len()),
+            Type::Function(..) => TypeHead::Function,
+            Type::MutableReference(_) => TypeHead::MutableReference,
+            Type::Struct(struct_type, _) => {
+                return Some(ResolvedTypeHead::Struct(struct_type.borrow().id))
+            }
+            Type::TypeVariable(..)
+            | Type::NamedGeneric(..)
+            | Type::Opaque(..)
+            | Type::Forall(..)
+            | Type::Constant(_)
+            | Type::NotConstant
+            | Type::Error => return None,
+        };
+        Some(ResolvedTypeHead::Builtin(head))
+    }
+}
+
+#[derive(Debug)]
+pub struct TraitImplementations {
+    impls: Vec<TraitImplementation>,
+    /// The index of each impl within `impls`, by its trait and the head of its object type
+    dispatch: DispatchTable<TraitId, ResolvedTypeHead>,
+    /// The traits with an impl defining each method name
+    traits_by_method: HashMap<String, Vec<TraitId>>,
+    /// The index of the impl each method belongs to
+    method_impls: HashMap<FuncId, usize>,
+    /// How many checks of where clauses a check of whether a type implements a trait may
+    /// be nested within
+    depth_limit: usize,
+    /// Whether each type without unknown parts implements each trait it has been checked for
+    cache: RefCell<HashMap<(TraitId, Type), bool>>,
+    cache_stats: Cell<CacheStats>,
+}
+
+impl Default for TraitImplementations {
+    fn default() -> Self {
+        TraitImplementations {
+            impls: Vec::new(),
+            dispatch: DispatchTable::default(),
+            traits_by_method: HashMap::new(),
+            method_impls: HashMap::new(),
+            depth_limit: DEFAULT_RESOLUTION_DEPTH,
+            cache: RefCell::default(),
+            cache_stats: Cell::default(),
+        }
+    }
+}
+
+impl TraitImplementations {
+    /// Records that `object_type` implements the trait `trait_id` wherever `where_clause` holds,
+    /// returning the index of the impl to add its methods with.
+    pub fn add(
+        &mut self,
+        trait_id: TraitId,
+        object_type: Type,
+        where_clause: Vec<HirTraitConstraint>,
+    ) -> usize {
+        let object_type = normalize_type(&object_type);
+        let where_clause = vecmap(where_clause, |constraint| HirTraitConstraint {
+            typ: normalize_type(&constraint.typ),
+            trait_id: constraint.trait_id,
+        });
+        let index = self.impls.len();
+        self.cache.get_mut().clear();
+        self.dispatch.insert(trait_id, index, ResolvedTypeHead::of(&object_type));
+        self.impls.push(TraitImplementation {
+            trait_id,
+            object_type,
+            where_clause,
+            methods: HashMap::new(),
+        });
+        index
+    }
+
+    /// Sets how deeply checks of whether a type implements a trait may recurse through the
+    /// where clauses of impls before being reported as a [`TraitResolutionOverflow`].
+    pub fn set_depth_limit(&mut self, depth_limit: usize) {
+        self.cache.get_mut().clear();
+        self.depth_limit = depth_limit;
+    }
+
+    /// Returns how many checks of whether a type implements a trait have been answered by the
+    /// cache, and how many had to be computed.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache_stats.get()
+    }
+
+    /// Adds `method_id`, named `method_name`, to the methods of the impl at `impl_index`.
+    pub fn add_method(&mut self, impl_index: usize, method_name: String, method_id: FuncId) {
+        let trait_impl = &mut self.impls[impl_index];
+        let traits = self.traits_by_method.entry(method_name.clone()).or_default();
+        if !traits.contains(&trait_impl.trait_id) {
+            traits.push(trait_impl.trait_id);
+        }
+        trait_impl.methods.insert(method_name, method_id);
+        self.method_impls.insert(method_id, impl_index);
+    }
+
+    /// Returns the impl `method_id` is a method of, if it is the method of a trait impl.
+    pub fn impl_of_method(&self, method_id: &FuncId) -> Option<&TraitImplementation> {
+        self.method_impls.get(method_id).map(|index| &self.impls[*index])
+    }
+
+    /// Returns the impls of `trait_id` which may apply to `typ`, a normalized type, in the order
+    /// they were added.
+    fn candidates<'a>(
+        &'a self,
+        trait_id: &TraitId,
+        typ: &Type,
+    ) -> impl Iterator<Item = &'a TraitImplementation> {
+        let head = ResolvedTypeHead::of(typ);
+        self.dispatch.candidates(trait_id, head.as_ref()).map(|index| &self.impls[index])
+    }
+
+    /// Returns whether `typ` implements the trait `trait_id`. Types which aren't known yet are
+    /// assumed to implement it, as are parts of types which aren't known yet.
+    pub fn implements(
+        &self,
+        trait_id: TraitId,
+        typ: &Type,
+    ) -> Result<bool, TraitResolutionOverflow> {
+        let typ = normalize_type(typ);
+        // Type variables within a type may yet be bound, changing the type and its hash
+        if has_unknown_parts(&typ) {
+            return self.implements_within(trait_id, &typ, &mut Vec::new());
+        }
+
+        let mut stats = self.cache_stats.get();
+        let key = (trait_id, typ);
+        if let Some(implemented) = self.cache.borrow().get(&key) {
+            stats.hits += 1;
+            self.cache_stats.set(stats);
+            return Ok(*implemented);
+        }
+
+        stats.misses += 1;
+        self.cache_stats.set(stats);
+        let implemented = self.implements_within(trait_id, &key.1, &mut Vec::new())?;
+        self.cache.borrow_mut().insert(key, implemented);
+        Ok(implemented)
+    }
+
+    /// Checks whether `typ`, a normalized type, implements `trait_id` as part of the chain of
+    /// checks `chain`, the innermost of which required it.
+    fn implements_within(
+        &self,
+        trait_id: TraitId,
+        typ: &Type,
+        chain: &mut Vec<(TraitId, Type)>,
+    ) -> Result<bool, TraitResolutionOverflow> {
+        if is_unknown(typ) {
+            return Ok(true);
+        }
+
+        chain.push((trait_id, typ.clone()));
+        if chain.len() > self.depth_limit {
+            return Err(TraitResolutionOverflow { limit: self.depth_limit, chain: chain.clone() });
+        }
+        let mut implemented = false;
+        for trait_impl in self.candidates(&trait_id, typ) {
+            if self.applies_within(trait_impl, typ, chain)? {
+                implemented = true;
+                break;
+            }
+        }
+        chain.pop();
+        Ok(implemented)
+    }
+
+    /// Checks whether `trait_impl` applies to `typ`, a normalized type: whether the impl's
+    /// object type can be instantiated to `typ` and its where clause then holds.
+    fn applies_within(
+        &self,
+        trait_impl: &TraitImplementation,
+        typ: &Type,
+        chain: &mut Vec<(TraitId, Type)>,
+    ) -> Result<bool, TraitResolutionOverflow> {
+        let mut bindings = TypeBindings::new();
+        if !match_impl_type(&trait_impl.object_type, typ, &mut bindings) {
+            return Ok(false);
+        }
+        for constraint in &trait_impl.where_clause {
+            let required_type = normalize_type(&constraint.typ.substitute(&bindings));
+            if !self.implements_within(constraint.trait_id, &required_type, chain)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns the method named `method_name` of the trait impl which applies to `typ`, or
+    /// `None` if no impl defining the method applies or `typ` isn't known well enough to tell.
+    ///
+    /// When several impls apply, as `impl Foo for u32` and `impl<T> Foo for T` do to `u32`, the
+    /// most specific is chosen: the one whose object type has the most concrete positions, as
+    /// counted by [`specificity`]. The method is ambiguous if no single impl is most specific.
+    pub fn select_method(
+        &self,
+        typ: &Type,
+        method_name: &str,
+    ) -> Result<Option<FuncId>, MethodSelectionError> {
+        let Some(traits) = self.traits_by_method.get(method_name) else { return Ok(None) };
+        let typ = &normalize_type(typ);
+        if has_unknown_parts(typ) {
+            return Ok(None);
+        }
+
+        let mut applicable = Vec::new();
+        for trait_id in traits {
+            for trait_impl in self.candidates(trait_id, typ) {
+                if !trait_impl.methods.contains_key(method_name) {
+                    continue;
+                }
+                let mut chain = vec![(*trait_id, typ.clone())];
+                let applies = self
+                    .applies_within(trait_impl, typ, &mut chain)
+                    .map_err(MethodSelectionError::Overflow)?;
+                if applies {
+                    applicable.push((specificity(&trait_impl.object_type), trait_impl));
+                }
+            }
+        }
+
+        let Some(most_specific) = applicable.iter().map(|(rank, _)| *rank).max() else {
+            return Ok(None);
+        };
+        let mut candidates: Vec<_> = applicable
+            .into_iter()
+            .filter(|(rank, _)| *rank == most_specific)
+            .map(|(_, trait_impl)| trait_impl)
+            .collect();
+        if candidates.len() == 1 {
+            Ok(Some(candidates.remove(0).methods[method_name]))
+        } else {
+            Err(MethodSelectionError::Ambiguous(candidates))
+        }
+    }
+}
+
+/// Returns `typ` in the form impls are matched in, with each type variable which has been bound
+/// replaced by its binding wherever it occurs within `typ`.
+pub fn normalize_type(typ: &Type) -> Type {
+    typ.follow_bindings()
+}
+
+/// Returns whether `impl_type`, the object type of an impl, can be instantiated to `typ` by
+/// binding the impl's generics, recording the type each generic is bound to in `bindings`. Each
+/// use of a generic must be bound to the same type, and parts of `typ` which are still unknown
+/// match any part of the impl.
+fn match_impl_type(impl_type: &Type, typ: &Type, bindings: &mut TypeBindings) -> bool {
+    fn all_match(impl_types: &[Type], types: &[Type], bindings: &mut TypeBindings) -> bool {
+        impl_types.len() == types.len()
+            && impl_types
+                .iter()
+                .zip(types)
+                .all(|(impl_type, typ)| match_impl_type(impl_type, typ, bindings))
+    }
+
+    match (impl_type, typ) {
+        (Type::NamedGeneric(generic, _), typ) => {
+            let id = match &*generic.borrow() {
+                TypeBinding::Bound(impl_type) => return match_impl_type(impl_type, typ, bindings),
+                TypeBinding::Unbound(id) => *id,
+            };
+            match bindings.get(&id) {
+                Some((_, bound)) => {
+                    bound == typ || has_unknown_parts(bound) || has_unknown_parts(typ)
+                }
+                None => {
+                    bindings.insert(id, (generic.clone(), typ.clone()));
+                    true
+                }
+            }
+        }
+        (_, typ) if is_unknown(typ) => true,
+        (Type::Array(impl_length, impl_element), Type::Array(length, element)) => {
+            match_impl_type(impl_length, length, bindings)
+                && match_impl_type(impl_element, element, bindings)
+        }
+        (Type::String(impl_length), Type::String(length)) => {
+            match_impl_type(impl_length, length, bindings)
+        }
+        (Type::Struct(impl_struct, impl_args), Type::Struct(struct_type, args)) => {
+            impl_struct.borrow().id == struct_type.borrow().id
+                && all_match(impl_args, args, bindings)
+        }
+        (Type::Tuple(impl_elements), Type::Tuple(elements)) => {
+            all_match(impl_elements, elements, bindings)
+        }
+        (Type::MutableReference(impl_element), Type::MutableReference(element)) => {
+            match_impl_type(impl_element, element, bindings)
+        }
+        (impl_type, typ) => impl_type == typ,
+    }
+}
+
+/// Returns the number of positions within `typ`, the object type of an impl, which are concrete
+/// rather than one of the impl's generics. `[T; 2]` has two such positions, the array and its
+/// length, so is more specific than `T` and less specific than `[Field; 2]`.
+pub fn specificity(typ: &Type) -> usize {
+    let all = |types: &[Type]| -> usize { types.iter().map(specificity).sum() };
+    match typ {
+        Type::NamedGeneric(..) | Type::TypeVariable(..) | Type::Error => 0,
+        Type::Array(length, element) => 1 + specificity(length) + specificity(element),
+        Type::String(length) => 1 + specificity(length),
+        Type::FmtString(length, elements) => 1 + specificity(length) + specificity(elements),
+        Type::Struct(_, args) => 1 + all(args),
+        Type::Tuple(elements) => 1 + all(elements),
+        Type::Function(args, ret, env) => 1 + all(args) + specificity(ret) + specificity(env),
+        Type::MutableReference(element) => 1 + specificity(element),
+        _ => 1,
+    }
+}
+
+/// True if any part of `typ` isn't known yet, so that impls may apply to it once it is.
+fn has_unknown_parts(typ: &Type) -> bool {
+    let any_unknown = |types: &[Type]| types.iter().any(has_unknown_parts);
+    match typ {
+        Type::Array(length, element) => has_unknown_parts(length) || has_unknown_parts(element),
+        Type::String(length) => has_unknown_parts(length),
+        Type::FmtString(length, elements) => {
+            has_unknown_parts(length) || has_unknown_parts(elements)
+        }
+        Type::Struct(_, args) => any_unknown(args),
+        Type::Tuple(elements) => any_unknown(elements),
+        Type::Function(args, ret, env) => {
+            any_unknown(args) || has_unknown_parts(ret) || has_unknown_parts(env)
+        }
+        Type::MutableReference(element) => has_unknown_parts(element),
+        Type::Opaque(..) | Type::Forall(..) => true,
+        typ => is_unknown(typ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use noirc_errors::Span;
+
+    use super::{specificity, CacheStats, MethodSelectionError, TraitImplementations};
+    use crate::graph::CrateId;
+    use crate::hir::def_map::{LocalModuleId, ModuleId};
+    use crate::hir_def::function::HirTraitConstraint;
+    use crate::node_interner::{NodeInterner, StructId, TraitId};
+    use crate::{Shared, Signedness, StructType, Type, TypeBinding, TypeVariableId};
+
+    fn module_id(index: usize) -> ModuleId {
+        let local_id = LocalModuleId(arena::Index::from_raw_parts(index, 0));
+        ModuleId { krate: CrateId::Root(0), local_id }
+    }
+
+    fn trait_id(index: usize) -> TraitId {
+        TraitId(module_id(index))
+    }
+
+    fn generic(id: usize, name: &str) -> Type {
+        let binding = Shared::new(TypeBinding::Unbound(TypeVariableId(id)));
+        Type::NamedGeneric(binding, Rc::new(name.to_string()))
+    }
+
+    #[test]
+    fn selects_methods_of_impls_applying_to_a_type() {
+        let (eq, default) = (trait_id(1), trait_id(2));
+        let u32_type = Type::Integer(Signedness::Unsigned, 32);
+        let pair = |element: Type| Type::Array(Box::new(Type::Constant(2)), Box::new(element));
+
+        let mut interner = NodeInterner::default();
+        let mut impls = TraitImplementations::default();
+        let mut methods = Vec::new();
+        for (trait_id, typ, method_name) in [
+            (eq, Type::FieldElement, "eq"),
+            (eq, pair(Type::Bool), "eq"),
+            (default, u32_type.clone(), "default"),
+            (default, pair(Type::Bool), "eq"),
+        ] {
+            let method_id = interner.push_empty_fn();
+            let impl_index = impls.add(trait_id, typ, Vec::new());
+            impls.add_method(impl_index, method_name.to_string(), method_id);
+            methods.push(method_id);
+        }
+
+        let implements = |trait_id, typ: &Type| impls.implements(trait_id, typ).unwrap();
+        assert!(implements(eq, &Type::FieldElement));
+        assert!(implements(eq, &pair(Type::Bool)));
+        assert!(!implements(eq, &pair(Type::FieldElement)));
+        assert!(!implements(eq, &u32_type));
+        assert!(implements(default, &u32_type));
+
+        // Type variables are matched as the types they are bound to
+        let bound = interner.next_type_variable();
+        let Type::TypeVariable(binding, _) = &bound else { unreachable!() };
+        *binding.borrow_mut() = TypeBinding::Bound(Type::FieldElement);
+        assert!(!implements(eq, &pair(bound.clone())));
+        assert_eq!(impls.select_method(&bound, "eq").unwrap(), Some(methods[0]));
+
+        let select = |typ: &Type, method_name| impls.select_method(typ, method_name).unwrap();
+        assert_eq!(select(&Type::FieldElement, "eq"), Some(methods[0]));
+        assert_eq!(select(&u32_type, "default"), Some(methods[2]));
+        assert_eq!(select(&u32_type, "eq"), None);
+        assert_eq!(select(&Type::FieldElement, "ne"), None);
+        assert_eq!(
+            impls.impl_of_method(&methods[1]).map(|trait_impl| trait_impl.trait_id),
+            Some(eq)
+        );
+
+        // Of the impls `eq` has for `[bool; 2]`, none is more specific than the other
+        let Err(MethodSelectionError::Ambiguous(candidates)) =
+            impls.select_method(&pair(Type::Bool), "eq")
+        else {
+            panic!("expected the method to be ambiguous")
+        };
+        assert_eq!(candidates.len(), 2);
+    }
+
+    #[test]
+    fn prefers_the_most_specific_impl() {
+        let foo = trait_id(1);
+        let pair = |element: Type| Type::Array(Box::new(Type::Constant(2)), Box::new(element));
+        let u32_type = Type::Integer(Signedness::Unsigned, 32);
+
+        let mut interner = NodeInterner::default();
+        let mut impls = TraitImplementations::default();
+        let mut methods = Vec::new();
+        let object_types =
+            [generic(0, "T"), u32_type.clone(), pair(generic(0, "T")), pair(Type::Bool)];
+        for object_type in object_types {
+            let method_id = interner.push_empty_fn();
+            let impl_index = impls.add(foo, object_type, Vec::new());
+            impls.add_method(impl_index, "foo".to_string(), method_id);
+            methods.push(method_id);
+        }
+
+        assert_eq!(specificity(&pair(generic(0, "T"))), 2);
+        let select = |typ: &Type| impls.select_method(typ, "foo").unwrap();
+        assert_eq!(select(&Type::FieldElement), Some(methods[0]));
+        assert_eq!(select(&u32_type), Some(methods[1]));
+        assert_eq!(select(&pair(u32_type.clone())), Some(methods[2]));
+        assert_eq!(select(&pair(Type::Bool)), Some(methods[3]));
+    }
+
+    #[test]
+    fn impls_apply_where_their_where_clauses_hold() {
+        let (eq, same, foo) = (trait_id(1), trait_id(2), trait_id(3));
+        let pair = |element: Type| Type::Array(Box::new(Type::Constant(2)), Box::new(element));
+        let wrapper = Shared::new(StructType::new(
+            StructId(module_id(4)),
+            "Wrapper".to_string().into(),
+            Span::default(),
+            vec![],
+            vec![],
+        ));
+        let wrap = |typ: Type| Type::Struct(wrapper.clone(), vec![typ]);
+        let t = generic(1, "T");
+        let constraint = |typ: Type, trait_id| HirTraitConstraint { typ, trait_id };
+
+        let mut interner = NodeInterner::default();
+        let mut impls = TraitImplementations::default();
+        impls.add(eq, Type::FieldElement, Vec::new());
+        // impl<T> Eq for [T; 2] where T: Eq
+        impls.add(eq, pair(t.clone()), vec![constraint(t.clone(), eq)]);
+        // impl<T> Same for (T, T)
+        impls.add(same, Type::Tuple(vec![t.clone(), t.clone()]), Vec::new());
+        // impl<T> Foo for Wrapper<T> where Wrapper<Wrapper<T>>: Foo
+        let impl_index = impls.add(foo, wrap(t.clone()), vec![constraint(wrap(wrap(t)), foo)]);
+        impls.add_method(impl_index, "foo".to_string(), interner.push_empty_fn());
+
+        let implements = |trait_id, typ: &Type| impls.implements(trait_id, typ).unwrap();
+        assert!(implements(eq, &pair(pair(Type::FieldElement))));
+        assert!(!implements(eq, &pair(Type::Bool)));
+        assert!(implements(same, &Type::Tuple(vec![Type::Bool, Type::Bool])));
+        assert!(!implements(same, &Type::Tuple(vec![Type::Bool, Type::FieldElement])));
+
+        impls.set_depth_limit(3);
+        let u8_type = Type::Integer(Signedness::Unsigned, 8);
+        let overflow = impls.implements(foo, &wrap(u8_type.clone())).unwrap_err();
+        assert_eq!(overflow.limit, 3);
+        let expected_chain = vec![
+            (foo, wrap(u8_type.clone())),
+            (foo, wrap(wrap(u8_type.clone()))),
+            (foo, wrap(wrap(wrap(u8_type.clone())))),
+            (foo, wrap(wrap(wrap(wrap(u8_type.clone()))))),
+        ];
+        assert_eq!(overflow.chain, expected_chain);
+        assert!(matches!(
+            impls.select_method(&wrap(u8_type), "foo"),
+            Err(MethodSelectionError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn caches_checks_until_an_impl_is_added() {
+        let eq = trait_id(1);
+        let pair = |element: Type| Type::Array(Box::new(Type::Constant(2)), Box::new(element));
+        let t = generic(1, "T");
+
+        let mut impls = TraitImplementations::default();
+        // impl<T> Eq for [T; 2] where T: Eq
+        impls.add(eq, pair(t.clone()), vec![HirTraitConstraint { typ: t, trait_id: eq }]);
+        assert!(!impls.implements(eq, &pair(Type::FieldElement)).unwrap());
+        assert!(!impls.implements(eq, &pair(Type::FieldElement)).unwrap());
+        // Types with unknown parts are checked anew each time
+        assert!(impls.implements(eq, &pair(generic(2, "U"))).unwrap());
+        assert_eq!(impls.cache_stats(), CacheStats { hits: 1, misses: 1 });
+
+        impls.add(eq, Type::FieldElement, Vec::new());
+        assert!(impls.implements(eq, &pair(Type::FieldElement)).unwrap());
+        assert_eq!(impls.cache_stats(), CacheStats { hits: 1, misses: 2 });
+    }
+}
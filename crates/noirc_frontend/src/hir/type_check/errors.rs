@@ -49,6 +49,8 @@ pub enum TypeCheckError {
     InvalidCast { from: Type, span: Span },
     #[error("Expected a function, but found a(n) {found}")]
     ExpectedFunction { found: Type, span: Span },
+    #[error("Expected a function of type {expected} but found one of type {found}")]
+    FunctionSignatureMismatch { expected: String, found: String, span: Span },
     #[error("Type {lhs_type} has no member named {field_name}")]
     AccessUnknownMember { lhs_type: Type, field_name: String, span: Span },
     #[error("Function expects {expected} parameters but {found} given")]
@@ -61,6 +63,21 @@ pub enum TypeCheckError {
     VariableMustBeMutable { name: String, span: Span },
     #[error("No method named '{method_name}' found for type '{object_type}'")]
     UnresolvedMethodCall { method_name: String, object_type: Type, span: Span },
+    #[error("Multiple applicable methods named '{method_name}' found for type '{object_type}'")]
+    AmbiguousMethodCall {
+        method_name: String,
+        object_type: Type,
+        /// Each impl defining the method which applies to the type, as in `impl Foo for T`
+        candidates: Vec<String>,
+        span: Span,
+    },
+    #[error("Checking trait bounds recursed more than {limit} times: {chain}")]
+    TraitResolutionOverflow {
+        limit: usize,
+        /// Each bound checked, as in `Wrapper<u8>: Foo, requiring Wrapper<Wrapper<u8>>: Foo`
+        chain: String,
+        span: Span,
+    },
     #[error("Comparisons are invalid on Field types. Try casting the operands to a sized integer type first")]
     InvalidComparisonOnField { span: Span },
     #[error("Integers must have the same signedness LHS is {sign_x:?}, RHS is {sign_y:?}")]
@@ -96,6 +113,12 @@ pub enum TypeCheckError {
     TypeAnnotationsNeeded { span: Span },
     #[error("use of deprecated function {name}")]
     CallDeprecated { name: String, note: Option<String>, span: Span },
+    #[error("method `{method_name}` is private")]
+    PrivateMethod { method_name: String, span: Span },
+    #[error("The trait bound `{typ}: {trait_name}` is not satisfied")]
+    UnsatisfiedTraitConstraint { typ: Type, trait_name: String, span: Span },
+    #[error("The where clause predicate `{predicate}` is not satisfied")]
+    UnsatisfiedGenericPredicate { predicate: String, generic: String, value: u64, span: Span },
     #[error("{0}")]
     ResolverError(ResolverError),
     #[error("Unused expression result of type {expr_type}")]
@@ -164,6 +187,7 @@ impl From<TypeCheckError> for Diagnostic {
             }
             TypeCheckError::InvalidCast { span, .. }
             | TypeCheckError::ExpectedFunction { span, .. }
+            | TypeCheckError::FunctionSignatureMismatch { span, .. }
             | TypeCheckError::AccessUnknownMember { span, .. }
             | TypeCheckError::UnsupportedCast { span }
             | TypeCheckError::TupleIndexOutOfBounds { span, .. }
@@ -187,6 +211,43 @@ impl From<TypeCheckError> for Diagnostic {
                 format!("return type is {typ}"),
                 span,
             ),
+            TypeCheckError::PrivateMethod { span, .. } => Diagnostic::simple_error(
+                error.to_string(),
+                "private methods can only be called from the module defining them".to_string(),
+                span,
+            ),
+            TypeCheckError::AmbiguousMethodCall { ref candidates, span, .. } => {
+                let mut diagnostic = Diagnostic::simple_error(
+                    error.to_string(),
+                    "no impl of this method applying to the type is more specific than the others"
+                        .to_string(),
+                    span,
+                );
+                for candidate in candidates {
+                    diagnostic.add_note(format!("the method is defined by `{candidate}`"));
+                }
+                diagnostic
+            }
+            TypeCheckError::TraitResolutionOverflow { span, .. } => Diagnostic::simple_error(
+                error.to_string(),
+                "the where clauses of impls require ever larger types to satisfy this bound"
+                    .to_string(),
+                span,
+            ),
+            TypeCheckError::UnsatisfiedTraitConstraint { ref typ, ref trait_name, span } => {
+                Diagnostic::simple_error(
+                    error.to_string(),
+                    format!("No impl of `{trait_name}` for `{typ}` was found"),
+                    span,
+                )
+            }
+            TypeCheckError::UnsatisfiedGenericPredicate { ref generic, value, span, .. } => {
+                Diagnostic::simple_error(
+                    error.to_string(),
+                    format!("`{generic}` is instantiated with {value} here"),
+                    span,
+                )
+            }
             TypeCheckError::TypeAnnotationsNeeded { span } => Diagnostic::simple_error(
                 "Expression type is ambiguous".to_string(),
                 "Type must be known at this point".to_string(),
@@ -2,13 +2,16 @@ use iter_extended::vecmap;
 use noirc_errors::Span;
 
 use crate::{
-    hir::{resolution::resolver::verify_mutable_reference, type_check::errors::Source},
+    hir::{
+        resolution::resolver::verify_mutable_reference, trait_impls::MethodSelectionError,
+        type_check::errors::Source,
+    },
     hir_def::{
         expr::{
             self, HirArrayLiteral, HirBinaryOp, HirExpression, HirLiteral, HirMethodCallExpression,
             HirPrefixExpression,
         },
-        types::Type,
+        types::{TraitItemType, Type, TypeBindings},
     },
     node_interner::{DefinitionKind, ExprId, FuncId},
     token::Attribute::Deprecated,
@@ -36,6 +39,20 @@ impl<'interner> TypeChecker<'interner> {
             }
         }
     }
+
+    /// Reports an error if `func_id` is a private method of an inherent impl defined outside the
+    /// module being checked. Private methods may be called from their own module or any module
+    /// nested within it.
+    fn check_if_private(&mut self, func_id: &FuncId, span: Span) {
+        let meta = self.interner.function_meta(func_id);
+        if meta.is_private && !self.current_modules.contains(&meta.module_id) {
+            self.errors.push(TypeCheckError::PrivateMethod {
+                method_name: self.interner.function_name(func_id).to_string(),
+                span,
+            });
+        }
+    }
+
     /// Infers a type for a given expression, and return this type.
     /// As a side-effect, this function will also remember this type in the NodeInterner
     /// for the given expr_id key.
@@ -53,6 +70,14 @@ impl<'interner> TypeChecker<'interner> {
                 // variable to handle generic functions.
                 let t = self.interner.id_type(ident.id);
                 let (typ, bindings) = t.instantiate(self.interner);
+
+                if let Some(DefinitionKind::Function(func_id)) =
+                    self.interner.try_definition(ident.id).map(|def| def.kind.clone())
+                {
+                    self.check_if_private(&func_id, ident.location.span);
+                    self.check_trait_constraints(&func_id, &bindings, ident.location.span);
+                }
+
                 self.interner.store_instantiation_bindings(*expr_id, bindings);
                 typ
             }
@@ -146,6 +171,8 @@ impl<'interner> TypeChecker<'interner> {
                 let method_name = method_call.method.0.contents.as_str();
                 match self.lookup_method(&object_type, method_name, expr_id) {
                     Some(method_id) => {
+                        // Methods of an opaque type are those of its concrete type
+                        let object_type = reveal_opaque_type(object_type);
                         let mut args = vec![(
                             object_type,
                             method_call.object,
@@ -473,6 +500,8 @@ impl<'interner> TypeChecker<'interner> {
             }
 
             let (function_type, instantiation_bindings) = func_meta.typ.instantiate(self.interner);
+            self.check_if_private(func_id, span);
+            self.check_trait_constraints(func_id, &instantiation_bindings, span);
 
             self.interner.store_instantiation_bindings(*function_ident_id, instantiation_bindings);
             self.interner.push_expr_type(function_ident_id, function_type.clone());
@@ -481,6 +510,55 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    /// Checks that the types the generics of `func_id` are instantiated with by `bindings`
+    /// satisfy the function's where clause. The generics may only be inferred later in the
+    /// caller, so each constraint is checked once the rest of the caller has been type checked.
+    /// Constraints on generics which are still unknown by then are assumed to hold.
+    fn check_trait_constraints(&mut self, func_id: &FuncId, bindings: &TypeBindings, span: Span) {
+        let meta = self.interner.function_meta(func_id);
+        for constraint in meta.trait_constraints {
+            let typ = constraint.typ.substitute(bindings);
+            let trait_id = constraint.trait_id;
+
+            self.push_delayed_type_check(Box::new(move |interner| {
+                let typ = typ.follow_bindings();
+                match interner.trait_implementations().implements(trait_id, &typ) {
+                    Ok(true) => Ok(()),
+                    Ok(false) => {
+                        let trait_name = interner.get_trait(trait_id).borrow().name.to_string();
+                        Err(TypeCheckError::UnsatisfiedTraitConstraint { typ, trait_name, span })
+                    }
+                    Err(overflow) => Err(TypeCheckError::TraitResolutionOverflow {
+                        limit: overflow.limit,
+                        chain: overflow.describe_chain(interner),
+                        span,
+                    }),
+                }
+            }));
+        }
+
+        for generic_predicate in meta.generic_predicates {
+            let typ = generic_predicate.typ.substitute(bindings);
+            let predicate = generic_predicate.predicate;
+
+            self.push_delayed_type_check(Box::new(move |_| {
+                match typ.follow_bindings().evaluate_to_u64() {
+                    Some(value) if !predicate.holds(value) => {
+                        let generic = predicate.generic.to_string();
+                        let predicate = predicate.to_string();
+                        Err(TypeCheckError::UnsatisfiedGenericPredicate {
+                            predicate,
+                            generic,
+                            value,
+                            span,
+                        })
+                    }
+                    _ => Ok(()),
+                }
+            }));
+        }
+    }
+
     fn check_if_expr(&mut self, if_expr: &expr::HirIfExpression, expr_id: &ExprId) -> Type {
         let cond_type = self.check_expression(&if_expr.condition);
         let then_type = self.check_expression(&if_expr.consequence);
@@ -683,7 +761,7 @@ impl<'interner> TypeChecker<'interner> {
                 if !op.kind.is_valid_for_field_type() && (other.is_bindable() || other.is_field()) {
                     let other = other.follow_bindings();
 
-                    self.push_delayed_type_check(Box::new(move || {
+                    self.push_delayed_type_check(Box::new(move |_| {
                         if other.is_field() || other.is_bindable() {
                             Err(TypeCheckError::InvalidComparisonOnField { span })
                         } else {
@@ -793,41 +871,76 @@ impl<'interner> TypeChecker<'interner> {
         method_name: &str,
         expr_id: &ExprId,
     ) -> Option<FuncId> {
-        match object_type {
-            Type::Struct(typ, _args) => {
-                match self.interner.lookup_method(typ.borrow().id, method_name) {
-                    Some(method_id) => Some(method_id),
-                    None => {
-                        self.errors.push(TypeCheckError::UnresolvedMethodCall {
-                            method_name: method_name.to_string(),
-                            object_type: object_type.clone(),
-                            span: self.interner.expr_span(expr_id),
-                        });
-                        None
-                    }
-                }
-            }
+        let inherent_method = match object_type {
+            Type::Struct(typ, _args) => self.interner.lookup_method(typ.borrow().id, method_name),
             // Mutable references to another type should resolve to methods of their element type.
             // This may be a struct or a primitive type.
-            Type::MutableReference(element) => self.lookup_method(element, method_name, expr_id),
+            Type::MutableReference(element) => {
+                return self.lookup_method(element, method_name, expr_id)
+            }
+            // Only the methods of the traits bounding an opaque type may be called on it, even
+            // though they resolve to the methods of its concrete type
+            Type::Opaque(traits, _, concrete_type) => {
+                let is_trait_method = traits.iter().any(|trait_id| {
+                    let trait_def = self.interner.get_trait(*trait_id);
+                    let trait_def = trait_def.borrow();
+                    trait_def.items.iter().any(|item| {
+                        matches!(item, TraitItemType::Function { name, .. } if name.0.contents == method_name)
+                    })
+                });
+                if is_trait_method {
+                    return self.lookup_method(concrete_type, method_name, expr_id);
+                }
+                None
+            }
             // If we fail to resolve the object to a struct type, we have no way of type
             // checking its arguments as we can't even resolve the name of the function
-            Type::Error => None,
-
-            // In the future we could support methods for non-struct types if we have a context
-            // (in the interner?) essentially resembling HashMap<Type, Methods>
-            other => match self.interner.lookup_primitive_method(other, method_name) {
-                Some(method_id) => Some(method_id),
-                None => {
-                    self.errors.push(TypeCheckError::UnresolvedMethodCall {
-                        method_name: method_name.to_string(),
-                        object_type: object_type.clone(),
-                        span: self.interner.expr_span(expr_id),
-                    });
-                    None
-                }
-            },
+            Type::Error => return None,
+            other => self.interner.lookup_primitive_method(other, method_name),
+        };
+
+        // Methods of inherent impls are preferred over those of trait impls, which are chosen
+        // among the impls applying to the object type. The impl a method was last added for is
+        // only used if the object type isn't known well enough to choose.
+        let trait_impls = self.interner.trait_implementations();
+        let is_inherent = |method_id: &FuncId| trait_impls.impl_of_method(method_id).is_none();
+        if let Some(method_id) = inherent_method.filter(is_inherent) {
+            return Some(method_id);
+        }
+        let method = match trait_impls.select_method(object_type, method_name) {
+            Ok(method) => method.or(inherent_method),
+            Err(MethodSelectionError::Ambiguous(candidates)) => {
+                let candidates = vecmap(candidates, |trait_impl| {
+                    let trait_def = self.interner.get_trait(trait_impl.trait_id);
+                    let trait_name = trait_def.borrow().name.to_string();
+                    format!("impl {trait_name} for {}", trait_impl.object_type)
+                });
+                self.errors.push(TypeCheckError::AmbiguousMethodCall {
+                    method_name: method_name.to_string(),
+                    object_type: object_type.clone(),
+                    candidates,
+                    span: self.interner.expr_span(expr_id),
+                });
+                return None;
+            }
+            Err(MethodSelectionError::Overflow(overflow)) => {
+                self.errors.push(TypeCheckError::TraitResolutionOverflow {
+                    limit: overflow.limit,
+                    chain: overflow.describe_chain(self.interner),
+                    span: self.interner.expr_span(expr_id),
+                });
+                return None;
+            }
+        };
+
+        if method.is_none() {
+            self.errors.push(TypeCheckError::UnresolvedMethodCall {
+                method_name: method_name.to_string(),
+                object_type: object_type.clone(),
+                span: self.interner.expr_span(expr_id),
+            });
         }
+        method
     }
 
     fn bind_function_type_impl(
@@ -847,10 +960,16 @@ impl<'interner> TypeChecker<'interner> {
         }
 
         for (param, (arg, _, arg_span)) in fn_params.iter().zip(callsite_args) {
-            self.unify(arg, param, || TypeCheckError::TypeMismatch {
-                expected_typ: param.to_string(),
-                expr_typ: arg.to_string(),
-                expr_span: *arg_span,
+            self.unify(arg, param, || match (arrow_type(param), arrow_type(arg)) {
+                // Closure environments are left out as they rarely cause the mismatch
+                (Some(expected), Some(found)) => {
+                    TypeCheckError::FunctionSignatureMismatch { expected, found, span: *arg_span }
+                }
+                _ => TypeCheckError::TypeMismatch {
+                    expected_typ: param.to_string(),
+                    expr_typ: arg.to_string(),
+                    expr_span: *arg_span,
+                },
             });
         }
 
@@ -923,7 +1042,7 @@ impl<'interner> TypeChecker<'interner> {
                     // This will be an error if these types later resolve to a Field, or stay
                     // polymorphic as the bit size will be unknown. Delay this error until the function
                     // finishes resolving so we can still allow cases like `let x: u8 = 1 << 2;`.
-                    self.push_delayed_type_check(Box::new(move || {
+                    self.push_delayed_type_check(Box::new(move |_| {
                         if other.is_field() {
                             Err(TypeCheckError::InvalidBitwiseOperationOnField { span })
                         } else if other.is_bindable() {
@@ -1058,6 +1177,57 @@ impl<'interner> TypeChecker<'interner> {
     }
 }
 
+/// Replaces an opaque type, or a mutable reference to one, with its concrete type.
+fn reveal_opaque_type(typ: Type) -> Type {
+    match typ {
+        Type::Opaque(_, _, concrete_type) => *concrete_type,
+        Type::MutableReference(element) => {
+            Type::MutableReference(Box::new(reveal_opaque_type(*element)))
+        }
+        typ => typ,
+    }
+}
+
+/// Formats a function type as `fn(A, B) -> R`, leaving out its closure environment, or returns
+/// `None` if `typ` isn't a function.
+fn arrow_type(typ: &Type) -> Option<String> {
+    let Type::Function(args, ret, _env) = typ.follow_bindings() else { return None };
+    let args = vecmap(args, |arg| arg.to_string());
+    Some(format!("fn({}) -> {ret}", args.join(", ")))
+}
+
+/// True if `typ` is not yet known well enough to tell which impls apply to it.
+pub(crate) fn is_unknown(typ: &Type) -> bool {
+    matches!(typ, Type::TypeVariable(..) | Type::NamedGeneric(..) | Type::Error)
+}
+
+/// Returns whether the object type of an impl, `impl_type`, may be instantiated to `typ`.
+/// Generics of the impl match any type, without checking that repeated uses of a generic
+/// match the same type, and parts of `typ` which are still unknown match any part of the impl.
+pub(crate) fn may_match(impl_type: &Type, typ: &Type) -> bool {
+    let all_match = |impl_types: &[Type], types: &[Type]| {
+        impl_types.len() == types.len()
+            && impl_types.iter().zip(types).all(|(impl_type, typ)| may_match(impl_type, typ))
+    };
+
+    match (impl_type, typ) {
+        (Type::NamedGeneric(..), _) => true,
+        (_, typ) if is_unknown(typ) => true,
+        (Type::Array(impl_length, impl_element), Type::Array(length, element)) => {
+            may_match(impl_length, length) && may_match(impl_element, element)
+        }
+        (Type::String(impl_length), Type::String(length)) => may_match(impl_length, length),
+        (Type::Struct(impl_struct, impl_args), Type::Struct(struct_type, args)) => {
+            impl_struct.borrow().id == struct_type.borrow().id && all_match(impl_args, args)
+        }
+        (Type::Tuple(impl_elements), Type::Tuple(elements)) => all_match(impl_elements, elements),
+        (Type::MutableReference(impl_element), Type::MutableReference(element)) => {
+            may_match(impl_element, element)
+        }
+        (impl_type, typ) => impl_type == typ,
+    }
+}
+
 /// Taken from: https://stackoverflow.com/a/47127500
 fn sort_by_key_ref<T, F, K>(xs: &mut [T], key: F)
 where
@@ -12,8 +12,13 @@ mod expr;
 mod stmt;
 
 pub use errors::TypeCheckError;
+pub(crate) use expr::{is_unknown, may_match};
+
+use std::collections::HashMap;
 
 use crate::{
+    graph::CrateId,
+    hir::def_map::{CrateDefMap, ModuleId},
     hir_def::{expr::HirExpression, stmt::HirStatement},
     node_interner::{ExprId, FuncId, NodeInterner, StmtId},
     Type,
@@ -21,25 +26,40 @@ use crate::{
 
 use self::errors::Source;
 
-type TypeCheckFn = Box<dyn FnOnce() -> Result<(), TypeCheckError>>;
+/// A check run once the rest of a function has been type checked, when the types it checks
+/// are known as well as they can be.
+type TypeCheckFn = Box<dyn FnOnce(&NodeInterner) -> Result<(), TypeCheckError>>;
 
 pub struct TypeChecker<'interner> {
     delayed_type_checks: Vec<TypeCheckFn>,
     interner: &'interner mut NodeInterner,
     errors: Vec<TypeCheckError>,
+    /// The module of the function being checked followed by each module it is nested in, as
+    /// private methods may be called from within any of these. This is empty when checking
+    /// globals.
+    current_modules: Vec<ModuleId>,
 }
 
 /// Type checks a function and assigns the
 /// appropriate types to expressions in a side table
-pub fn type_check_func(interner: &mut NodeInterner, func_id: FuncId) -> Vec<TypeCheckError> {
+pub fn type_check_func(
+    interner: &mut NodeInterner,
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    func_id: FuncId,
+) -> Vec<TypeCheckError> {
     let meta = interner.function_meta(&func_id);
-    let declared_return_type = meta.return_type().clone();
+    // The body of a function returning an opaque type returns its concrete type
+    let declared_return_type = match meta.return_type() {
+        Type::Opaque(_, _, concrete_type) => concrete_type.as_ref().clone(),
+        return_type => return_type.clone(),
+    };
     let can_ignore_ret = meta.can_ignore_return_type();
 
     let function_body = interner.function(&func_id);
     let function_body_id = function_body.as_expr();
 
-    let mut type_checker = TypeChecker::new(interner);
+    let current_modules = module_and_ancestors(def_maps, meta.module_id);
+    let mut type_checker = TypeChecker::new(interner, current_modules);
 
     // Bind each parameter to its annotated type.
     // This is locally obvious, but it must be bound here so that the
@@ -53,7 +73,7 @@ pub fn type_check_func(interner: &mut NodeInterner, func_id: FuncId) -> Vec<Type
 
     // Go through any delayed type checking errors to see if they are resolved, or error otherwise.
     for type_check_fn in delayed_type_check_functions {
-        if let Err(error) = type_check_fn() {
+        if let Err(error) = type_check_fn(interner) {
             errors.push(error);
         }
     }
@@ -112,9 +132,24 @@ fn function_info(
     (expr_span, empty_function)
 }
 
+/// Returns `module` followed by each module it is nested in, up to the root module of its crate.
+fn module_and_ancestors(
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    module: ModuleId,
+) -> Vec<ModuleId> {
+    let mut modules = vec![module];
+    let Some(def_map) = def_maps.get(&module.krate) else { return modules };
+    let mut local_id = module.local_id;
+    while let Some(parent) = def_map.modules().get(local_id.0).and_then(|module| module.parent) {
+        modules.push(ModuleId { krate: module.krate, local_id: parent });
+        local_id = parent;
+    }
+    modules
+}
+
 impl<'interner> TypeChecker<'interner> {
-    fn new(interner: &'interner mut NodeInterner) -> Self {
-        Self { delayed_type_checks: Vec::new(), interner, errors: vec![] }
+    fn new(interner: &'interner mut NodeInterner, current_modules: Vec<ModuleId>) -> Self {
+        Self { delayed_type_checks: Vec::new(), interner, errors: vec![], current_modules }
     }
 
     pub fn push_delayed_type_check(&mut self, f: TypeCheckFn) {
@@ -130,7 +165,7 @@ impl<'interner> TypeChecker<'interner> {
     }
 
     pub fn check_global(id: &StmtId, interner: &'interner mut NodeInterner) -> Vec<TypeCheckError> {
-        let mut this = Self { delayed_type_checks: Vec::new(), interner, errors: vec![] };
+        let mut this = Self::new(interner, Vec::new());
         this.check_statement(id);
         this.errors
     }
@@ -262,11 +297,14 @@ mod test {
             contract_function_type: None,
             is_internal: None,
             is_unconstrained: false,
+            is_private: false,
             typ: Type::Function(
                 vec![Type::FieldElement, Type::FieldElement],
                 Box::new(Type::Unit),
                 Box::new(Type::Unit),
             ),
+            trait_constraints: Vec::new(),
+            generic_predicates: Vec::new(),
             parameters: vec![
                 (Identifier(x), Type::FieldElement, Visibility::Private),
                 (Identifier(y), Type::FieldElement, Visibility::Private),
@@ -279,7 +317,7 @@ mod test {
         };
         interner.push_fn_meta(func_meta, func_id);
 
-        let errors = super::type_check_func(&mut interner, func_id);
+        let errors = super::type_check_func(&mut interner, &HashMap::new(), func_id);
         assert!(errors.is_empty());
     }
 
@@ -446,7 +484,11 @@ mod test {
         }
 
         // Type check section
-        let errors = super::type_check_func(&mut interner, func_ids.first().cloned().unwrap());
+        let errors = super::type_check_func(
+            &mut interner,
+            &HashMap::new(),
+            func_ids.first().cloned().unwrap(),
+        );
         assert_eq!(errors, vec![]);
     }
 }
@@ -4,9 +4,25 @@ use noirc_errors::{Location, Span};
 use super::expr::{HirBlockExpression, HirExpression, HirIdent};
 use super::stmt::HirPattern;
 use crate::hir::def_map::ModuleId;
-use crate::node_interner::{ExprId, NodeInterner};
+use crate::node_interner::{ExprId, NodeInterner, TraitId};
 use crate::{token::Attribute, FunctionKind};
-use crate::{ContractFunctionType, Distinctness, FunctionReturnType, Type, Visibility};
+use crate::{
+    ContractFunctionType, Distinctness, FunctionReturnType, GenericPredicate, Type, Visibility,
+};
+
+/// A where clause constraint such as `T: Eq`, with the constrained type resolved.
+#[derive(Debug, Clone)]
+pub struct HirTraitConstraint {
+    pub typ: Type,
+    pub trait_id: TraitId,
+}
+
+/// A where clause predicate such as `N > 0`, with the generic it compares resolved.
+#[derive(Debug, Clone)]
+pub struct HirGenericPredicate {
+    pub typ: Type,
+    pub predicate: GenericPredicate,
+}
 
 /// A Hir function is a block expression
 /// with a list of statements
@@ -114,6 +130,11 @@ pub struct FuncMeta {
 
     pub is_unconstrained: bool,
 
+    /// True if this is a method of an inherent impl which wasn't marked `pub` while another
+    /// method of the impl was, and so can only be called from within the module defining the
+    /// impl or the modules nested in it.
+    pub is_private: bool,
+
     pub parameters: Parameters,
 
     pub return_type: FunctionReturnType,
@@ -126,6 +147,14 @@ pub struct FuncMeta {
     /// or a Type::Forall for generic functions.
     pub typ: Type,
 
+    /// The constraints of this function's where clause on its own generics, or those of its
+    /// impl, which are checked at each call to the function.
+    pub trait_constraints: Vec<HirTraitConstraint>,
+
+    /// The predicates of this function's where clause on its numeric generics, which are checked
+    /// at each call to the function in the same way.
+    pub generic_predicates: Vec<HirGenericPredicate>,
+
     pub location: Location,
 
     // This flag is needed for the attribute check pass
@@ -12,7 +12,9 @@ use iter_extended::vecmap;
 use noirc_errors::Span;
 use noirc_printable_type::PrintableType;
 
-use crate::{node_interner::StructId, node_interner::TraitId, Ident, Signedness};
+use crate::{
+    node_interner::StructId, node_interner::TraitId, Ident, ObjectSafetyViolation, Signedness,
+};
 
 use super::expr::{HirCallExpression, HirExpression, HirIdent};
 
@@ -75,6 +77,12 @@ pub enum Type {
     /// &mut T
     MutableReference(Box<Type>),
 
+    /// The opaque return type of a trait method, as in `impl Iterator`, as returned by one of the
+    /// method's impls. Only the methods of the given traits may be called on it, but the
+    /// concrete type returned by the impl is kept so it can be monomorphized. The name is how
+    /// the type was written, for displaying it in errors.
+    Opaque(Vec<TraitId>, Rc<String>, Box<Type>),
+
     /// A type generic over the given type variables.
     /// Storing both the TypeVariableId and TypeVariable isn't necessary
     /// but it makes handling them both easier. The TypeVariableId should
@@ -134,8 +142,16 @@ pub enum TraitItemType {
         span: Span,
     },
 
-    /// A constant declaration in a trait.
-    Constant { name: Ident, ty: Type, span: Span },
+    /// A constant declaration in a trait, along with the value of its default, if it has one.
+    /// Impls only need to define a constant without a default if they implement each trait in
+    /// `self_constraints`, the traits its where clause requires of `Self`.
+    Constant {
+        name: Ident,
+        ty: Type,
+        default_value: Option<u128>,
+        self_constraints: Vec<TraitId>,
+        span: Span,
+    },
 
     /// A type declaration in a trait.
     Type { name: Ident, ty: Type, span: Span },
@@ -154,6 +170,13 @@ pub struct Trait {
     pub name: Ident,
     pub generics: Generics,
     pub span: Span,
+
+    /// The traits which every type implementing this trait must also implement, from
+    /// constraints on `Self` such as `trait Foo where Self: Clone`
+    pub self_constraints: Vec<TraitId>,
+
+    /// The items which prevent this trait from being used as a trait object
+    pub object_safety_violations: Vec<ObjectSafetyViolation>,
 }
 
 /// Corresponds to generic lists such as `<T, U>` in the source
@@ -194,12 +217,40 @@ impl Trait {
         items: Vec<TraitItemType>,
         generics: Generics,
     ) -> Trait {
-        Trait { id, name, span, items, generics }
+        Trait {
+            id,
+            name,
+            span,
+            items,
+            generics,
+            self_constraints: Vec::new(),
+            object_safety_violations: Vec::new(),
+        }
     }
 
     pub fn set_items(&mut self, items: Vec<TraitItemType>) {
         self.items = items;
     }
+
+    pub fn set_self_constraints(&mut self, self_constraints: Vec<TraitId>) {
+        self.self_constraints = self_constraints;
+    }
+
+    pub fn set_object_safety_violations(&mut self, violations: Vec<ObjectSafetyViolation>) {
+        self.object_safety_violations = violations;
+    }
+
+    /// Returns the default value of the constant `name` declared by this trait, if it has one.
+    pub fn constant_default(&self, name: &str) -> Option<u128> {
+        self.items.iter().find_map(|item| match item {
+            TraitItemType::Constant { name: constant, default_value, .. }
+                if constant.0.contents == name =>
+            {
+                *default_value
+            }
+            _ => None,
+        })
+    }
 }
 
 impl std::fmt::Display for Trait {
@@ -566,6 +617,7 @@ impl Type {
                 })
             }
             Type::MutableReference(element) => element.contains_numeric_typevar(target_id),
+            Type::Opaque(_, _, concrete_type) => concrete_type.contains_numeric_typevar(target_id),
             Type::String(length) => named_generic_id_matches_target(length),
             Type::FmtString(length, elements) => {
                 elements.contains_numeric_typevar(target_id)
@@ -653,6 +705,7 @@ impl std::fmt::Display for Type {
             Type::MutableReference(element) => {
                 write!(f, "&mut {element}")
             }
+            Type::Opaque(_, name, _) => write!(f, "{name}"),
             Type::NotConstant => write!(f, "_"),
         }
     }
@@ -941,6 +994,15 @@ impl Type {
 
             (MutableReference(elem_a), MutableReference(elem_b)) => elem_a.try_unify(elem_b),
 
+            // An opaque type only unifies with the same opaque type, never with its concrete type
+            (Opaque(traits_a, _, concrete_a), Opaque(traits_b, _, concrete_b)) => {
+                if traits_a == traits_b {
+                    concrete_a.try_unify(concrete_b)
+                } else {
+                    Err(UnificationError)
+                }
+            }
+
             (other_a, other_b) => {
                 if other_a == other_b {
                     Ok(())
@@ -1134,6 +1196,10 @@ impl Type {
             Type::MutableReference(element) => {
                 Type::MutableReference(Box::new(element.substitute(type_bindings)))
             }
+            Type::Opaque(traits, name, concrete_type) => {
+                let concrete_type = Box::new(concrete_type.substitute(type_bindings));
+                Type::Opaque(traits.clone(), name.clone(), concrete_type)
+            }
 
             Type::FieldElement
             | Type::Integer(_, _)
@@ -1172,6 +1238,7 @@ impl Type {
                     || env.occurs(target_id)
             }
             Type::MutableReference(element) => element.occurs(target_id),
+            Type::Opaque(_, _, concrete_type) => concrete_type.occurs(target_id),
 
             Type::FieldElement
             | Type::Integer(_, _)
@@ -1222,6 +1289,9 @@ impl Type {
             }
 
             MutableReference(element) => MutableReference(Box::new(element.follow_bindings())),
+            Opaque(traits, name, concrete_type) => {
+                Opaque(traits.clone(), name.clone(), Box::new(concrete_type.follow_bindings()))
+            }
 
             // Expect that this function should only be called on instantiated types
             Forall(..) => unreachable!(),
@@ -1335,6 +1405,7 @@ impl From<&Type> for PrintableType {
             Type::Forall(..) => unreachable!(),
             Type::Function(_, _, _) => unreachable!(),
             Type::MutableReference(_) => unreachable!("cannot print &mut"),
+            Type::Opaque(_, _, concrete_type) => concrete_type.as_ref().into(),
             Type::NotConstant => unreachable!(),
         }
     }
@@ -25,10 +25,24 @@ impl<'a> Lexer<'a> {
     /// Given a source file of noir code, return all the tokens in the file
     /// in order, along with any lexing errors that occurred.
     pub fn lex(source: &'a str) -> (Tokens, Vec<LexerErrorKind>) {
-        let lexer = Lexer::new(source);
+        Lexer::new(source).lex_remaining()
+    }
+
+    /// Like [`Lexer::lex`], but starts lexing at the character at `offset` in `source`.
+    /// The spans of the returned tokens are still relative to the start of `source`.
+    pub fn lex_from(source: &'a str, offset: Position) -> (Tokens, Vec<LexerErrorKind>) {
+        let mut lexer = Lexer::new(source);
+        if let Some(last_skipped) = offset.checked_sub(1) {
+            lexer.char_iter.nth(last_skipped as usize);
+        }
+        lexer.position = offset;
+        lexer.lex_remaining()
+    }
+
+    fn lex_remaining(self) -> (Tokens, Vec<LexerErrorKind>) {
         let mut tokens = vec![];
         let mut errors = vec![];
-        for result in lexer {
+        for result in self {
             match result {
                 Ok(token) => tokens.push(token),
                 Err(error) => errors.push(error),
@@ -104,6 +118,7 @@ impl<'a> Lexer<'a> {
             Some(']') => self.single_char_token(Token::RightBracket),
             Some('"') => self.eat_string_literal(),
             Some('f') => self.eat_format_string_or_alpha_numeric(),
+            Some('r') if self.peek_char_is('#') => self.eat_raw_identifier(),
             Some('#') => self.eat_attribute(),
             Some(ch) if ch.is_ascii_alphanumeric() || ch == '_' => self.eat_alpha_numeric(ch),
             Some(ch) => {
@@ -345,6 +360,29 @@ impl<'a> Lexer<'a> {
         Ok(str_literal_token.into_span(start, end))
     }
 
+    /// Eats a raw identifier such as `r#type`, which is an identifier even if its name is a
+    /// keyword. The leading `r` has already been eaten.
+    fn eat_raw_identifier(&mut self) -> SpannedTokenResult {
+        let start = self.position;
+        self.next_char();
+
+        let initial_char = match self.next_char() {
+            Some(ch) if ch.is_ascii_alphabetic() || ch == '_' => ch,
+            found => {
+                return Err(LexerErrorKind::UnexpectedCharacter {
+                    span: Span::single_char(self.position),
+                    found,
+                    expected: "an identifier".to_owned(),
+                })
+            }
+        };
+        let word = self.eat_while(Some(initial_char), |ch| {
+            ch.is_ascii_alphabetic() || ch.is_numeric() || ch == '_'
+        });
+
+        Ok(Token::Ident(word).into_span(start, self.position))
+    }
+
     fn eat_format_string_or_alpha_numeric(&mut self) -> SpannedTokenResult {
         if self.peek_char_is('"') {
             self.eat_fmt_string()
@@ -411,7 +449,7 @@ impl<'a> Iterator for Lexer<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::token::TestScope;
+    use crate::token::{CfgPredicate, InlineHint, TestScope};
     #[test]
     fn test_single_double_char() {
         let input = "! != + ( ) { } [ ] | , ; : :: < <= > >= & - -> . .. % / * = == << >>";
@@ -489,6 +527,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn inline_attribute() {
+        let input = "#[inline(always)]#[inline(never)]";
+        let expected = vec![
+            Token::Attribute(Attribute::Inline(InlineHint::Always)),
+            Token::Attribute(Attribute::Inline(InlineHint::Never)),
+        ];
+
+        let mut lexer = Lexer::new(input);
+        for token in expected.into_iter() {
+            let got = lexer.next_token().unwrap();
+            assert_eq!(got, token);
+        }
+
+        let mut lexer = Lexer::new("#[inline(sometimes)]");
+        assert!(lexer.next().unwrap().is_err());
+    }
+
     #[test]
     fn test_custom_gate_syntax() {
         let input = "#[foreign(sha256)]#[foreign(blake2s)]#[builtin(sum)]";
@@ -518,6 +574,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn derive_attribute() {
+        let input = r#"#[derive(Eq, Default)]"#;
+        let mut lexer = Lexer::new(input);
+
+        let token = lexer.next().unwrap().unwrap();
+        assert_eq!(
+            token.token(),
+            &Token::Attribute(Attribute::Derive(vec!["Eq".to_string(), "Default".to_string()]))
+        );
+    }
+
+    #[test]
+    fn cfg_attribute() {
+        let input = r#"#[cfg(test)] #[cfg(feature = "foo")] #[cfg(not(feature = "bar"))]"#;
+        let mut lexer = Lexer::new(input);
+
+        let feature = |name: &str| CfgPredicate::Feature(name.to_string());
+        let expected =
+            [feature("test"), feature("foo"), CfgPredicate::Not(Box::new(feature("bar")))];
+
+        for predicate in expected {
+            let token = lexer.next().unwrap().unwrap();
+            assert_eq!(token.token(), &Token::Attribute(Attribute::Cfg(predicate)));
+        }
+    }
+
+    #[test]
+    fn malformed_cfg_attribute() {
+        let input = r#"#[cfg(feature = foo)]"#;
+        let mut lexer = Lexer::new(input);
+
+        assert!(lexer.next().unwrap().is_err());
+    }
+
     #[test]
     fn test_attribute() {
         let input = r#"#[test]"#;
@@ -555,6 +646,24 @@ mod tests {
         assert_eq!(sub_string, "test(invalid_scope)");
     }
 
+    #[test]
+    fn raw_identifiers() {
+        let input = "r#type r#u8 r#foo r";
+
+        let expected = vec![
+            Token::Ident("type".to_string()),
+            Token::Ident("u8".to_string()),
+            Token::Ident("foo".to_string()),
+            Token::Ident("r".to_string()),
+        ];
+
+        let mut lexer = Lexer::new(input);
+        for token in expected.into_iter() {
+            let got = lexer.next_token().unwrap();
+            assert_eq!(got, token);
+        }
+    }
+
     #[test]
     fn test_int_type() {
         let input = "u16 i16 i108 u104.5";
@@ -1,6 +1,7 @@
 use acvm::FieldElement;
+use iter_extended::vecmap;
 use noirc_errors::{Position, Span, Spanned};
-use std::{fmt, iter::Map, vec::IntoIter};
+use std::{collections::HashSet, fmt, iter::Map, vec::IntoIter};
 
 use crate::lexer::errors::LexerErrorKind;
 
@@ -346,6 +347,89 @@ impl fmt::Display for TestScope {
     }
 }
 
+/// InlineHint is given by an `#[inline(...)]` attribute to say whether calls to a function
+/// should be inlined into their callers.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, PartialOrd, Ord)]
+pub enum InlineHint {
+    /// `#[inline(always)]`
+    Always,
+    /// `#[inline(never)]`
+    Never,
+}
+
+impl InlineHint {
+    fn lookup_str(string: &str) -> Option<InlineHint> {
+        match string {
+            "always" => Some(InlineHint::Always),
+            "never" => Some(InlineHint::Never),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InlineHint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InlineHint::Always => write!(f, "always"),
+            InlineHint::Never => write!(f, "never"),
+        }
+    }
+}
+
+/// CfgPredicate is the condition of a `#[cfg(...)]` attribute, which decides whether the
+/// item it is attached to is compiled given the set of active features.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+pub enum CfgPredicate {
+    /// Written as either `#[cfg(name)]` or `#[cfg(feature = "name")]`
+    Feature(String),
+    /// `#[cfg(not(predicate))]`
+    Not(Box<CfgPredicate>),
+}
+
+impl CfgPredicate {
+    /// Parses a predicate from the parenthesized segments following `cfg`, so that
+    /// `cfg(not(feature = "foo"))` is given as `["not", "feature = \"foo\""]`.
+    fn lookup_segments(segments: &[&str]) -> Option<CfgPredicate> {
+        match segments {
+            ["not", predicate @ ..] if !predicate.is_empty() => {
+                let predicate = CfgPredicate::lookup_segments(predicate)?;
+                Some(CfgPredicate::Not(Box::new(predicate)))
+            }
+            [feature] => {
+                let feature = match feature.trim().strip_prefix("feature") {
+                    Some(value) => {
+                        let value = value.trim_start().strip_prefix('=')?.trim();
+                        value.strip_prefix('"')?.strip_suffix('"')?
+                    }
+                    None => feature.trim(),
+                };
+
+                let is_valid = !feature.is_empty()
+                    && feature.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+                is_valid.then(|| CfgPredicate::Feature(feature.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if the predicate holds when exactly the given features are active
+    pub fn is_satisfied(&self, features: &HashSet<String>) -> bool {
+        match self {
+            CfgPredicate::Feature(feature) => features.contains(feature),
+            CfgPredicate::Not(predicate) => !predicate.is_satisfied(features),
+        }
+    }
+}
+
+impl fmt::Display for CfgPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CfgPredicate::Feature(feature) => write!(f, r#"feature = "{feature}""#),
+            CfgPredicate::Not(predicate) => write!(f, "not({predicate})"),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
 // Attributes are special language markers in the target language
 // An example of one is `#[SHA256]` . Currently only Foreign attributes are supported
@@ -356,6 +440,9 @@ pub enum Attribute {
     Oracle(String),
     Deprecated(Option<String>),
     Test(TestScope),
+    Derive(Vec<String>),
+    Cfg(CfgPredicate),
+    Inline(InlineHint),
     Custom(String),
 }
 
@@ -368,6 +455,9 @@ impl fmt::Display for Attribute {
             Attribute::Test(scope) => write!(f, "#[test{}]", scope),
             Attribute::Deprecated(None) => write!(f, "#[deprecated]"),
             Attribute::Deprecated(Some(ref note)) => write!(f, r#"#[deprecated("{note}")]"#),
+            Attribute::Derive(ref traits) => write!(f, "#[derive({})]", traits.join(", ")),
+            Attribute::Cfg(ref predicate) => write!(f, "#[cfg({predicate})]"),
+            Attribute::Inline(hint) => write!(f, "#[inline({hint})]"),
             Attribute::Custom(ref k) => write!(f, "#[{k}]"),
         }
     }
@@ -421,6 +511,30 @@ impl Attribute {
 
                 Attribute::Deprecated(name.trim_matches('"').to_string().into())
             }
+            ["derive", traits] => {
+                let traits = traits.split(',').map(str::trim).filter(|name| !name.is_empty());
+                let traits = vecmap(traits, ToString::to_string);
+                traits.iter().try_for_each(|name| validate(name))?;
+                Attribute::Derive(traits)
+            }
+            ["cfg", predicate @ ..] => match CfgPredicate::lookup_segments(predicate) {
+                Some(predicate) => Attribute::Cfg(predicate),
+                None => {
+                    return Err(LexerErrorKind::MalformedFuncAttribute {
+                        span,
+                        found: word.to_owned(),
+                    })
+                }
+            },
+            ["inline", hint] => match InlineHint::lookup_str(hint) {
+                Some(hint) => Attribute::Inline(hint),
+                None => {
+                    return Err(LexerErrorKind::MalformedFuncAttribute {
+                        span,
+                        found: word.to_owned(),
+                    })
+                }
+            },
             ["test"] => Attribute::Test(TestScope::None),
             ["test", name] => {
                 validate(name)?;
@@ -458,9 +572,25 @@ impl Attribute {
         matches!(self, Attribute::Foreign(_))
     }
 
+    pub fn inline_hint(&self) -> Option<InlineHint> {
+        match self {
+            Attribute::Inline(hint) => Some(*hint),
+            _ => None,
+        }
+    }
+
     pub fn is_low_level(&self) -> bool {
         matches!(self, Attribute::Foreign(_) | Attribute::Builtin(_))
     }
+
+    /// Returns false if this is a `#[cfg(...)]` attribute whose predicate does not hold
+    /// for the given active features.
+    pub fn is_cfg_enabled(&self, features: &HashSet<String>) -> bool {
+        match self {
+            Attribute::Cfg(predicate) => predicate.is_satisfied(features),
+            _ => true,
+        }
+    }
 }
 
 impl AsRef<str> for Attribute {
@@ -470,7 +600,11 @@ impl AsRef<str> for Attribute {
             Attribute::Builtin(string) => string,
             Attribute::Oracle(string) => string,
             Attribute::Deprecated(Some(string)) => string,
-            Attribute::Test { .. } | Attribute::Deprecated(None) => "",
+            Attribute::Test { .. }
+            | Attribute::Deprecated(None)
+            | Attribute::Derive(_)
+            | Attribute::Cfg(_)
+            | Attribute::Inline(_) => "",
             Attribute::Custom(string) => string,
         }
     }
@@ -493,6 +627,7 @@ pub enum Keyword {
     Crate,
     Dep,
     Distinct,
+    Dyn,
     Else,
     Field,
     Fn,
@@ -533,6 +668,7 @@ impl fmt::Display for Keyword {
             Keyword::Crate => write!(f, "crate"),
             Keyword::Dep => write!(f, "dep"),
             Keyword::Distinct => write!(f, "distinct"),
+            Keyword::Dyn => write!(f, "dyn"),
             Keyword::Else => write!(f, "else"),
             Keyword::Field => write!(f, "Field"),
             Keyword::Fn => write!(f, "fn"),
@@ -576,6 +712,7 @@ impl Keyword {
             "crate" => Keyword::Crate,
             "dep" => Keyword::Dep,
             "distinct" => Keyword::Distinct,
+            "dyn" => Keyword::Dyn,
             "else" => Keyword::Else,
             "Field" => Keyword::Field,
             "fn" => Keyword::Fn,
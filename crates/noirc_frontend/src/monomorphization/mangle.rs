@@ -0,0 +1,220 @@
+//! Stable names for the monomorphized methods of trait impls, for backends which need to name
+//! each function they emit.
+//!
+//! A method `method` of the impl of `Bar` for `Foo`, instantiated with the generic arguments
+//! `u32` and `[Field; 2]`, is named `Foo$impl$Bar$method$u32$_5b_Field_3b__20_2_5d_`. Each
+//! component is escaped so that it only contains ASCII letters, digits and underscores: an
+//! underscore is written twice, and any other character as its code point in hex between two
+//! underscores. As `$` can never appear within a component, every name is produced by exactly
+//! one method and can be parsed back into its components by [`demangle`].
+//!
+//! Types are named as they are displayed, so the names are stable between compilations of the
+//! same program, except that each struct is followed by the crate and module of its definition,
+//! as in `Foo@0.3`. This keeps structs of the same name in different modules apart.
+
+use iter_extended::vecmap;
+
+use crate::graph::CrateId;
+use crate::hir::def_map::ModuleId;
+use crate::{StructType, Type};
+
+/// Separates the components of a mangled name.
+const SEPARATOR: char = '$';
+
+/// Separates the object type of an impl from the name of the trait it implements.
+const IMPL_MARKER: &str = "impl";
+
+/// The components of a mangled method name, as returned by [`demangle`]. Types are given as
+/// they are named by the [scheme](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MangledMethod {
+    pub object_type: String,
+    pub trait_name: String,
+    pub method: String,
+    pub generic_args: Vec<String>,
+}
+
+/// Returns the mangled name of `method` from the impl of `trait_name` for `object_type`,
+/// instantiated with `generic_args`. See the [module documentation](self) for the scheme.
+pub fn mangle_method(
+    trait_name: &str,
+    object_type: &Type,
+    method: &str,
+    generic_args: &[Type],
+) -> String {
+    let mut components = vec![
+        escape(&type_name(object_type)),
+        IMPL_MARKER.to_string(),
+        escape(trait_name),
+        escape(method),
+    ];
+    components.extend(generic_args.iter().map(|arg| escape(&type_name(arg))));
+    components.join(&SEPARATOR.to_string())
+}
+
+/// Parses a name produced by [`mangle_method`] back into its components, or returns `None` if
+/// `name` isn't a mangled method name.
+pub fn demangle(name: &str) -> Option<MangledMethod> {
+    let mut components = name.split(SEPARATOR);
+    let object_type = unescape(components.next()?)?;
+    if components.next()? != IMPL_MARKER {
+        return None;
+    }
+    let trait_name = unescape(components.next()?)?;
+    let method = unescape(components.next()?)?;
+    let generic_args = components.map(unescape).collect::<Option<Vec<_>>>()?;
+    Some(MangledMethod { object_type, trait_name, method, generic_args })
+}
+
+/// Names `typ` as it is displayed, but with each struct followed by the id of its definition.
+fn type_name(typ: &Type) -> String {
+    let names = |types: &[Type]| vecmap(types, type_name).join(", ");
+    match typ.follow_bindings() {
+        Type::Array(len, element) if matches!(*len, Type::NotConstant) => {
+            format!("[{}]", type_name(&element))
+        }
+        Type::Array(len, element) => format!("[{}; {len}]", type_name(&element)),
+        Type::FmtString(len, elements) => format!("fmtstr<{len}, {}>", type_name(&elements)),
+        Type::Struct(struct_type, args) if args.is_empty() => struct_name(&struct_type.borrow()),
+        Type::Struct(struct_type, args) => {
+            format!("{}<{}>", struct_name(&struct_type.borrow()), names(&args))
+        }
+        Type::Tuple(elements) => format!("({})", names(&elements)),
+        Type::Function(args, ret, env) => {
+            let env = match *env {
+                Type::Unit => String::new(),
+                env => format!(" with closure environment {}", type_name(&env)),
+            };
+            format!("fn({}) -> {}{env}", names(&args), type_name(&ret))
+        }
+        Type::MutableReference(element) => format!("&mut {}", type_name(&element)),
+        typ => typ.to_string(),
+    }
+}
+
+fn struct_name(struct_type: &StructType) -> String {
+    let ModuleId { krate, local_id } = struct_type.id.0;
+    let krate = match krate {
+        CrateId::Root(index) | CrateId::Crate(index) | CrateId::Stdlib(index) => index.to_string(),
+        CrateId::Dummy => "dummy".to_string(),
+    };
+    let (module, _) = local_id.0.into_raw_parts();
+    format!("{}@{krate}.{module}", struct_type.name)
+}
+
+fn escape(component: &str) -> String {
+    let mut escaped = String::with_capacity(component.len());
+    for character in component.chars() {
+        match character {
+            '_' => escaped.push_str("__"),
+            character if character.is_ascii_alphanumeric() => escaped.push(character),
+            character => escaped.push_str(&format!("_{:x}_", character as u32)),
+        }
+    }
+    escaped
+}
+
+fn unescape(component: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(component.len());
+    let mut characters = component.chars();
+    while let Some(character) = characters.next() {
+        if character.is_ascii_alphanumeric() {
+            unescaped.push(character);
+            continue;
+        }
+        if character != '_' {
+            return None;
+        }
+
+        // Either a second underscore or a code point in hex, up to the next underscore
+        let mut code = String::new();
+        loop {
+            match characters.next()? {
+                '_' => break,
+                digit => code.push(digit),
+            }
+        }
+        if code.is_empty() {
+            unescaped.push('_');
+        } else {
+            let code = u32::from_str_radix(&code, 16).ok()?;
+            unescaped.push(char::from_u32(code)?);
+        }
+    }
+    Some(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use noirc_errors::Span;
+
+    use super::{demangle, mangle_method, MangledMethod};
+    use crate::graph::CrateId;
+    use crate::hir::def_map::{LocalModuleId, ModuleId};
+    use crate::hir_def::types::Shared;
+    use crate::node_interner::StructId;
+    use crate::{Signedness, StructType, Type};
+
+    #[test]
+    fn mangled_names_round_trip() {
+        let u32_type = Type::Integer(Signedness::Unsigned, 32);
+        let pair = Type::Array(Box::new(Type::Constant(2)), Box::new(Type::FieldElement));
+        assert_eq!(
+            mangle_method("Bar", &Type::FieldElement, "method", &[u32_type.clone()]),
+            "Field$impl$Bar$method$u32"
+        );
+
+        let nested = Type::Tuple(vec![pair.clone(), Type::Bool]);
+        let cases = [
+            ("Bar", Type::FieldElement, "method", vec![]),
+            ("Default", pair.clone(), "default_value", vec![u32_type.clone(), nested.clone()]),
+            ("my_trait", nested, "__private", vec![Type::Unit, pair]),
+        ];
+
+        let mut names = Vec::new();
+        for (trait_name, object_type, method, generic_args) in cases {
+            let name = mangle_method(trait_name, &object_type, method, &generic_args);
+            let is_identifier_safe = |c: char| c.is_ascii_alphanumeric() || "_$".contains(c);
+            assert!(name.chars().all(is_identifier_safe), "{name}");
+
+            let expected = MangledMethod {
+                object_type: object_type.to_string(),
+                trait_name: trait_name.to_string(),
+                method: method.to_string(),
+                generic_args: generic_args.iter().map(ToString::to_string).collect(),
+            };
+            assert_eq!(demangle(&name), Some(expected), "{name}");
+            names.push(name);
+        }
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), 3, "{names:?}");
+
+        for name in
+            ["Field$Bar$method", "Field$impl$Bar", "Field$impl$Bar$me_x$u32", "a-b$impl$c$d"]
+        {
+            assert_eq!(demangle(name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn same_named_structs_are_mangled_apart() {
+        let foo_in_module = |module| {
+            let local_id = LocalModuleId(arena::Index::from_raw_parts(module, 0));
+            let id = StructId(ModuleId { krate: CrateId::Root(0), local_id });
+            let foo =
+                StructType::new(id, "Foo".to_string().into(), Span::default(), vec![], vec![]);
+            Type::Struct(Shared::new(foo), vec![])
+        };
+
+        let first = mangle_method("Bar", &foo_in_module(1), "method", &[]);
+        let second = mangle_method("Bar", &foo_in_module(2), "method", &[]);
+        assert_eq!(first, "Foo_40_0_2e_1$impl$Bar$method");
+        assert_ne!(first, second);
+        assert_eq!(demangle(&second).map(|method| method.object_type), Some("Foo@0.2".to_string()));
+
+        let in_array = Type::Array(Box::new(Type::Constant(2)), Box::new(foo_in_module(1)));
+        let name = mangle_method("Bar", &Type::FieldElement, "method", &[in_array]);
+        assert_eq!(demangle(&name).unwrap().generic_args, vec!["[Foo@0.1; 2]".to_string()]);
+    }
+}
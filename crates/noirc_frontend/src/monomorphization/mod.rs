@@ -29,6 +29,7 @@ use crate::{
 use self::ast::{Definition, FuncId, Function, LocalId, Program};
 
 pub mod ast;
+pub mod mangle;
 pub mod printer;
 
 struct LambdaContext {
@@ -729,6 +730,8 @@ impl<'interner> Monomorphizer<'interner> {
                 ast::Type::MutableReference(Box::new(element))
             }
 
+            HirType::Opaque(_, _, concrete_type) => Self::convert_type(concrete_type),
+
             HirType::Forall(_, _)
             | HirType::Constant(_)
             | HirType::NotConstant
@@ -777,10 +780,24 @@ impl<'interner> Monomorphizer<'interner> {
 
         if let ast::Expression::Ident(ident) = original_func.as_ref() {
             if let Definition::Oracle(name) = &ident.definition {
-                if name.as_str() == "println" {
-                    // Oracle calls are required to be wrapped in an unconstrained function
-                    // Thus, the only argument to the `println` oracle is expected to always be an ident
-                    self.append_printable_type_info(&hir_arguments[0], &mut arguments);
+                // Oracle calls are required to be wrapped in an unconstrained function
+                // Thus, the message argument of the `println`, `log` and `host_assert` oracles is expected to always be an ident
+                match name.as_str() {
+                    "println" => self.append_printable_type_info(&hir_arguments[0], &mut arguments),
+                    // The message of a stdlib `log` call follows its level. The oracle is namespaced
+                    // so that user oracles named `log` aren't mistaken for it.
+                    "__nargo_log" => {
+                        if let Some(message) = hir_arguments.get(1) {
+                            self.append_printable_type_info(message, &mut arguments);
+                        }
+                    }
+                    // As does the message of a stdlib `host_assert` call its condition
+                    "__nargo_host_assert" => {
+                        if let Some(message) = hir_arguments.get(1) {
+                            self.append_printable_type_info(message, &mut arguments);
+                        }
+                    }
+                    _ => (),
                 }
             }
         }
@@ -1411,6 +1428,7 @@ mod tests {
         // Type check section
         let errors = crate::hir::type_check::type_check_func(
             &mut interner,
+            &HashMap::new(),
             func_ids.first().cloned().unwrap(),
         );
         assert_eq!(errors, vec![]);
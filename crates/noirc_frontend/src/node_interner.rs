@@ -9,6 +9,7 @@ use crate::ast::Ident;
 use crate::graph::CrateId;
 use crate::hir::def_collector::dc_crate::{UnresolvedStruct, UnresolvedTrait, UnresolvedTypeAlias};
 use crate::hir::def_map::{LocalModuleId, ModuleId};
+use crate::hir::trait_impls::TraitImplementations;
 use crate::hir::StorageSlot;
 use crate::hir_def::stmt::HirLetStatement;
 use crate::hir_def::types::{StructType, Trait, Type};
@@ -71,6 +72,9 @@ pub struct NodeInterner {
     // We'd just lookup their methods as needed through the NodeInterner.
     traits: HashMap<TraitId, Shared<Trait>>,
 
+    /// Every resolved impl of a trait, indexed by the trait and the head of its object type.
+    trait_implementations: TraitImplementations,
+
     /// Map from ExprId (referring to a Function/Method call) to its corresponding TypeBindings,
     /// filled out during type checking from instantiated variables. Used during monomorphization
     /// to map call site types back onto function parameter types, and undo this binding as needed.
@@ -285,6 +289,7 @@ impl Default for NodeInterner {
             structs: HashMap::new(),
             type_aliases: Vec::new(),
             traits: HashMap::new(),
+            trait_implementations: TraitImplementations::default(),
             instantiation_bindings: HashMap::new(),
             field_indices: HashMap::new(),
             next_type_variable_id: 0,
@@ -328,23 +333,24 @@ impl NodeInterner {
     }
 
     pub fn push_empty_trait(&mut self, type_id: TraitId, typ: &UnresolvedTrait) {
-        self.traits.insert(
+        let mut new_trait = Trait::new(
             type_id,
-            Shared::new(Trait::new(
-                type_id,
-                typ.trait_def.name.clone(),
-                typ.trait_def.span,
-                Vec::new(),
-                vecmap(&typ.trait_def.generics, |_| {
-                    // Temporary type variable ids before the trait is resolved to its actual ids.
-                    // This lets us record how many arguments the type expects so that other types
-                    // can refer to it with generic arguments before the generic parameters themselves
-                    // are resolved.
-                    let id = TypeVariableId(0);
-                    (id, Shared::new(TypeBinding::Unbound(id)))
-                }),
-            )),
+            typ.trait_def.name.clone(),
+            typ.trait_def.span,
+            Vec::new(),
+            vecmap(&typ.trait_def.generics, |_| {
+                // Temporary type variable ids before the trait is resolved to its actual ids.
+                // This lets us record how many arguments the type expects so that other types
+                // can refer to it with generic arguments before the generic parameters themselves
+                // are resolved.
+                let id = TypeVariableId(0);
+                (id, Shared::new(TypeBinding::Unbound(id)))
+            }),
         );
+        // Object safety only depends on the trait's declaration, so it is known before any type
+        // referring to the trait as a trait object is resolved
+        new_trait.set_object_safety_violations(typ.trait_def.object_safety_violations());
+        self.traits.insert(type_id, Shared::new(new_trait));
     }
 
     pub fn push_empty_struct(&mut self, type_id: StructId, typ: &UnresolvedStruct) {
@@ -599,6 +605,14 @@ impl NodeInterner {
         self.traits[&id].clone()
     }
 
+    pub fn trait_implementations(&self) -> &TraitImplementations {
+        &self.trait_implementations
+    }
+
+    pub fn trait_implementations_mut(&mut self) -> &mut TraitImplementations {
+        &mut self.trait_implementations
+    }
+
     pub fn get_type_alias(&self, id: TypeAliasId) -> &TypeAliasType {
         &self.type_aliases[id.0]
     }
@@ -661,9 +675,9 @@ impl NodeInterner {
         self.function_definition_ids[&function]
     }
 
-    /// Add a method to a type.
-    /// This will panic for non-struct types currently as we do not support methods
-    /// for primitives. We could allow this in the future however.
+    /// Add a method to a type, returning the method it replaces if one of the same name was
+    /// already defined.
+    /// This will panic for types which can't have methods, see `supports_primitive_methods`.
     pub fn add_method(
         &mut self,
         self_type: &Type,
@@ -708,10 +722,16 @@ enum TypeMethodKey {
     Bool,
     String,
     Unit,
-    Tuple,
+    /// Tuples of different lengths are distinct types, so each length has its own methods
+    Tuple(usize),
     Function,
 }
 
+/// Returns true if methods may be added to the non-struct type `typ`.
+pub(crate) fn supports_primitive_methods(typ: &Type) -> bool {
+    get_type_method_key(typ).is_some()
+}
+
 fn get_type_method_key(typ: &Type) -> Option<TypeMethodKey> {
     use TypeMethodKey::*;
     let typ = typ.follow_bindings();
@@ -723,7 +743,7 @@ fn get_type_method_key(typ: &Type) -> Option<TypeMethodKey> {
         Type::Bool => Some(Bool),
         Type::String(_) => Some(String),
         Type::Unit => Some(Unit),
-        Type::Tuple(_) => Some(Tuple),
+        Type::Tuple(elements) => Some(Tuple(elements.len())),
         Type::Function(_, _, _) => Some(Function),
         Type::MutableReference(element) => get_type_method_key(element),
 
@@ -735,6 +755,7 @@ fn get_type_method_key(typ: &Type) -> Option<TypeMethodKey> {
         | Type::Error
         | Type::NotConstant
         | Type::Struct(_, _)
+        | Type::Opaque(..)
         | Type::FmtString(_, _) => None,
     }
 }
@@ -31,6 +31,14 @@ pub enum ParserErrorReason {
     ExperimentalFeature(&'static str),
     #[error("Where clauses are allowed only on functions with generic parameters")]
     WhereClauseOnNonGenericFunction,
+    #[error("Negative trait bounds are allowed only in where clauses")]
+    NegativeBoundOutsideWhereClause,
+    #[error("Comparisons on generics such as `N > 0` are allowed only in the where clauses of functions")]
+    GenericPredicateOutsideFunction,
+    #[error("Expected an integer which fits in 64 bits to compare the generic against")]
+    InvalidGenericPredicateValue,
+    #[error("Only `Fn` bounds may be given a signature such as `Fn(Field) -> Field`")]
+    ParenthesizedNonFnBound,
 }
 
 /// Represents a parsing error, or a parsing error in the making.
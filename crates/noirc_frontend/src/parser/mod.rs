@@ -27,7 +27,7 @@ use chumsky::primitive::Container;
 pub use errors::ParserError;
 pub use errors::ParserErrorReason;
 use noirc_errors::Span;
-pub use parser::parse_program;
+pub use parser::{parse_program, parse_trait};
 
 /// Counter used to generate unique names when desugaring
 /// code in the parser requires the creation of fresh variables.
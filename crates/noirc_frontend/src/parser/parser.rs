@@ -38,10 +38,10 @@ use crate::parser::{force, ignore_then_commit, statement_recovery};
 use crate::token::{Attribute, Keyword, Token, TokenKind};
 use crate::{
     BinaryOp, BinaryOpKind, BlockExpression, ConstrainStatement, Distinctness, FunctionDefinition,
-    FunctionReturnType, Ident, IfExpression, InfixExpression, LValue, Lambda, Literal,
-    NoirFunction, NoirStruct, NoirTrait, NoirTypeAlias, Path, PathKind, Pattern, Recoverable,
-    TraitBound, TraitConstraint, TraitImpl, TraitImplItem, TraitItem, TypeImpl, UnaryOp,
-    UnresolvedTypeExpression, UseTree, UseTreeKind, Visibility,
+    FunctionReturnType, GenericPredicate, Ident, IfExpression, InfixExpression, LValue, Lambda,
+    Literal, NoirFunction, NoirStruct, NoirTrait, NoirTypeAlias, Path, PathKind, Pattern,
+    Recoverable, TraitBound, TraitConstraint, TraitImpl, TraitImplItem, TraitItem, TypeImpl,
+    UnaryOp, UnresolvedTypeExpression, UseTree, UseTreeKind, Visibility, FN_TRAIT_NAME,
 };
 
 use chumsky::prelude::*;
@@ -64,6 +64,39 @@ pub fn parse_program(source_program: &str) -> (ParsedModule, Vec<CustomDiagnosti
     (module.unwrap(), errors)
 }
 
+/// Parses only the trait definition which starts at the character at `offset` in `source`,
+/// such as to reparse a single trait after it is edited. Anything after the end of the trait's
+/// body is ignored, including any lexing errors within it.
+///
+/// The spans within the returned trait are relative to the start of `source`. Only errors are
+/// returned, warnings such as the one for traits being experimental are discarded.
+pub fn parse_trait(source: &str, offset: u32) -> Result<NoirTrait, Vec<CustomDiagnostic>> {
+    let (tokens, lexing_errors) = Lexer::lex_from(source, offset);
+    let (statement, parsing_errors) = trait_definition().parse_recovery_verbose(tokens);
+
+    let mut errors: Vec<CustomDiagnostic> = vecmap(parsing_errors, Into::into);
+    errors.retain(CustomDiagnostic::is_error);
+
+    match statement {
+        Some(TopLevelStatement::Trait(noir_trait)) => {
+            let trait_end = noir_trait.span.end();
+            let lexing_errors =
+                lexing_errors.into_iter().filter(|error| error.span().start() <= trait_end);
+            errors.extend(lexing_errors.map(Into::into));
+
+            if errors.is_empty() {
+                Ok(noir_trait)
+            } else {
+                Err(errors)
+            }
+        }
+        _ => {
+            errors.extend(lexing_errors.into_iter().map(Into::into));
+            Err(errors)
+        }
+    }
+}
+
 /// program: module EOF
 fn program() -> impl NoirParser<ParsedModule> {
     module().then_ignore(force(just(Token::EOF)))
@@ -169,22 +202,25 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
         .then(generics())
         .then(parenthesized(function_parameters(allow_self)))
         .then(function_return_type())
-        .then(where_clause())
+        .then(where_clause_with_predicates())
         .then(spanned(block(expression())))
         .validate(|(((args, ret), where_clause), (body, body_span)), span, emit| {
             let ((((attribute, modifiers), name), generics), parameters) = args;
+            let (where_clause, where_predicates) = where_clause;
             validate_where_clause(&generics, &where_clause, span, emit);
             FunctionDefinition {
                 span: body_span,
                 name,
                 attribute, // XXX: Currently we only have one attribute defined. If more attributes are needed per function, we can make this a vector and make attribute definition more expressive
-                is_unconstrained: modifiers.0,
-                is_open: modifiers.1,
-                is_internal: modifiers.2,
+                is_pub: modifiers.0,
+                is_unconstrained: modifiers.1,
+                is_open: modifiers.2,
+                is_internal: modifiers.3,
                 generics,
                 parameters,
                 body,
                 where_clause,
+                where_predicates,
                 return_type: ret.1,
                 return_visibility: ret.0 .1,
                 return_distinctness: ret.0 .0,
@@ -193,16 +229,17 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
         })
 }
 
-/// function_modifiers: 'unconstrained'? 'open'? 'internal'?
+/// function_modifiers: 'pub'? 'unconstrained'? 'open'? 'internal'?
 ///
-/// returns (is_unconstrained, is_open, is_internal) for whether each keyword was present
-fn function_modifiers() -> impl NoirParser<(bool, bool, bool)> {
-    keyword(Keyword::Unconstrained)
+/// returns (is_pub, is_unconstrained, is_open, is_internal) for whether each keyword was present
+fn function_modifiers() -> impl NoirParser<(bool, bool, bool, bool)> {
+    keyword(Keyword::Pub)
         .or_not()
+        .then(keyword(Keyword::Unconstrained).or_not())
         .then(keyword(Keyword::Open).or_not())
         .then(keyword(Keyword::Internal).or_not())
-        .map(|((unconstrained, open), internal)| {
-            (unconstrained.is_some(), open.is_some(), internal.is_some())
+        .map(|(((is_pub, unconstrained), open), internal)| {
+            (is_pub.is_some(), unconstrained.is_some(), open.is_some(), internal.is_some())
         })
 }
 
@@ -234,11 +271,15 @@ fn struct_definition() -> impl NoirParser<TopLevelStatement> {
         ),
     );
 
-    keyword(Struct).ignore_then(ident()).then(generics()).then(fields).map_with_span(
-        |((name, generics), fields), span| {
-            TopLevelStatement::Struct(NoirStruct { name, generics, fields, span })
-        },
-    )
+    attribute()
+        .repeated()
+        .then_ignore(keyword(Struct))
+        .then(ident())
+        .then(generics())
+        .then(fields)
+        .map_with_span(|(((attributes, name), generics), fields), span| {
+            TopLevelStatement::Struct(NoirStruct { name, attributes, generics, fields, span })
+        })
 }
 
 fn type_alias_definition() -> impl NoirParser<TopLevelStatement> {
@@ -265,7 +306,7 @@ fn function_return_type() -> impl NoirParser<((Distinctness, Visibility), Functi
     just(Token::Arrow)
         .ignore_then(optional_distinctness())
         .then(optional_visibility())
-        .then(spanned(parse_type()))
+        .then(spanned(opaque_type().or(parse_type())))
         .or_not()
         .map_with_span(|ret, span| match ret {
             Some((head, (ty, span))) => (head, FunctionReturnType::Ty(ty, span)),
@@ -369,17 +410,33 @@ fn trait_definition() -> impl NoirParser<TopLevelStatement> {
     keyword(Keyword::Trait)
         .ignore_then(ident())
         .then(generics())
+        .then(supertraits())
         .then(where_clause())
         .then_ignore(just(Token::LeftBrace))
         .then(trait_body())
         .then_ignore(just(Token::RightBrace))
-        .validate(|(((name, generics), where_clause), items), span, emit| {
-            validate_where_clause(&generics, &where_clause, span, emit);
+        .validate(|((((name, generics), supertraits), where_clause), items), span, emit| {
+            // Constraints on `Self` are allowed even if the trait has no generics
+            if where_clause.iter().any(|constraint| !constraint.typ.is_self_type()) {
+                validate_where_clause(&generics, &where_clause, span, emit);
+            }
             emit(ParserError::with_reason(ParserErrorReason::ExperimentalFeature("Traits"), span));
-            TopLevelStatement::Trait(NoirTrait { name, generics, where_clause, span, items })
+            TopLevelStatement::Trait(NoirTrait {
+                name,
+                generics,
+                supertraits,
+                where_clause,
+                span,
+                items,
+            })
         })
 }
 
+/// supertraits: (':' trait_bounds)?
+fn supertraits() -> impl NoirParser<Vec<TraitBound>> {
+    just(Token::Colon).ignore_then(trait_bounds()).or_not().map(Option::unwrap_or_default)
+}
+
 fn trait_body() -> impl NoirParser<Vec<TraitItem>> {
     trait_function_declaration()
         .or(trait_type_declaration())
@@ -392,28 +449,50 @@ fn optional_default_value() -> impl NoirParser<Option<Expression>> {
     ignore_then_commit(just(Token::Assign), expression()).or_not()
 }
 
+/// trait_constant_declaration: 'let' ident ':' type where_clause ('=' expression)?
 fn trait_constant_declaration() -> impl NoirParser<TraitItem> {
     keyword(Keyword::Let)
         .ignore_then(ident())
         .then_ignore(just(Token::Colon))
         .then(parse_type())
+        .then(where_clause())
         .then(optional_default_value())
-        .map(|((name, typ), default_value)| TraitItem::Constant { name, typ, default_value })
+        .map(|(((name, typ), where_clause), default_value)| TraitItem::Constant {
+            name,
+            typ,
+            where_clause,
+            default_value,
+        })
 }
 
 /// trait_function_declaration: 'fn' ident generics '(' declaration_parameters ')' function_return_type
 fn trait_function_declaration() -> impl NoirParser<TraitItem> {
-    keyword(Keyword::Fn)
-        .ignore_then(ident())
+    attribute()
+        .repeated()
+        .then_ignore(keyword(Keyword::Fn))
+        .then(ident())
         .then(generics())
         .then(parenthesized(function_declaration_parameters()))
         .then(function_return_type().map(|(_, typ)| typ))
         .then(where_clause())
         .then(block(expression()).or_not())
         .validate(
-            |(((((name, generics), parameters), return_type), where_clause), body), span, emit| {
+            |(
+                (((((attributes, name), generics), parameters), return_type), where_clause),
+                body,
+            ),
+             span,
+             emit| {
                 validate_where_clause(&generics, &where_clause, span, emit);
-                TraitItem::Function { name, generics, parameters, return_type, where_clause, body }
+                TraitItem::Function {
+                    attributes,
+                    name,
+                    generics,
+                    parameters,
+                    return_type,
+                    where_clause,
+                    body,
+                }
             },
         )
 }
@@ -459,23 +538,44 @@ fn function_declaration_parameters() -> impl NoirParser<Vec<(Ident, UnresolvedTy
         .labelled(ParsingRuleLabel::Parameter)
 }
 
-/// trait_type_declaration: 'type' ident generics
+/// trait_type_declaration: 'type' ident generics (':' trait_bounds)? where_clause
 fn trait_type_declaration() -> impl NoirParser<TraitItem> {
-    keyword(Keyword::Type).ignore_then(ident()).map(|name| TraitItem::Type { name })
+    let bounds =
+        just(Token::Colon).ignore_then(trait_bounds()).or_not().map(Option::unwrap_or_default);
+
+    keyword(Keyword::Type)
+        .ignore_then(ident())
+        .then(generics())
+        .then(bounds)
+        .then(where_clause())
+        .map(|(((name, generics), bounds), where_clause)| TraitItem::Type {
+            name,
+            generics,
+            bounds,
+            where_clause,
+        })
 }
 
 /// Parses a non-trait implementation, adding a set of methods to a type.
 ///
 /// implementation: 'impl' generics type '{' function_definition ... '}'
 fn implementation() -> impl NoirParser<TopLevelStatement> {
-    keyword(Keyword::Impl)
-        .ignore_then(generics())
+    attribute()
+        .repeated()
+        .then_ignore(keyword(Keyword::Impl))
+        .then(generics())
         .then(parse_type().map_with_span(|typ, span| (typ, span)))
         .then_ignore(just(Token::LeftBrace))
         .then(function_definition(true).repeated())
         .then_ignore(just(Token::RightBrace))
-        .map(|((generics, (object_type, type_span)), methods)| {
-            TopLevelStatement::Impl(TypeImpl { generics, object_type, type_span, methods })
+        .map(|(((attributes, generics), (object_type, type_span)), methods)| {
+            TopLevelStatement::Impl(TypeImpl {
+                attributes,
+                generics,
+                object_type,
+                type_span,
+                methods,
+            })
         })
 }
 
@@ -485,8 +585,10 @@ fn implementation() -> impl NoirParser<TopLevelStatement> {
 ///
 /// trait_implementation: 'impl' generics ident generic_args for type '{' trait_implementation_body '}'
 fn trait_implementation() -> impl NoirParser<TopLevelStatement> {
-    keyword(Keyword::Impl)
-        .ignore_then(generics())
+    attribute()
+        .repeated()
+        .then_ignore(keyword(Keyword::Impl))
+        .then(generics())
         .then(ident())
         .then(generic_type_args(parse_type()))
         .then_ignore(keyword(Keyword::For))
@@ -497,11 +599,14 @@ fn trait_implementation() -> impl NoirParser<TopLevelStatement> {
         .then_ignore(just(Token::RightBrace))
         .validate(|args, span, emit| {
             let ((other_args, where_clause), items) = args;
-            let (((impl_generics, trait_name), trait_generics), (object_type, object_type_span)) =
-                other_args;
+            let (
+                (((attributes, impl_generics), trait_name), trait_generics),
+                (object_type, object_type_span),
+            ) = other_args;
 
             emit(ParserError::with_reason(ParserErrorReason::ExperimentalFeature("Traits"), span));
             TopLevelStatement::TraitImpl(TraitImpl {
+                attributes,
                 impl_generics,
                 trait_name,
                 trait_generics,
@@ -509,6 +614,7 @@ fn trait_implementation() -> impl NoirParser<TopLevelStatement> {
                 object_type_span,
                 items,
                 where_clause,
+                provenance: None,
             })
         })
 }
@@ -518,51 +624,154 @@ fn trait_implementation_body() -> impl NoirParser<Vec<TraitImplItem>> {
 
     let alias = keyword(Keyword::Type)
         .ignore_then(ident())
+        .then(generics())
         .then_ignore(just(Token::Assign))
         .then(parse_type())
         .then_ignore(just(Token::Semicolon))
-        .map(|(name, alias)| TraitImplItem::Type { name, alias });
+        .map(|((name, generics), alias)| TraitImplItem::Type { name, generics, alias });
 
-    function.or(alias).repeated()
+    let constant = keyword(Keyword::Let)
+        .ignore_then(ident())
+        .then_ignore(just(Token::Colon))
+        .then(parse_type())
+        .then_ignore(just(Token::Assign))
+        .then(expression())
+        .then_ignore(just(Token::Semicolon))
+        .map(|((name, typ), value)| TraitImplItem::Constant(name, typ, value));
+
+    function.or(alias).or(constant).repeated()
 }
 
 fn where_clause() -> impl NoirParser<Vec<TraitConstraint>> {
-    struct MultiTraitConstraint {
-        typ: UnresolvedType,
-        trait_bounds: Vec<TraitBound>,
+    where_clause_with_predicates().validate(|(constraints, predicates), _span, emit| {
+        for predicate in predicates {
+            emit(ParserError::with_reason(
+                ParserErrorReason::GenericPredicateOutsideFunction,
+                predicate.span,
+            ));
+        }
+        constraints
+    })
+}
+
+/// A where clause which may also compare numeric generics against integers, as allowed on
+/// function definitions.
+///
+/// where_clause_with_predicates: ('where' (type ':' trait_bounds | generic_predicate) (',' ..)*)?
+fn where_clause_with_predicates() -> impl NoirParser<(Vec<TraitConstraint>, Vec<GenericPredicate>)>
+{
+    enum WhereClauseItem {
+        Constraints { typ: UnresolvedType, trait_bounds: Vec<TraitBound> },
+        Predicate(GenericPredicate),
     }
 
-    let constraints = parse_type().then_ignore(just(Token::Colon)).then(trait_bounds()).validate(
-        |(typ, trait_bounds), span, emit| {
+    let constraints = parse_type()
+        .then_ignore(just(Token::Colon))
+        .then(possibly_negative_trait_bounds())
+        .validate(|(typ, trait_bounds), span, emit| {
             emit(ParserError::with_reason(ParserErrorReason::ExperimentalFeature("Traits"), span));
-            MultiTraitConstraint { typ, trait_bounds }
-        },
-    );
+            WhereClauseItem::Constraints { typ, trait_bounds }
+        });
+    let predicate = generic_predicate().map(WhereClauseItem::Predicate);
 
     keyword(Keyword::Where)
-        .ignore_then(constraints.separated_by(just(Token::Comma)))
+        .ignore_then(constraints.or(predicate).separated_by(just(Token::Comma)))
         .or_not()
         .map(|option| option.unwrap_or_default())
-        .map(|x: Vec<MultiTraitConstraint>| {
-            let mut result: Vec<TraitConstraint> = Vec::new();
-            for constraint in x {
-                for bound in constraint.trait_bounds {
-                    result
-                        .push(TraitConstraint { typ: constraint.typ.clone(), trait_bound: bound });
+        .map(|items: Vec<WhereClauseItem>| {
+            let mut constraints: Vec<TraitConstraint> = Vec::new();
+            let mut predicates = Vec::new();
+            for item in items {
+                match item {
+                    WhereClauseItem::Constraints { typ, trait_bounds } => {
+                        for bound in trait_bounds {
+                            constraints
+                                .push(TraitConstraint { typ: typ.clone(), trait_bound: bound });
+                        }
+                    }
+                    WhereClauseItem::Predicate(predicate) => predicates.push(predicate),
                 }
             }
-            result
+            (constraints, predicates)
         })
 }
 
+/// generic_predicate: ident ('==' | '!=' | '<' | '<=' | '>' | '>=') int
+fn generic_predicate() -> impl NoirParser<GenericPredicate> {
+    let comparison =
+        any().try_map(|token: Token, span| match token.clone().try_into_binary_op(span) {
+            Some(operator) if operator.contents.is_comparator() => Ok(operator.contents),
+            _ => Err(ParserError::expected_label(ParsingRuleLabel::BinaryOperator, token, span)),
+        });
+    let value = token_kind(TokenKind::Literal).validate(|token, span, emit| {
+        let value = match token {
+            Token::Int(value) => value.try_into_u128().and_then(|value| u64::try_from(value).ok()),
+            _ => None,
+        };
+        value.unwrap_or_else(|| {
+            emit(ParserError::with_reason(ParserErrorReason::InvalidGenericPredicateValue, span));
+            0
+        })
+    });
+
+    ident().then(comparison).then(value).map_with_span(|((generic, operator), value), span| {
+        GenericPredicate { generic, operator, value, span }
+    })
+}
+
+/// Trait bounds which must all be positive, as outside of a where clause.
 fn trait_bounds() -> impl NoirParser<Vec<TraitBound>> {
+    possibly_negative_trait_bounds().validate(|bounds, span, emit| {
+        if bounds.iter().any(|bound| bound.negative) {
+            emit(ParserError::with_reason(
+                ParserErrorReason::NegativeBoundOutsideWhereClause,
+                span,
+            ));
+        }
+        bounds
+    })
+}
+
+fn possibly_negative_trait_bounds() -> impl NoirParser<Vec<TraitBound>> {
     trait_bound().separated_by(just(Token::Plus)).at_least(1).allow_trailing()
 }
 
 fn trait_bound() -> impl NoirParser<TraitBound> {
+    let bound = just(Token::Bang).or_not().then(ident()).then(generic_type_args(parse_type())).map(
+        |((negative, trait_name), trait_generics)| TraitBound {
+            trait_name,
+            trait_generics,
+            negative: negative.is_some(),
+        },
+    );
+    fn_trait_bound().or(bound)
+}
+
+/// Parses a bound on a function or closure's signature, such as `Fn(Field) -> Field`. The
+/// signature is kept as the bound's only generic, a function type with no environment.
+fn fn_trait_bound() -> impl NoirParser<TraitBound> {
+    let args = parenthesized(parse_type().separated_by(just(Token::Comma)).allow_trailing());
+    let ret = just(Token::Arrow).ignore_then(parse_type()).or_not();
+
     ident()
-        .then(generic_type_args(parse_type()))
-        .map(|(trait_name, trait_generics)| TraitBound { trait_name, trait_generics })
+        .then(args)
+        .then(ret)
+        .map_with_span(|((trait_name, args), ret), span| {
+            let ret = ret.unwrap_or_else(|| UnresolvedTypeData::Unit.with_span(span));
+            let env = UnresolvedTypeData::Unit.with_span(span);
+            let signature = UnresolvedTypeData::Function(args, Box::new(ret), Box::new(env));
+            TraitBound {
+                trait_name,
+                trait_generics: vec![signature.with_span(span)],
+                negative: false,
+            }
+        })
+        .validate(|bound, span, emit| {
+            if bound.trait_name.0.contents != FN_TRAIT_NAME {
+                emit(ParserError::with_reason(ParserErrorReason::ParenthesizedNonFnBound, span));
+            }
+            bound
+        })
 }
 
 fn block_expr<'a, P>(expr_parser: P) -> impl NoirParser<Expression> + 'a
@@ -881,6 +1090,8 @@ fn parse_type_inner(
         recursive_type_parser.clone().delimited_by(just(Token::LeftParen), just(Token::RightParen)),
         tuple_type(recursive_type_parser.clone()),
         function_type(recursive_type_parser.clone()),
+        projection_type(recursive_type_parser.clone()),
+        trait_object_type(recursive_type_parser.clone()),
         mutable_reference_type(recursive_type_parser),
     ))
 }
@@ -1045,6 +1256,43 @@ where
         })
 }
 
+fn projection_type<T>(type_parser: T) -> impl NoirParser<UnresolvedType>
+where
+    T: NoirParser<UnresolvedType>,
+{
+    type_parser
+        .then_ignore(keyword(Keyword::As))
+        .then(ident())
+        .delimited_by(just(Token::Less), just(Token::Greater))
+        .then_ignore(just(Token::DoubleColon))
+        .then(ident())
+        .map_with_span(|((object_type, trait_name), item_name), span| {
+            let object_type = Box::new(object_type);
+            UnresolvedTypeData::Projection { object_type, trait_name, item_name }.with_span(span)
+        })
+}
+
+fn trait_object_type<T>(type_parser: T) -> impl NoirParser<UnresolvedType>
+where
+    T: NoirParser<UnresolvedType>,
+{
+    keyword(Keyword::Dyn).ignore_then(ident()).then(generic_type_args(type_parser)).map_with_span(
+        |(trait_name, trait_generics), span| {
+            let bound = TraitBound { trait_name, trait_generics, negative: false };
+            UnresolvedTypeData::TraitObject(bound).with_span(span)
+        },
+    )
+}
+
+/// opaque_type: 'impl' trait_bounds
+///
+/// Only parsed as the return type of a function, the one place opaque types may be written.
+fn opaque_type() -> impl NoirParser<UnresolvedType> {
+    keyword(Keyword::Impl).ignore_then(trait_bounds()).map_with_span(|bounds, span| {
+        UnresolvedTypeData::Opaque { bounds, concrete_type: None }.with_span(span)
+    })
+}
+
 fn mutable_reference_type<T>(type_parser: T) -> impl NoirParser<UnresolvedType>
 where
     T: NoirParser<UnresolvedType>,
@@ -1903,6 +2151,8 @@ mod test {
                 "fn f<T>(f: pub Field, y : T, z : Field) -> u8 { x + a }",
                 "fn func_name(x: [Field], y : [Field;2],y : pub [Field;2], z : pub [u8;5])  {}",
                 "fn main(x: pub u8, y: pub u8) -> distinct pub [u8; 2] { [x, y] }",
+                "pub fn func_name(x: Field) -> Field { x }",
+                "pub unconstrained fn func_name(x: Field) -> Field { x }",
                 "fn f(f: pub Field, y : Field, z : comptime Field) -> u8 { x + a }",
                 "fn f<T>(f: pub Field, y : T, z : comptime Field) -> u8 { x + a }",
                 "fn func_name<T>(f: Field, y : T) where T: SomeTrait {}",
@@ -1917,6 +2167,8 @@ mod test {
                 // 'where u32: SomeTrait' is allowed in Rust.
                 // It will result in compiler error in case SomeTrait isn't implemented for u32.
                 "fn func_name<T>(f: Field, y : T) where u32: SomeTrait {}",
+                "fn func_name<T, N>(f: Field, y : T) where [T; N]: SomeTrait {}",
+                "fn func_name<A, B>(f: Field, y : A) where (A, B): SomeTrait, [(A, B); 2]: SomeTrait {}",
                 // A trailing plus is allowed by Rust, so we support it as well.
                 "fn func_name<T>(f: Field, y : T) where T: SomeTrait + {}",
                 // The following should produce compile error on later stage. From the parser's perspective it's fine
@@ -1942,6 +2194,34 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_where_clause_predicates() {
+        let functions = parse_all(
+            function_definition(false),
+            vec![
+                "fn first<N>(xs: [Field; N]) -> Field where N > 0 { xs[0] }",
+                "fn f<T, N>(xs: [T; N]) where T: Eq, N >= 2, N != 7 {}",
+            ],
+        );
+        let predicates: Vec<_> = functions
+            .iter()
+            .flat_map(|function| vecmap(&function.def.where_predicates, ToString::to_string))
+            .collect();
+        assert_eq!(predicates, vec!["N > 0", "N >= 2", "N != 7"]);
+        assert_eq!(functions[1].def.where_clause.len(), 1);
+
+        parse_all_failing(
+            function_definition(false),
+            vec![
+                "fn f<N, M>(xs: [Field; N]) where N > M {}",
+                "fn f<N>(xs: [Field; N]) where N + 1 {}",
+                "fn f<N>(xs: [Field; N]) where N > -1 {}",
+            ],
+        );
+        // Predicates are only checked when the function is called
+        parse_all_failing(trait_definition(), vec!["trait Sized<N> where N > 0 {}"]);
+    }
+
     #[test]
     fn parse_trait() {
         parse_all(
@@ -1960,6 +2240,11 @@ mod test {
                 "trait GenericTrait<T> { fn elem(&mut self, index: Field) -> T; }",
                 "trait GenericTraitWithConstraints<T> where T: SomeTrait { fn elem(self, index: Field) -> T; }",
                 "trait TraitWithMultipleGenericParams<A, B, C> where A: SomeTrait, B: AnotherTrait<C> { let Size: Field; fn zero() -> Self; }",
+                "trait TraitWithSupertrait: Eq { fn cmp(self, other: Self) -> Field; }",
+                "trait TraitWithSupertraits<T>: Eq + Into<T> where T: Default { }",
+                "trait TraitWithBoundedType { type Item: Eq + Ord; }",
+                "trait TraitWithBoundedTypeAndWhereClause { type Item: Eq + Ord where Self: Clone; }",
+                "trait TraitWithMethodAttributes { #[allow(unused_self)] fn foo(self) {}; }",
             ],
         );
 
@@ -1969,10 +2254,153 @@ mod test {
                 "trait MissingBody",
                 "trait WrongDelimiter { fn foo() -> u8, fn bar() -> u8 }",
                 "trait WhereClauseWithoutGenerics where A: SomeTrait { }",
+                "trait MissingSupertrait: { }",
+                "trait WhereClauseBeforeBounds { type Item where Self: Clone: Eq; }",
             ],
         );
     }
 
+    #[test]
+    fn parse_single_trait() {
+        let src = "fn foo() {}\ntrait Foo { fn bar(self); }\nfn baz() { 1 + }\n\"unterminated";
+        let offset = src.find("trait").unwrap();
+
+        let noir_trait = parse_trait(src, offset as u32).unwrap();
+        assert_eq!(noir_trait.name.0.contents, "Foo");
+        assert_eq!(noir_trait.items.len(), 1);
+        assert_eq!(noir_trait.span.start() as usize, offset);
+        assert_eq!(&src[noir_trait.name.span().start() as usize..][..3], "Foo");
+
+        // Errors within the trait are still reported
+        let src = "trait Foo { fn (self); }";
+        assert!(parse_trait(src, 0).is_err());
+
+        // There must be a trait at the given offset
+        assert!(parse_trait("fn foo() {}", 0).is_err());
+    }
+
+    #[test]
+    fn parse_negative_trait_bounds() {
+        let cases = vec!["fn foo<T>() where T: !Eq {}", "fn foo<T>() where T: Ord + !Eq<Field> {}"];
+        for function in parse_all(function_definition(false), cases) {
+            let where_clause = &function.def.where_clause;
+            let last = where_clause.last().unwrap();
+            assert!(last.trait_bound.negative);
+            assert_eq!(last.trait_bound.trait_name.0.contents, "Eq");
+            assert!(where_clause[..where_clause.len() - 1]
+                .iter()
+                .all(|constraint| !constraint.trait_bound.negative));
+        }
+
+        for src in ["trait Foo: !Eq {}", "trait Foo { type Item: !Eq; }"] {
+            let (_, errors) = parse_recover(trait_definition(), src);
+            assert!(errors.iter().any(|error| error.is_error()), "{src} should fail to parse");
+        }
+    }
+
+    #[test]
+    fn parse_trait_type_with_bounds_and_where_clause() {
+        let src = "type Item: Eq + Ord where Self: Clone";
+        let item = parse_with(trait_type_declaration(), src).unwrap();
+
+        let TraitItem::Type { name, bounds, where_clause, .. } = &item else {
+            panic!("Expected an associated type, found {item}");
+        };
+        assert_eq!(name.0.contents, "Item");
+        assert_eq!(vecmap(bounds, ToString::to_string), vec!["Eq", "Ord"]);
+        assert_eq!(vecmap(where_clause, ToString::to_string), vec!["Self: Clone"]);
+
+        // The displayed item should parse back into the same bounds and where clause
+        let displayed = item.to_string();
+        assert_eq!(displayed, format!("{src};"));
+        let reparsed = parse_with(trait_type_declaration(), displayed.trim_end_matches(';'));
+        assert_eq!(reparsed.unwrap().to_string(), displayed);
+    }
+
+    #[test]
+    fn parse_trait_constant_with_where_clause() {
+        let src = "let SIZE: u32 where Self: Sized";
+        let item = parse_with(trait_constant_declaration(), src).unwrap();
+
+        let TraitItem::Constant { name, where_clause, default_value, .. } = &item else {
+            panic!("Expected an associated constant, found {item}");
+        };
+        assert_eq!(name.0.contents, "SIZE");
+        assert_eq!(vecmap(where_clause, ToString::to_string), vec!["Self: Sized"]);
+        assert!(default_value.is_none());
+
+        let displayed = item.to_string();
+        assert_eq!(displayed, format!("{src};"));
+        let reparsed = parse_with(trait_constant_declaration(), displayed.trim_end_matches(';'));
+        assert_eq!(reparsed.unwrap().to_string(), displayed);
+
+        // The where clause comes before any default
+        let src = "let SIZE: u32 where Self: Sized, Self: Eq = 4";
+        let item = parse_with(trait_constant_declaration(), src).unwrap();
+        let TraitItem::Constant { where_clause, default_value, .. } = &item else {
+            panic!("Expected an associated constant, found {item}");
+        };
+        assert_eq!(where_clause.len(), 2);
+        assert!(default_value.is_some());
+        let reparsed =
+            parse_with(trait_constant_declaration(), item.to_string().trim_end_matches(';'));
+        assert_eq!(reparsed.unwrap().to_string(), item.to_string());
+    }
+
+    #[test]
+    fn parse_generic_associated_types() {
+        let item = parse_with(trait_type_declaration(), "type Out<T>: Eq").unwrap();
+        let TraitItem::Type { generics, .. } = &item else {
+            panic!("Expected an associated type, found {item}");
+        };
+        assert_eq!(vecmap(generics, ToString::to_string), vec!["T"]);
+        assert_eq!(item.to_string(), "type Out<T>: Eq;");
+
+        let src = "type Out<T, N> = Wrap<T, N>;";
+        let items = parse_with(trait_implementation_body(), src).unwrap();
+        let [TraitImplItem::Type { generics, .. }] = items.as_slice() else {
+            panic!("Expected a single associated type");
+        };
+        assert_eq!(generics.len(), 2);
+        assert_eq!(items[0].to_string(), src);
+    }
+
+    #[test]
+    fn parse_impls_with_attributes() {
+        parse_all(
+            implementation(),
+            vec![
+                "#[cfg(test)] impl Foo { fn foo() {} }",
+                "#[cfg(not(test))] #[cfg(feature = \"bar\")] impl<T> Foo<T> {}",
+            ],
+        );
+
+        parse_all(
+            trait_implementation(),
+            vec![
+                "#[cfg(test)] impl Default for Foo { fn default() -> Self { Self::new() } }",
+                "#[cfg(not(feature = \"bar\"))] impl<T> Eq for Foo<T> where T: Eq {}",
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_trait_impl_items() {
+        parse_all(
+            trait_implementation(),
+            vec![
+                "impl Foo for Bar { type Item = Field; }",
+                "impl Foo for Bar { let Size: u32 = 3; }",
+                "impl<T> Foo<T> for Bar<T> { type Item = T; let Size: u32 = 3; fn foo() {} }",
+            ],
+        );
+
+        parse_all_failing(
+            trait_implementation(),
+            vec!["impl Foo for Bar { let Size: u32; }", "impl Foo for Bar { let Size = 3; }"],
+        );
+    }
+
     #[test]
     fn parse_parenthesized_expression() {
         parse_all(
@@ -2138,6 +2566,8 @@ mod test {
             "struct Foo { }",
             "struct Bar { ident: Field, }",
             "struct Baz { ident: Field, other: Field }",
+            "#[derive(Eq)] struct Qux { ident: Field }",
+            "#[derive(Eq, Default)] struct Quux { ident: Field, other: [u8; 2] }",
         ];
         parse_all(struct_definition(), cases);
 
@@ -2154,6 +2584,63 @@ mod test {
         parse_all_failing(type_alias_definition(), failing);
     }
 
+    #[test]
+    fn parse_projection_types() {
+        let cases =
+            vec!["<Bar as Baz>::Assoc", "<Foo<Field> as Baz>::Assoc", "< <A as B>::C as D>::E"];
+        parse_all(parse_type(), cases);
+
+        let failing =
+            vec!["<Bar>::Assoc", "<Bar as Baz>", "<Bar as Baz>::", "<Bar as Baz<T>::Assoc"];
+        parse_all_failing(parse_type(), failing);
+    }
+
+    #[test]
+    fn parse_trait_object_types() {
+        let cases = vec!["dyn Shape", "dyn Into<Field>", "&mut dyn Shape", "[dyn Shape; 2]"];
+        parse_all(parse_type(), cases);
+
+        let failing = vec!["dyn", "dyn !Shape", "dyn 3"];
+        parse_all_failing(parse_type(), failing);
+    }
+
+    #[test]
+    fn parse_opaque_return_types() {
+        parse_all(
+            function_definition(false),
+            vec![
+                "fn iter(self) -> impl Iterator { self.range() }",
+                "fn f() -> impl Into<Field> + Eq {}",
+                "fn f<T>() -> impl Container<T> where T: Eq {}",
+            ],
+        );
+
+        parse_all_failing(
+            function_definition(false),
+            vec!["fn f() -> impl {}", "fn f() -> impl !Eq {}", "fn f(x: impl Eq) {}"],
+        );
+    }
+
+    #[test]
+    fn parse_fn_bounds() {
+        parse_all(
+            trait_definition(),
+            vec![
+                "trait Map { fn map<F>(self, f: F) -> Self where F: Fn(Field) -> Field; }",
+                "trait Each { fn each<F>(self, f: F) where F: Fn(Field, u8); }",
+                "trait Fold { fn fold<F, T>(self, f: F) -> T where F: Fn(T, Field) -> T, T: Eq; }",
+            ],
+        );
+
+        parse_all_failing(
+            trait_definition(),
+            vec![
+                "trait Map { fn map<F>(self, f: F) where F: Eq(Field) -> Field; }",
+                "trait Map { fn map<F>(self, f: F) where F: Fn(Field) ->; }",
+            ],
+        );
+    }
+
     #[test]
     fn parse_member_access() {
         let cases = vec!["a.b", "a + b.c", "foo.bar as u32"];
@@ -66,6 +66,94 @@ pub enum PrintableValueDisplay {
     FmtString(String, Vec<(PrintableValue, PrintableType)>),
 }
 
+/// The message of a single call to `println`, with the values printed kept separate from how
+/// they're rendered so that tools may display them differently.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrintEvent {
+    /// A single value, as in `println(x)`
+    Value(PrintableValue, PrintableType),
+    /// A format string, as in `println(f"x = {x}")`
+    Format(FormatString),
+}
+
+/// A format string's template along with the value substituted for each of its placeholders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatString {
+    pub template: String,
+    pub arguments: Vec<FormatArgument>,
+}
+
+/// The value substituted for a placeholder such as `{x}`, which has the name `x`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatArgument {
+    pub name: String,
+    pub value: PrintableValue,
+    pub typ: PrintableType,
+}
+
+/// A piece of a [`FormatString`]'s template.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FormatSegment<'a> {
+    Literal(&'a str),
+    Argument(&'a FormatArgument),
+}
+
+impl FormatString {
+    /// Splits the template into its literal text and the arguments substituted between it,
+    /// in the order in which they appear. Placeholders without a matching argument are
+    /// treated as literal text.
+    pub fn segments(&self) -> Vec<FormatSegment<'_>> {
+        let mut segments = Vec::new();
+        let mut arguments = self.arguments.iter();
+        let mut last_match = 0;
+        for placeholder in placeholder_regex().find_iter(&self.template) {
+            let Some(argument) = arguments.next() else { break };
+            if placeholder.start() > last_match {
+                segments
+                    .push(FormatSegment::Literal(&self.template[last_match..placeholder.start()]));
+            }
+            segments.push(FormatSegment::Argument(argument));
+            last_match = placeholder.end();
+        }
+        if last_match < self.template.len() {
+            segments.push(FormatSegment::Literal(&self.template[last_match..]));
+        }
+        segments
+    }
+}
+
+impl From<PrintableValueDisplay> for PrintEvent {
+    fn from(display: PrintableValueDisplay) -> Self {
+        match display {
+            PrintableValueDisplay::Plain(value, typ) => PrintEvent::Value(value, typ),
+            PrintableValueDisplay::FmtString(template, values) => {
+                let names = placeholder_regex()
+                    .captures_iter(&template)
+                    .map(|captures| captures[1].to_string())
+                    .collect::<Vec<_>>();
+                let arguments = values
+                    .into_iter()
+                    .zip(names)
+                    .map(|((value, typ), name)| FormatArgument { name, value, typ })
+                    .collect();
+                PrintEvent::Format(FormatString { template, arguments })
+            }
+        }
+    }
+}
+
+impl TryFrom<&[Vec<Value>]> for PrintEvent {
+    type Error = ForeignCallError;
+
+    fn try_from(foreign_call_inputs: &[Vec<Value>]) -> Result<Self, Self::Error> {
+        PrintableValueDisplay::try_from(foreign_call_inputs).map(PrintEvent::from)
+    }
+}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{([a-zA-Z0-9_]+)\}").expect("placeholder regex is valid")
+}
+
 #[derive(Debug, Error)]
 pub enum ForeignCallError {
     #[error("Foreign call inputs needed for execution are missing")]
@@ -73,6 +161,9 @@ pub enum ForeignCallError {
 
     #[error("Could not parse PrintableType argument. {0}")]
     ParsingError(#[from] serde_json::Error),
+
+    #[error("Invalid inputs to foreign call `{function}`: {message}")]
+    InvalidInputs { function: String, message: String },
 }
 
 impl TryFrom<&[Vec<Value>]> for PrintableValueDisplay {
@@ -237,7 +328,7 @@ impl std::fmt::Display for PrintableValueDisplay {
             }
             Self::FmtString(template, values) => {
                 let mut display_iter = values.iter();
-                let re = Regex::new(r"\{([a-zA-Z0-9_]+)\}").map_err(|_| std::fmt::Error)?;
+                let re = placeholder_regex();
 
                 let formatted_str = replace_all(&re, template, |_: &Captures| {
                     let (value, typ) = display_iter.next().ok_or(std::fmt::Error)?;
@@ -250,6 +341,33 @@ impl std::fmt::Display for PrintableValueDisplay {
     }
 }
 
+impl std::fmt::Display for FormatString {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for segment in self.segments() {
+            match segment {
+                FormatSegment::Literal(text) => write!(fmt, "{text}")?,
+                FormatSegment::Argument(FormatArgument { value, typ, .. }) => {
+                    let output_string = to_string(value, typ).ok_or(std::fmt::Error)?;
+                    write!(fmt, "{output_string}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for PrintEvent {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrintEvent::Value(value, typ) => {
+                let output_string = to_string(value, typ).ok_or(std::fmt::Error)?;
+                write!(fmt, "{output_string}")
+            }
+            PrintEvent::Format(format_string) => format_string.fmt(fmt),
+        }
+    }
+}
+
 /// This trims any leading zeroes.
 /// A singular '0' will be prepended as well if the trimmed string has an odd length.
 /// A hex string's length needs to be even to decode into bytes, as two digits correspond to
@@ -319,3 +437,51 @@ fn decode_string_value(field_elements: &[FieldElement]) -> String {
     let final_string = str::from_utf8(&string_as_slice).unwrap();
     final_string.to_owned()
 }
+
+#[cfg(test)]
+mod tests {
+    use acvm::brillig_vm::brillig::Value;
+    use acvm::FieldElement;
+    use iter_extended::vecmap;
+
+    use super::{FormatSegment, PrintEvent, PrintableType, PrintableValue};
+
+    fn encode_string(string: &str) -> Vec<Value> {
+        vecmap(string.bytes(), |byte| Value::from(u128::from(byte)))
+    }
+
+    #[test]
+    fn format_strings_keep_their_arguments() {
+        // The message, the number of values, each value followed by each type, then the flag
+        // marking this as a format string
+        let inputs = vec![
+            encode_string("x = {x}, y = {y}!"),
+            vec![Value::from(2_u128)],
+            vec![Value::from(5_u128)],
+            vec![Value::from(1_u128)],
+            encode_string(r#"{"kind":"field"}"#),
+            encode_string(r#"{"kind":"boolean"}"#),
+            vec![Value::from(1_u128)],
+        ];
+        let PrintEvent::Format(format_string) = PrintEvent::try_from(inputs.as_slice()).unwrap()
+        else {
+            panic!("expected a format string");
+        };
+
+        assert_eq!(format_string.template, "x = {x}, y = {y}!");
+        let names = vecmap(&format_string.arguments, |argument| argument.name.as_str());
+        assert_eq!(names, vec!["x", "y"]);
+        assert_eq!(
+            format_string.arguments[0].value,
+            PrintableValue::Field(FieldElement::from(5_u128))
+        );
+        assert_eq!(format_string.arguments[1].typ, PrintableType::Boolean);
+
+        let segments = format_string.segments();
+        assert_eq!(segments.len(), 5);
+        assert_eq!(segments[0], FormatSegment::Literal("x = "));
+        assert_eq!(segments[4], FormatSegment::Literal("!"));
+
+        assert_eq!(format_string.to_string(), "x = 0x05, y = true!");
+    }
+}